@@ -0,0 +1,225 @@
+//! Everything the game loads besides its code: fonts, art, prefabs, raws and palettes. Each asset
+//! is looked up along a chain of sources, in this order:
+//!
+//! 1. the user data directory, given by `--data-dir` or `INNIT_DATA_DIR`
+//! 2. the mod directory, given by `--mod-dir` or `INNIT_MOD_DIR`
+//! 3. the defaults embedded into the game
+//!
+//! Both directories share the same layout, e.g. a mod replaces the font with its own
+//! `fonts/rex_paint_8x8.png` and the species with its own `objects.json`.
+
+use std::path::PathBuf;
+use std::sync::Once;
+
+use crate::core::innit_env;
+use crate::util::platform;
+
+rltk::embedded_resource!(FONT_8_RAW, "../resources/fonts/rex_paint_8x8.png");
+rltk::embedded_resource!(FONT_10_RAW, "../resources/fonts/rex_paint_10x10.png");
+rltk::embedded_resource!(FONT_12_RAW, "../resources/fonts/rex_paint_12x12.png");
+rltk::embedded_resource!(FONT_14_RAW, "../resources/fonts/rex_paint_14x14.png");
+rltk::embedded_resource!(FONT_16_RAW, "../resources/fonts/rex_paint_16x16.png");
+rltk::embedded_resource!(MENU_ART_RAW, "../resources/art/main.xp");
+rltk::embedded_resource!(CAPILLARY_RAW, "../resources/prefabs/capillary.xp");
+rltk::embedded_resource!(ALVEOLUS_RAW, "../resources/prefabs/alveolus.xp");
+rltk::embedded_resource!(SPAWN_RAW, "../raws/spawns.json");
+rltk::embedded_resource!(OBJECT_RAW, "../raws/objects.json");
+rltk::embedded_resource!(PREFAB_RAW, "../raws/prefabs.json");
+rltk::embedded_resource!(TERRAIN_RAW, "../raws/terrain.json");
+rltk::embedded_resource!(BOSS_RAW, "../raws/bosses.json");
+rltk::embedded_resource!(STORY_RAW, "../raws/story.json");
+rltk::embedded_resource!(DIFFICULTY_RAW, "../raws/difficulty.json");
+rltk::embedded_resource!(ECOSYSTEM_RAW, "../raws/ecosystem.json");
+
+pub const FONT_8X8: Asset = Asset::new(
+    "fonts/rex_paint_8x8.png",
+    "../resources/fonts/rex_paint_8x8.png",
+);
+pub const FONT_10X10: Asset = Asset::new(
+    "fonts/rex_paint_10x10.png",
+    "../resources/fonts/rex_paint_10x10.png",
+);
+pub const FONT_12X12: Asset = Asset::new(
+    "fonts/rex_paint_12x12.png",
+    "../resources/fonts/rex_paint_12x12.png",
+);
+pub const FONT_14X14: Asset = Asset::new(
+    "fonts/rex_paint_14x14.png",
+    "../resources/fonts/rex_paint_14x14.png",
+);
+pub const FONT_16X16: Asset = Asset::new(
+    "fonts/rex_paint_16x16.png",
+    "../resources/fonts/rex_paint_16x16.png",
+);
+pub const MENU_ART: Asset = Asset::new("art/main.xp", "../resources/art/main.xp");
+pub const CAPILLARY_PREFAB: Asset =
+    Asset::new("prefabs/capillary.xp", "../resources/prefabs/capillary.xp");
+pub const ALVEOLUS_PREFAB: Asset =
+    Asset::new("prefabs/alveolus.xp", "../resources/prefabs/alveolus.xp");
+pub const SPAWNS: Asset = Asset::new("spawns.json", "../raws/spawns.json");
+pub const OBJECTS: Asset = Asset::new("objects.json", "../raws/objects.json");
+pub const PREFABS: Asset = Asset::new("prefabs.json", "../raws/prefabs.json");
+pub const TERRAIN: Asset = Asset::new("terrain.json", "../raws/terrain.json");
+pub const BOSSES: Asset = Asset::new("bosses.json", "../raws/bosses.json");
+pub const STORY: Asset = Asset::new("story.json", "../raws/story.json");
+pub const DIFFICULTY: Asset = Asset::new("difficulty.json", "../raws/difficulty.json");
+pub const ECOSYSTEM: Asset = Asset::new("ecosystem.json", "../raws/ecosystem.json");
+/// The game has no palette file of its own, its default palette is defined in code.
+pub const PALETTE: Asset = Asset::user_only("palette.json");
+
+static LINK_EMBEDDED: Once = Once::new();
+
+/// Make the embedded defaults available to the rltk resource lookup.
+fn link_embedded() {
+    LINK_EMBEDDED.call_once(|| {
+        rltk::link_resource!(FONT_8_RAW, "../resources/fonts/rex_paint_8x8.png");
+        rltk::link_resource!(FONT_10_RAW, "../resources/fonts/rex_paint_10x10.png");
+        rltk::link_resource!(FONT_12_RAW, "../resources/fonts/rex_paint_12x12.png");
+        rltk::link_resource!(FONT_14_RAW, "../resources/fonts/rex_paint_14x14.png");
+        rltk::link_resource!(FONT_16_RAW, "../resources/fonts/rex_paint_16x16.png");
+        rltk::link_resource!(MENU_ART_RAW, "../resources/art/main.xp");
+        rltk::link_resource!(CAPILLARY_RAW, "../resources/prefabs/capillary.xp");
+        rltk::link_resource!(ALVEOLUS_RAW, "../resources/prefabs/alveolus.xp");
+        rltk::link_resource!(SPAWN_RAW, "../raws/spawns.json");
+        rltk::link_resource!(OBJECT_RAW, "../raws/objects.json");
+        rltk::link_resource!(PREFAB_RAW, "../raws/prefabs.json");
+        rltk::link_resource!(TERRAIN_RAW, "../raws/terrain.json");
+        rltk::link_resource!(BOSS_RAW, "../raws/bosses.json");
+        rltk::link_resource!(STORY_RAW, "../raws/story.json");
+        rltk::link_resource!(DIFFICULTY_RAW, "../raws/difficulty.json");
+        rltk::link_resource!(ECOSYSTEM_RAW, "../raws/ecosystem.json");
+    });
+}
+
+/// A place that an asset can be taken from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetSource {
+    UserData(PathBuf),
+    Mod(PathBuf),
+    /// Key of the resource embedded into the game.
+    Embedded(&'static str),
+}
+
+impl AssetSource {
+    /// Path of the asset, for libraries that load assets on their own. Embedded assets are given
+    /// by their resource key, which rltk looks up before the file system.
+    pub fn path(&self) -> String {
+        match self {
+            AssetSource::UserData(path) | AssetSource::Mod(path) => path.display().to_string(),
+            AssetSource::Embedded(key) => key.to_string(),
+        }
+    }
+
+    fn read(&self) -> Result<Vec<u8>, String> {
+        match self {
+            AssetSource::UserData(path) | AssetSource::Mod(path) => {
+                platform::read_user_file(path).unwrap_or_else(|| Err("file not found".into()))
+            }
+            AssetSource::Embedded(key) => rltk::embedding::EMBED
+                .lock()
+                .get_resource(key.to_string())
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| format!("no embedded resource {}", key)),
+        }
+    }
+}
+
+/// A file that the game loads and that users or mods may replace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Asset {
+    /// Path of the asset within the user data and mod directories.
+    pub name: &'static str,
+    /// Key of the embedded default, `None` if the game has no file of its own.
+    embedded: Option<&'static str>,
+}
+
+impl Asset {
+    const fn new(name: &'static str, embedded: &'static str) -> Self {
+        Asset {
+            name,
+            embedded: Some(embedded),
+        }
+    }
+
+    const fn user_only(name: &'static str) -> Self {
+        Asset {
+            name,
+            embedded: None,
+        }
+    }
+
+    /// All sources that provide the asset, in the order of precedence.
+    pub fn sources(&self) -> Vec<AssetSource> {
+        let (data_dir, mod_dir) = {
+            let env = innit_env();
+            (env.data_dir.clone(), env.mod_dir.clone())
+        };
+        self.sources_in(data_dir, mod_dir)
+    }
+
+    /// All sources that provide the asset, given the user data and mod directories.
+    pub(crate) fn sources_in(
+        &self,
+        data_dir: Option<PathBuf>,
+        mod_dir: Option<PathBuf>,
+    ) -> Vec<AssetSource> {
+        link_embedded();
+        let mut sources = Vec::new();
+        if let Some(path) = data_dir.map(|dir| dir.join(self.name)) {
+            if platform::has_user_file(&path) {
+                sources.push(AssetSource::UserData(path));
+            }
+        }
+        if let Some(path) = mod_dir.map(|dir| dir.join(self.name)) {
+            if platform::has_user_file(&path) {
+                sources.push(AssetSource::Mod(path));
+            }
+        }
+        if let Some(key) = self.embedded {
+            sources.push(AssetSource::Embedded(key));
+        }
+        sources
+    }
+
+    /// The source that the asset is taken from, i.e. the first one that provides it.
+    pub fn resolve(&self) -> Option<AssetSource> {
+        self.sources().into_iter().next()
+    }
+
+    /// Where the asset comes from, as shown in log messages and error reports.
+    pub fn origin(&self, source: &AssetSource) -> String {
+        match source {
+            AssetSource::Embedded(_) => format!("{} (built-in)", self.name),
+            _ => source.path(),
+        }
+    }
+
+    /// Load the asset from the first source that provides a usable version of it. A source that
+    /// cannot be read, or whose content `parse` rejects, is skipped in favour of the next one.
+    /// `parse` receives the origin of the content and reports its own problems, problems with
+    /// reading the sources are appended to `errors`.
+    pub fn load<T>(
+        &self,
+        errors: &mut Vec<String>,
+        mut parse: impl FnMut(&str, &[u8], &mut Vec<String>) -> Option<T>,
+    ) -> Option<T> {
+        for source in self.sources() {
+            let origin = self.origin(&source);
+            match source.read() {
+                Ok(bytes) => {
+                    if let Some(value) = parse(&origin, &bytes, errors) {
+                        if !matches!(source, AssetSource::Embedded(_)) {
+                            info!("loaded {}", origin);
+                        }
+                        return Some(value);
+                    }
+                }
+                Err(e) => {
+                    error!("failed to read {}: {}", origin, e);
+                    errors.push(format!("{}: {}", origin, e));
+                }
+            }
+        }
+        None
+    }
+}
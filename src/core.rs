@@ -1,12 +1,31 @@
+pub mod achievements;
+pub mod bookmarks;
+pub mod bug_report;
+pub mod claims;
+pub mod clock;
+pub mod daily;
+pub mod ecosystem;
+pub mod encyclopedia;
+pub mod events;
 pub mod game_env;
+pub mod game_error;
 pub mod game_objects;
 pub mod game_state;
+pub mod highscores;
+pub mod host;
 pub mod position;
+pub mod run_code;
+pub mod save;
+pub mod snapshot;
+pub mod story;
+pub mod telemetry;
+pub mod visibility;
 pub mod world;
 
 use std::sync::{Mutex, MutexGuard};
 
 use crate::core::game_env::GameEnv;
+use crate::core::telemetry::Telemetry;
 
 lazy_static! {
     static ref GAME_ENV: Mutex<GameEnv> = Mutex::new(GameEnv::new());
@@ -15,3 +34,11 @@ lazy_static! {
 pub fn innit_env<'a>() -> MutexGuard<'a, GameEnv> {
     GAME_ENV.lock().unwrap()
 }
+
+lazy_static! {
+    static ref TELEMETRY: Mutex<Telemetry> = Mutex::new(Telemetry::new());
+}
+
+pub fn telemetry<'a>() -> MutexGuard<'a, Telemetry> {
+    TELEMETRY.lock().unwrap()
+}
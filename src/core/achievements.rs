@@ -0,0 +1,105 @@
+//! Achievements reward milestones of the player across all runs. They are unlocked by game events
+//! and kept in the data directory, so that they persist from one run to the next.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::events::GameEvent;
+use crate::util::platform;
+
+const STORAGE_KEY: &str = "achievements.json";
+
+pub struct Achievement {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Whether the event fulfills the achievement.
+    unlocked_by: fn(&GameEvent) -> bool,
+}
+
+/// All achievements there are to unlock.
+pub const ACHIEVEMENTS: [Achievement; 4] = [
+    Achievement {
+        id: "first_mutation",
+        name: "Mutant",
+        description: "Change your genome for the first time.",
+        unlocked_by: |e| matches!(e, GameEvent::GenomeChanged { .. }),
+    },
+    Achievement {
+        id: "antibiotic_survivor",
+        name: "Resistant",
+        description: "Survive an antibiotic treatment of the host.",
+        unlocked_by: |e| matches!(e, GameEvent::TreatmentSurvived(_)),
+    },
+    Achievement {
+        id: "long_genome",
+        name: "Genetic Hoarder",
+        description: "Grow a genome of 20 genes or more.",
+        unlocked_by: |e| matches!(e, GameEvent::GenomeChanged { genes } if *genes >= 20),
+    },
+    Achievement {
+        id: "engulf_predator",
+        name: "Apex Microbe",
+        description: "Engulf an organism that hunts others.",
+        unlocked_by: |e| matches!(e, GameEvent::PredatorKilled { .. }),
+    },
+];
+
+/// Ids of the achievements the player has unlocked so far.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Achievements {
+    unlocked: Vec<String>,
+}
+
+impl Achievements {
+    pub fn new() -> Self {
+        Achievements::default()
+    }
+
+    /// Read the unlocked achievements from storage. Nothing is unlocked if there is nothing stored
+    /// yet or the stored data can't be read.
+    pub fn load() -> Self {
+        match platform::read_data(STORAGE_KEY) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| {
+                error!("cannot parse achievements: {}", e);
+                Achievements::new()
+            }),
+            Err(_) => Achievements::new(),
+        }
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|e| e.into())
+            .and_then(|data| platform::write_data(STORAGE_KEY, &data));
+        if let Err(e) = result {
+            error!("cannot save achievements: {}", e);
+        }
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|u| u == id)
+    }
+
+    /// Unlock every achievement that the event fulfills, without storing them. Returns the newly
+    /// unlocked achievements.
+    pub fn unlock(&mut self, event: &GameEvent) -> Vec<&'static Achievement> {
+        let mut unlocked = Vec::new();
+        for achievement in ACHIEVEMENTS.iter() {
+            if !self.is_unlocked(achievement.id) && (achievement.unlocked_by)(event) {
+                self.unlocked.push(achievement.id.to_string());
+                unlocked.push(achievement);
+            }
+        }
+        unlocked
+    }
+
+    /// Check the events for achievements and store any newly unlocked ones.
+    pub fn process(&mut self, events: &[GameEvent]) -> Vec<&'static Achievement> {
+        let unlocked: Vec<&'static Achievement> =
+            events.iter().flat_map(|e| self.unlock(e)).collect();
+        if !unlocked.is_empty() {
+            self.save();
+        }
+        unlocked
+    }
+}
@@ -0,0 +1,90 @@
+//! Bookmarks let the player keep track of notable organisms, e.g. rivals in a long run, even when
+//! they are out of sight or have died.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::game_objects::GameObjects;
+use crate::core::position::Position;
+use crate::entity::object::Object;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bookmark {
+    /// Matches the `bookmark` id of the organism.
+    pub id: u32,
+    pub name: String,
+    /// Where the organism was last seen.
+    pub last_pos: Position,
+    pub alive: bool,
+}
+
+impl Bookmark {
+    pub fn to_text(&self) -> String {
+        if self.alive {
+            format!(
+                "{:<20} at {}, {}",
+                self.name, self.last_pos.x, self.last_pos.y
+            )
+        } else {
+            format!(
+                "{:<20} died, last seen at {}, {}",
+                self.name, self.last_pos.x, self.last_pos.y
+            )
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Bookmarks {
+    entries: Vec<Bookmark>,
+    next_id: u32,
+}
+
+impl Bookmarks {
+    pub fn new() -> Self {
+        Bookmarks::default()
+    }
+
+    pub fn entries(&self) -> &[Bookmark] {
+        &self.entries
+    }
+
+    /// Bookmark the organism, or remove its bookmark if it already has one. Returns whether the
+    /// organism is bookmarked afterwards.
+    pub fn toggle(&mut self, organism: &mut Object) -> bool {
+        if let Some(id) = organism.bookmark.take() {
+            self.entries.retain(|b| b.id != id);
+            false
+        } else {
+            organism.bookmark = Some(self.next_id);
+            self.entries.push(Bookmark {
+                id: self.next_id,
+                name: organism.visual.name.clone(),
+                last_pos: organism.pos,
+                alive: organism.alive,
+            });
+            self.next_id += 1;
+            true
+        }
+    }
+
+    /// Refresh names and positions of the bookmarked organisms. Positions are only updated while
+    /// the organism is in sight and organisms that can't be found anymore are considered dead.
+    pub fn update(&mut self, objects: &GameObjects) {
+        for bookmark in self.entries.iter_mut().filter(|b| b.alive) {
+            match objects
+                .get_vector()
+                .iter()
+                .flatten()
+                .find(|o| o.bookmark == Some(bookmark.id))
+            {
+                Some(organism) if organism.alive => {
+                    bookmark.name = organism.visual.name.clone();
+                    if organism.physics.is_visible {
+                        bookmark.last_pos = organism.pos;
+                    }
+                }
+                _ => bookmark.alive = false,
+            }
+        }
+    }
+}
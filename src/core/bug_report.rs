@@ -0,0 +1,127 @@
+//! Bug reports gather everything needed to reproduce a problem in a single JSON file in the data
+//! directory: the seed and run code, the most recent player inputs, the tail of the message log and
+//! a full save of the current game. It's plain JSON rather than an archive, so that it can be kept
+//! in the local storage of the browser as well and attached to an issue as it is.
+
+use std::collections::VecDeque;
+use std::error::Error;
+
+use serde::Serialize;
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::run_code::RunCode;
+use crate::ui::game_input::PlayerInput;
+use crate::util::platform;
+
+/// Directory within the data directory that all bug reports are stored in.
+pub const BUG_REPORT_DIR: &str = "bug_reports";
+/// Number of inputs the recorder keeps at most, older ones are dropped.
+const MAX_RECORDED_INPUTS: usize = 200;
+/// Number of log messages included in a bug report.
+const LOG_TAIL: usize = 50;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RecordedInput {
+    pub turn: u128,
+    pub input: String,
+}
+
+/// Keeps the last inputs of the player, as long as recording is switched on.
+#[derive(Debug)]
+pub struct InputRecorder {
+    pub is_recording: bool,
+    inputs: VecDeque<RecordedInput>,
+}
+
+impl InputRecorder {
+    pub fn new() -> Self {
+        InputRecorder {
+            is_recording: true,
+            inputs: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, turn: u128, input: &PlayerInput) {
+        if !self.is_recording || matches!(input, PlayerInput::Undefined) {
+            return;
+        }
+        if self.inputs.len() >= MAX_RECORDED_INPUTS {
+            self.inputs.pop_front();
+        }
+        self.inputs.push_back(RecordedInput {
+            turn,
+            input: format!("{:?}", input),
+        });
+    }
+
+    /// Switch recording on or off. Returns whether inputs are recorded from now on.
+    pub fn toggle(&mut self) -> bool {
+        self.is_recording = !self.is_recording;
+        self.is_recording
+    }
+
+    pub fn clear(&mut self) {
+        self.inputs.clear();
+    }
+
+    pub fn inputs(&self) -> impl Iterator<Item = &RecordedInput> {
+        self.inputs.iter()
+    }
+}
+
+impl Default for InputRecorder {
+    fn default() -> Self {
+        InputRecorder::new()
+    }
+}
+
+#[derive(Serialize)]
+struct BugReport<'a> {
+    version: &'a str,
+    seed: u64,
+    run_code: String,
+    daily: Option<&'a str>,
+    turn: u128,
+    inputs: Vec<&'a RecordedInput>,
+    log_tail: Vec<String>,
+    save: (&'a GameState, &'a GameObjects),
+}
+
+/// Name of a new bug report file for the current turn. Further reports of the same turn are
+/// numbered, so that none of them overwrites an earlier one.
+pub fn bug_report_name(state: &GameState) -> String {
+    let mut name = format!("{}/turn_{}.json", BUG_REPORT_DIR, state.turn);
+    let mut count = 1;
+    while platform::read_data(&name).is_ok() {
+        count += 1;
+        name = format!("{}/turn_{}_{}.json", BUG_REPORT_DIR, state.turn, count);
+    }
+    name
+}
+
+/// Write a bug report of the current game to the data directory. Returns the name of the file.
+pub fn create_bug_report(
+    state: &GameState,
+    objects: &GameObjects,
+    recorder: &InputRecorder,
+) -> Result<String, Box<dyn Error>> {
+    let messages = &state.log.messages;
+    let report = BugReport {
+        version: env!("CARGO_PKG_VERSION"),
+        seed: state.seed,
+        run_code: RunCode::new(state.seed).encode(),
+        daily: state.daily.as_deref(),
+        turn: state.turn,
+        inputs: recorder.inputs().collect(),
+        log_tail: messages
+            .iter()
+            .skip(messages.len().saturating_sub(LOG_TAIL))
+            .map(|m| format!("[{}] {}", m.turn, m.to_text()))
+            .collect(),
+        save: (state, objects),
+    };
+    let name = bug_report_name(state);
+    platform::write_data(&name, &serde_json::to_string_pretty(&report)?)?;
+    Ok(name)
+}
@@ -0,0 +1,74 @@
+//! Objects take their turns one after another, so when two organisms move into the same tile in
+//! one turn the first to act would always get it. To keep this fair, every tile an organism moves
+//! into is claimed for the rest of the turn and may be contested by the others that act later:
+//!
+//! - On even turns the claim holds, the organism earlier in the turn order keeps the tile.
+//! - On odd turns the organism later in the turn order wins and pushes the claimant back to where
+//!   it came from, if that is still free.
+//!
+//! Between any two organisms each wins every other contest. Moves of the player are final and the
+//! player never pushes others back, so that the controls stay predictable. Claims are saved along
+//! with the game, so that a game loaded mid-turn carries on exactly as before.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::position::Position;
+
+/// A tile that an organism moved into during the current turn.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+struct Claim {
+    /// The claimed tile.
+    pos: Position,
+    /// Where the claimant came from.
+    from: Position,
+    /// Index of the claimant in the turn order.
+    idx: usize,
+}
+
+/// Tiles claimed by moves in the current turn.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct TileClaims {
+    turn: u128,
+    claims: Vec<Claim>,
+}
+
+/// Check whether the organism at index `challenger` wins a contested tile from the one at index
+/// `claimant` in the given turn.
+pub fn wins_contest(turn: u128, challenger: usize, claimant: usize) -> bool {
+    if turn.is_multiple_of(2) {
+        challenger < claimant
+    } else {
+        challenger > claimant
+    }
+}
+
+impl TileClaims {
+    /// Forget the claims of earlier turns.
+    fn refresh(&mut self, turn: u128) {
+        if self.turn != turn {
+            self.turn = turn;
+            self.claims.clear();
+        }
+    }
+
+    /// Claim the tile `pos` for the organism at index `idx`, which moved in from `from`.
+    pub fn claim(&mut self, turn: u128, idx: usize, from: Position, pos: Position) {
+        self.refresh(turn);
+        self.claims.retain(|c| c.pos != pos);
+        self.claims.push(Claim { pos, from, idx });
+    }
+
+    /// Contest the tile `pos` for the organism at index `idx`. If it wins, the index of the
+    /// claimant and the position it came from are returned. Its claim passes on once the winner
+    /// claims the tile.
+    pub fn contest(&self, turn: u128, idx: usize, pos: Position) -> Option<(usize, Position)> {
+        if self.turn != turn {
+            return None;
+        }
+        self.claims
+            .iter()
+            .find(|c| c.pos == pos && c.idx != idx && wins_contest(turn, idx, c.idx))
+            .map(|c| (c.idx, c.from))
+    }
+}
@@ -0,0 +1,87 @@
+//! The biological clock of the host. Each turn takes a few minutes of host time, so that the turns
+//! add up to days and hours. The rhythms of the host follow the clock: nutrients flood the tissue
+//! after its meals and the immune system patrols while it sleeps.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Minutes of host time that pass with each turn.
+pub const MINUTES_PER_TURN: u32 = 2;
+const MINUTES_PER_HOUR: u32 = 60;
+const HOURS_PER_DAY: u32 = 24;
+const MINUTES_PER_DAY: u32 = HOURS_PER_DAY * MINUTES_PER_HOUR;
+/// Time of the first turn of a run, given in [min] after midnight of the first day.
+const START_TIME: u32 = 8 * MINUTES_PER_HOUR;
+
+/// Hours at which the host eats.
+pub const MEAL_HOURS: [u32; 3] = [7, 12, 19];
+/// Time in [min] after a meal during which the host has more nutrients to spare.
+const MEAL_DIGESTION: u32 = 90;
+/// Percentage of the usual nutrients available while the host digests a meal.
+const MEAL_NUTRIENTS: i32 = 150;
+/// Hour of the night at which the host's immune system patrols the tissue.
+pub const IMMUNE_PATROL_HOUR: u32 = 3;
+/// Hours between two patrols of the immune system under the usual rules, i.e. once a night.
+pub const IMMUNE_PATROL_INTERVAL: u32 = HOURS_PER_DAY;
+/// Number of antibodies released by the nightly patrol.
+pub const IMMUNE_PATROL_SIZE: usize = 5;
+
+/// A point in host time. Days are counted from 1, the first day of the run.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClockTime {
+    pub day: u32,
+    pub hour: u32,
+    #[serde(default)]
+    pub minute: u32,
+}
+
+impl ClockTime {
+    /// Host time at which the given turn takes place.
+    pub fn from_turn(turn: u128) -> Self {
+        let minutes = START_TIME as u128 + turn * MINUTES_PER_TURN as u128;
+        let day = (minutes / MINUTES_PER_DAY as u128) as u32 + 1;
+        let minute_of_day = (minutes % MINUTES_PER_DAY as u128) as u32;
+        ClockTime {
+            day,
+            hour: minute_of_day / MINUTES_PER_HOUR,
+            minute: minute_of_day % MINUTES_PER_HOUR,
+        }
+    }
+
+    fn minute_of_day(&self) -> u32 {
+        self.hour * MINUTES_PER_HOUR + self.minute
+    }
+
+    /// Check whether a full hour struck during the turn that takes place at this time.
+    pub fn is_full_hour(&self) -> bool {
+        self.minute < MINUTES_PER_TURN
+    }
+
+    /// Check whether the immune system patrols at this full hour, given the hours between two
+    /// patrols. The first patrol sets out in the first night.
+    pub fn is_patrol_hour(&self, interval: u32) -> bool {
+        let hours = (self.day - 1) * HOURS_PER_DAY + self.hour;
+        hours >= IMMUNE_PATROL_HOUR && (hours - IMMUNE_PATROL_HOUR).is_multiple_of(interval.max(1))
+    }
+
+    /// Percentage of the usual nutrients that the host's meals provide at this time.
+    pub fn nutrient_cycle(&self) -> i32 {
+        let now = self.minute_of_day();
+        let digesting = MEAL_HOURS.iter().any(|hour| {
+            let meal = hour * MINUTES_PER_HOUR;
+            (meal..meal + MEAL_DIGESTION).contains(&now)
+        });
+        if digesting {
+            MEAL_NUTRIENTS
+        } else {
+            100
+        }
+    }
+}
+
+impl fmt::Display for ClockTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} {:02}:{:02}", self.day, self.hour, self.minute)
+    }
+}
@@ -0,0 +1,37 @@
+//! Daily runs give every player the same world for a day. The world seed is derived from the
+//! current date, so that all runs started on the same day are identical.
+
+use crate::util::{fnv_hash, platform};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Today's date as `YYYY-MM-DD`, in UTC so that players in all time zones share their runs.
+pub fn today() -> String {
+    let (year, month, day) = date_from_days((platform::unix_time_secs() / SECONDS_PER_DAY) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Convert the number of days since 1970-01-01 into a calendar date.
+pub fn date_from_days(days: i64) -> (i64, u32, u32) {
+    // shift the epoch to 0000-03-01, so that leap days come last in each 400 year era
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// World seed of the daily run on the given date.
+pub fn daily_seed(date: &str) -> u64 {
+    fnv_hash(format!("innit-daily-{}", date).as_bytes())
+}
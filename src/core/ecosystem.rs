@@ -0,0 +1,310 @@
+//! Over long runs the population of a world may die out or overrun the tissue. The ecosystem
+//! regulator counts the organisms at regular intervals and gently adjusts how many new ones spawn
+//! and how many nutrients the tissue yields, to keep the population within the bounds given in
+//! the raws. Outside of any trouble both rates drift back to the usual.
+//!
+//! Each census also keeps the statistics of the lineages, i.e. the living organisms per species.
+//! Once a species dies out, a new strain of it migrates in from the edges of the world, so that
+//! long runs don't settle into a handful of survivors.
+
+use std::collections::BTreeMap;
+
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::innit_env;
+use crate::core::position::Position;
+use crate::core::world::object_from_template;
+use crate::core::world::world_gen_organic::is_valid_location;
+use crate::entity::object::Object;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::raws::ecosystem::EcosystemBounds;
+use crate::raws::object_template::ObjectTemplate;
+use crate::raws::spawn::{from_dungeon_level, Spawn};
+use crate::raws::{load_object_templates, load_spawns};
+use crate::util::game_rng::RngStream;
+
+/// Number of migrants of a new strain that re-colonize the world after their species died out.
+const MIGRANTS: usize = 2;
+/// Point mutations that set each migrant apart from its extinct ancestors.
+const MIGRANT_MUTATIONS: usize = 2;
+/// Depth of the band of open tissue along the edges of the world that migrants come in from.
+const EDGE_BAND: i32 = 3;
+
+/// State of the ecosystem regulator.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Ecosystem {
+    /// Factor on the number of organisms spawned per census.
+    pub spawn_rate: f64,
+    /// Factor on the nutrients that organisms can metabolise.
+    pub nutrient_rate: f64,
+    pub bounds: EcosystemBounds,
+    /// Living organisms per species at the last census.
+    pub lineages: BTreeMap<String, usize>,
+}
+
+impl Default for Ecosystem {
+    fn default() -> Self {
+        Ecosystem::new(EcosystemBounds::default())
+    }
+}
+
+impl Ecosystem {
+    pub fn new(bounds: EcosystemBounds) -> Self {
+        Ecosystem {
+            spawn_rate: 1.0,
+            nutrient_rate: 1.0,
+            bounds,
+            lineages: BTreeMap::new(),
+        }
+    }
+
+    /// Check whether the population is due to be counted in the given turn.
+    pub fn is_census_due(&self, turn: u128) -> bool {
+        self.bounds.census_interval > 0 && turn.is_multiple_of(self.bounds.census_interval as u128)
+    }
+
+    /// Adjust the rates to the counted population. Returns the number of organisms to spawn,
+    /// which never takes the population beyond its upper bound.
+    pub fn census(&mut self, population: usize) -> usize {
+        let bounds = &self.bounds;
+        self.spawn_rate = adjust(
+            self.spawn_rate,
+            population,
+            bounds,
+            (bounds.min_spawn_rate, bounds.max_spawn_rate),
+        );
+        self.nutrient_rate = adjust(
+            self.nutrient_rate,
+            population,
+            bounds,
+            (bounds.min_nutrient_rate, bounds.max_nutrient_rate),
+        );
+        let spawns = (bounds.spawns_per_census * self.spawn_rate)
+            .round()
+            .max(0.0) as usize;
+        spawns.min(bounds.max_population.saturating_sub(population))
+    }
+
+    /// Take note of the living organisms per species. Returns the species that died out since the
+    /// last census.
+    pub fn record_lineages(&mut self, lineages: BTreeMap<String, usize>) -> Vec<String> {
+        let extinct = self
+            .lineages
+            .iter()
+            .filter(|(species, count)| **count > 0 && !lineages.contains_key(*species))
+            .map(|(species, _)| species.clone())
+            .collect();
+        self.lineages = lineages;
+        extinct
+    }
+
+    /// Scale the nutrients given in percent of the usual supply.
+    pub fn nutrients(&self, nutrients: i32) -> i32 {
+        (nutrients as f64 * self.nutrient_rate).round() as i32
+    }
+}
+
+/// Spawn tables and object templates that new organisms are drawn from, loaded once per game
+/// instead of at every census.
+#[derive(Clone, Default)]
+pub struct SpawnRaws {
+    pub spawns: Vec<Spawn>,
+    pub templates: Vec<ObjectTemplate>,
+}
+
+impl SpawnRaws {
+    /// Load the spawn tables and object templates. Problems with the entries are appended to
+    /// `errors`.
+    pub fn load(errors: &mut Vec<String>) -> Self {
+        SpawnRaws {
+            spawns: load_spawns(errors),
+            templates: load_object_templates(errors),
+        }
+    }
+}
+
+/// Raise a rate by one step while the population is too small, lower it while it is too large
+/// and otherwise let it drift back to 1, always keeping it within `(min, max)`.
+fn adjust(rate: f64, population: usize, bounds: &EcosystemBounds, (min, max): (f64, f64)) -> f64 {
+    let step = bounds.step.abs();
+    let adjusted = if population < bounds.min_population {
+        rate + step
+    } else if population > bounds.max_population {
+        rate - step
+    } else if (rate - 1.0).abs() <= step {
+        1.0
+    } else {
+        rate - step * (rate - 1.0).signum()
+    };
+    adjusted.min(max).max(min)
+}
+
+/// Living organisms in the world, apart from the player.
+fn organisms(objects: &GameObjects) -> impl Iterator<Item = &Object> {
+    objects.get_non_tiles().iter().flatten().filter(|o| {
+        o.alive && o.tile.is_none() && o.item.is_none() && o.control.is_some() && !o.is_player()
+    })
+}
+
+/// Number of living organisms in the world, apart from the player.
+pub fn population(objects: &GameObjects) -> usize {
+    organisms(objects).count()
+}
+
+/// Number of living organisms per species, apart from the player.
+pub fn lineages(objects: &GameObjects) -> BTreeMap<String, usize> {
+    let mut lineages = BTreeMap::new();
+    for organism in organisms(objects) {
+        *lineages.entry(organism.visual.name.clone()).or_insert(0) += 1;
+    }
+    lineages
+}
+
+/// Spawn up to `count` organisms from the spawn tables of the current level, out of the player's
+/// sight. Returns the number of organisms spawned.
+pub fn spawn_organisms(
+    state: &mut GameState,
+    objects: &mut GameObjects,
+    spawns: &[Spawn],
+    templates: &[ObjectTemplate],
+    count: usize,
+) -> usize {
+    let chances: Vec<u32> = spawns
+        .iter()
+        .map(|s| {
+            let weight = from_dungeon_level(&s.spawn_transitions, state.dungeon_level);
+            innit_env().difficulty.spawn_weight(&s.npc, weight)
+        })
+        .collect();
+    let distribution = match WeightedIndex::new(&chances) {
+        Ok(distribution) => distribution,
+        Err(_) => return 0,
+    };
+
+    let mut spawned = 0;
+    // give up eventually on very crowded worlds
+    for _ in 0..count * 10 {
+        if spawned == count {
+            break;
+        }
+        let rng = state.rng_streams.get(RngStream::Host);
+        let spawn = &spawns[distribution.sample(rng)];
+        let pos = Position::new(
+            rng.gen_range(1..WORLD_WIDTH - 1),
+            rng.gen_range(1..WORLD_HEIGHT - 1),
+        );
+        let is_in_sight = objects
+            .get_tile_at(pos.x as usize, pos.y as usize)
+            .as_ref()
+            .is_some_and(|t| t.physics.is_visible);
+        if is_in_sight
+            || objects.is_pos_blocked(&pos)
+            || objects.is_pos_occupied(&pos)
+            || !is_valid_location(objects, &pos, spawn.location)
+        {
+            continue;
+        }
+        match templates.iter().find(|t| t.npc == spawn.npc) {
+            Some(template) => match object_from_template(state, template, pos.x, pos.y) {
+                Ok(organism) => {
+                    objects.push(organism);
+                    spawned += 1;
+                }
+                Err(msg) => error!("{}", msg),
+            },
+            None => error!("No object template found for NPC type '{}'", spawn.npc),
+        }
+    }
+    spawned
+}
+
+/// Let a new strain of an extinct species migrate in from the edges of the world, as long as the
+/// species still spawns on the current level. Returns the number of migrants.
+pub fn recolonize(
+    state: &mut GameState,
+    objects: &mut GameObjects,
+    spawns: &[Spawn],
+    templates: &[ObjectTemplate],
+    species: &str,
+) -> usize {
+    let spawns_here = spawns.iter().any(|s| {
+        s.npc == species && from_dungeon_level(&s.spawn_transitions, state.dungeon_level) > 0
+    });
+    let template = match templates.iter().find(|t| t.npc == species) {
+        Some(template) if spawns_here => template,
+        _ => return 0,
+    };
+
+    let mut arrivals = edge_positions(objects);
+    let mut migrants = 0;
+    while migrants < MIGRANTS && !arrivals.is_empty() {
+        let idx = state.rng_for(RngStream::Host).gen_range(0..arrivals.len());
+        let pos = arrivals.swap_remove(idx);
+        if objects.is_pos_blocked(&pos) || objects.is_pos_occupied(&pos) {
+            continue;
+        }
+        match object_from_template(state, template, pos.x, pos.y) {
+            Ok(mut migrant) => {
+                mutate_strain(state, &mut migrant);
+                objects.push(migrant);
+                migrants += 1;
+            }
+            Err(msg) => {
+                error!("{}", msg);
+                break;
+            }
+        }
+    }
+    migrants
+}
+
+/// Open tiles out of the player's sight that lie closest to the edges of the world.
+fn edge_positions(objects: &GameObjects) -> Vec<Position> {
+    let open: Vec<(Position, i32)> = objects
+        .get_tiles()
+        .iter()
+        .flatten()
+        .filter(|t| !t.physics.is_blocking && !t.physics.is_visible)
+        .map(|t| {
+            let depth = t
+                .pos
+                .x
+                .min(t.pos.y)
+                .min(WORLD_WIDTH - 1 - t.pos.x)
+                .min(WORLD_HEIGHT - 1 - t.pos.y);
+            (t.pos, depth)
+        })
+        .collect();
+    let nearest = match open.iter().map(|(_, depth)| *depth).min() {
+        Some(nearest) => nearest,
+        None => return Vec::new(),
+    };
+    open.into_iter()
+        .filter(|(_, depth)| *depth < nearest + EDGE_BAND)
+        .map(|(pos, _)| pos)
+        .collect()
+}
+
+/// Give a migrant a few point mutations, which set its strain apart from its ancestors.
+fn mutate_strain(state: &mut GameState, migrant: &mut Object) {
+    let mut genes = migrant.dna.simplified.clone();
+    if genes.is_empty() {
+        return;
+    }
+    for _ in 0..MIGRANT_MUTATIONS {
+        let rng = state.rng_streams.get(RngStream::Mutation);
+        let idx = rng.gen_range(0..genes.len());
+        let mut mutation = state
+            .gene_library
+            .mutate_gene(rng, migrant.dna.dna_type, &genes[idx]);
+        mutation.position = genes[idx].position;
+        genes[idx] = mutation;
+    }
+    let raw_dna = state.gene_library.g_traits_to_dna(&genes);
+    migrant.express_dna(state, &raw_dna);
+}
@@ -0,0 +1,283 @@
+//! The encyclopedia, or "Innitpedia", explains the traits, species and mechanics of the game.
+//! Entries start out locked and unlock as the player encounters them: traits once they are part of
+//! the player's genome, species once they come into sight and mechanics once they first affect the
+//! run. The discoveries are kept in the save game.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::events::GameEvent;
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::entity::genetics::{GeneLibrary, TraitFamily};
+use crate::entity::object::Object;
+use crate::raws::object_template::ObjectTemplate;
+use crate::raws::TISSUE_TEMPLATE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Topic {
+    Trait,
+    Species,
+    Mechanic,
+}
+
+impl Topic {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Topic::Trait => "Trait",
+            Topic::Species => "Species",
+            Topic::Mechanic => "Mechanic",
+        }
+    }
+}
+
+/// A rule of the game world that the encyclopedia explains.
+pub struct Mechanic {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub text: &'static [&'static str],
+}
+
+pub const MECHANICS: [Mechanic; 10] = [
+    Mechanic {
+        id: "meals",
+        name: "Meals",
+        text: &[
+            "The host eats three times a day.",
+            "Afterwards nutrients flood the tissue",
+            "and energy is easier to come by.",
+        ],
+    },
+    Mechanic {
+        id: "immune patrol",
+        name: "Immune Patrols",
+        text: &[
+            "While the host sleeps, its immune system",
+            "patrols the tissue and releases antibodies",
+            "that hunt down foreign organisms.",
+        ],
+    },
+    Mechanic {
+        id: "antibiotics",
+        name: "Antibiotics",
+        text: &[
+            "Now and then the host is treated with",
+            "antibiotics. They harm every organism",
+            "without a gene to resist them.",
+        ],
+    },
+    Mechanic {
+        id: "fever",
+        name: "Fever",
+        text: &[
+            "While the host runs a fever, every",
+            "action costs more energy.",
+        ],
+    },
+    Mechanic {
+        id: "inflammation",
+        name: "Inflammation",
+        text: &[
+            "An inflamed host releases antibodies",
+            "at regular intervals.",
+        ],
+    },
+    Mechanic {
+        id: "medication",
+        name: "Medication",
+        text: &[
+            "While the host takes medication,",
+            "pathogens cannot multiply.",
+        ],
+    },
+    Mechanic {
+        id: "collapse",
+        name: "Host Collapse",
+        text: &[
+            "A host whose health runs out collapses",
+            "and floods its body with antibodies",
+            "until it recovers.",
+        ],
+    },
+    Mechanic {
+        id: "bloodstream",
+        name: "Bloodstream",
+        text: &[
+            "The blood flow carries along every",
+            "organism that isn't anchored.",
+        ],
+    },
+    Mechanic {
+        id: "toxins",
+        name: "Toxins",
+        text: &[
+            "Some organisms secrete toxins that eat",
+            "away at anyone without antitoxins.",
+        ],
+    },
+    Mechanic {
+        id: "barriers",
+        name: "Barriers",
+        text: &[
+            "Sphincters and membrane pores seal off",
+            "parts of the tissue. Organisms with pili",
+            "can pry them open for a few turns.",
+            "Valves only open for organisms with",
+            "the gene they are keyed to.",
+        ],
+    },
+];
+
+/// Look up a mechanic by its id.
+pub fn mechanic(id: &str) -> Option<&'static Mechanic> {
+    MECHANICS.iter().find(|m| m.id == id)
+}
+
+/// Encyclopedia entries the player has unlocked in this run.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct Discoveries {
+    traits: BTreeSet<String>,
+    species: BTreeSet<String>,
+    mechanics: BTreeSet<String>,
+}
+
+impl Discoveries {
+    fn topic(&self, topic: Topic) -> &BTreeSet<String> {
+        match topic {
+            Topic::Trait => &self.traits,
+            Topic::Species => &self.species,
+            Topic::Mechanic => &self.mechanics,
+        }
+    }
+
+    /// Unlock an entry. Returns `true` if it hadn't been unlocked before.
+    pub fn discover(&mut self, topic: Topic, id: &str) -> bool {
+        let entries = match topic {
+            Topic::Trait => &mut self.traits,
+            Topic::Species => &mut self.species,
+            Topic::Mechanic => &mut self.mechanics,
+        };
+        !entries.contains(id) && entries.insert(id.to_string())
+    }
+
+    pub fn is_discovered(&self, topic: Topic, id: &str) -> bool {
+        self.topic(topic).contains(id)
+    }
+}
+
+/// A single page of the encyclopedia.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub topic: Topic,
+    pub name: String,
+    pub text: Vec<String>,
+    pub is_unlocked: bool,
+}
+
+/// All pages of the encyclopedia, ordered by topic. Traits come from the gene library, species
+/// from the object templates and mechanics from the list above.
+pub fn entries(
+    discoveries: &Discoveries,
+    library: &GeneLibrary,
+    templates: &[ObjectTemplate],
+) -> Vec<Entry> {
+    let traits = library
+        .traits()
+        .iter()
+        .filter(|t| !matches!(t.trait_family, TraitFamily::Junk(_)))
+        .map(|t| {
+            let mut text = vec![format!("Family: {}", t.trait_family)];
+            if let Some(effect) = t.attribute.describe() {
+                text.push(format!("Effect: {}", effect));
+            }
+            if let Some(action) = &t.action {
+                text.push(format!("Enables: {}", action.to_text()));
+            }
+            Entry {
+                topic: Topic::Trait,
+                name: t.trait_name.clone(),
+                text,
+                is_unlocked: discoveries.is_discovered(Topic::Trait, &t.trait_name),
+            }
+        });
+
+    let species = templates
+        .iter()
+        .filter(|t| t.npc != TISSUE_TEMPLATE)
+        .map(|t| {
+            let mut text = vec![format!("Faction: {}", t.faction.name())];
+            match &t.item {
+                Some(item) => text.push(format!("Can be picked up as {}.", item.name)),
+                None if t.controller.is_some() => text.push("Moves on its own.".to_string()),
+                None => text.push("Drifts along with the tissue.".to_string()),
+            }
+            if t.drops.max_fragments > 0 {
+                text.push(format!(
+                    "Leaves up to {} gene fragments behind.",
+                    t.drops.max_fragments
+                ));
+            }
+            Entry {
+                topic: Topic::Species,
+                name: t.npc.clone(),
+                text,
+                is_unlocked: discoveries.is_discovered(Topic::Species, &t.npc),
+            }
+        });
+
+    let mechanics = MECHANICS.iter().map(|m| Entry {
+        topic: Topic::Mechanic,
+        name: m.name.to_string(),
+        text: m.text.iter().map(|l| l.to_string()).collect(),
+        is_unlocked: discoveries.is_discovered(Topic::Mechanic, m.id),
+    });
+
+    traits.chain(species).chain(mechanics).collect()
+}
+
+/// Most genes listed when a new species is encountered.
+const NOTABLE_GENES: usize = 5;
+
+/// Unlock the traits of the player's genome and the species of all organisms in sight. The first
+/// organism of each species is announced with a `GameEvent::SpeciesEncountered`.
+pub fn discover_in_sight(state: &mut GameState, objects: &GameObjects) {
+    let player = objects
+        .get_vector()
+        .iter()
+        .flatten()
+        .find(|o| o.is_player());
+    for object in objects.get_vector().iter().flatten() {
+        if object.is_player() {
+            for gene in functional_genes(object) {
+                state.discover(Topic::Trait, gene);
+            }
+        } else if object.physics.is_visible
+            && object.tile.is_none()
+            && state.discover(Topic::Species, &object.visual.name)
+            && object.item.is_none()
+        {
+            state.events.publish(GameEvent::SpeciesEncountered {
+                name: object.visual.name.clone(),
+                threat: player.map_or(0.0, |p| p.assess_threat(object)),
+                genes: functional_genes(object)
+                    .take(NOTABLE_GENES)
+                    .map(|g| g.to_string())
+                    .collect(),
+            });
+        }
+    }
+}
+
+/// Names of the distinct non-junk genes of the object, in the order of its genome.
+fn functional_genes(object: &Object) -> impl Iterator<Item = &str> {
+    let mut seen = BTreeSet::new();
+    object
+        .dna
+        .simplified
+        .iter()
+        .filter(|g| !matches!(g.trait_family, TraitFamily::Junk(_)))
+        .map(|g| g.trait_name.as_str())
+        .filter(move |name| seen.insert(*name))
+}
@@ -0,0 +1,54 @@
+//! Notable things that happen during the game are published as events, so that systems outside of
+//! the turn processing, e.g. achievements, can react to them without being wired into every
+//! action.
+
+use crate::core::clock::ClockTime;
+use crate::core::host::AntibioticClass;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// The player's genome changed and now holds the given number of genes.
+    GenomeChanged { genes: usize },
+    /// The player lived through an antibiotic treatment of the host.
+    TreatmentSurvived(AntibioticClass),
+    /// The player killed an organism that hunts others.
+    PredatorKilled { name: String },
+    /// The player swapped places with the organism of the given name.
+    PlacesSwapped { name: String },
+    /// The player was carried along by the blood flow.
+    BloodstreamReached,
+    /// The player lived through the flood of antibodies of a collapsing host.
+    ImmuneWaveSurvived,
+    /// The host's clock struck a full hour.
+    HourStruck(ClockTime),
+    /// An organism of a species the player hasn't seen before in this run came into sight.
+    SpeciesEncountered {
+        name: String,
+        /// Threat the organism poses to the player, see `Object::assess_threat`.
+        threat: f32,
+        /// Names of the distinct functional genes of the organism.
+        genes: Vec<String>,
+    },
+}
+
+/// Events published since they were last collected.
+#[derive(Debug, Default)]
+pub struct EventBus {
+    events: Vec<GameEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus::default()
+    }
+
+    pub fn publish(&mut self, event: GameEvent) {
+        trace!("event: {:?}", event);
+        self.events.push(event);
+    }
+
+    /// Take all pending events, oldest first.
+    pub fn drain(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
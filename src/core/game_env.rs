@@ -1,11 +1,183 @@
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
-#[derive(Clone, Copy, Serialize, Deserialize, Default)]
+use crate::entity::genetics::MAX_DNA_LEN;
+use crate::raws::difficulty::Difficulty;
+
+/// How the actions of NPCs between two player turns are shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum GameSpeed {
+    /// Render the world only once all NPCs have acted.
+    Instant,
+    /// Render each visible NPC action as soon as possible.
+    #[default]
+    Fast,
+    /// Render each visible NPC action and hold it for a moment.
+    Animated,
+}
+
+impl GameSpeed {
+    pub fn name(&self) -> &str {
+        match self {
+            GameSpeed::Instant => "instant",
+            GameSpeed::Fast => "fast",
+            GameSpeed::Animated => "animated",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "instant" => Some(GameSpeed::Instant),
+            "fast" => Some(GameSpeed::Fast),
+            "animated" => Some(GameSpeed::Animated),
+            _ => None,
+        }
+    }
+
+    /// The next speed setting, wrapping around after the slowest one.
+    pub fn next(&self) -> Self {
+        match self {
+            GameSpeed::Instant => GameSpeed::Fast,
+            GameSpeed::Fast => GameSpeed::Animated,
+            GameSpeed::Animated => GameSpeed::Instant,
+        }
+    }
+}
+
+/// How much the message log tells about each hit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum CombatDetail {
+    /// Only who hit whom for how much damage.
+    #[default]
+    Summary,
+    /// How the damage came about and how much hp the target has left.
+    Full,
+}
+
+impl CombatDetail {
+    pub fn name(&self) -> &str {
+        match self {
+            CombatDetail::Summary => "summary",
+            CombatDetail::Full => "full",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            CombatDetail::Summary => CombatDetail::Full,
+            CombatDetail::Full => CombatDetail::Summary,
+        }
+    }
+}
+
+/// Quantity that the debug heat map colors the tissue by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum HeatMap {
+    #[default]
+    Off,
+    /// Strength of the pheromone trails.
+    Scent,
+    /// Concentration of toxins.
+    Toxins,
+    /// Walking distance from the player, as a Dijkstra map.
+    Distance,
+}
+
+impl HeatMap {
+    pub fn name(&self) -> &str {
+        match self {
+            HeatMap::Off => "off",
+            HeatMap::Scent => "scent",
+            HeatMap::Toxins => "toxins",
+            HeatMap::Distance => "distance",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            HeatMap::Off => HeatMap::Scent,
+            HeatMap::Scent => HeatMap::Toxins,
+            HeatMap::Toxins => HeatMap::Distance,
+            HeatMap::Distance => HeatMap::Off,
+        }
+    }
+}
+
+/// What happens to a run once the player dies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum GameMode {
+    /// The run is over and its save game is deleted.
+    #[default]
+    Permadeath,
+    /// The player respawns at the last checkpoint, at the cost of some score.
+    Checkpoint,
+}
+
+impl GameMode {
+    pub fn name(&self) -> &str {
+        match self {
+            GameMode::Permadeath => "permadeath",
+            GameMode::Checkpoint => "checkpoints",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            GameMode::Permadeath => GameMode::Checkpoint,
+            GameMode::Checkpoint => GameMode::Permadeath,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct GameEnv {
     /// if true: run innit in debug mode
     pub debug_mode: bool,
     /// if true: use random seed for reproducible random number generation
     pub use_fixed_seed: bool,
+    /// if true: show screen shake, hit flashes and floating damage numbers
+    pub use_visual_effects: bool,
+    /// if true: animate drifting specks and a shimmer of the visible tissue, purely cosmetic
+    pub use_ambience: bool,
+    /// if true: mark visible organisms by their stance towards the player, independent of colors
+    #[serde(default)]
+    pub use_faction_badges: bool,
+    /// if true: introduce each species with a popup the first time it comes into sight in a run
+    pub use_encounter_popups: bool,
+    /// if set: load raws and palette from this directory instead of the embedded ones
+    pub data_dir: Option<PathBuf>,
+    /// if set: load assets missing from the data directory from this directory, before falling
+    /// back to the embedded ones
+    #[serde(default)]
+    pub mod_dir: Option<PathBuf>,
+    /// number of turns the world goes on after the player died, 0 ends the game right away
+    pub spectator_turns: u32,
+    /// if true: reveal the whole world regardless of the player's senses, only in debug mode
+    pub omniscient: bool,
+    /// if true: show the target, path and decision of each NPC, only in debug mode
+    #[serde(default)]
+    pub show_ai_traces: bool,
+    /// quantity the tissue is colored by, only in debug mode
+    #[serde(default)]
+    pub heat_map: HeatMap,
+    /// how NPC actions between the player's turns are rendered
+    pub game_speed: GameSpeed,
+    /// how much detail the message log records about each hit
+    pub combat_detail: CombatDetail,
+    /// if true: mirror the in-game message log to a text file in the data directory
+    pub log_messages: bool,
+    /// time in [ms] a movement key has to be held before it repeats
+    pub key_repeat_delay_ms: u32,
+    /// time in [ms] between two repeats of a held movement key, 0 switches repeating off
+    pub key_repeat_rate_ms: u32,
+    /// maximum length in [bytes] of an organism's raw DNA
+    pub max_dna_len: usize,
+    /// parameters that scale the simulation, set when a new game starts or a game is loaded
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// what happens to new games once the player dies
+    #[serde(default)]
+    pub game_mode: GameMode,
 }
 
 impl GameEnv {
@@ -13,6 +185,24 @@ impl GameEnv {
         GameEnv {
             debug_mode: false,
             use_fixed_seed: false,
+            use_visual_effects: true,
+            use_ambience: true,
+            use_faction_badges: false,
+            use_encounter_popups: true,
+            data_dir: None,
+            mod_dir: None,
+            spectator_turns: 200,
+            omniscient: false,
+            show_ai_traces: false,
+            heat_map: HeatMap::Off,
+            game_speed: GameSpeed::Fast,
+            combat_detail: CombatDetail::Summary,
+            log_messages: false,
+            key_repeat_delay_ms: 300,
+            key_repeat_rate_ms: 120,
+            max_dna_len: MAX_DNA_LEN,
+            difficulty: Difficulty::default(),
+            game_mode: GameMode::Permadeath,
         }
     }
 
@@ -20,7 +210,79 @@ impl GameEnv {
         self.debug_mode = debug_mode;
     }
 
+    pub fn set_visual_effects(&mut self, use_visual_effects: bool) {
+        self.use_visual_effects = use_visual_effects;
+    }
+
+    pub fn set_ambience(&mut self, use_ambience: bool) {
+        self.use_ambience = use_ambience;
+    }
+
+    pub fn set_faction_badges(&mut self, use_faction_badges: bool) {
+        self.use_faction_badges = use_faction_badges;
+    }
+
+    pub fn set_encounter_popups(&mut self, use_encounter_popups: bool) {
+        self.use_encounter_popups = use_encounter_popups;
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = Some(data_dir);
+    }
+
+    pub fn set_mod_dir(&mut self, mod_dir: PathBuf) {
+        self.mod_dir = Some(mod_dir);
+    }
+
+    pub fn set_spectator_turns(&mut self, spectator_turns: u32) {
+        self.spectator_turns = spectator_turns;
+    }
+
+    pub fn set_game_speed(&mut self, game_speed: GameSpeed) {
+        self.game_speed = game_speed;
+    }
+
+    pub fn set_combat_detail(&mut self, combat_detail: CombatDetail) {
+        self.combat_detail = combat_detail;
+    }
+
+    pub fn set_log_messages(&mut self, log_messages: bool) {
+        self.log_messages = log_messages;
+    }
+
+    pub fn set_key_repeat(&mut self, delay_ms: u32, rate_ms: u32) {
+        self.key_repeat_delay_ms = delay_ms;
+        self.key_repeat_rate_ms = rate_ms;
+    }
+
+    pub fn set_max_dna_len(&mut self, max_dna_len: usize) {
+        self.max_dna_len = max_dna_len;
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    pub fn set_game_mode(&mut self, game_mode: GameMode) {
+        self.game_mode = game_mode;
+    }
+
     pub fn set_rng_seeding(&mut self, use_fixed_seed: bool) {
         self.use_fixed_seed = use_fixed_seed;
     }
+
+    /// Switch off everything that gives an edge over other players, i.e. debug features and
+    /// user provided raws.
+    pub fn lock(&mut self) {
+        self.debug_mode = false;
+        self.omniscient = false;
+        self.show_ai_traces = false;
+        self.heat_map = HeatMap::Off;
+        self.use_fixed_seed = false;
+        self.data_dir = None;
+        self.mod_dir = None;
+        self.max_dna_len = MAX_DNA_LEN;
+        self.difficulty = Difficulty::default();
+        self.game_mode = GameMode::Permadeath;
+    }
 }
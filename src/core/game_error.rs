@@ -0,0 +1,44 @@
+//! Recoverable failures that are reported to the player instead of crashing the game.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone)]
+pub enum GameError {
+    /// The game could not be written to the save backend.
+    SaveFailed(String),
+    /// There is no save game or it could not be read.
+    LoadFailed(String),
+    /// A built-in asset like the menu background could not be loaded.
+    MissingAsset(String),
+    /// The game reached a state it cannot continue from.
+    InvalidState(String),
+}
+
+impl GameError {
+    pub fn title(&self) -> &str {
+        match self {
+            GameError::SaveFailed(_) => "Cannot Save Game",
+            GameError::LoadFailed(_) => "Cannot Load Game",
+            GameError::MissingAsset(_) => "Missing Asset",
+            GameError::InvalidState(_) => "Invalid Game State",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            GameError::SaveFailed(msg)
+            | GameError::LoadFailed(msg)
+            | GameError::MissingAsset(msg)
+            | GameError::InvalidState(msg) => msg,
+        }
+    }
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.title(), self.message())
+    }
+}
+
+impl Error for GameError {}
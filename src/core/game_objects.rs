@@ -1,12 +1,16 @@
 use crate::core::innit_env;
 use crate::core::position::Position;
-use crate::core::world::Tile;
+use crate::core::world::{
+    Barrier, Debris, Environment, Region, Tile, SCENT_MAX, TOXIN_MAX, WALL_INTEGRITY,
+};
+use crate::entity::action::Target;
 use crate::entity::genetics::{DnaType, GeneLibrary, GENE_LEN};
 use crate::entity::object::Object;
 use crate::entity::player::PLAYER;
 use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::raws::object_template::ObjectTemplate;
 use crate::util::game_rng::GameRng;
-use rltk::{Algorithm2D, BaseMap, Point};
+use rltk::{a_star_search, Algorithm2D, BaseMap, Point, SmallVec};
 use serde::{Deserialize, Serialize};
 use std::ops::{Index, IndexMut};
 
@@ -20,6 +24,9 @@ use std::ops::{Index, IndexMut};
 pub struct GameObjects {
     num_world_tiles: usize,
     obj_vec: Vec<Option<Object>>,
+    /// Positions of tiles that have been replaced since the last visibility update.
+    #[serde(skip)]
+    changed_tiles: Vec<Position>,
 }
 
 impl GameObjects {
@@ -32,6 +39,7 @@ impl GameObjects {
         GameObjects {
             num_world_tiles,
             obj_vec,
+            changed_tiles: Vec::new(),
         }
     }
 
@@ -40,6 +48,18 @@ impl GameObjects {
         &mut self.obj_vec[(y * (WORLD_WIDTH as usize) + x) + 1]
     }
 
+    /// Replace the tile at the given position during the game, so that systems depending on the
+    /// layout of the world, like the fields of view, get to know about it.
+    pub fn replace_tile(&mut self, p: &Position, tile: Object) {
+        self.get_tile_at(p.x as usize, p.y as usize).replace(tile);
+        self.changed_tiles.push(*p);
+    }
+
+    /// Take the positions of all tiles replaced since the last call.
+    pub fn take_changed_tiles(&mut self) -> Vec<Position> {
+        std::mem::take(&mut self.changed_tiles)
+    }
+
     /// Allocate enough space in the object vector to fit the player and all world tiles.
     pub fn blank_world(&mut self) {
         assert!(self.obj_vec.is_empty());
@@ -69,10 +89,11 @@ impl GameObjects {
         }
     }
 
+    /// Set the genome of all tiles according to the given species template.
     pub fn set_tile_dna(
         &mut self,
         rng: &mut GameRng,
-        traits: Vec<String>,
+        template: &ObjectTemplate,
         gene_library: &GeneLibrary,
     ) {
         for y in 0..WORLD_HEIGHT {
@@ -80,10 +101,16 @@ impl GameObjects {
                 if let Some(tile) =
                     &mut self.obj_vec[((y as usize) * (WORLD_WIDTH as usize) + (x as usize)) + 1]
                 {
-                    let (sensors, processors, actuators, dna) = gene_library.dna_to_traits(
-                        DnaType::Nucleus,
-                        &gene_library.trait_strs_to_dna(rng, &traits),
-                    );
+                    let raw_dna =
+                        match gene_library.dna_from_template(rng, &template.dna_template, false) {
+                            Ok(raw_dna) => raw_dna,
+                            Err(msg) => {
+                                error!("cannot set the genome of tiles: {}", msg);
+                                return;
+                            }
+                        };
+                    let (sensors, processors, actuators, dna) =
+                        gene_library.dna_to_traits(template.dna_type, &raw_dna);
                     tile.change_genome(sensors, processors, actuators, dna);
                 }
             }
@@ -198,12 +225,262 @@ impl GameObjects {
         }
     }
 
+    /// Return the index of the tile at the given position, if the position lies within the world.
+    fn tile_index(&self, p: &Position) -> Option<usize> {
+        if p.x >= 0 && p.x < WORLD_WIDTH && p.y >= 0 && p.y < WORLD_HEIGHT {
+            Some((p.y * WORLD_WIDTH + p.x) as usize + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Leave a fresh pheromone trail on the tile at the given position.
+    pub fn leave_scent(&mut self, p: &Position) {
+        if let Some(idx) = self.tile_index(p) {
+            if let Some(tile) = self.obj_vec[idx].as_mut().and_then(|o| o.tile.as_mut()) {
+                tile.scent = SCENT_MAX;
+            }
+        }
+    }
+
+    /// Return the strength of the pheromone trail at the given position, 0 if there is none.
+    pub fn get_scent(&self, p: &Position) -> i32 {
+        self.tile_index(p)
+            .and_then(|idx| self.obj_vec[idx].as_ref())
+            .and_then(|o| o.tile.as_ref())
+            .map_or(0, |t| t.scent)
+    }
+
+    /// Add toxins to the tile at the given position, up to the highest concentration a tile holds.
+    pub fn secrete_toxin(&mut self, p: &Position, amount: i32) {
+        if let Some(idx) = self.tile_index(p) {
+            if let Some(tile) = self.obj_vec[idx].as_mut().and_then(|o| o.tile.as_mut()) {
+                tile.toxin = (tile.toxin + amount).min(TOXIN_MAX);
+            }
+        }
+    }
+
+    /// Leave the remains of a dead organism on the tile at the given position. Older debris on the
+    /// tile is buried underneath.
+    pub fn leave_debris(&mut self, p: &Position, debris: Debris) {
+        if let Some(idx) = self.tile_index(p) {
+            let tile = self.obj_vec.get_mut(idx).and_then(|o| o.as_mut());
+            if let Some(tile) = tile.and_then(|o| o.tile.as_mut()) {
+                tile.debris = Some(debris);
+            }
+        }
+    }
+
+    /// Take the debris off the tile at the given position, if there is any.
+    pub fn take_debris(&mut self, p: &Position) -> Option<Debris> {
+        let idx = self.tile_index(p)?;
+        self.obj_vec
+            .get_mut(idx)
+            .and_then(|o| o.as_mut())
+            .and_then(|o| o.tile.as_mut())
+            .and_then(|t| t.debris.take())
+    }
+
+    /// Return the concentration of toxins at the given position, 0 if there are none.
+    pub fn get_toxin(&self, p: &Position) -> i32 {
+        self.tile_index(p)
+            .and_then(|idx| self.obj_vec[idx].as_ref())
+            .and_then(|o| o.tile.as_ref())
+            .map_or(0, |t| t.toxin)
+    }
+
+    /// Digest the wall at the given position by `amount`. Once its integrity is used up, the wall
+    /// dissolves into floor that keeps the local conditions. Returns the remaining integrity, or
+    /// `None` if there is no wall or it belongs to the outer boundary of the world.
+    pub fn digest_wall(&mut self, p: &Position, amount: i32) -> Option<i32> {
+        if p.x <= 0 || p.y <= 0 || p.x >= WORLD_WIDTH - 1 || p.y >= WORLD_HEIGHT - 1 {
+            return None;
+        }
+        let slot = self.get_tile_at(p.x as usize, p.y as usize);
+        let wall = slot.as_mut().filter(|o| o.physics.is_blocking)?;
+        let tile = wall.tile.as_mut()?;
+        tile.digested += amount;
+        let integrity = (WALL_INTEGRITY - tile.digested).max(0);
+        if integrity == 0 {
+            let wall = slot.take()?;
+            let mut floor = Tile::empty(p.x, p.y, wall.physics.is_visible);
+            floor.dna = wall.dna;
+            floor.tile = wall.tile;
+            if let Some(tile) = floor.tile.as_mut() {
+                tile.digested = 0;
+                tile.barrier = None;
+            }
+            self.replace_tile(p, floor);
+        }
+        Some(integrity)
+    }
+
+    /// Return the barrier at the given position, if there is one.
+    pub fn get_barrier(&self, p: &Position) -> Option<Barrier> {
+        self.tile_index(p)
+            .and_then(|idx| self.obj_vec[idx].as_ref())
+            .and_then(|o| o.tile.as_ref())
+            .and_then(|t| t.barrier.clone())
+    }
+
+    /// Open the closed barrier at the given position for as long as its kind allows. Returns
+    /// `false` if there is no closed barrier.
+    pub fn open_barrier(&mut self, p: &Position) -> bool {
+        let object = match self
+            .tile_index(p)
+            .and_then(|idx| self.obj_vec[idx].as_mut())
+        {
+            Some(object) => object,
+            None => return false,
+        };
+        let barrier = match object.tile.as_mut().and_then(|t| t.barrier.as_mut()) {
+            Some(barrier) if !barrier.is_open() => barrier,
+            _ => return false,
+        };
+        barrier.open_for = barrier.kind.open_turns();
+        let barrier = barrier.clone();
+        barrier.apply(object);
+        self.changed_tiles.push(*p);
+        true
+    }
+
+    /// Count down the open barriers and close those whose time is up, unless something blocking
+    /// is in the way. Called once per turn.
+    pub fn update_barriers(&mut self) {
+        let open: Vec<Position> = self.obj_vec[1..=self.num_world_tiles]
+            .iter()
+            .flatten()
+            .filter(|o| {
+                o.tile
+                    .as_ref()
+                    .is_some_and(|t| t.barrier.as_ref().is_some_and(|b| b.is_open()))
+            })
+            .map(|o| o.pos)
+            .collect();
+        for p in open {
+            let is_blocked = self.is_pos_blocked(&p);
+            let object = match self.get_tile_at(p.x as usize, p.y as usize).as_mut() {
+                Some(object) => object,
+                None => continue,
+            };
+            let barrier = match object.tile.as_mut().and_then(|t| t.barrier.as_mut()) {
+                Some(barrier) => barrier,
+                None => continue,
+            };
+            if barrier.open_for > 1 || !is_blocked {
+                barrier.open_for -= 1;
+            }
+            if !barrier.is_open() {
+                let barrier = barrier.clone();
+                barrier.apply(object);
+                self.changed_tiles.push(p);
+            }
+        }
+    }
+
+    /// Let all pheromone trails and toxins fade a little. Called once per turn.
+    pub fn decay_chemicals(&mut self) {
+        for tile in self.obj_vec[1..=self.num_world_tiles]
+            .iter_mut()
+            .flatten()
+            .filter_map(|o| o.tile.as_mut())
+        {
+            tile.scent = (tile.scent - 1).max(0);
+            tile.toxin = (tile.toxin - 1).max(0);
+        }
+    }
+
+    /// Return the index of the visible organism at the given position, other than the player.
+    pub fn organism_index_at(&self, p: &Position) -> Option<usize> {
+        self.obj_vec
+            .iter()
+            .enumerate()
+            .skip(self.num_world_tiles + 1)
+            .find(|(_, o)| {
+                o.as_ref().is_some_and(|o| {
+                    o.occupies(p) && o.alive && o.physics.is_visible && o.control.is_some()
+                })
+            })
+            .map(|(idx, _)| idx)
+    }
+
+    /// Return the local conditions at the given position.
+    pub fn get_environment(&self, p: &Position) -> Environment {
+        self.tile_index(p)
+            .and_then(|idx| self.obj_vec[idx].as_ref())
+            .and_then(|o| o.tile.as_ref())
+            .map_or_else(Environment::default, |t| t.environment)
+    }
+
+    pub fn get_region(&self, p: &Position) -> Region {
+        self.tile_index(p)
+            .and_then(|idx| self.obj_vec[idx].as_ref())
+            .and_then(|o| o.tile.as_ref())
+            .map_or_else(Region::default, |t| t.region)
+    }
+
+    /// Return the direction of the current flowing through the given position.
+    pub fn get_current(&self, p: &Position) -> Target {
+        self.tile_index(p)
+            .and_then(|idx| self.obj_vec[idx].as_ref())
+            .and_then(|o| o.tile.as_ref())
+            .map_or(Target::Center, |t| t.current.clone())
+    }
+
+    /// Let a current flow through the tile at the given position and display it with an arrow.
+    pub fn set_current(&mut self, p: &Position, current: Target) {
+        if let Some(idx) = self.tile_index(p) {
+            if let Some(object) = self.obj_vec[idx].as_mut() {
+                if let Some(tile) = object.tile.as_mut() {
+                    object.visual.glyph = match current {
+                        Target::North => '↑',
+                        Target::South => '↓',
+                        Target::East => '→',
+                        Target::West => '←',
+                        _ => object.visual.glyph,
+                    };
+                    tile.current = current;
+                }
+            }
+        }
+    }
+
+    /// Check whether the tile at the given position has been explored and can be walked on.
+    fn is_explored_and_open(&self, p: &Position) -> bool {
+        self.tile_index(p)
+            .and_then(|idx| self.obj_vec[idx].as_ref())
+            .is_some_and(|o| {
+                !o.physics.is_blocking && o.tile.as_ref().is_some_and(|t| t.is_explored)
+            })
+    }
+
+    /// Find the shortest path across explored, open tiles from `start` to `end`.
+    /// The returned steps exclude the starting position and end with the destination.
+    pub fn find_path(&self, start: &Position, end: &Position) -> Option<Vec<Position>> {
+        if !self.is_explored_and_open(end) {
+            return None;
+        }
+        let start_idx = self.tile_index(start)?;
+        let end_idx = self.tile_index(end)?;
+        let path = a_star_search(start_idx, end_idx, self);
+        if !path.success {
+            return None;
+        }
+        Some(
+            path.steps
+                .iter()
+                .skip(1)
+                .map(|idx| Position::from(self.index_to_point2d(*idx)))
+                .collect(),
+        )
+    }
+
     /// Check whether there is an object, tile or not, blocking access to the given world coordinate
     pub fn is_pos_blocked(&self, p: &Position) -> bool {
         self.obj_vec
             .iter()
             .flatten()
-            .any(|object| object.physics.is_blocking && object.pos.is_equal(p))
+            .any(|object| object.physics.is_blocking && object.occupies(p))
     }
 
     /// Check whether there is any non-tile object located at the given position.
@@ -212,7 +489,7 @@ impl GameObjects {
         self.get_non_tiles()
             .iter()
             .flatten()
-            .any(|object| object.pos.is_equal(p))
+            .any(|object| object.occupies(p))
     }
 
     pub fn get_obj_count(&self) -> usize {
@@ -239,6 +516,63 @@ impl GameObjects {
         let start: usize = WORLD_HEIGHT as usize * WORLD_WIDTH as usize;
         &self.obj_vec[start..]
     }
+
+    /// Check the invariants of the world. Returns a description of each violation, naming the
+    /// objects involved.
+    pub fn validate(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        let mut blocking: Vec<Vec<usize>> = vec![Vec::new(); self.num_world_tiles];
+        for (idx, slot) in self.obj_vec.iter().enumerate() {
+            let object = match slot {
+                Some(object) => object,
+                None => {
+                    violations.push(format!("object #{} is missing", idx));
+                    continue;
+                }
+            };
+            let context = format!("#{} {} at {:?}", idx, object.visual.name, object.pos);
+            let is_tile_slot = (1..=self.num_world_tiles).contains(&idx);
+            if is_tile_slot != object.tile.is_some() {
+                violations.push(format!("{} is misplaced among the tiles", context));
+            } else if is_tile_slot
+                && idx != (object.pos.y * WORLD_WIDTH + object.pos.x) as usize + 1
+            {
+                violations.push(format!("{} is stored at the wrong index", context));
+            }
+
+            let mut positions = vec![object.pos];
+            positions.extend(object.segments.iter().map(|s| s.pos));
+            for pos in positions {
+                if !(0..WORLD_WIDTH).contains(&pos.x) || !(0..WORLD_HEIGHT).contains(&pos.y) {
+                    violations.push(format!("{} occupies {:?} outside the world", context, pos));
+                } else if object.physics.is_blocking {
+                    let blockers = &mut blocking[(pos.y * WORLD_WIDTH + pos.x) as usize];
+                    if blockers.last() != Some(&idx) {
+                        blockers.push(idx);
+                    }
+                }
+            }
+
+            if object.alive && object.tile.is_none() && object.control.is_some() {
+                if object.dna.raw.is_empty() {
+                    violations.push(format!("{} is alive without DNA", context));
+                }
+                let processors = &object.processors;
+                if !(0..=processors.energy_storage).contains(&processors.energy) {
+                    violations.push(format!(
+                        "{} has {} energy, storage is {}",
+                        context, processors.energy, processors.energy_storage
+                    ));
+                }
+            }
+        }
+
+        for (i, indices) in blocking.iter().enumerate().filter(|(_, o)| o.len() > 1) {
+            let pos = Position::new(i as i32 % WORLD_WIDTH, i as i32 / WORLD_WIDTH);
+            violations.push(format!("{:?} is blocked by objects {:?}", pos, indices));
+        }
+        violations
+    }
 }
 
 impl Index<usize> for GameObjects {
@@ -267,7 +601,8 @@ impl BaseMap for GameObjects {
     fn is_opaque(&self, idx: usize) -> bool {
         if idx > 0 && idx < self.obj_vec.len() {
             if let Some(o) = &self.obj_vec[idx] {
-                o.physics.is_blocking
+                // closed membrane pores block the way, but not the view
+                o.physics.is_blocking_sight
             } else {
                 false
             }
@@ -276,10 +611,37 @@ impl BaseMap for GameObjects {
         }
     }
 
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        let mut exits = SmallVec::new();
+        let pos = Position::from(self.index_to_point2d(idx));
+        for target in &[
+            Target::North,
+            Target::NorthEast,
+            Target::East,
+            Target::SouthEast,
+            Target::South,
+            Target::SouthWest,
+            Target::West,
+            Target::NorthWest,
+        ] {
+            let exit = pos.get_translated(&target.to_pos());
+            if self.is_explored_and_open(&exit) {
+                if let Some(exit_idx) = self.tile_index(&exit) {
+                    let cost = if exit.x != pos.x && exit.y != pos.y {
+                        1.45
+                    } else {
+                        1.0
+                    };
+                    exits.push((exit_idx, cost));
+                }
+            }
+        }
+        exits
+    }
+
     fn get_pathing_distance(&self, idx1: usize, idx2: usize) -> f32 {
-        let w = WORLD_WIDTH as usize;
-        let p1 = Point::new(idx1 % w, idx1 / w);
-        let p2 = Point::new(idx2 % w, idx2 / w);
+        let p1 = self.index_to_point2d(idx1);
+        let p2 = self.index_to_point2d(idx2);
         rltk::DistanceAlg::Pythagoras.distance2d(p1, p2)
     }
 }
@@ -1,14 +1,44 @@
+use crate::core::bookmarks::Bookmarks;
+use crate::core::claims::TileClaims;
+use crate::core::clock::{
+    ClockTime, IMMUNE_PATROL_HOUR, IMMUNE_PATROL_INTERVAL, IMMUNE_PATROL_SIZE, MEAL_HOURS,
+};
+use crate::core::ecosystem::{
+    lineages, population, recolonize, spawn_organisms, Ecosystem, SpawnRaws,
+};
+use crate::core::encyclopedia::{discover_in_sight, mechanic, Discoveries, Topic};
+use crate::core::events::{EventBus, GameEvent};
+use crate::core::game_env::GameMode;
 use crate::core::game_objects::GameObjects;
-use crate::core::innit_env;
+use crate::core::host::{
+    Host, HostCondition, HostEvent, ANTIBIOTIC_DAMAGE, ANTIBODY_FLOOD, INFLAMMATION_INTERVAL,
+};
+use crate::core::position::Position;
+use crate::core::visibility::Visibility;
+use crate::core::world::{toxin_damage, Region};
+use crate::core::{innit_env, telemetry};
 use crate::entity::action::*;
-use crate::entity::genetics::GeneLibrary;
+use crate::entity::bark::bark_in_sight;
+use crate::entity::faction::Relations;
+use crate::entity::genetics::{DnaType, GeneLibrary};
 use crate::entity::object::Object;
 use crate::entity::player::PLAYER;
-use crate::util::game_rng::GameRng;
+use crate::raws::difficulty::Difficulty;
+use crate::ui::register_damage_effects;
+use crate::util::game_rng::{GameRng, RngStream, RngStreams};
+use crate::util::platform;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
-#[derive(PartialEq, Debug, Serialize, Deserialize)]
+/// Damage taken each turn an organism can't pay its energy upkeep.
+const STARVATION_DAMAGE: i32 = 1;
+/// Consecutive turns of starvation after which an organism falls dormant to save energy.
+const STARVATION_DORMANCY: i32 = 3;
+/// Key of the text file that the message log is mirrored to, if enabled.
+const MESSAGE_LOG_FILE: &str = "messages.log";
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy)]
 pub enum MsgClass {
     Info,
     Action,
@@ -16,10 +46,48 @@ pub enum MsgClass {
     Story,
 }
 
+/// A single entry of the message log.
+/// Besides the text itself it remembers when and where it happened and how often it was repeated
+/// in a row, so that the HUD can collapse duplicates into a single line.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct Message {
+    pub text: String,
+    pub class: MsgClass,
+    /// Turn in which the message was first logged.
+    pub turn: u128,
+    /// World position of the event that caused this message, if there is any.
+    pub origin: Option<Position>,
+    /// How often the message occurred consecutively.
+    pub repeat: u32,
+}
+
+impl Message {
+    pub fn new(text: String, class: MsgClass, turn: u128, origin: Option<Position>) -> Self {
+        Message {
+            text,
+            class,
+            turn,
+            origin,
+            repeat: 1,
+        }
+    }
+
+    /// Return the message text, suffixed with the repeat count if it occurred more than once.
+    pub fn to_text(&self) -> String {
+        if self.repeat > 1 {
+            format!("{} x{}", self.text, self.repeat)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Default)]
 pub struct Log {
     pub is_changed: bool,
-    pub messages: Vec<(String, MsgClass)>,
+    pub messages: Vec<Message>,
+    /// The current turn, used to stamp new messages.
+    turn: u128,
 }
 
 impl Log {
@@ -27,38 +95,73 @@ impl Log {
         Log {
             is_changed: false,
             messages: Vec::new(),
+            turn: 0,
         }
     }
-}
 
-/// The message log can add text from any string collection.
-pub trait MessageLog {
-    fn add<T: Into<String>>(&mut self, message: T, class: MsgClass);
-}
+    pub fn set_turn(&mut self, turn: u128) {
+        self.turn = turn;
+    }
 
-impl MessageLog for Log {
     /// Push a message into the log under two conditions:
     /// - either the log is empty
     /// - or the last message is not identical to the new message
-    fn add<T: Into<String>>(&mut self, msg: T, class: MsgClass) {
-        if self.messages.is_empty() {
-            self.messages.push((msg.into(), class));
-            self.is_changed = true;
-            return;
+    ///
+    /// Otherwise increase the repeat count of the last message.
+    fn push(&mut self, text: String, class: MsgClass, origin: Option<Position>) {
+        if innit_env().log_messages {
+            self.mirror(&text);
         }
 
-        if let Some(recent_msg) = self.messages.last() {
-            let msg_str = msg.into();
-            if !recent_msg.0.eq(&msg_str) {
-                self.messages.push((msg_str, class));
+        if let Some(recent_msg) = self.messages.last_mut() {
+            if recent_msg.text.eq(&text) {
+                recent_msg.repeat += 1;
+                recent_msg.origin = origin.or(recent_msg.origin);
                 self.is_changed = true;
+                return;
             }
         }
+
+        self.messages
+            .push(Message::new(text, class, self.turn, origin));
+        self.is_changed = true;
+    }
+
+    /// Append the message to the log file, so that long runs can be reviewed afterwards. Mirroring
+    /// is switched off for good if the file can't be written.
+    fn mirror(&self, text: &str) {
+        let line = format!("[turn {}] {}\n", self.turn, text);
+        if let Err(e) = platform::append_data(MESSAGE_LOG_FILE, &line) {
+            error!("cannot write message log to file: {}", e);
+            innit_env().set_log_messages(false);
+        }
     }
 }
 
+/// The message log can add text from any string collection.
+pub trait MessageLog {
+    fn add<T: Into<String>>(&mut self, message: T, class: MsgClass);
+
+    /// Add a message that refers to an event at the given world position.
+    fn add_at<T: Into<String>>(&mut self, message: T, class: MsgClass, origin: Position);
+}
+
+impl MessageLog for Log {
+    fn add<T: Into<String>>(&mut self, msg: T, class: MsgClass) {
+        self.push(msg.into(), class, None);
+    }
+
+    fn add_at<T: Into<String>>(&mut self, msg: T, class: MsgClass, origin: Position) {
+        self.push(msg.into(), class, Some(origin));
+    }
+}
+
+/// Length of a chain of consequences at which the remaining ones are dropped, to keep actions
+/// from triggering each other forever.
+pub const MAX_CONSEQUENCES: usize = 8;
+
 /// Results from processing an objects action for that turn, in ascending rank.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum ObjectFeedback {
     NoAction,   // object did not act and is still pondering its turn
     NoFeedback, // action completed, but requires no visual feedback
@@ -73,11 +176,73 @@ pub enum ObjectFeedback {
 /// file and thus persistent data. No volatile data is allowed here.
 #[derive(Serialize, Deserialize)]
 pub struct GameState {
-    pub rng: GameRng,
+    /// Random number generators of the subsystems, see `rng_for`.
+    #[serde(default)]
+    pub rng_streams: RngStreams,
     pub log: Log,
     pub turn: u128,
     pub dungeon_level: u32,
     pub gene_library: GeneLibrary,
+    /// Seed the world of this run was generated from.
+    #[serde(default)]
+    pub seed: u64,
+    /// Date of the daily run, if this is one.
+    #[serde(default)]
+    pub daily: Option<String>,
+    /// Difficulty the run is played at, restored along with the game.
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// What happens to the run once the player dies.
+    #[serde(default)]
+    pub game_mode: GameMode,
+    /// Number of times the player respawned at a checkpoint, each one costs some score.
+    #[serde(default)]
+    pub respawns: u32,
+    /// Turn of the last checkpoint, see `save::CHECKPOINT_INTERVAL`.
+    #[serde(default)]
+    pub last_checkpoint: u128,
+    /// Condition of the organism the game takes place in.
+    #[serde(default)]
+    pub host: Host,
+    /// Organisms the player keeps track of.
+    #[serde(default)]
+    pub bookmarks: Bookmarks,
+    /// Standing of the factions towards each other.
+    #[serde(default)]
+    pub relations: Relations,
+    /// Names of the bosses defeated so far.
+    #[serde(default)]
+    pub defeated_bosses: Vec<String>,
+    /// Lines of story that organisms have told in this run, so that none is told twice.
+    #[serde(default)]
+    pub told_barks: BTreeSet<String>,
+    /// Ids of the story milestones reached in this run.
+    #[serde(default)]
+    pub reached_milestones: BTreeSet<String>,
+    /// Regions of the host that the player has set foot in.
+    #[serde(default)]
+    pub discovered_regions: BTreeSet<Region>,
+    /// Kinds of items whose genes the player has found out about in this run.
+    #[serde(default)]
+    pub identified_items: BTreeSet<String>,
+    /// Entries of the encyclopedia the player has unlocked so far.
+    #[serde(default)]
+    pub discoveries: Discoveries,
+    /// Tiles that organisms moved into this turn, to settle contested moves.
+    #[serde(default)]
+    pub tile_claims: TileClaims,
+    /// Regulator that keeps the population of the world from collapsing or exploding.
+    #[serde(default)]
+    pub ecosystem: Ecosystem,
+    /// What the player currently senses, rebuilt after loading a game.
+    #[serde(skip)]
+    pub visibility: Visibility,
+    /// Events that happened since the game loop last collected them.
+    #[serde(skip)]
+    pub events: EventBus,
+    /// Raws the ecosystem spawns organisms from, loaded on the first census after loading a game.
+    #[serde(skip)]
+    pub spawn_raws: Option<SpawnRaws>,
     pub obj_idx: usize,    // current object index
     pub player_idx: usize, // current player index
 }
@@ -89,19 +254,49 @@ impl GameState {
         } else {
             rand::thread_rng().next_u64()
         };
+        GameState::with_seed(level, rng_seed)
+    }
 
+    /// Create a game state whose random numbers, and with them the generated world, are
+    /// determined by the given seed.
+    pub fn with_seed(level: u32, rng_seed: u64) -> Self {
         GameState {
             // create the list of game messages and their colours, starts empty
-            rng: GameRng::new_from_u64_seed(rng_seed),
+            rng_streams: RngStreams::new(rng_seed),
+            seed: rng_seed,
+            daily: None,
+            difficulty: Difficulty::default(),
+            game_mode: GameMode::Permadeath,
+            respawns: 0,
+            last_checkpoint: 0,
             log: Log::new(),
             turn: 0,
             dungeon_level: level,
             gene_library: GeneLibrary::new(),
+            host: Host::new(),
+            bookmarks: Bookmarks::new(),
+            relations: Relations::new(),
+            defeated_bosses: Vec::new(),
+            told_barks: BTreeSet::new(),
+            reached_milestones: BTreeSet::new(),
+            discovered_regions: BTreeSet::new(),
+            identified_items: BTreeSet::new(),
+            discoveries: Discoveries::default(),
+            tile_claims: TileClaims::default(),
+            ecosystem: Ecosystem::default(),
+            events: EventBus::new(),
+            spawn_raws: None,
+            visibility: Visibility::new(),
             obj_idx: 0,
             player_idx: PLAYER,
         }
     }
 
+    /// Random number generator of the given subsystem.
+    pub fn rng_for(&mut self, stream: RngStream) -> &mut GameRng {
+        self.rng_streams.get(stream)
+    }
+
     pub fn is_players_turn(&self) -> bool {
         self.obj_idx == self.player_idx
     }
@@ -132,6 +327,13 @@ impl GameState {
             );
             // }
 
+            // the remains of a dead player stay in the world, but don't act anymore
+            if !active_object.alive {
+                objects.replace(self.obj_idx, active_object);
+                self.next_object(objects);
+                return ObjectFeedback::NoFeedback;
+            }
+
             if active_object.is_player() {
                 // update player index just in case we have multiple player controlled objects
                 self.player_idx = self.obj_idx;
@@ -145,7 +347,9 @@ impl GameState {
             }
 
             // TURN PREPARATION ///////////////////////////////////////////////////////////////////
-            // Innit doesn't have any action preparations as of yet.
+            // Innit doesn't have any action preparations as of yet, apart from remembering where
+            // the turn started, to notice the player crossing into another region.
+            let start_region = objects.get_region(&active_object.pos);
 
             // TURN ACTION ////////////////////////////////////////////////////////////////////////
             let mut process_result =
@@ -154,23 +358,45 @@ impl GameState {
                     ObjectFeedback::NoFeedback
                 } else if active_object.processors.energy < active_object.processors.energy_storage {
                     // replenish energy
-                    active_object.metabolize();
+                    let env = objects.get_environment(&active_object.pos);
+                    let nutrients = self.host.nutrients() * self.clock().nutrient_cycle() / 100;
+                    let nutrients = innit_env().difficulty.nutrients(nutrients);
+                    let nutrients = self.ecosystem.nutrients(nutrients);
+                    active_object.metabolize(env.nutrient_supply(nutrients));
                     if self.is_players_turn() {
                         ObjectFeedback::Render
                     } else {
                         ObjectFeedback::NoFeedback
                     }
                 } else if let Some(next_action) = active_object.extract_next_action(self, objects) {
-                    // use up energy before action
-                    if active_object.physics.is_visible && next_action.get_identifier().ne("pass") {
-                        debug!("next action: {}", next_action.get_identifier());
-                    }
-                    if next_action.get_energy_cost() > active_object.processors.energy_storage {
-                        self.log.add("You don't have enough energy for that!", MsgClass::Info);
-                        ObjectFeedback::NoFeedback
+                    if active_object.dormant && next_action.get_identifier().ne("pass") {
+                        // doing anything but passing takes a turn to awaken first
+                        active_object.dormant = false;
+                        if active_object.is_player() {
+                            self.log.add("You awaken from dormancy.", MsgClass::Info);
+                        }
+                        ObjectFeedback::Render
                     } else {
-                        active_object.processors.energy -= next_action.get_energy_cost();
-                        self.process_action(objects, &mut active_object, next_action)
+                        // use up energy before action
+                        if active_object.physics.is_visible
+                            && next_action.get_identifier().ne("pass")
+                        {
+                            debug!("next action: {}", next_action.get_identifier());
+                        }
+                        let env = objects.get_environment(&active_object.pos);
+                        let mut energy_cost =
+                            active_object.action_cost(next_action.as_ref(), &env);
+                        if energy_cost > 0 {
+                            energy_cost += self.host.energy_surcharge();
+                        }
+                        if energy_cost > active_object.processors.energy_storage {
+                            self.log
+                                .add("You don't have enough energy for that!", MsgClass::Info);
+                            ObjectFeedback::NoFeedback
+                        } else {
+                            active_object.processors.energy -= energy_cost;
+                            self.process_action(objects, &mut active_object, next_action)
+                        }
                     }
                 } else {
                     panic!("How can an object 'has_next_action' but NOT have an action?");
@@ -181,17 +407,122 @@ impl GameState {
                 process_result = ObjectFeedback::NoFeedback;
             }
 
+            // TURN DRIFT /////////////////////////////////////////////////////////////////////////
+            // Currents carry along any living object that is not anchored to the tissue.
+            if active_object.tile.is_none()
+                && active_object.alive
+                && !active_object.is_anchored(objects)
+            {
+                let current = objects.get_current(&active_object.pos);
+                if current != Target::Center {
+                    let drift_pos = active_object.pos.get_translated(&current.to_pos());
+                    if !objects.is_pos_blocked(&drift_pos) {
+                        active_object.move_to(drift_pos);
+                        if active_object.is_player() {
+                            self.events.publish(GameEvent::BloodstreamReached);
+                            self.discover(Topic::Mechanic, "bloodstream");
+                        }
+                        if active_object.physics.is_visible
+                            && process_result == ObjectFeedback::NoFeedback
+                        {
+                            process_result = ObjectFeedback::Render;
+                        }
+                    }
+                }
+            }
+
+            if active_object.is_player() {
+                let region = objects.get_region(&active_object.pos);
+                if region != start_region {
+                    self.enter_region(region);
+                }
+            }
+
             // TURN CONCLUSION ////////////////////////////////////////////////////////////////////
             // Apply recurring effects so that the player can factor this into the next action.
 
-            if active_object.inventory.items.len() as i32 > active_object.actuators.volume {
+            // Producers harvest light and the chemistry of the tissue where they stand.
+            if active_object.tile.is_none()
+                && active_object.alive
+                && active_object.control.is_some()
+            {
+                let env = objects.get_environment(&active_object.pos);
+                active_object.produce(&env);
+            }
+
+            // Sustaining biomass costs energy. Organisms that can't pay starve and eventually fall
+            // dormant until their energy is replenished.
+            if active_object.tile.is_none()
+                && active_object.alive
+                && active_object.control.is_some()
+                && !active_object.dormant
+                && active_object.pay_upkeep()
+            {
+                active_object.actuators.hp -= STARVATION_DAMAGE;
+                register_damage_effects(&active_object, STARVATION_DAMAGE);
+                if active_object.processors.starvation >= STARVATION_DORMANCY {
+                    active_object.dormant = true;
+                    if active_object.is_player() {
+                        self.log.add(
+                            "You're starving and fall dormant to save energy.",
+                            MsgClass::Alert,
+                        );
+                    }
+                } else if active_object.is_player() {
+                    self.log
+                        .add("You're starving! Taking damage...", MsgClass::Alert);
+                }
+            }
+
+            // Antibiotics harm all cells that lack a matching resistance gene. Viruses and dormant
+            // spores have no metabolism for them to disrupt.
+            if let Some(antibiotic) = self.host.antibiotic() {
+                if active_object.tile.is_none()
+                    && active_object.alive
+                    && active_object.control.is_some()
+                    && !active_object.dormant
+                    && active_object.dna.dna_type != DnaType::Rna
+                    && !active_object.processors.resistances.contains(&antibiotic)
+                {
+                    active_object.actuators.hp -= ANTIBIOTIC_DAMAGE;
+                    register_damage_effects(&active_object, ANTIBIOTIC_DAMAGE);
+                    if active_object.is_player() {
+                        self.log.add(
+                            format!("The {} antibiotics damage you!", antibiotic.name()),
+                            MsgClass::Alert,
+                        );
+                    }
+                }
+            }
+
+            // Received chemical signals and emitted auras fade over time.
+            if active_object.tile.is_none() {
+                active_object.decay_signals();
+            }
+
+            if active_object.is_overloaded() {
                 active_object.actuators.hp -= 1;
+                register_damage_effects(&active_object, 1);
                 if active_object.is_player() {
                     self.log
                         .add("You're overloaded! Taking damage...", MsgClass::Alert);
                 }
             }
 
+            // Toxins on the tile eat away at anyone without antitoxins.
+            if active_object.tile.is_none() && active_object.processors.toxin_resistance == 0 {
+                let damage = toxin_damage(objects.get_toxin(&active_object.pos));
+                if damage > 0 {
+                    active_object.actuators.hp -= damage;
+                    register_damage_effects(&active_object, damage);
+                    if active_object.is_player() {
+                        self.log
+                            .add("Toxins burn through your membrane!", MsgClass::Alert);
+                        self.discover(Topic::Mechanic, "toxins");
+                    }
+                }
+            }
+
             // Random mutation
             // TODO: Perform random mutation when cells are procreating/multiplying, not just by chance every turn.
             // if active_object.dna.raw.is_empty() {
@@ -243,16 +574,24 @@ impl GameState {
             //     }
             // }
 
+            if active_object.tile.is_none() && !active_object.is_player() {
+                self.visibility.refresh(&mut active_object);
+            }
+
             // check whether object is still alive
-            if active_object.actuators.hp == 0 {
+            if active_object.actuators.hp <= 0 {
                 active_object.die(self, objects);
+                if let Some(boss) = active_object.boss_name() {
+                    self.boss_defeated(boss);
+                }
             }
 
             // return object back to objects vector, if still alive
             if !active_object.alive && active_object.physics.is_visible {
-                self.log.add(
+                self.log.add_at(
                     format!("{} died!", active_object.visual.name),
                     MsgClass::Alert,
+                    active_object.pos,
                 );
                 debug!("{} died!", active_object.visual.name);
 
@@ -269,14 +608,22 @@ impl GameState {
                     process_result = ObjectFeedback::GameOver;
                 }
             } else {
+                let is_player = active_object.is_player();
                 objects[self.obj_idx].replace(active_object);
+                if is_player {
+                    self.visibility.update(objects);
+                    bark_in_sight(self, objects);
+                    discover_in_sight(self, objects);
+                }
+            }
+            // walls that were digested or built open up or block the view
+            let changed = objects.take_changed_tiles();
+            if !changed.is_empty() {
+                self.visibility.tiles_changed(objects, &changed);
             }
 
             // finally increase object index and turn counter
-            self.obj_idx = (self.obj_idx + 1) % objects.get_obj_count();
-            if self.obj_idx == PLAYER {
-                self.turn += 1;
-            }
+            self.next_object(objects);
 
             // return the result of our action
             process_result
@@ -287,33 +634,320 @@ impl GameState {
         }
     }
 
-    /// Process an action of the given object.
-    fn process_action(
+    /// Check the invariants of the game, including those of the world. Returns a description of
+    /// each violation.
+    pub fn validate(&self, objects: &GameObjects) -> Vec<String> {
+        let mut violations = Vec::new();
+        let count = objects.get_obj_count();
+        if self.obj_idx >= count {
+            violations.push(format!(
+                "active index {} exceeds {} objects",
+                self.obj_idx, count
+            ));
+        }
+        if self.player_idx >= count {
+            violations.push(format!(
+                "player index {} exceeds {} objects",
+                self.player_idx, count
+            ));
+        } else if !objects[self.player_idx]
+            .as_ref()
+            .is_some_and(|o| o.is_player())
+        {
+            violations.push(format!(
+                "player index {} points to no player",
+                self.player_idx
+            ));
+        }
+        violations.extend(objects.validate());
+        violations
+    }
+
+    /// Hand the turn to the next object and start a new turn once everyone has acted.
+    fn next_object(&mut self, objects: &mut GameObjects) {
+        self.obj_idx = (self.obj_idx + 1) % objects.get_obj_count();
+        if self.obj_idx == PLAYER {
+            self.turn += 1;
+            self.log.set_turn(self.turn);
+            telemetry().record_turn();
+            if innit_env().debug_mode {
+                for violation in self.validate(objects) {
+                    error!("invariant violated in turn {}: {}", self.turn, violation);
+                }
+            }
+            objects.decay_chemicals();
+            objects.update_barriers();
+            self.update_host(objects);
+            self.strike_hour(objects);
+            self.regulate_ecosystem(objects);
+            self.visibility.update(objects);
+            bark_in_sight(self, objects);
+            discover_in_sight(self, objects);
+            self.bookmarks.update(objects);
+        }
+    }
+
+    /// Unlock an entry of the encyclopedia. Newly found species and mechanics are announced,
+    /// traits are too many to mention each. Returns `true` if the entry was new.
+    pub fn discover(&mut self, topic: Topic, id: &str) -> bool {
+        if !self.discoveries.discover(topic, id) {
+            return false;
+        }
+        let name = match topic {
+            Topic::Trait => return true,
+            Topic::Species => id,
+            Topic::Mechanic => mechanic(id).map_or(id, |m| m.name),
+        };
+        self.log
+            .add(format!("New Innitpedia entry: {}.", name), MsgClass::Info);
+        true
+    }
+
+    /// Host time of the current turn.
+    pub fn clock(&self) -> ClockTime {
+        ClockTime::from_turn(self.turn)
+    }
+
+    /// Count the population whenever a census is due, let the ecosystem regulator adjust its rates
+    /// and spawn the organisms it asks for. Species that died out since the last census are
+    /// replaced by a new strain. The regulator's decisions are logged in debug mode.
+    fn regulate_ecosystem(&mut self, objects: &mut GameObjects) {
+        if !self.ecosystem.is_census_due(self.turn) {
+            return;
+        }
+        let population = population(objects);
+        let spawns = self.ecosystem.census(population);
+        let extinct = self.ecosystem.record_lineages(lineages(objects));
+        let mut spawned = 0;
+        if spawns > 0 || !extinct.is_empty() {
+            // problems with the raws have been reported to the player when the game started
+            let raws = self.spawn_raws.take().unwrap_or_else(|| {
+                let mut errors = Vec::new();
+                let raws = SpawnRaws::load(&mut errors);
+                for e in errors {
+                    error!("{}", e);
+                }
+                raws
+            });
+            spawned = spawn_organisms(self, objects, &raws.spawns, &raws.templates, spawns);
+            for species in extinct {
+                if recolonize(self, objects, &raws.spawns, &raws.templates, &species) > 0 {
+                    self.log.add(
+                        format!(
+                            "The last {} died out, a new strain migrates in from afar.",
+                            species
+                        ),
+                        MsgClass::Story,
+                    );
+                }
+            }
+            self.spawn_raws = Some(raws);
+        }
+        let report = format!(
+            "Ecosystem: {} organisms, spawn rate {:.1}, nutrient rate {:.1}, {} spawned.",
+            population, self.ecosystem.spawn_rate, self.ecosystem.nutrient_rate, spawned
+        );
+        debug!("{}", report);
+        if innit_env().debug_mode {
+            self.log.add(report, MsgClass::Info);
+        }
+    }
+
+    /// Let the host follow its daily rhythm whenever its clock strikes a full hour.
+    fn strike_hour(&mut self, objects: &mut GameObjects) {
+        let time = self.clock();
+        if !time.is_full_hour() {
+            return;
+        }
+        self.events.publish(GameEvent::HourStruck(time));
+        if MEAL_HOURS.contains(&time.hour) {
+            self.log.add(
+                "Your host eats, nutrients flood the tissue.",
+                MsgClass::Info,
+            );
+            self.discover(Topic::Mechanic, "meals");
+        }
+        let patrol_interval = innit_env()
+            .difficulty
+            .immune_interval(IMMUNE_PATROL_INTERVAL);
+        if time.is_patrol_hour(patrol_interval) {
+            let message = if time.hour == IMMUNE_PATROL_HOUR {
+                "Your host sleeps, its immune system patrols the tissue."
+            } else {
+                "The immune system of your host patrols the tissue."
+            };
+            self.log.add(message, MsgClass::Story);
+            self.discover(Topic::Mechanic, "immune patrol");
+            self.host.release_antibodies(
+                IMMUNE_PATROL_SIZE,
+                self.rng_streams.get(RngStream::Host),
+                &self.gene_library,
+                objects,
+            );
+        }
+    }
+
+    /// Victory hook, called whenever a boss dies.
+    fn boss_defeated(&mut self, name: String) {
+        info!("boss {} defeated in turn {}", name, self.turn);
+        self.log
+            .add(format!("{} has been defeated!", name), MsgClass::Story);
+        self.defeated_bosses.push(name);
+    }
+
+    /// Process all objects until the current turn is over.
+    pub fn simulate_turn(&mut self, objects: &mut GameObjects) {
+        let turn = self.turn;
+        while self.turn == turn {
+            self.process_object(objects);
+        }
+    }
+
+    /// Announce that the player crossed into another region, with more fanfare the first time.
+    pub fn enter_region(&mut self, region: Region) {
+        if self.discovered_regions.insert(region) {
+            self.log.add(
+                format!("You discover the {}.", region.name()),
+                MsgClass::Story,
+            );
+        } else {
+            self.log
+                .add(format!("You enter the {}.", region.name()), MsgClass::Info);
+        }
+    }
+
+    /// Let the host react to the pathogens and toxins in it, once per turn.
+    fn update_host(&mut self, objects: &mut GameObjects) {
+        for event in self
+            .host
+            .update(self.rng_streams.get(RngStream::Host), objects)
+        {
+            match event {
+                HostEvent::Collapsed => {
+                    self.log.add(
+                        "Your host collapses! Its immune system floods the body with antibodies.",
+                        MsgClass::Story,
+                    );
+                    self.discover(Topic::Mechanic, "collapse");
+                    self.host.release_antibodies(
+                        ANTIBODY_FLOOD,
+                        self.rng_streams.get(RngStream::Host),
+                        &self.gene_library,
+                        objects,
+                    );
+                }
+                HostEvent::Recovered => {
+                    self.log
+                        .add("Your host recovers from its collapse.", MsgClass::Story);
+                    if objects[self.player_idx].as_ref().is_some_and(|p| p.alive) {
+                        self.events.publish(GameEvent::ImmuneWaveSurvived);
+                    }
+                }
+                HostEvent::TreatmentImminent(antibiotic) => {
+                    self.log.add(
+                        format!(
+                            "Your host is about to be treated with {} antibiotics.",
+                            antibiotic.name()
+                        ),
+                        MsgClass::Story,
+                    );
+                }
+                HostEvent::TreatmentStarted(antibiotic) => {
+                    self.log.add(
+                        format!(
+                            "A surge of {} antibiotics floods the host!",
+                            antibiotic.name()
+                        ),
+                        MsgClass::Alert,
+                    );
+                    self.discover(Topic::Mechanic, "antibiotics");
+                }
+                HostEvent::ConditionStarted(condition) => {
+                    self.log.add(condition.onset(), MsgClass::Alert);
+                    self.discover(Topic::Mechanic, condition.name());
+                }
+                HostEvent::ConditionEnded(condition) => {
+                    self.log.add(condition.relief(), MsgClass::Story);
+                }
+                HostEvent::TreatmentEnded(antibiotic) => {
+                    self.log.add(
+                        format!("The {} antibiotics wear off.", antibiotic.name()),
+                        MsgClass::Story,
+                    );
+                    if let Some(player) = objects[self.player_idx].as_mut().filter(|p| p.alive) {
+                        self.events
+                            .publish(GameEvent::TreatmentSurvived(antibiotic));
+                        // the stress of the treatment mobilises dormant stretches of the genome
+                        if player.reactivate_junk(self, 1) {
+                            self.log.add(
+                                "The stress of the treatment reactivates some of your junk DNA.",
+                                MsgClass::Story,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        let inflammation_interval = innit_env()
+            .difficulty
+            .immune_interval(INFLAMMATION_INTERVAL);
+        if self.host.has_condition(HostCondition::Inflammation)
+            && self.turn.is_multiple_of(inflammation_interval as u128)
+        {
+            self.host.release_antibodies(
+                1,
+                self.rng_streams.get(RngStream::Host),
+                &self.gene_library,
+                objects,
+            );
+        }
+    }
+
+    /// Let the host go through the given phase for the given number of turns, e.g. as the effect
+    /// of a story choice.
+    pub fn start_condition(&mut self, condition: HostCondition, turns: u32) {
+        if self.host.start_condition(condition, turns) {
+            self.log.add(condition.onset(), MsgClass::Alert);
+        }
+    }
+
+    /// Process an action of the given object, including all the consequences it entails.
+    /// Returns the highest ranking feedback of the whole chain.
+    pub fn process_action(
+        &mut self,
+        objects: &mut GameObjects,
+        actor: &mut Object,
+        action: Box<dyn Action>,
+    ) -> ObjectFeedback {
+        self.resolve_action(objects, actor, action, 0)
+    }
+
+    fn resolve_action(
         &mut self,
         objects: &mut GameObjects,
         actor: &mut Object,
         action: Box<dyn Action>,
+        depth: usize,
     ) -> ObjectFeedback {
-        // first execute action, then process result and return
         match action.perform(self, objects, actor) {
-            ActionResult::Success { callback } => match callback {
-                ObjectFeedback::NoFeedback => ObjectFeedback::NoFeedback,
-                _ => callback,
-            },
-            ActionResult::Failure => ObjectFeedback::NoAction, // how to handle fails?
+            ActionResult::Success { callback } => callback,
+            ActionResult::Failure => ObjectFeedback::NoAction,
             ActionResult::Consequence {
                 callback,
                 follow_up,
             } => {
-                let consequence_feedback = self.process_action(objects, actor, follow_up);
-                match (&callback, &consequence_feedback) {
-                    (ObjectFeedback::NoFeedback, _) => consequence_feedback,
-                    (ObjectFeedback::NoAction, _) => consequence_feedback,
-                    (ObjectFeedback::GameOver, _) => callback,
-                    (ObjectFeedback::Render, _) => callback,
-                    (ObjectFeedback::UpdateHud, _) => callback,
-                    (ObjectFeedback::GenomeManipulator, _) => callback,
+                if depth >= MAX_CONSEQUENCES {
+                    warn!(
+                        "{} exceeds {} consequences, dropping {}",
+                        actor.visual.name,
+                        MAX_CONSEQUENCES,
+                        follow_up.get_identifier()
+                    );
+                    return callback;
                 }
+                let consequence_feedback =
+                    self.resolve_action(objects, actor, follow_up, depth + 1);
+                callback.max(consequence_feedback)
             }
         }
     }
@@ -0,0 +1,78 @@
+//! The highscore list keeps the best runs of the player, stored in the data directory.
+
+use serde::{Deserialize, Serialize};
+
+use crate::raws::difficulty::STANDARD_DIFFICULTY;
+use crate::util::platform;
+
+const STORAGE_KEY: &str = "highscores.json";
+/// Number of runs kept in the list.
+const MAX_ENTRIES: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Score {
+    pub turns: u128,
+    pub bosses: usize,
+    /// Date of the daily run this score was achieved in, if it was one.
+    #[serde(default)]
+    pub daily: Option<String>,
+    /// Name of the difficulty the run was played at.
+    #[serde(default = "standard_difficulty")]
+    pub difficulty: String,
+}
+
+fn standard_difficulty() -> String {
+    STANDARD_DIFFICULTY.to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Highscores {
+    entries: Vec<Score>,
+}
+
+impl Highscores {
+    pub fn new() -> Self {
+        Highscores::default()
+    }
+
+    /// Read the highscore list from storage, which is empty if nothing is stored yet.
+    pub fn load() -> Self {
+        match platform::read_data(STORAGE_KEY) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| {
+                error!("cannot parse highscores: {}", e);
+                Highscores::new()
+            }),
+            Err(_) => Highscores::new(),
+        }
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|e| e.into())
+            .and_then(|data| platform::write_data(STORAGE_KEY, &data));
+        if let Err(e) = result {
+            error!("cannot save highscores: {}", e);
+        }
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn entries(&self) -> &[Score] {
+        &self.entries
+    }
+
+    /// Add the score, ranked by defeated bosses first and survived turns second. Returns its rank,
+    /// or `None` if it didn't make the list.
+    pub fn insert(&mut self, score: Score) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|s| (score.bosses, score.turns) > (s.bosses, s.turns))
+            .unwrap_or(self.entries.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+        self.entries.insert(rank, score);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
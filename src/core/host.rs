@@ -0,0 +1,363 @@
+//! The host is the organism the game takes place in. Its health suffers from the biomass of the
+//! pathogens spreading through it and the toxins released by dying organisms, and recovers slowly
+//! on its own.
+//!
+//! A collapsing host floods the world with antibodies, whereas a healthy host has fewer nutrients
+//! to spare for the microbes living in it. Every now and then the host is treated with antibiotics,
+//! which harm every organism lacking a matching resistance gene.
+//!
+//! The host also goes through phases that change the rules for a while: a fever makes every action
+//! more costly, an inflammation calls in more immune cells and medication keeps pathogens from
+//! multiplying. The host falls into them as its health declines, story choices can cause them too.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::game_objects::GameObjects;
+use crate::core::position::Position;
+use crate::entity::ai::AiTrailFollower;
+use crate::entity::control::Controller;
+use crate::entity::faction::Faction;
+use crate::entity::genetics::{DnaType, GeneLibrary};
+use crate::entity::object::Object;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::util::game_rng::GameRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+pub const HOST_HEALTH_MAX: f32 = 100.0;
+/// Health the host regains each turn on its own.
+const HOST_RECOVERY: f32 = 0.5;
+/// Health lost per turn for each unit of pathogen biomass in the world.
+const PATHOGEN_PRESSURE: f32 = 0.003;
+/// Health lost per turn for each unit of toxins in the host.
+const TOXIN_PRESSURE: f32 = 0.01;
+/// Fraction of toxins that remains after each turn.
+const TOXIN_RETENTION: f32 = 0.95;
+/// Above this health the host starts to starve its microbes of nutrients.
+const HOST_HEALTHY: f32 = 80.0;
+/// Once collapsed, the host has to recover up to this health before it can collapse again.
+const HOST_RECOVERED: f32 = 50.0;
+/// Percentage of the usual nutrients available while the host is healthy.
+const HEALTHY_NUTRIENTS: i32 = 50;
+/// Number of antibodies released when the host collapses.
+pub const ANTIBODY_FLOOD: usize = 20;
+/// Minimal and maximal number of turns between two antibiotic treatments.
+const TREATMENT_INTERVAL_MIN: u32 = 150;
+const TREATMENT_INTERVAL_MAX: u32 = 300;
+/// Number of turns an antibiotic treatment lasts.
+const TREATMENT_DURATION: u32 = 10;
+/// Number of turns in advance that a treatment is announced.
+const TREATMENT_WARNING: u32 = 10;
+/// Damage dealt each turn of a treatment to organisms without a matching resistance gene.
+pub const ANTIBIOTIC_DAMAGE: i32 = 1;
+/// Below this health the host runs a fever.
+const HOST_FEVERISH: f32 = 60.0;
+/// Above this amount of toxins the host's tissue becomes inflamed.
+const INFLAMMATION_TOXINS: f32 = 40.0;
+/// Number of turns the phases last when the host falls into them on its own.
+const FEVER_DURATION: u32 = 60;
+const INFLAMMATION_DURATION: u32 = 40;
+const MEDICATION_DURATION: u32 = 80;
+/// Extra energy that each action costs during a fever.
+const FEVER_ENERGY_COST: i32 = 1;
+/// Number of turns between two antibodies released during an inflammation.
+pub const INFLAMMATION_INTERVAL: u32 = 10;
+
+/// Classes of antibiotics, each of which can be resisted by its own resistance gene.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum AntibioticClass {
+    BetaLactam,
+    Tetracycline,
+}
+
+const ANTIBIOTIC_CLASSES: [AntibioticClass; 2] =
+    [AntibioticClass::BetaLactam, AntibioticClass::Tetracycline];
+
+impl AntibioticClass {
+    pub fn name(&self) -> &str {
+        match self {
+            AntibioticClass::BetaLactam => "beta-lactam",
+            AntibioticClass::Tetracycline => "tetracycline",
+        }
+    }
+}
+
+/// Phases of the host that change the rules of the simulation while they last.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum HostCondition {
+    /// Every action costs more energy.
+    Fever,
+    /// The immune system releases antibodies at regular intervals.
+    Inflammation,
+    /// Pathogens cannot multiply.
+    Medication,
+}
+
+impl HostCondition {
+    pub fn name(&self) -> &str {
+        match self {
+            HostCondition::Fever => "fever",
+            HostCondition::Inflammation => "inflammation",
+            HostCondition::Medication => "medication",
+        }
+    }
+
+    /// Message that announces the start of the phase.
+    pub fn onset(&self) -> &str {
+        match self {
+            HostCondition::Fever => "Your host runs a fever, every action takes more energy.",
+            HostCondition::Inflammation => "The tissue becomes inflamed and immune cells swarm in.",
+            HostCondition::Medication => {
+                "Your host takes medication, pathogens can no longer multiply."
+            }
+        }
+    }
+
+    /// Message that announces the end of the phase.
+    pub fn relief(&self) -> &str {
+        match self {
+            HostCondition::Fever => "The fever breaks.",
+            HostCondition::Inflammation => "The inflammation subsides.",
+            HostCondition::Medication => "The medication wears off.",
+        }
+    }
+}
+
+/// A phase the host is going through, along with the number of turns it still lasts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConditionPhase {
+    pub condition: HostCondition,
+    pub turns_left: u32,
+}
+
+/// Noteworthy changes of the host's condition.
+#[derive(PartialEq, Debug)]
+pub enum HostEvent {
+    Collapsed,
+    Recovered,
+    TreatmentImminent(AntibioticClass),
+    TreatmentStarted(AntibioticClass),
+    TreatmentEnded(AntibioticClass),
+    ConditionStarted(HostCondition),
+    ConditionEnded(HostCondition),
+}
+
+/// An ongoing antibiotic treatment of the host.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Treatment {
+    pub antibiotic: AntibioticClass,
+    pub turns_left: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Host {
+    pub health: f32,
+    /// Toxins released by dying organisms, slowly washed out over time.
+    pub toxins: f32,
+    /// Whether the host has collapsed and not yet recovered since.
+    pub collapsed: bool,
+    pub treatment: Option<Treatment>,
+    /// Turns until the next antibiotic treatment starts.
+    next_treatment: u32,
+    /// Antibiotic class of the next treatment.
+    next_antibiotic: AntibioticClass,
+    /// Phases the host is currently going through.
+    pub conditions: Vec<ConditionPhase>,
+}
+
+impl Default for Host {
+    fn default() -> Self {
+        Host {
+            health: HOST_HEALTH_MAX,
+            toxins: 0.0,
+            collapsed: false,
+            treatment: None,
+            next_treatment: TREATMENT_INTERVAL_MIN,
+            next_antibiotic: AntibioticClass::BetaLactam,
+            conditions: Vec::new(),
+        }
+    }
+}
+
+impl Host {
+    pub fn new() -> Self {
+        Host::default()
+    }
+
+    /// Advance the host's condition by one turn.
+    pub fn update(&mut self, rng: &mut GameRng, objects: &GameObjects) -> Vec<HostEvent> {
+        let mut events = Vec::new();
+        let (last_health, toxins) = (self.health, self.toxins);
+        let pressure =
+            pathogen_biomass(objects) as f32 * PATHOGEN_PRESSURE + self.toxins * TOXIN_PRESSURE;
+        self.health = (self.health + HOST_RECOVERY - pressure).clamp(0.0, HOST_HEALTH_MAX);
+        self.toxins *= TOXIN_RETENTION;
+
+        for phase in &mut self.conditions {
+            phase.turns_left = phase.turns_left.saturating_sub(1);
+            if phase.turns_left == 0 {
+                events.push(HostEvent::ConditionEnded(phase.condition));
+            }
+        }
+        self.conditions.retain(|phase| phase.turns_left > 0);
+
+        if !self.collapsed && self.health <= 0.0 {
+            self.collapsed = true;
+            events.push(HostEvent::Collapsed);
+        } else if self.collapsed && self.health >= HOST_RECOVERED {
+            self.collapsed = false;
+            events.push(HostEvent::Recovered);
+            // the host is put on medication to keep the pathogens from coming back
+            if self.start_condition(HostCondition::Medication, MEDICATION_DURATION) {
+                events.push(HostEvent::ConditionStarted(HostCondition::Medication));
+            }
+        }
+        if last_health >= HOST_FEVERISH
+            && self.health < HOST_FEVERISH
+            && self.start_condition(HostCondition::Fever, FEVER_DURATION)
+        {
+            events.push(HostEvent::ConditionStarted(HostCondition::Fever));
+        }
+        // the tissue stays inflamed for as long as the toxins keep piling up
+        if toxins > INFLAMMATION_TOXINS
+            && !self.has_condition(HostCondition::Inflammation)
+            && self.start_condition(HostCondition::Inflammation, INFLAMMATION_DURATION)
+        {
+            events.push(HostEvent::ConditionStarted(HostCondition::Inflammation));
+        }
+
+        if let Some(treatment) = &mut self.treatment {
+            treatment.turns_left = treatment.turns_left.saturating_sub(1);
+            if treatment.turns_left == 0 {
+                events.push(HostEvent::TreatmentEnded(treatment.antibiotic));
+                self.treatment = None;
+            }
+        } else {
+            self.next_treatment = self.next_treatment.saturating_sub(1);
+            if self.next_treatment == TREATMENT_WARNING {
+                events.push(HostEvent::TreatmentImminent(self.next_antibiotic));
+            } else if self.next_treatment == 0 {
+                self.treatment = Some(Treatment {
+                    antibiotic: self.next_antibiotic,
+                    turns_left: TREATMENT_DURATION,
+                });
+                events.push(HostEvent::TreatmentStarted(self.next_antibiotic));
+                self.next_treatment = rng.gen_range(TREATMENT_INTERVAL_MIN..TREATMENT_INTERVAL_MAX);
+                self.next_antibiotic = *ANTIBIOTIC_CLASSES.choose(rng).unwrap();
+            }
+        }
+
+        events
+    }
+
+    /// The antibiotic the host is currently treated with, if any.
+    pub fn antibiotic(&self) -> Option<AntibioticClass> {
+        self.treatment.as_ref().map(|t| t.antibiotic)
+    }
+
+    /// Let the host go through the given phase for the given number of turns. A phase that is
+    /// already underway lasts at least as long. Returns whether the phase has just started.
+    pub fn start_condition(&mut self, condition: HostCondition, turns: u32) -> bool {
+        if let Some(phase) = self
+            .conditions
+            .iter_mut()
+            .find(|phase| phase.condition == condition)
+        {
+            phase.turns_left = phase.turns_left.max(turns);
+            false
+        } else {
+            self.conditions.push(ConditionPhase {
+                condition,
+                turns_left: turns,
+            });
+            true
+        }
+    }
+
+    pub fn has_condition(&self, condition: HostCondition) -> bool {
+        self.conditions
+            .iter()
+            .any(|phase| phase.condition == condition)
+    }
+
+    /// Extra energy that actions currently cost.
+    pub fn energy_surcharge(&self) -> i32 {
+        if self.has_condition(HostCondition::Fever) {
+            FEVER_ENERGY_COST
+        } else {
+            0
+        }
+    }
+
+    /// Toxins are released into the host whenever an organism dies.
+    pub fn release_toxins(&mut self, amount: i32) {
+        self.toxins += amount as f32;
+    }
+
+    /// Percentage of the usual nutrients that organisms can metabolise from the host.
+    pub fn nutrients(&self) -> i32 {
+        if self.health >= HOST_HEALTHY {
+            HEALTHY_NUTRIENTS
+        } else {
+            100
+        }
+    }
+
+    /// Release the given number of antibodies at random open positions of the world.
+    pub fn release_antibodies(
+        &self,
+        count: usize,
+        rng: &mut GameRng,
+        gene_library: &GeneLibrary,
+        objects: &mut GameObjects,
+    ) {
+        let mut released = 0;
+        // give up eventually on very crowded worlds
+        for _ in 0..count * 10 {
+            if released == count {
+                break;
+            }
+            let pos = Position::new(
+                rng.gen_range(1..WORLD_WIDTH - 1),
+                rng.gen_range(1..WORLD_HEIGHT - 1),
+            );
+            if !objects.is_pos_blocked(&pos) && !objects.is_pos_occupied(&pos) {
+                objects.push(antibody(gene_library, pos));
+                released += 1;
+            }
+        }
+    }
+}
+
+/// Total biomass of all living viruses in the world.
+fn pathogen_biomass(objects: &GameObjects) -> i32 {
+    objects
+        .get_non_tiles()
+        .iter()
+        .flatten()
+        .filter(|o| o.alive && o.dna.dna_type == DnaType::Rna)
+        .map(|o| o.actuators.biomass)
+        .sum()
+}
+
+/// Antibodies hunt down anything that leaves a trail, pathogen or not.
+pub fn antibody(gene_library: &GeneLibrary, pos: Position) -> Object {
+    let traits: Vec<String> = ["Move", "Attack", "Chemoreceptor", "Cell Membrane"]
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+    let raw_dna = gene_library
+        .trait_strs_to_dna(&traits)
+        .expect("antibody traits are part of the gene library");
+    let (s, p, a, mut d) = gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+    d.raw = raw_dna;
+    Object::new()
+        .position(pos.x, pos.y)
+        .living(true)
+        .visualize("Antibody", 'Y', (255, 230, 90))
+        .physical(true, false, false)
+        .faction(Faction::ImmuneSystem)
+        .control(Controller::Npc(Box::new(AiTrailFollower::new())))
+        .genome(0.99, (s, p, a, d))
+}
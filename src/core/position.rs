@@ -52,6 +52,13 @@ impl Position {
             && ((other.x - self.x) - (other.y - self.y)).abs() == 1
     }
 
+    /// Check whether the other position is one of the eight surrounding positions, including
+    /// diagonals.
+    pub fn is_neighbour(&self, other: &Position) -> bool {
+        let (dx, dy) = self.offset(other);
+        dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0)
+    }
+
     pub fn offset(&self, other: &Position) -> (i32, i32) {
         (other.x - self.x, other.y - self.y)
     }
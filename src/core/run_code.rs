@@ -0,0 +1,126 @@
+//! Run codes are short strings that describe the starting conditions of a run, so that players can
+//! share them and play the very same world. A code holds the world seed and a hash of the settings
+//! that affect the game, to tell whether both players play under the same conditions.
+//!
+//! The code is written in Crockford's base 32, which avoids easily confused characters and ignores
+//! case, and is split into groups with dashes for readability.
+
+use crate::core::innit_env;
+use crate::raws::difficulty::STANDARD_DIFFICULTY;
+use crate::util::fnv_hash;
+
+const VERSION: u8 = 1;
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// Number of characters between two dashes.
+const GROUP_LEN: usize = 5;
+/// Length of the encoded data: version, seed and settings hash.
+const CODE_BYTES: usize = 1 + 8 + 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunCode {
+    pub seed: u64,
+    pub settings_hash: u32,
+}
+
+impl RunCode {
+    /// Describe a run with the given seed under the current settings.
+    pub fn new(seed: u64) -> Self {
+        RunCode {
+            seed,
+            settings_hash: settings_hash(),
+        }
+    }
+
+    /// Check whether the run was shared under the same settings as the current ones.
+    pub fn matches_settings(&self) -> bool {
+        self.settings_hash == settings_hash()
+    }
+
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![VERSION];
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.settings_hash.to_le_bytes());
+        let chars = to_base32(&bytes);
+        chars
+            .as_bytes()
+            .chunks(GROUP_LEN)
+            .map(|group| String::from_utf8_lossy(group).to_string())
+            .collect::<Vec<String>>()
+            .join("-")
+    }
+
+    pub fn decode(code: &str) -> Result<RunCode, String> {
+        let bytes = from_base32(code)?;
+        if bytes.len() != CODE_BYTES {
+            return Err("the code has the wrong length".to_string());
+        }
+        if bytes[0] != VERSION {
+            return Err(format!("unknown code version {}", bytes[0]));
+        }
+        let mut seed = [0; 8];
+        seed.copy_from_slice(&bytes[1..9]);
+        let mut settings_hash = [0; 4];
+        settings_hash.copy_from_slice(&bytes[9..13]);
+        Ok(RunCode {
+            seed: u64::from_le_bytes(seed),
+            settings_hash: u32::from_le_bytes(settings_hash),
+        })
+    }
+}
+
+/// Hash of the settings that change how the game plays.
+fn settings_hash() -> u32 {
+    let env = innit_env();
+    let mut settings = format!(
+        "{}|{}|{:?}|{:?}|{}",
+        env.debug_mode, env.omniscient, env.data_dir, env.mod_dir, env.max_dna_len
+    );
+    // codes of runs at the standard difficulty stay the same as before difficulties existed
+    if env.difficulty.name != STANDARD_DIFFICULTY {
+        settings.push_str(&format!("|{}", env.difficulty.name));
+    }
+    fnv_hash(settings.as_bytes()) as u32
+}
+
+fn to_base32(bytes: &[u8]) -> String {
+    let mut chars = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            chars.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        chars.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    chars
+}
+
+fn from_base32(code: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in code.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+        // the letters that look like digits are read as those digits
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let value = ALPHABET
+            .iter()
+            .position(|a| *a as char == c)
+            .ok_or_else(|| format!("invalid character '{}'", c))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
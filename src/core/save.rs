@@ -0,0 +1,102 @@
+//! Save games are stored through a `SaveBackend`, so that the game loop doesn't need to know
+//! where they end up.
+
+use std::error::Error;
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::util::platform;
+
+/// Number of turns between two checkpoints of a run in checkpoint mode.
+pub const CHECKPOINT_INTERVAL: u128 = 100;
+/// Turns taken off the score for each respawn at a checkpoint.
+pub const RESPAWN_PENALTY: u128 = 250;
+
+/// Storage for a single save game.
+pub trait SaveBackend {
+    /// Store the serialized save game, replacing the previous one.
+    fn write(&mut self, data: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Retrieve the serialized save game.
+    fn read(&self) -> Result<String, Box<dyn Error>>;
+
+    /// Delete the save game, if there is one.
+    fn delete(&mut self) -> Result<(), Box<dyn Error>>;
+
+    /// Serialize and store GameState and Objects.
+    fn save_game(
+        &mut self,
+        state: &GameState,
+        objects: &GameObjects,
+    ) -> Result<(), Box<dyn Error>> {
+        let save_data = serde_json::to_string(&(state, objects))?;
+        self.write(&save_data)?;
+        debug!("SAVED GAME");
+        Ok(())
+    }
+
+    /// Count a respawn against the stored checkpoint right away, so that resuming it from the main
+    /// menu doesn't dodge the penalty.
+    fn charge_respawn(&mut self) -> Result<(), Box<dyn Error>> {
+        let (mut state, objects) = self.load_game()?;
+        state.respawns += 1;
+        self.save_game(&state, &objects)
+    }
+
+    /// Retrieve and deserialize GameState and Objects.
+    fn load_game(&self) -> Result<(GameState, GameObjects), Box<dyn Error>> {
+        let json_save_state = self.read()?;
+        let result = serde_json::from_str::<(GameState, GameObjects)>(&json_save_state)?;
+        Ok(result)
+    }
+}
+
+/// Saves into the system data directory, or the local storage of the page in the browser.
+pub struct PlatformSave {
+    key: &'static str,
+}
+
+impl PlatformSave {
+    pub fn new() -> Self {
+        PlatformSave { key: "savegame" }
+    }
+}
+
+impl SaveBackend for PlatformSave {
+    fn write(&mut self, data: &str) -> Result<(), Box<dyn Error>> {
+        platform::write_data(self.key, data)
+    }
+
+    fn read(&self) -> Result<String, Box<dyn Error>> {
+        platform::read_data(self.key)
+    }
+
+    fn delete(&mut self) -> Result<(), Box<dyn Error>> {
+        platform::remove_data(self.key)
+    }
+}
+
+/// Keeps the save game in memory only, for tests and headless runs.
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Default)]
+pub struct MemorySave {
+    data: Option<String>,
+}
+
+impl SaveBackend for MemorySave {
+    fn write(&mut self, data: &str) -> Result<(), Box<dyn Error>> {
+        self.data = Some(data.to_string());
+        Ok(())
+    }
+
+    fn read(&self) -> Result<String, Box<dyn Error>> {
+        self.data
+            .clone()
+            .ok_or_else(|| "no game has been saved".into())
+    }
+
+    fn delete(&mut self) -> Result<(), Box<dyn Error>> {
+        self.data = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+//! Snapshots capture the world for bug reports and sharing: a plain text map of the world and a
+//! JSON dump of every object in it. Both end up in the data directory, next to the screenshot
+//! taken by the game loop.
+
+use std::error::Error;
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::util::platform;
+
+/// Directory within the data directory that all snapshots are stored in.
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Draw the whole world as plain text, regardless of what the player has explored. Walls are
+/// drawn as `#`, open tiles as `.` and everything else with its own glyph.
+pub fn ascii_map(objects: &GameObjects) -> String {
+    let mut map = vec![vec![' '; WORLD_WIDTH as usize]; WORLD_HEIGHT as usize];
+    let mut put = |x: i32, y: i32, glyph: char| {
+        if (0..WORLD_WIDTH).contains(&x) && (0..WORLD_HEIGHT).contains(&y) {
+            map[y as usize][x as usize] = glyph;
+        }
+    };
+
+    let (tiles, mut others): (Vec<_>, Vec<_>) = objects
+        .get_vector()
+        .iter()
+        .flatten()
+        .partition(|o| o.tile.is_some());
+    for tile in tiles {
+        let glyph = if tile.physics.is_blocking { '#' } else { '.' };
+        put(tile.pos.x, tile.pos.y, glyph);
+    }
+    // blocking objects are drawn last, just like in the game
+    others.sort_by_key(|o| o.physics.is_blocking);
+    for object in others {
+        put(object.pos.x, object.pos.y, object.visual.glyph);
+    }
+
+    map.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Base name of the snapshot files for the current turn, without extension.
+pub fn snapshot_name(state: &GameState) -> String {
+    format!("{}/turn_{}", SNAPSHOT_DIR, state.turn)
+}
+
+/// Write the text map and the object dump of the current turn to the data directory. Returns the
+/// base name of the written files.
+pub fn export_snapshot(state: &GameState, objects: &GameObjects) -> Result<String, Box<dyn Error>> {
+    let name = snapshot_name(state);
+    platform::write_data(&format!("{}_map.txt", name), &ascii_map(objects))?;
+    platform::write_data(
+        &format!("{}_objects.json", name),
+        &serde_json::to_string_pretty(objects)?,
+    )?;
+    Ok(name)
+}
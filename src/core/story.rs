@@ -0,0 +1,163 @@
+//! The story accompanies the player's run with vignettes at its milestones. Milestones are defined
+//! in the raws and reached by game events, each at most once per run. A milestone can offer
+//! choices, each of which modifies the run in its own way.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::clock::ClockTime;
+use crate::core::events::GameEvent;
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::{GameState, MessageLog, MsgClass};
+use crate::core::host::HostCondition;
+use crate::entity::faction::Faction;
+
+/// Kinds of game events that a milestone can be reached by.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum StoryTrigger {
+    GenomeChanged,
+    BloodstreamReached,
+    ImmuneWaveSurvived,
+    TreatmentSurvived,
+    PredatorKilled,
+    /// The host's clock reached the given time.
+    Clock(ClockTime),
+}
+
+impl StoryTrigger {
+    fn matches(&self, event: &GameEvent) -> bool {
+        match self {
+            StoryTrigger::GenomeChanged => matches!(event, GameEvent::GenomeChanged { .. }),
+            StoryTrigger::BloodstreamReached => matches!(event, GameEvent::BloodstreamReached),
+            StoryTrigger::ImmuneWaveSurvived => matches!(event, GameEvent::ImmuneWaveSurvived),
+            StoryTrigger::TreatmentSurvived => matches!(event, GameEvent::TreatmentSurvived(_)),
+            StoryTrigger::PredatorKilled => matches!(event, GameEvent::PredatorKilled { .. }),
+            StoryTrigger::Clock(time) => {
+                matches!(event, GameEvent::HourStruck(now) if now >= time)
+            }
+        }
+    }
+}
+
+/// Lasting effect of a story choice on the player's run.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum RunModifier {
+    /// Gain energy, any overflow goes into the reserve.
+    Energy(i32),
+    /// Raise or lower the maximum hp, along with the current hp.
+    MaxHp(i32),
+    /// Shift the standing between the player's lineage and another faction.
+    Standing { faction: Faction, delta: i32 },
+    /// Turn up to the given number of the player's junk genes into functional genes.
+    ReactivateJunk(usize),
+    /// Let the host go through a phase for the given number of turns.
+    Condition {
+        condition: HostCondition,
+        turns: u32,
+    },
+}
+
+impl RunModifier {
+    pub fn describe(&self) -> String {
+        match self {
+            RunModifier::Energy(amount) => format!("{:+} energy", amount),
+            RunModifier::MaxHp(amount) => format!("{:+} max hp", amount),
+            RunModifier::Standing { faction, delta } => {
+                format!("{:+} standing with {}", delta, faction.name())
+            }
+            RunModifier::ReactivateJunk(count) => format!("reactivate {} junk genes", count),
+            RunModifier::Condition { condition, turns } => {
+                format!("host {} for {} turns", condition.name(), turns)
+            }
+        }
+    }
+
+    /// Apply the modifier to the player.
+    pub fn apply(&self, state: &mut GameState, objects: &mut GameObjects) {
+        match self {
+            RunModifier::Energy(amount) => {
+                if let Some(player) = objects[state.player_idx].as_mut() {
+                    if *amount > 0 {
+                        player.store_energy(*amount);
+                    } else {
+                        player.processors.energy = (player.processors.energy + amount).max(0);
+                    }
+                }
+            }
+            RunModifier::MaxHp(amount) => {
+                if let Some(player) = objects[state.player_idx].as_mut() {
+                    player.actuators.max_hp = (player.actuators.max_hp + amount).max(1);
+                    player.actuators.hp =
+                        (player.actuators.hp + amount).clamp(1, player.actuators.max_hp);
+                }
+            }
+            RunModifier::Standing { faction, delta } => {
+                state.relations.shift(Faction::Lineage, *faction, *delta);
+            }
+            RunModifier::ReactivateJunk(count) => {
+                if let Some(player) = objects[state.player_idx].as_mut() {
+                    player.reactivate_junk(state, *count);
+                }
+            }
+            RunModifier::Condition { condition, turns } => {
+                state.start_condition(*condition, *turns);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StoryChoice {
+    pub label: String,
+    #[serde(default)]
+    pub modifiers: Vec<RunModifier>,
+}
+
+impl StoryChoice {
+    /// Label of the choice followed by its effects, if any.
+    pub fn to_text(&self) -> String {
+        if self.modifiers.is_empty() {
+            self.label.clone()
+        } else {
+            let effects: Vec<String> = self.modifiers.iter().map(|m| m.describe()).collect();
+            format!("{} ({})", self.label, effects.join(", "))
+        }
+    }
+
+    /// Apply all modifiers of the choice and tell the player about it.
+    pub fn apply(&self, state: &mut GameState, objects: &mut GameObjects) {
+        for modifier in &self.modifiers {
+            modifier.apply(state, objects);
+        }
+        state.log.add(self.to_text(), MsgClass::Story);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Milestone {
+    pub id: String,
+    pub trigger: StoryTrigger,
+    pub title: String,
+    /// The vignette, one paragraph per line.
+    pub text: Vec<String>,
+    /// Choices offered to the player. Without choices the vignette is merely shown.
+    #[serde(default)]
+    pub choices: Vec<StoryChoice>,
+}
+
+/// Find the milestones that the events reach for the first time in this run and mark them as
+/// reached. Returns the newly reached milestones in the order of the events.
+pub fn reach(
+    state: &mut GameState,
+    milestones: &[Milestone],
+    events: &[GameEvent],
+) -> Vec<Milestone> {
+    let mut reached = Vec::new();
+    for event in events {
+        for milestone in milestones.iter().filter(|m| m.trigger.matches(event)) {
+            if state.reached_milestones.insert(milestone.id.clone()) {
+                reached.push(milestone.clone());
+            }
+        }
+    }
+    reached
+}
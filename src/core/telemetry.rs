@@ -0,0 +1,118 @@
+//! Counters of the work that the scheduler does, to give performance work on it data to target.
+//! The counters are shown in the debug overlay and can be exported to the data directory.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use serde::Serialize;
+
+use crate::util::platform;
+
+/// Directory within the data directory that all telemetry exports are stored in.
+pub const TELEMETRY_DIR: &str = "telemetry";
+
+/// Number and duration of the decisions of one kind of AI.
+#[derive(Debug, Default, Serialize, Clone, Copy)]
+struct DecisionTime {
+    decisions: u64,
+    /// Time in [ns] spent on all decisions.
+    total_ns: u64,
+}
+
+impl DecisionTime {
+    /// Average time in [µs] of a decision.
+    fn average_us(&self) -> f64 {
+        if self.decisions == 0 {
+            0.0
+        } else {
+            self.total_ns as f64 / self.decisions as f64 / 1000.0
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Telemetry {
+    /// Frames in which objects took their turns.
+    frames: u64,
+    /// Actions processed over all frames.
+    actions: u64,
+    /// Actions processed in the busiest frame.
+    max_actions_per_frame: u64,
+    /// Decisions per kind of AI.
+    decisions: BTreeMap<&'static str, DecisionTime>,
+    /// Number of times any DNA was decoded into traits.
+    decodes: u64,
+    /// Turns that passed.
+    turns: u64,
+}
+
+impl Telemetry {
+    pub fn new() -> Self {
+        Telemetry::default()
+    }
+
+    /// Account for a frame in which objects took the given number of actions.
+    pub fn record_frame(&mut self, actions: u64) {
+        self.frames += 1;
+        self.actions += actions;
+        self.max_actions_per_frame = self.max_actions_per_frame.max(actions);
+    }
+
+    /// Account for a decision of an AI of the given kind that took the given time in [ns].
+    pub fn record_decision(&mut self, kind: &'static str, time_ns: u128) {
+        let decision_time = self.decisions.entry(kind).or_default();
+        decision_time.decisions += 1;
+        decision_time.total_ns += time_ns as u64;
+    }
+
+    pub fn record_decode(&mut self) {
+        self.decodes += 1;
+    }
+
+    pub fn record_turn(&mut self) {
+        self.turns += 1;
+    }
+
+    pub fn actions_per_frame(&self) -> f64 {
+        if self.frames == 0 {
+            0.0
+        } else {
+            self.actions as f64 / self.frames as f64
+        }
+    }
+
+    pub fn max_actions_per_frame(&self) -> u64 {
+        self.max_actions_per_frame
+    }
+
+    pub fn decodes_per_turn(&self) -> f64 {
+        if self.turns == 0 {
+            0.0
+        } else {
+            self.decodes as f64 / self.turns as f64
+        }
+    }
+
+    /// Summary of all counters, one line each, as shown in the debug overlay.
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "actions/frame: {:.1} (max {})",
+                self.actions_per_frame(),
+                self.max_actions_per_frame()
+            ),
+            format!("decodes/turn: {:.2}", self.decodes_per_turn()),
+        ];
+        for (kind, decision_time) in &self.decisions {
+            lines.push(format!("{}: {:.1} us", kind, decision_time.average_us()));
+        }
+        lines
+    }
+
+    /// Write all counters as JSON to the data directory. Returns the name of the file.
+    pub fn export(&self, turn: u128) -> Result<String, Box<dyn Error>> {
+        let name = format!("{}/turn_{}.json", TELEMETRY_DIR, turn);
+        platform::write_data(&name, &serde_json::to_string_pretty(self)?)?;
+        Ok(name)
+    }
+}
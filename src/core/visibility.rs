@@ -0,0 +1,130 @@
+//! The visibility system keeps track of what the player can currently sense and what they have
+//! explored so far. It is updated while the turns are processed, so that the game logic and saved
+//! games always agree with what is on screen, while the renderer merely draws the result.
+
+use crate::core::game_objects::GameObjects;
+use crate::core::innit_env;
+use crate::core::position::Position;
+use crate::entity::object::Object;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use rltk::field_of_view;
+
+#[derive(Debug, Clone, Default)]
+pub struct Visibility {
+    /// Positions the world is sensed from, together with their sensing ranges.
+    viewers: Vec<(Position, i32)>,
+    /// Field of view of each viewer.
+    fields: Vec<Vec<Position>>,
+    /// Whether each position of the world is in sight, in row-major order.
+    visible: Vec<bool>,
+    /// Free roaming viewpoint that replaces the senses of the player, e.g. while spectating.
+    camera: Option<(Position, i32)>,
+    /// Whether everything is visible, regardless of the viewers.
+    omniscient: bool,
+}
+
+impl Visibility {
+    pub fn new() -> Self {
+        Visibility::default()
+    }
+
+    pub fn viewers(&self) -> &[(Position, i32)] {
+        &self.viewers
+    }
+
+    pub fn camera(&self) -> Option<Position> {
+        self.camera.map(|(pos, _)| pos)
+    }
+
+    /// Sense the world from the given position and range instead of the player's position.
+    pub fn set_camera(&mut self, camera: Option<(Position, i32)>) {
+        self.camera = camera;
+    }
+
+    pub fn is_visible(&self, pos: &Position) -> bool {
+        self.omniscient
+            || ((0..WORLD_WIDTH).contains(&pos.x)
+                && (0..WORLD_HEIGHT).contains(&pos.y)
+                && self
+                    .visible
+                    .get((pos.y * WORLD_WIDTH + pos.x) as usize)
+                    .copied()
+                    .unwrap_or(false))
+    }
+
+    /// Recompute the fields of view of all viewers, then update which objects are visible and
+    /// mark the tiles in sight as explored.
+    pub fn update(&mut self, objects: &mut GameObjects) {
+        self.omniscient = innit_env().omniscient;
+        self.viewers = match self.camera {
+            Some(camera) => vec![camera],
+            None => objects
+                .get_vector()
+                .iter()
+                .flatten()
+                .filter(|o| o.is_player())
+                .map(|o| (o.pos, o.sensors.sensing_range))
+                .collect(),
+        };
+
+        // everything is recomputed anyway
+        objects.take_changed_tiles();
+        self.fields = self
+            .viewers
+            .iter()
+            .map(|(pos, range)| field_of_view_of(pos, *range, objects))
+            .collect();
+        self.apply_fields(objects);
+    }
+
+    /// Recompute only the fields of view that reach any of the given tiles, which have changed
+    /// since the last update.
+    pub fn tiles_changed(&mut self, objects: &mut GameObjects, changed: &[Position]) {
+        let mut is_affected = false;
+        for ((pos, range), field) in self.viewers.iter().zip(self.fields.iter_mut()) {
+            if changed
+                .iter()
+                .any(|p| p.distance(pos) <= (*range + 1) as f32)
+            {
+                *field = field_of_view_of(pos, *range, objects);
+                is_affected = true;
+            }
+        }
+        if is_affected {
+            self.apply_fields(objects);
+        }
+    }
+
+    /// Mark everything within the fields of view as visible and refresh all objects accordingly.
+    fn apply_fields(&mut self, objects: &mut GameObjects) {
+        self.visible = vec![false; (WORLD_WIDTH * WORLD_HEIGHT) as usize];
+        for p in self.fields.iter().flatten() {
+            self.visible[(p.y * WORLD_WIDTH + p.x) as usize] = true;
+        }
+
+        for object in objects.get_vector_mut().iter_mut().flatten() {
+            self.refresh(object);
+        }
+    }
+
+    /// Update whether a single object is visible, e.g. after it has moved, without recomputing
+    /// the fields of view. Multi-tile organisms are visible as soon as any of their segments is.
+    pub fn refresh(&self, object: &mut Object) {
+        object.physics.is_visible =
+            self.is_visible(&object.pos) || object.segments.iter().any(|s| self.is_visible(&s.pos));
+        if let Some(tile) = &mut object.tile {
+            if object.physics.is_visible {
+                tile.is_explored = true;
+            }
+        }
+    }
+}
+
+/// Positions within the world that can be seen from `pos` up to the given range.
+fn field_of_view_of(pos: &Position, range: i32, objects: &GameObjects) -> Vec<Position> {
+    field_of_view((*pos).into(), range, objects)
+        .into_iter()
+        .filter(|p| (0..WORLD_WIDTH).contains(&p.x) && (0..WORLD_HEIGHT).contains(&p.y))
+        .map(Position::from)
+        .collect()
+}
@@ -1,14 +1,27 @@
 //! The world generation module contains the trait that all world generators have to implement to
 //! be changeably used to create the game environments.
 
+pub mod connectivity;
+pub mod gates;
+pub mod prefab;
 pub mod world_gen_organic;
 pub mod world_gen_rogue;
 
 use crate::core::game_objects::GameObjects;
 use crate::core::game_state::GameState;
-use crate::entity::object::Object;
+use crate::entity::action::registry::action_registry;
+use crate::entity::action::Target;
+use crate::entity::ai::ai_from_name;
+use crate::entity::control::Controller;
+use crate::entity::genetics::{DnaType, GeneticTrait};
+use crate::entity::object::{InventoryItem, Object};
+use crate::entity::player::PlayerCtrl;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
 use crate::raws::object_template::ObjectTemplate;
 use crate::raws::spawn::Spawn;
+use crate::util::game_rng::GameRng;
+use crate::util::game_rng::RngStream;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 /// The world generation trait only requests to implement a method that
@@ -26,11 +39,414 @@ pub trait WorldGen {
     fn get_player_start_pos(&self) -> (i32, i32);
 }
 
+/// Create a new organism or item at the given position as described by an object template.
+pub fn object_from_template(
+    state: &mut GameState,
+    template: &ObjectTemplate,
+    x: i32,
+    y: i32,
+) -> Result<Object, String> {
+    let controller: Option<Controller> = if let Some(ctrl) = &template.controller {
+        match ctrl.as_str() {
+            "player" => Some(Controller::Player(PlayerCtrl::new())),
+            s => Some(Controller::Npc(ai_from_name(s)?)),
+        }
+    } else {
+        None
+    };
+
+    let raw_dna = state.gene_library.dna_from_template(
+        state.rng_streams.get(RngStream::WorldGen),
+        &template.dna_template,
+        template.dna_type == DnaType::Rna,
+    )?;
+
+    let inventory_item = if let Some(item) = &template.item {
+        let action_instance = if item.action.is_empty() {
+            None
+        } else {
+            match action_registry().create(&item.action, &item.action_params) {
+                Ok(action) => Some(action),
+                Err(msg) => return Err(format!("error getting action from string: {}", msg)),
+            }
+        };
+        Some(InventoryItem::new(&item.name, action_instance))
+    } else {
+        None
+    };
+
+    let object = Object::new()
+        .position(x, y)
+        .living(true)
+        .visualize(template.npc.as_str(), template.glyph, template.color)
+        .physical(
+            template.physics.is_blocking,
+            template.physics.is_blocking_sight,
+            template.physics.is_always_visible,
+        )
+        .control_opt(controller)
+        .itemize(inventory_item)
+        .faction(template.faction)
+        .barks(template.barks.clone())
+        .drops(template.drops);
+    // items without a genome, like organelles, have no traits to express
+    let object = if raw_dna.is_empty() {
+        object
+    } else {
+        object.genome(
+            template.stability,
+            state
+                .gene_library
+                .dna_to_traits(template.dna_type, &raw_dna),
+        )
+    };
+    // each segment is as tough as the head
+    let segment_hp = object.actuators.max_hp;
+    Ok(object.segmented(template.segments, segment_hp))
+}
+
+/// Strength of a freshly laid scent. Scents decay by one each turn.
+pub const SCENT_MAX: i32 = 8;
+/// Highest concentration of toxins a tile can hold. Toxins decay by one each turn.
+pub const TOXIN_MAX: i32 = 12;
+/// Concentration of toxins that deals one point of damage per turn to organisms without
+/// antitoxins.
+const TOXIN_PER_DAMAGE: i32 = 4;
+
+/// Amount of digestion a wall withstands before it dissolves into floor.
+pub const WALL_INTEGRITY: i32 = 6;
+
+/// Damage that toxins of the given concentration deal to an organism each turn.
+pub fn toxin_damage(toxin: i32) -> i32 {
+    (toxin.max(0) + TOXIN_PER_DAMAGE - 1) / TOXIN_PER_DAMAGE
+}
+
+/// Conditions of a healthy host, which every organism is adapted to.
+pub const BODY_TEMPERATURE: f32 = 37.0;
+pub const BODY_PH: f32 = 7.4;
+/// Deviation from the healthy conditions that organisms tolerate without any tolerance genes.
+/// Each tolerance gene widens this range by the same amount again.
+const TEMPERATURE_COMFORT: f32 = 1.5;
+const PH_COMFORT: f32 = 0.3;
+/// Light that photosynthesis genes need to yield energy.
+const PHOTOSYNTHESIS_LIGHT: f32 = 0.5;
+/// Nutrient richness that chemosynthesis genes need to yield energy.
+const CHEMOSYNTHESIS_NUTRIENTS: f32 = 1.2;
+
+/// Local conditions of the host's tissue that organisms have to cope with.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Environment {
+    /// Temperature in degrees Celsius.
+    pub temperature: f32,
+    pub ph: f32,
+    /// Nutrient richness relative to the host's supply, 1.0 being the regular supply.
+    #[serde(default = "default_nutrients")]
+    pub nutrients: f32,
+    /// Light shining through the thin tissue close to the host's surface, from 0 to 1.
+    #[serde(default)]
+    pub light: f32,
+}
+
+fn default_nutrients() -> f32 {
+    1.0
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment {
+            temperature: BODY_TEMPERATURE,
+            ph: BODY_PH,
+            nutrients: default_nutrients(),
+            light: 0.0,
+        }
+    }
+}
+
+impl Environment {
+    /// How much the conditions stress an organism with the given number of heat and pH tolerance
+    /// genes. Zero means the organism is comfortable, each step above costs extra energy.
+    pub fn stress(&self, heat_tolerance: i32, ph_tolerance: i32) -> i32 {
+        let excess = |deviation: f32, comfort: f32, tolerance: i32| {
+            ((deviation - comfort * (1 + tolerance) as f32) / comfort)
+                .ceil()
+                .max(0.0) as i32
+        };
+        excess(
+            (self.temperature - BODY_TEMPERATURE).abs(),
+            TEMPERATURE_COMFORT,
+            heat_tolerance,
+        ) + excess((self.ph - BODY_PH).abs(), PH_COMFORT, ph_tolerance)
+    }
+
+    /// Amount of nutrients an organism can take up here, given the host's overall supply.
+    pub fn nutrient_supply(&self, host_nutrients: i32) -> i32 {
+        (host_nutrients as f32 * self.nutrients).round() as i32
+    }
+
+    /// Energy that producers harvest here each turn, one point per photosynthesis gene on lit
+    /// tiles and one per chemosynthesis gene on nutrient-rich tiles.
+    pub fn production(&self, photosynthesis: i32, chemosynthesis: i32) -> i32 {
+        let mut energy = 0;
+        if self.light >= PHOTOSYNTHESIS_LIGHT {
+            energy += photosynthesis;
+        }
+        if self.nutrients >= CHEMOSYNTHESIS_NUTRIENTS {
+            energy += chemosynthesis;
+        }
+        energy
+    }
+}
+
+/// Lay temperature and pH gradients over the world. Inflamed hot spots and cool patches warp the
+/// temperature, while the pH shifts steadily from one end of the world to the other, interrupted
+/// by acidic pockets. Light falls in from the host's surface at the top of the world and reaches
+/// less deep on deeper levels. Deeper levels have more extreme conditions.
+pub fn generate_environment(objects: &mut GameObjects, rng: &mut GameRng, level: u32) {
+    let severity = level as f32;
+    let spots: Vec<(f32, f32, f32)> = (0..rng.gen_range(2..5))
+        .map(|_| {
+            let amplitude = rng.gen_range(1.0..3.0 + severity);
+            let sign = if rng.gen_bool(0.7) { 1.0 } else { -1.0 };
+            (
+                rng.gen_range(0.0..WORLD_WIDTH as f32),
+                rng.gen_range(0.0..WORLD_HEIGHT as f32),
+                sign * amplitude,
+            )
+        })
+        .collect();
+    let pockets: Vec<(f32, f32, f32)> = (0..rng.gen_range(0..3))
+        .map(|_| {
+            (
+                rng.gen_range(0.0..WORLD_WIDTH as f32),
+                rng.gen_range(0.0..WORLD_HEIGHT as f32),
+                -rng.gen_range(0.5..0.5 + 0.3 * severity),
+            )
+        })
+        .collect();
+    let ph_slope = rng.gen_range(-0.3..0.3) * severity;
+    let spread = 2.0 * 10.0_f32.powi(2);
+
+    let influence = |sources: &[(f32, f32, f32)], x: f32, y: f32| -> f32 {
+        sources
+            .iter()
+            .map(|(sx, sy, amplitude)| {
+                amplitude * (-((x - sx).powi(2) + (y - sy).powi(2)) / spread).exp()
+            })
+            .sum()
+    };
+
+    for y in 0..WORLD_HEIGHT {
+        for x in 0..WORLD_WIDTH {
+            let (fx, fy) = (x as f32, y as f32);
+            if let Some(tile) = objects
+                .get_tile_at(x as usize, y as usize)
+                .as_mut()
+                .and_then(|o| o.tile.as_mut())
+            {
+                tile.environment = Environment {
+                    temperature: BODY_TEMPERATURE + influence(&spots, fx, fy),
+                    ph: BODY_PH
+                        + ph_slope * (fx / WORLD_WIDTH as f32 - 0.5)
+                        + influence(&pockets, fx, fy),
+                    light: (1.0 - fy * (1.0 + severity) / WORLD_HEIGHT as f32).max(0.0),
+                    ..tile.environment
+                };
+            }
+        }
+    }
+}
+
+/// Size of the square blocks that the world is partitioned into regions by.
+pub const REGION_BLOCK: i32 = 10;
+/// Tiles with a current that turn a block into a capillary bed.
+const CAPILLARY_CURRENTS: i32 = 3;
+
+/// Named parts of the host's tissue.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Region {
+    #[default]
+    MucusLayer,
+    StomachLining,
+    CapillaryBed,
+}
+
+impl Region {
+    pub const ALL: [Region; 3] = [
+        Region::MucusLayer,
+        Region::StomachLining,
+        Region::CapillaryBed,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Region::MucusLayer => "mucus layer",
+            Region::StomachLining => "stomach lining",
+            Region::CapillaryBed => "capillary bed",
+        }
+    }
+}
+
+/// Partition the world into regions, block by block. Blocks that blood flows through form the
+/// capillary beds, the most acidic third of the remaining blocks the stomach lining and the rest
+/// is covered by the mucus layer. Has to run after the currents and environment are generated.
+pub fn generate_regions(objects: &mut GameObjects) {
+    let blocks_x = (WORLD_WIDTH + REGION_BLOCK - 1) / REGION_BLOCK;
+    let blocks_y = (WORLD_HEIGHT + REGION_BLOCK - 1) / REGION_BLOCK;
+    let block_of = |x: i32, y: i32| ((y / REGION_BLOCK) * blocks_x + x / REGION_BLOCK) as usize;
+
+    // open tiles, tiles with a current and summed up pH of each block
+    let mut blocks = vec![(0, 0, 0.0); (blocks_x * blocks_y) as usize];
+    for y in 0..WORLD_HEIGHT {
+        for x in 0..WORLD_WIDTH {
+            if let Some(o) = objects.get_tile_at(x as usize, y as usize) {
+                if let (Some(tile), false) = (&o.tile, o.physics.is_blocking) {
+                    let block = &mut blocks[block_of(x, y)];
+                    block.0 += 1;
+                    if tile.current != Target::Center {
+                        block.1 += 1;
+                    }
+                    block.2 += tile.environment.ph;
+                }
+            }
+        }
+    }
+
+    let mut acidity: Vec<f32> = blocks
+        .iter()
+        .filter(|(open, currents, _)| *open > 0 && *currents < CAPILLARY_CURRENTS)
+        .map(|(open, _, ph)| ph / *open as f32)
+        .collect();
+    acidity.sort_by(f32::total_cmp);
+    let threshold = acidity
+        .get(acidity.len().saturating_sub(1) / 3)
+        .copied()
+        .unwrap_or(0.0);
+    let regions: Vec<Region> = blocks
+        .iter()
+        .map(|(open, currents, ph)| {
+            if *currents >= CAPILLARY_CURRENTS {
+                Region::CapillaryBed
+            } else if *open > 0 && ph / *open as f32 <= threshold {
+                Region::StomachLining
+            } else {
+                Region::MucusLayer
+            }
+        })
+        .collect();
+
+    for y in 0..WORLD_HEIGHT {
+        for x in 0..WORLD_WIDTH {
+            if let Some(tile) = objects
+                .get_tile_at(x as usize, y as usize)
+                .as_mut()
+                .and_then(|o| o.tile.as_mut())
+            {
+                tile.region = regions[block_of(x, y)];
+            }
+        }
+    }
+}
+
 /// The tile is an object component that identifies an object as (mostly) fixed part of the game
 /// world.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Tile {
     pub is_explored: bool,
+    /// Strength of the pheromone trail left on this tile by passing organisms.
+    pub scent: i32,
+    /// Direction of the fluid current flowing through this tile, `Center` for still fluid.
+    pub current: Target,
+    #[serde(default)]
+    pub environment: Environment,
+    /// Concentration of toxins secreted onto this tile.
+    #[serde(default)]
+    pub toxin: i32,
+    /// How far organisms have digested this wall, see `WALL_INTEGRITY`.
+    #[serde(default)]
+    pub digested: i32,
+    #[serde(default)]
+    pub region: Region,
+    /// Remains of an organism that died on this tile.
+    #[serde(default)]
+    pub debris: Option<Debris>,
+    /// Barrier that seals off this tile until an organism opens it.
+    #[serde(default)]
+    pub barrier: Option<Barrier>,
+}
+
+/// Organic debris left behind by a dead organism. Its genes can still be absorbed by others.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Debris {
+    /// Name of the organism the debris is left of.
+    pub source: String,
+    pub genes: Vec<GeneticTrait>,
+}
+
+/// Kinds of barriers in the tissue that some organisms can open to pass through.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BarrierKind {
+    /// A ring of muscle that seals off a passage, and the view through it, while closed.
+    Sphincter,
+    /// A pore in a membrane, thin enough to see through even while closed.
+    Pore,
+    /// A valve that only opens for organisms carrying the gene it's keyed to.
+    Valve,
+}
+
+impl BarrierKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BarrierKind::Sphincter => "sphincter",
+            BarrierKind::Pore => "membrane pore",
+            BarrierKind::Valve => "valve",
+        }
+    }
+
+    fn glyph(&self, is_open: bool) -> char {
+        match (self, is_open) {
+            (BarrierKind::Sphincter, false) => 'Θ',
+            (BarrierKind::Sphincter, true) => '°',
+            (BarrierKind::Pore, false) => '≈',
+            (BarrierKind::Pore, true) => '∙',
+            (BarrierKind::Valve, false) => 'Φ',
+            (BarrierKind::Valve, true) => 'φ',
+        }
+    }
+
+    /// Number of turns the barrier stays open before it closes on its own.
+    pub fn open_turns(&self) -> i32 {
+        match self {
+            BarrierKind::Sphincter | BarrierKind::Valve => 3,
+            BarrierKind::Pore => 5,
+        }
+    }
+}
+
+/// State of a barrier tile. Organisms with pili can pry it open, unless it's keyed to a gene.
+/// After a few turns it closes again unless something is in the way.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Barrier {
+    pub kind: BarrierKind,
+    /// Turns until the barrier closes, zero while it's closed.
+    pub open_for: i32,
+    /// Name of the gene that an organism needs to express to open the barrier.
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+impl Barrier {
+    pub fn is_open(&self) -> bool {
+        self.open_for > 0
+    }
+
+    /// Let the tile object of the barrier block movement and sight, and look, according to the
+    /// state of the barrier.
+    pub fn apply(&self, object: &mut Object) {
+        let is_open = self.is_open();
+        object.physics.is_blocking = !is_open;
+        object.physics.is_blocking_sight = !is_open && self.kind != BarrierKind::Pore;
+        object.visual.glyph = self.kind.glyph(is_open);
+    }
 }
 
 impl Tile {
@@ -53,6 +469,37 @@ impl Tile {
             .tile_explored(is_visible)
         // .control(Controller::Npc(Box::new(AiPassive::new())))
     }
+
+    /// A closed barrier of the given kind.
+    pub fn barrier(x: i32, y: i32, kind: BarrierKind, is_visible: bool) -> Object {
+        Tile::keyed_barrier(x, y, kind, None, is_visible)
+    }
+
+    /// A closed barrier of the given kind that only opens for organisms expressing the key gene.
+    pub fn keyed_barrier(
+        x: i32,
+        y: i32,
+        kind: BarrierKind,
+        key: Option<String>,
+        is_visible: bool,
+    ) -> Object {
+        let barrier = Barrier {
+            kind,
+            open_for: 0,
+            key,
+        };
+        let mut object = Object::new()
+            .position(x, y)
+            .living(true)
+            .visualize(kind.name(), kind.glyph(false), (255, 255, 255))
+            .physical(true, true, is_visible)
+            .tile_explored(is_visible);
+        barrier.apply(&mut object);
+        if let Some(tile) = object.tile.as_mut() {
+            tile.barrier = Some(barrier);
+        }
+        object
+    }
 }
 
 /// For use in lambdas.
@@ -0,0 +1,132 @@
+//! Generators shape the world in several independent steps and prefabs in particular can wall off
+//! parts of the tissue. This pass makes sure that every open tile of a generated world, and with it
+//! every organism placed on one, can be reached from the player's starting position.
+
+use std::collections::VecDeque;
+
+use crate::core::game_objects::GameObjects;
+use crate::core::innit_env;
+use crate::core::position::Position;
+use crate::core::world::Tile;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+
+const NEIGHBOURS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+fn index(x: i32, y: i32) -> usize {
+    (y * WORLD_WIDTH + x) as usize
+}
+
+/// Barriers count as open, since they can be opened to pass through. Those keyed to a gene don't,
+/// they deliberately gate off parts of the tissue.
+fn is_open(objects: &mut GameObjects, x: i32, y: i32) -> bool {
+    matches!(
+        objects.get_tile_at(x as usize, y as usize),
+        Some(t) if !t.physics.is_blocking
+            || t.tile
+                .as_ref()
+                .and_then(|t| t.barrier.as_ref())
+                .is_some_and(|b| b.key.is_none())
+    )
+}
+
+/// Flood fill the open tiles from the given position. Returns, for every tile in row-major order,
+/// whether it can be reached without passing through walls.
+pub fn reachable_from(objects: &mut GameObjects, start: Position) -> Vec<bool> {
+    let mut reached = vec![false; (WORLD_WIDTH * WORLD_HEIGHT) as usize];
+    if !is_open(objects, start.x, start.y) {
+        return reached;
+    }
+    let mut queue = VecDeque::new();
+    reached[index(start.x, start.y)] = true;
+    queue.push_back((start.x, start.y));
+    while let Some((x, y)) = queue.pop_front() {
+        for (dx, dy) in NEIGHBOURS.iter() {
+            let (nx, ny) = (x + dx, y + dy);
+            if (0..WORLD_WIDTH).contains(&nx)
+                && (0..WORLD_HEIGHT).contains(&ny)
+                && !reached[index(nx, ny)]
+                && is_open(objects, nx, ny)
+            {
+                reached[index(nx, ny)] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    reached
+}
+
+/// Find the first open tile that can't be reached from the given position.
+pub fn find_unreachable(objects: &mut GameObjects, start: Position) -> Option<Position> {
+    let reached = reachable_from(objects, start);
+    (0..WORLD_HEIGHT)
+        .flat_map(|y| (0..WORLD_WIDTH).map(move |x| (x, y)))
+        .find(|(x, y)| !reached[index(*x, *y)] && is_open(objects, *x, *y))
+        .map(|(x, y)| Position::new(x, y))
+}
+
+/// All open tiles that can't be reached from the given position.
+pub fn unreachable_tiles(objects: &mut GameObjects, start: Position) -> Vec<Position> {
+    let reached = reachable_from(objects, start);
+    (0..WORLD_HEIGHT)
+        .flat_map(|y| (0..WORLD_WIDTH).map(move |x| (x, y)))
+        .filter(|(x, y)| !reached[index(*x, *y)] && is_open(objects, *x, *y))
+        .map(|(x, y)| Position::new(x, y))
+        .collect()
+}
+
+/// Connect every open area that is cut off from the given position by carving the shortest
+/// possible corridor from it to the reachable tissue. The world's border is never carved. Returns
+/// the number of carved wall tiles.
+pub fn ensure_connectivity(objects: &mut GameObjects, start: Position) -> usize {
+    let is_visible = innit_env().debug_mode;
+    let mut carved = 0;
+    while let Some(isolated) = find_unreachable(objects, start) {
+        let reached = reachable_from(objects, start);
+        // search outwards from the isolated tile, through walls, until hitting reachable tissue
+        let mut came_from: Vec<Option<(i32, i32)>> = vec![None; reached.len()];
+        let mut visited = vec![false; reached.len()];
+        let mut queue = VecDeque::new();
+        visited[index(isolated.x, isolated.y)] = true;
+        queue.push_back((isolated.x, isolated.y));
+        let mut target = None;
+        while let Some((x, y)) = queue.pop_front() {
+            if reached[index(x, y)] {
+                target = Some((x, y));
+                break;
+            }
+            for (dx, dy) in NEIGHBOURS.iter() {
+                let (nx, ny) = (x + dx, y + dy);
+                if (1..WORLD_WIDTH - 1).contains(&nx)
+                    && (1..WORLD_HEIGHT - 1).contains(&ny)
+                    && !visited[index(nx, ny)]
+                {
+                    visited[index(nx, ny)] = true;
+                    came_from[index(nx, ny)] = Some((x, y));
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        let (mut x, mut y) = match target {
+            Some(target) => target,
+            None => {
+                error!("cannot connect {:?} to the player's start", isolated);
+                break;
+            }
+        };
+        while let Some((px, py)) = came_from[index(x, y)] {
+            if !is_open(objects, px, py) {
+                objects
+                    .get_tile_at(px as usize, py as usize)
+                    .replace(Tile::empty(px, py, is_visible));
+                carved += 1;
+            }
+            x = px;
+            y = py;
+        }
+    }
+    if carved > 0 {
+        debug!("carved {} tiles to connect the world", carved);
+    }
+    carved
+}
@@ -0,0 +1,153 @@
+//! Gene-gated progression: a chokepoint of the tissue is sealed off with a valve that only opens
+//! for organisms expressing a certain gene. The remains of an organism carrying that gene are left
+//! elsewhere in the reachable tissue, for the player to absorb and come back.
+
+use rand::seq::SliceRandom;
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::position::Position;
+use crate::core::world::connectivity::{reachable_from, unreachable_tiles};
+use crate::core::world::{Barrier, BarrierKind, Debris};
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::util::game_rng::RngStream;
+
+/// Genes that valves can be keyed to.
+const KEY_GENES: [&str; 4] = ["Receptor", "Enzyme", "Protease", "Symbiosis Factor"];
+/// Fewest tiles that a valve has to seal off to be worth placing.
+const MIN_GATED_TILES: usize = 30;
+/// A valve may seal off at most this fraction of the tissue, i.e. a third.
+const MAX_GATED_SHARE: usize = 3;
+/// Number of chokepoints tried before giving up.
+const GATE_ATTEMPTS: usize = 40;
+/// Distance from the valve beyond which the key gene is preferably left.
+const MIN_KEY_DISTANCE: f32 = 10.0;
+
+/// Seal off part of the tissue behind a valve keyed to a random gene and leave the gene in the
+/// remains of an organism elsewhere. Returns the position of the valve, or `None` if no chokepoint
+/// seals off a suitable part of the tissue or the key has nowhere to go. The tissue is left
+/// untouched in that case.
+pub fn place_gate(
+    state: &mut GameState,
+    objects: &mut GameObjects,
+    start: Position,
+) -> Option<Position> {
+    let rng = state.rng_for(RngStream::WorldGen);
+    let key = KEY_GENES.choose(rng)?.to_string();
+    let gene = state
+        .gene_library
+        .traits()
+        .iter()
+        .find(|t| t.trait_name == key)?
+        .clone();
+    let rng = state.rng_for(RngStream::WorldGen);
+    let mut candidates = chokepoints(objects, start);
+    candidates.shuffle(rng);
+    let open_tiles = reachable_from(objects, start)
+        .iter()
+        .filter(|r| **r)
+        .count();
+
+    let (gate, floor) = candidates.into_iter().take(GATE_ATTEMPTS).find_map(|pos| {
+        let floor = set_valve(objects, &pos, key.clone())?;
+        let gated = unreachable_tiles(objects, start).len();
+        if gated >= MIN_GATED_TILES && gated * MAX_GATED_SHARE <= open_tiles {
+            Some((pos, floor))
+        } else {
+            remove_valve(objects, &pos, floor);
+            None
+        }
+    })?;
+    debug!("gated the tissue behind {:?}, keyed to {}", gate, key);
+
+    // the key lies in the reachable tissue, preferably away from the valve
+    let reached = reachable_from(objects, start);
+    let spots: Vec<Position> = (0..WORLD_HEIGHT)
+        .flat_map(|y| (0..WORLD_WIDTH).map(move |x| Position::new(x, y)))
+        .filter(|p| reached[(p.y * WORLD_WIDTH + p.x) as usize] && *p != start)
+        .filter(|p| objects.get_barrier(p).is_none())
+        .collect();
+    let far_spots: Vec<Position> = spots
+        .iter()
+        .copied()
+        .filter(|p| p.distance(&gate) >= MIN_KEY_DISTANCE)
+        .collect();
+    let rng = state.rng_for(RngStream::WorldGen);
+    let spot = match far_spots.choose(rng).or_else(|| spots.choose(rng)) {
+        Some(spot) => *spot,
+        None => {
+            // without a key the valve would seal off its tissue for good
+            remove_valve(objects, &gate, floor);
+            return None;
+        }
+    };
+    objects.leave_debris(
+        &spot,
+        Debris {
+            source: "an ancient microbe".to_string(),
+            genes: vec![gene],
+        },
+    );
+    Some(gate)
+}
+
+/// Open tiles, away from the border and the start, that sit between two walls with open tissue to
+/// either side.
+fn chokepoints(objects: &GameObjects, start: Position) -> Vec<Position> {
+    let tiles = objects.get_tiles();
+    let is_open = |x: i32, y: i32| {
+        tiles[(y * WORLD_WIDTH + x) as usize]
+            .as_ref()
+            .is_some_and(|t| !t.physics.is_blocking)
+    };
+    (2..WORLD_HEIGHT - 2)
+        .flat_map(|y| (2..WORLD_WIDTH - 2).map(move |x| (x, y)))
+        .filter(|(x, y)| {
+            let (n, s, w, e) = (
+                is_open(*x, y - 1),
+                is_open(*x, y + 1),
+                is_open(x - 1, *y),
+                is_open(x + 1, *y),
+            );
+            is_open(*x, *y) && ((!n && !s && w && e) || (n && s && !w && !e))
+        })
+        .map(|(x, y)| Position::new(x, y))
+        .filter(|p| *p != start && objects.get_barrier(p).is_none())
+        .collect()
+}
+
+/// Turn the open tile at the given position into a valve keyed to the gene. The rest of the tile
+/// stays as it is. Returns the glyph and name of the open tile, to restore them with
+/// `remove_valve`.
+fn set_valve(objects: &mut GameObjects, pos: &Position, key: String) -> Option<(char, String)> {
+    let object = objects
+        .get_tile_at(pos.x as usize, pos.y as usize)
+        .as_mut()?;
+    let floor = (object.visual.glyph, object.visual.name.clone());
+    let barrier = Barrier {
+        kind: BarrierKind::Valve,
+        open_for: 0,
+        key: Some(key),
+    };
+    barrier.apply(object);
+    object.visual.name = barrier.kind.name().to_string();
+    if let Some(tile) = object.tile.as_mut() {
+        tile.barrier = Some(barrier);
+    }
+    Some(floor)
+}
+
+/// Turn the valve at the given position back into the open tile it was placed on.
+fn remove_valve(objects: &mut GameObjects, pos: &Position, (glyph, name): (char, String)) {
+    let object = match objects.get_tile_at(pos.x as usize, pos.y as usize).as_mut() {
+        Some(object) => object,
+        None => return,
+    };
+    object.physics.is_blocking = false;
+    object.physics.is_blocking_sight = false;
+    object.visual.glyph = glyph;
+    object.visual.name = name;
+    if let Some(tile) = object.tile.as_mut() {
+        tile.barrier = None;
+    }
+}
@@ -0,0 +1,321 @@
+//! Prefabs are handcrafted map pieces that world generators stamp into the generated maps to add
+//! recognisable organ structures and setpieces, e.g. capillaries, infected wounds or lymph nodes.
+//!
+//! They are either drawn in REX Paint or written as text in the prefab raws. Walls are drawn as
+//! `#`, open tissue as `.`, sphincters as `=` and membrane pores as `~`. Transparent cells or
+//! spaces leave the generated map as it is. Any other character is a spawn marker, an open tile
+//! with an object spawned on it.
+//!
+//! Prefabs are registered with a [PrefabRegistry], from which generators pick them at random and
+//! place them rotated and mirrored.
+
+use std::collections::HashMap;
+
+use crate::assets;
+use crate::core::game_objects::GameObjects;
+use crate::core::innit_env;
+use crate::core::position::Position;
+use crate::core::world::{BarrierKind, Tile};
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::raws::load_prefab_templates;
+use crate::raws::prefab_template::PrefabTemplate;
+use crate::ui::rex_assets::load_xp;
+use crate::util::game_rng::GameRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rltk::rex::XpFile;
+
+/// Number of attempts to find a spot for a prefab before giving up.
+const PLACEMENT_ATTEMPTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrefabCell {
+    /// Leave the generated tile untouched.
+    Keep,
+    Wall,
+    Floor,
+    Barrier(BarrierKind),
+}
+
+/// An object to spawn at a position relative to the prefab's top left corner, or in world
+/// coordinates once the prefab is placed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefabSpawn {
+    pub pos: Position,
+    pub npc: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Prefab {
+    pub name: String,
+    pub width: i32,
+    pub height: i32,
+    /// Cells in row-major order.
+    cells: Vec<PrefabCell>,
+    pub spawns: Vec<PrefabSpawn>,
+}
+
+impl Prefab {
+    /// Build a prefab from rows of characters. Spawn markers are looked up in `markers`, unknown
+    /// characters are ignored.
+    fn from_chars(name: &str, rows: &[Vec<char>], markers: &HashMap<char, String>) -> Self {
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * rows.len());
+        let mut spawns = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for x in 0..width {
+                cells.push(match row.get(x).copied().unwrap_or(' ') {
+                    '#' => PrefabCell::Wall,
+                    '.' => PrefabCell::Floor,
+                    '=' => PrefabCell::Barrier(BarrierKind::Sphincter),
+                    '~' => PrefabCell::Barrier(BarrierKind::Pore),
+                    ' ' => PrefabCell::Keep,
+                    c => match markers.get(&c) {
+                        Some(npc) => {
+                            spawns.push(PrefabSpawn {
+                                pos: Position::new(x as i32, y as i32),
+                                npc: npc.clone(),
+                            });
+                            PrefabCell::Floor
+                        }
+                        None => {
+                            warn!("prefab {}: unknown marker '{}'", name, c);
+                            PrefabCell::Keep
+                        }
+                    },
+                });
+            }
+        }
+        Prefab {
+            name: name.to_string(),
+            width: width as i32,
+            height: rows.len() as i32,
+            cells,
+            spawns,
+        }
+    }
+
+    /// Read the prefab from the first layer of a REX Paint image. Fails on images without layers
+    /// or whose first layer doesn't have as many cells as its dimensions say.
+    pub fn from_xp(
+        name: &str,
+        xp: &XpFile,
+        markers: &HashMap<char, String>,
+    ) -> Result<Self, String> {
+        let layer = xp
+            .layers
+            .first()
+            .ok_or_else(|| format!("prefab {} has no layers", name))?;
+        // the layer looks cells up by its dimensions, which have to match its content
+        if layer.cells.len() != layer.width * layer.height {
+            return Err(format!(
+                "prefab {} has {} cells instead of {}x{}",
+                name,
+                layer.cells.len(),
+                layer.width,
+                layer.height
+            ));
+        }
+        let rows = (0..layer.height)
+            .map(|y| {
+                (0..layer.width)
+                    .map(|x| {
+                        let cell = layer.get(x, y).ok_or_else(|| {
+                            format!("prefab {} has no cell at ({}, {})", name, x, y)
+                        })?;
+                        Ok(if cell.bg.is_transparent() {
+                            ' '
+                        } else {
+                            std::char::from_u32(cell.ch).unwrap_or(' ')
+                        })
+                    })
+                    .collect::<Result<Vec<char>, String>>()
+            })
+            .collect::<Result<Vec<Vec<char>>, String>>()?;
+        Ok(Prefab::from_chars(name, &rows, markers))
+    }
+
+    /// Read the prefab from its text definition in the raws.
+    pub fn from_template(template: &PrefabTemplate) -> Self {
+        let rows: Vec<Vec<char>> = template.rows.iter().map(|r| r.chars().collect()).collect();
+        Prefab::from_chars(&template.name, &rows, &template.markers)
+    }
+
+    pub fn cell(&self, x: i32, y: i32) -> PrefabCell {
+        if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
+            self.cells[(y * self.width + x) as usize]
+        } else {
+            PrefabCell::Keep
+        }
+    }
+
+    /// Rebuild the prefab with every cell and spawn moved to a new position.
+    fn transformed<F>(&self, width: i32, height: i32, new_pos: F) -> Prefab
+    where
+        F: Fn(i32, i32) -> (i32, i32),
+    {
+        let mut cells = vec![PrefabCell::Keep; (width * height) as usize];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = new_pos(x, y);
+                cells[(ny * width + nx) as usize] = self.cell(x, y);
+            }
+        }
+        let spawns = self
+            .spawns
+            .iter()
+            .map(|s| {
+                let (nx, ny) = new_pos(s.pos.x, s.pos.y);
+                PrefabSpawn {
+                    pos: Position::new(nx, ny),
+                    npc: s.npc.clone(),
+                }
+            })
+            .collect();
+        Prefab {
+            name: self.name.clone(),
+            width,
+            height,
+            cells,
+            spawns,
+        }
+    }
+
+    /// Rotate the prefab clockwise by 90 degrees.
+    pub fn rotated(&self) -> Prefab {
+        let height = self.height;
+        self.transformed(self.height, self.width, |x, y| (height - 1 - y, x))
+    }
+
+    /// Mirror the prefab along its vertical axis.
+    pub fn mirrored(&self) -> Prefab {
+        let width = self.width;
+        self.transformed(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    /// Stamp the prefab, randomly rotated and mirrored, into the world. It stays off the world's
+    /// border and away from `keep_clear`. Returns the objects to spawn in world coordinates, or
+    /// `None` if no spot could be found.
+    pub fn place(
+        &self,
+        objects: &mut GameObjects,
+        rng: &mut GameRng,
+        keep_clear: Position,
+    ) -> Option<Vec<PrefabSpawn>> {
+        let mut prefab = self.clone();
+        for _ in 0..rng.gen_range(0..4) {
+            prefab = prefab.rotated();
+        }
+        if rng.gen_bool(0.5) {
+            prefab = prefab.mirrored();
+        }
+        if prefab.width + 2 > WORLD_WIDTH || prefab.height + 2 > WORLD_HEIGHT {
+            return None;
+        }
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let x = rng.gen_range(1..WORLD_WIDTH - prefab.width);
+            let y = rng.gen_range(1..WORLD_HEIGHT - prefab.height);
+            let covers_start = (x - 2..x + prefab.width + 2).contains(&keep_clear.x)
+                && (y - 2..y + prefab.height + 2).contains(&keep_clear.y);
+            if !covers_start {
+                debug!("placing prefab {} at {}, {}", prefab.name, x, y);
+                return Some(prefab.stamp(objects, x, y));
+            }
+        }
+        None
+    }
+
+    /// Replace the tiles of the world with those of the prefab, top left corner first. Returns the
+    /// objects to spawn in world coordinates.
+    pub fn stamp(&self, objects: &mut GameObjects, x: i32, y: i32) -> Vec<PrefabSpawn> {
+        let is_visible = innit_env().debug_mode;
+        for py in 0..self.height {
+            for px in 0..self.width {
+                let (wx, wy) = (x + px, y + py);
+                if !(0..WORLD_WIDTH).contains(&wx) || !(0..WORLD_HEIGHT).contains(&wy) {
+                    continue;
+                }
+                let tile = match self.cell(px, py) {
+                    PrefabCell::Keep => continue,
+                    PrefabCell::Wall => Tile::wall(wx, wy, is_visible),
+                    PrefabCell::Floor => Tile::empty(wx, wy, is_visible),
+                    PrefabCell::Barrier(kind) => Tile::barrier(wx, wy, kind, is_visible),
+                };
+                objects.get_tile_at(wx as usize, wy as usize).replace(tile);
+            }
+        }
+        self.spawns
+            .iter()
+            .map(|s| PrefabSpawn {
+                pos: Position::new(x + s.pos.x, y + s.pos.y),
+                npc: s.npc.clone(),
+            })
+            .collect()
+    }
+}
+
+/// All prefabs that world generators can choose from.
+#[derive(Debug, Clone, Default)]
+pub struct PrefabRegistry {
+    prefabs: Vec<Prefab>,
+}
+
+impl PrefabRegistry {
+    pub fn new() -> Self {
+        PrefabRegistry::default()
+    }
+
+    /// Create a registry holding the prefabs drawn in REX Paint and those from the prefab raws.
+    /// Problems with user provided files are appended to `errors`.
+    pub fn load(errors: &mut Vec<String>) -> Self {
+        let mut registry = PrefabRegistry::new();
+        for (name, asset) in &[
+            ("capillary", assets::CAPILLARY_PREFAB),
+            ("alveolus", assets::ALVEOLUS_PREFAB),
+        ] {
+            if let Some(xp) = load_xp(asset, errors) {
+                match Prefab::from_xp(name, &xp, &HashMap::new()) {
+                    Ok(prefab) => registry.register(prefab),
+                    Err(e) => {
+                        error!("cannot load {}: {}", asset.name, e);
+                        errors.push(format!("{}: {}", asset.name, e));
+                    }
+                }
+            }
+        }
+        for template in load_prefab_templates(errors) {
+            registry.register(Prefab::from_template(&template));
+        }
+        registry
+    }
+
+    pub fn register(&mut self, prefab: Prefab) {
+        self.prefabs.push(prefab);
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn prefabs(&self) -> &[Prefab] {
+        &self.prefabs
+    }
+
+    /// Stamp up to `count` randomly chosen, rotated and mirrored prefabs into the world. Prefabs
+    /// stay off the world's border and away from `keep_clear`, usually the player's starting
+    /// position. Returns the objects to spawn in world coordinates.
+    pub fn place(
+        &self,
+        objects: &mut GameObjects,
+        rng: &mut GameRng,
+        count: usize,
+        keep_clear: Position,
+    ) -> Vec<PrefabSpawn> {
+        let mut spawns = Vec::new();
+        for _ in 0..count {
+            if let Some(prefab) = self.prefabs.choose(rng) {
+                if let Some(mut placed) = prefab.place(objects, rng, keep_clear) {
+                    spawns.append(&mut placed);
+                }
+            }
+        }
+        spawns
+    }
+}
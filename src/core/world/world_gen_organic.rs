@@ -1,37 +1,125 @@
 use crate::core::game_state::GameState;
 use crate::core::position::Position;
-use crate::core::world::{Tile, WorldGen};
+use crate::core::world::connectivity::ensure_connectivity;
+use crate::core::world::gates::place_gate;
+use crate::core::world::prefab::{Prefab, PrefabRegistry, PrefabSpawn};
+use crate::core::world::{
+    generate_environment, generate_regions, object_from_template, Tile, WorldGen,
+};
 use crate::core::{game_objects::GameObjects, innit_env};
-use crate::entity::action::action_from_string;
-use crate::entity::ai::AiPassive;
-use crate::entity::ai::AiRandom;
-use crate::entity::ai::AiRandomWalk;
-use crate::entity::ai::AiVirus;
-use crate::entity::control::Controller;
-use crate::entity::genetics::DnaType;
-use crate::entity::genetics::TraitFamily;
-use crate::entity::object::InventoryItem;
-use crate::entity::object::Object;
-use crate::entity::player::PlayerCtrl;
+use crate::entity::action::Target;
+use crate::entity::boss::boss_from_template;
 use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
-use crate::raws::object_template::DnaTemplate;
+use crate::raws::boss_template::BossTemplate;
 use crate::raws::object_template::ObjectTemplate;
-use crate::raws::spawn::{from_dungeon_level, Spawn};
+use crate::raws::prefab_template::PrefabTemplate;
+use crate::raws::spawn::{from_dungeon_level, Spawn, SpawnLocation, Transition};
+use crate::raws::terrain::TerrainParams;
+use crate::util::game_rng::RngStream;
 use crate::util::game_rng::{GameRng, RngExtended};
-use std::collections::HashSet;
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use rltk::{FastNoise, NoiseType};
+use std::collections::{HashMap, HashSet};
 
 const CA_CYCLES: i32 = 45;
+const MAX_STREAM_LEN: i32 = 120;
+/// Maximal number of prefabs placed in each world.
+const MAX_PREFABS: usize = 3;
 
 /// The organics world generator attempts to create organ-like environments e.g., long snaking blood
 /// vessels, branching fractal-like lungs, spongy tissue and more.
 pub struct OrganicsWorldGenerator {
     player_start: (i32, i32),
+    /// Handcrafted setpieces to scatter across the world.
+    prefabs: PrefabRegistry,
+    /// Terrain noise parameters per dungeon level.
+    terrain: Vec<Transition<TerrainParams>>,
+    /// Bosses to place in their arenas on the levels they guard.
+    bosses: Vec<BossTemplate>,
 }
 
 impl OrganicsWorldGenerator {
-    pub fn new() -> Self {
+    pub fn new(
+        prefabs: PrefabRegistry,
+        terrain: Vec<Transition<TerrainParams>>,
+        bosses: Vec<BossTemplate>,
+    ) -> Self {
         OrganicsWorldGenerator {
             player_start: (0, 0),
+            prefabs,
+            terrain,
+            bosses,
+        }
+    }
+}
+
+/// Noise fields that vary the terrain across the world, so that it falls into patches of thick and
+/// thin tissue, rich and poor nutrient supply and the occasional hazardous inflammation.
+struct TerrainNoise {
+    params: TerrainParams,
+    walls: FastNoise,
+    nutrients: FastNoise,
+    hazards: FastNoise,
+}
+
+impl TerrainNoise {
+    fn new(rng: &mut GameRng, params: TerrainParams) -> Self {
+        let mut field = || {
+            let mut noise = FastNoise::seeded(rng.gen());
+            noise.set_noise_type(NoiseType::SimplexFractal);
+            noise.set_fractal_octaves(3);
+            noise.set_frequency(params.frequency);
+            noise
+        };
+        TerrainNoise {
+            walls: field(),
+            nutrients: field(),
+            hazards: field(),
+            params,
+        }
+    }
+
+    /// Factor on the chance of tissue to open up at the given position.
+    fn openness(&self, x: i32, y: i32) -> f64 {
+        let noise = self.walls.get_noise(x as f32, y as f32);
+        (1.0 + self.params.wall_variation * noise) as f64
+    }
+
+    /// Nutrient richness at the given position, relative to the host's supply.
+    fn nutrients(&self, x: i32, y: i32) -> f32 {
+        let noise = self.nutrients.get_noise(x as f32, y as f32);
+        (1.0 + self.params.nutrient_variation * noise).max(0.0)
+    }
+
+    /// Intensity of the hazard at the given position, from 0 outside of hazardous patches to 1 at
+    /// their cores.
+    fn hazard(&self, x: i32, y: i32) -> f32 {
+        let threshold = 1.0 - 2.0 * self.params.hazard_coverage.clamp(0.0, 1.0);
+        let noise = self.hazards.get_noise(x as f32, y as f32);
+        if noise > threshold && threshold < 1.0 {
+            (noise - threshold) / (1.0 - threshold)
+        } else {
+            0.0
+        }
+    }
+
+    /// Apply nutrient richness and hazards to the environment of all tiles.
+    fn vary_environment(&self, objects: &mut GameObjects) {
+        for y in 0..WORLD_HEIGHT {
+            for x in 0..WORLD_WIDTH {
+                let nutrients = self.nutrients(x, y);
+                let hazard = self.hazard(x, y) * self.params.hazard_strength;
+                if let Some(tile) = objects
+                    .get_tile_at(x as usize, y as usize)
+                    .as_mut()
+                    .and_then(|o| o.tile.as_mut())
+                {
+                    tile.environment.nutrients = nutrients;
+                    tile.environment.temperature += hazard;
+                    tile.environment.ph -= hazard * 0.25;
+                }
+            }
         }
     }
 }
@@ -47,6 +135,9 @@ impl WorldGen for OrganicsWorldGenerator {
         object_templates: &[ObjectTemplate],
         level: u32,
     ) {
+        let params = from_dungeon_level(&self.terrain, level);
+        let noise = TerrainNoise::new(state.rng_for(RngStream::WorldGen), params);
+
         // step 1: generate foundation pattern
         let mid_x = WORLD_WIDTH / 2;
         let mid_y = WORLD_HEIGHT / 2;
@@ -65,7 +156,13 @@ impl WorldGen for OrganicsWorldGenerator {
             for y in 2..WORLD_HEIGHT - 2 {
                 for x in 2..WORLD_WIDTH - 2 {
                     // note whether a cell has changed
-                    if update_from_neighbours(objects, &mut state.rng, x, y) {
+                    if update_from_neighbours(
+                        objects,
+                        state.rng_for(RngStream::WorldGen),
+                        &noise,
+                        x,
+                        y,
+                    ) {
                         changed_tiles.insert((x, y));
                     }
                 }
@@ -79,8 +176,55 @@ impl WorldGen for OrganicsWorldGenerator {
             changed_tiles.clear();
         }
 
+        // step 3: stamp handcrafted organ structures into the tissue
+        let num_prefabs = state
+            .rng_for(RngStream::WorldGen)
+            .gen_range(1..=MAX_PREFABS);
+        let start = Position::new(self.player_start.0, self.player_start.1);
+        let mut prefab_spawns = self.prefabs.place(
+            objects,
+            state.rng_for(RngStream::WorldGen),
+            num_prefabs,
+            start,
+        );
+
+        // step 3b: build the arenas of the bosses guarding this level
+        for boss in self.bosses.iter().filter(|b| b.level == level) {
+            match boss_arena(boss).place(objects, state.rng_for(RngStream::WorldGen), start) {
+                Some(mut spawns) => prefab_spawns.append(&mut spawns),
+                None => error!("no room for the arena of {}", boss.name),
+            }
+        }
+
+        // step 4: connect tissue that the prefabs have walled off
+        ensure_connectivity(objects, start);
+
+        // step 5: let blood flow through the vessels
+        generate_currents(objects, state.rng_for(RngStream::WorldGen));
+
+        // step 6: warm up, cool down and acidify the tissue
+        generate_environment(objects, state.rng_for(RngStream::WorldGen), level);
+
+        // step 7: enrich and starve patches of tissue and inflame a few of them
+        noise.vary_environment(objects);
+
+        // step 8: partition the tissue into named regions
+        generate_regions(objects);
+
+        // step 9: seal off part of the tissue behind a valve, and leave its key elsewhere
+        if place_gate(state, objects, start).is_none() {
+            debug!("no chokepoint to gate off the tissue");
+        }
+
         // world gen done, now insert objects
         place_objects(state, objects, spawns, object_templates, level);
+        place_prefab_spawns(
+            state,
+            objects,
+            &prefab_spawns,
+            object_templates,
+            &self.bosses,
+        );
     }
 
     fn get_player_start_pos(&self) -> (i32, i32) {
@@ -88,7 +232,13 @@ impl WorldGen for OrganicsWorldGenerator {
     }
 }
 
-fn update_from_neighbours(objects: &mut GameObjects, rng: &mut GameRng, x: i32, y: i32) -> bool {
+fn update_from_neighbours(
+    objects: &mut GameObjects,
+    rng: &mut GameRng,
+    noise: &TerrainNoise,
+    x: i32,
+    y: i32,
+) -> bool {
     let directions = [
         // (-1, -1),
         (-1, 0, 4.0),
@@ -113,7 +263,79 @@ fn update_from_neighbours(objects: &mut GameObjects, rng: &mut GameRng, x: i32,
         }
     }
 
-    rng.flip_with_prob(access_count / 16.0)
+    rng.flip_with_prob((access_count / 16.0 * noise.openness(x, y)).min(1.0))
+}
+
+fn is_open(objects: &mut GameObjects, pos: &Position) -> bool {
+    pos.x > 0
+        && pos.x < WORLD_WIDTH - 1
+        && pos.y > 0
+        && pos.y < WORLD_HEIGHT - 1
+        && matches!(objects.get_tile_at(pos.x as usize, pos.y as usize), Some(t) if !t.physics.is_blocking)
+}
+
+/// Generate a handful of streams that flow along the main axis of the vessels, either east or
+/// west. Whenever a stream hits a wall it tries to meander around it north- or southwards and it
+/// ends when it runs into a dead end or joins another stream.
+fn generate_currents(objects: &mut GameObjects, rng: &mut GameRng) {
+    let flow = if rng.coinflip() {
+        Target::East
+    } else {
+        Target::West
+    };
+    let num_streams = rng.gen_range(3..7);
+    for _ in 0..num_streams {
+        let mut pos = Position::new(
+            rng.gen_range(1..WORLD_WIDTH - 1),
+            rng.gen_range(1..WORLD_HEIGHT - 1),
+        );
+        if !is_open(objects, &pos) {
+            continue;
+        }
+
+        for _ in 0..MAX_STREAM_LEN {
+            if objects.get_current(&pos) != Target::Center {
+                break;
+            }
+            let mut detours = [Target::North, Target::South];
+            detours.shuffle(rng);
+            let next = std::iter::once(flow.clone())
+                .chain(detours.iter().cloned())
+                .find(|t| {
+                    let next_pos = pos.get_translated(&t.to_pos());
+                    is_open(objects, &next_pos) && objects.get_current(&next_pos) == Target::Center
+                });
+            match next {
+                Some(direction) => {
+                    let next_pos = pos.get_translated(&direction.to_pos());
+                    objects.set_current(&pos, direction);
+                    pos = next_pos;
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// Check whether the given position suits the spawn location of a species.
+pub fn is_valid_location(
+    objects: &mut GameObjects,
+    pos: &Position,
+    location: SpawnLocation,
+) -> bool {
+    match location {
+        SpawnLocation::Anywhere => true,
+        SpawnLocation::Open => is_open(objects, pos),
+        SpawnLocation::Wall => {
+            is_open(objects, pos)
+                && [Target::North, Target::East, Target::South, Target::West]
+                    .iter()
+                    .any(|t| !is_open(objects, &pos.get_translated(&t.to_pos())))
+        }
+        SpawnLocation::Current => {
+            is_open(objects, pos) && objects.get_current(pos) != Target::Center
+        }
+    }
 }
 
 fn place_objects(
@@ -130,108 +352,85 @@ fn place_objects(
     // TODO: Set monster number per level via transitions.
     let max_monsters = 100;
 
-    let monster_chances: Vec<(&String, u32)> = spawns
+    let monster_chances: Vec<(&Spawn, u32)> = spawns
         .iter()
-        .map(|s| (&s.npc, from_dungeon_level(&s.spawn_transitions, level)))
+        .map(|s| {
+            let weight = from_dungeon_level(&s.spawn_transitions, level);
+            (s, innit_env().difficulty.spawn_weight(&s.npc, weight))
+        })
         .collect();
 
     let monster_dist = WeightedIndex::new(monster_chances.iter().map(|item| item.1)).unwrap();
 
     // choose random number of monsters
-    let num_monsters = state.rng.gen_range(0..max_monsters);
+    let num_monsters = state
+        .rng_for(RngStream::WorldGen)
+        .gen_range(0..max_monsters);
     for _ in 0..num_monsters {
         // choose random spot for this monster
-        // TODO: Make sure coordinates are accessible
-        let x = state.rng.gen_range(0 + 1..WORLD_WIDTH);
-        let y = state.rng.gen_range(0 + 1..WORLD_HEIGHT);
+        let x = state
+            .rng_for(RngStream::WorldGen)
+            .gen_range(0 + 1..WORLD_WIDTH);
+        let y = state
+            .rng_for(RngStream::WorldGen)
+            .gen_range(0 + 1..WORLD_HEIGHT);
+        let spawn = monster_chances[monster_dist.sample(state.rng_for(RngStream::WorldGen))].0;
+        let pos = Position::new(x, y);
 
-        if !objects.is_pos_occupied(&Position::new(x, y)) {
-            let npc_type = monster_chances[monster_dist.sample(&mut state.rng)].0;
-            // TODO: maybe build an object factory around all this to make it re-usable.
+        if !objects.is_pos_occupied(&pos) && is_valid_location(objects, &pos, spawn.location) {
+            let npc_type = &spawn.npc;
             if let Some(template) = object_templates.iter().find(|t| t.npc.eq(npc_type)) {
-                let controller: Option<Controller> = if let Some(ctrl) = &template.controller {
-                    match ctrl.as_str() {
-                        "player" => Some(Controller::Player(PlayerCtrl::new())),
-                        "AiPassive" => Some(Controller::Npc(Box::new(AiPassive))),
-                        "AiRandom" => Some(Controller::Npc(Box::new(AiRandom::new()))),
-                        "AiRandomWalk" => Some(Controller::Npc(Box::new(AiRandomWalk))),
-                        "AiVirus" => Some(Controller::Npc(Box::new(AiVirus::new()))),
-                        s => {
-                            error! {"Unknown controller type '{}'", s};
-                            // Controller::Npc(Box::new(AiPassive))
-                            continue;
-                        }
-                    }
-                } else {
-                    None
-                };
-
-                let raw_dna = match &template.dna_template {
-                    DnaTemplate::Random { genome_len } => state.gene_library.new_dna(
-                        &mut state.rng,
-                        template.dna_type == DnaType::Rna,
-                        *genome_len,
-                    ),
-                    DnaTemplate::Distributed {
-                        s_rate,
-                        p_rate,
-                        a_rate,
-                        genome_len,
-                    } => state.gene_library.dna_from_distribution(
-                        &mut state.rng,
-                        &[*s_rate, *p_rate, *a_rate],
-                        &[
-                            TraitFamily::Sensing,
-                            TraitFamily::Processing,
-                            TraitFamily::Actuating,
-                        ],
-                        template.dna_type == DnaType::Rna,
-                        *genome_len,
-                    ),
-                    DnaTemplate::Defined { traits } => state
-                        .gene_library
-                        .trait_strs_to_dna(&mut state.rng, &traits),
-                };
-
-                let inventory_item = if let Some(item) = &template.item {
-                    let action_instance = if item.action.is_empty() {
-                        None
-                    } else {
-                        match action_from_string(item.action.as_ref()) {
-                            Ok(action) => Some(action.clone()),
-                            Err(msg) => {
-                                error!("error getting action from string: {}", msg);
-                                continue;
-                            }
-                        }
-                    };
-                    Some(InventoryItem::new(&item.name, action_instance))
-                } else {
-                    None
-                };
-
-                let new_npc = Object::new()
-                    .position(x, y)
-                    .living(true)
-                    .visualize(template.npc.as_str(), template.glyph, template.color)
-                    .physical(
-                        template.physics.is_blocking,
-                        template.physics.is_blocking_sight,
-                        template.physics.is_always_visible,
-                    )
-                    .control_opt(controller)
-                    .genome(
-                        template.stability,
-                        state
-                            .gene_library
-                            .dna_to_traits(template.dna_type, &raw_dna),
-                    )
-                    .itemize(inventory_item);
-
-                objects.push(new_npc);
+                match object_from_template(state, template, x, y) {
+                    Ok(new_npc) => objects.push(new_npc),
+                    Err(msg) => error!("{}", msg),
+                }
             } else {
                 error!("No object template found for NPC type '{}'", npc_type);
             }
         }
     }
 }
+
+/// Turn the arena of a boss into a prefab, with the boss spawning at its marker.
+fn boss_arena(boss: &BossTemplate) -> Prefab {
+    let mut markers = HashMap::new();
+    markers.insert('B', boss.name.clone());
+    Prefab::from_template(&PrefabTemplate {
+        name: format!("arena of {}", boss.name),
+        rows: boss.arena.clone(),
+        markers,
+    })
+}
+
+/// Spawn the inhabitants of the placed prefabs, unless something else got there first. Bosses
+/// always get their spot.
+fn place_prefab_spawns(
+    state: &mut GameState,
+    objects: &mut GameObjects,
+    prefab_spawns: &[PrefabSpawn],
+    object_templates: &[ObjectTemplate],
+    bosses: &[BossTemplate],
+) {
+    for spawn in prefab_spawns {
+        let boss = bosses.iter().find(|b| b.name == spawn.npc);
+        if objects.is_pos_occupied(&spawn.pos) && boss.is_none() {
+            continue;
+        }
+        let npc = boss.map_or(&spawn.npc, |b| &b.npc);
+        let template = match object_templates.iter().find(|t| &t.npc == npc) {
+            Some(template) => template,
+            None => {
+                error!("No object template found for NPC type '{}'", npc);
+                continue;
+            }
+        };
+        let new_npc = match boss {
+            Some(boss) => boss_from_template(state, boss, template, spawn.pos),
+            None => object_from_template(state, template, spawn.pos.x, spawn.pos.y),
+        };
+        match new_npc {
+            Ok(new_npc) => objects.push(new_npc),
+            Err(msg) => error!("{}", msg),
+        }
+    }
+}
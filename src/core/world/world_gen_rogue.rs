@@ -7,6 +7,7 @@ use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
 use crate::raws::object_template::ObjectTemplate;
 use crate::raws::spawn::{from_dungeon_level, Spawn};
 use crate::ui::palette;
+use crate::util::game_rng::{RngExtended, RngStream};
 use rand::Rng;
 use std::{cmp, thread, time};
 
@@ -46,12 +47,20 @@ impl WorldGen for RogueWorldGenerator {
 
         for _ in 0..MAX_ROOMS {
             // random width and height
-            let w = state.rng.gen_range(ROOM_MIN_SIZE..=ROOM_MAX_SIZE);
-            let h = state.rng.gen_range(ROOM_MIN_SIZE..=ROOM_MAX_SIZE);
+            let w = state
+                .rng_for(RngStream::WorldGen)
+                .gen_range(ROOM_MIN_SIZE..=ROOM_MAX_SIZE);
+            let h = state
+                .rng_for(RngStream::WorldGen)
+                .gen_range(ROOM_MIN_SIZE..=ROOM_MAX_SIZE);
 
             // random position without exceeding the boundaries of the map
-            let x = state.rng.gen_range(0..WORLD_WIDTH - w);
-            let y = state.rng.gen_range(0..WORLD_HEIGHT - h);
+            let x = state
+                .rng_for(RngStream::WorldGen)
+                .gen_range(0..WORLD_WIDTH - w);
+            let y = state
+                .rng_for(RngStream::WorldGen)
+                .gen_range(0..WORLD_HEIGHT - h);
 
             // create room and store in vector
             let new_room = Rect::new(x, y, w, h);
@@ -82,7 +91,7 @@ impl WorldGen for RogueWorldGenerator {
                     let (prev_x, prev_y) = self.rooms[self.rooms.len() - 1].center();
 
                     // connect both rooms with a horizontal and a vertical tunnel - in random order
-                    if rand::random() {
+                    if state.rng_for(RngStream::WorldGen).coinflip() {
                         // move horizontally, then vertically
                         create_h_tunnel(objects, prev_x, new_x, prev_y);
                         create_v_tunnel(objects, prev_y, new_y, new_x);
@@ -156,14 +165,21 @@ fn place_objects(
     let monster_dist = WeightedIndex::new(monster_chances.iter().map(|item| item.1)).unwrap();
 
     // choose random number of monsters
-    let num_monsters = state.rng.gen_range(0..max_monsters);
+    let num_monsters = state
+        .rng_for(RngStream::WorldGen)
+        .gen_range(0..max_monsters);
     for _ in 0..num_monsters {
         // choose random spot for this monster
-        let x = state.rng.gen_range(0 + 1..WORLD_WIDTH);
-        let y = state.rng.gen_range(0 + 1..WORLD_HEIGHT);
+        let x = state
+            .rng_for(RngStream::WorldGen)
+            .gen_range(0 + 1..WORLD_WIDTH);
+        let y = state
+            .rng_for(RngStream::WorldGen)
+            .gen_range(0 + 1..WORLD_HEIGHT);
 
         if !objects.is_pos_occupied(&Position::new(x, y)) {
-            let monster_type = monster_chances[monster_dist.sample(&mut state.rng)].0;
+            let monster_type =
+                monster_chances[monster_dist.sample(state.rng_for(RngStream::WorldGen))].0;
             let mut monster = Object::new()
                 .position(x, y)
                 .living(true)
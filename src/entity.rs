@@ -1,7 +1,17 @@
 pub mod action;
 pub mod ai;
+pub mod bark;
+pub mod boss;
+pub mod combat;
 pub mod control;
+pub mod danger;
+pub mod faction;
 pub mod genetics;
 pub mod inventory;
+pub mod loot;
+pub mod mutation;
 pub mod object;
+pub mod organelle;
 pub mod player;
+pub mod signal;
+pub mod trade;
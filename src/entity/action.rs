@@ -5,11 +5,13 @@
 
 pub(crate) mod hereditary;
 pub(crate) mod inventory;
+pub(crate) mod organelle;
+pub(crate) mod registry;
+pub(crate) mod trade;
 
 use crate::core::game_objects::GameObjects;
 use crate::core::game_state::{GameState, ObjectFeedback};
 use crate::core::position::Position;
-use crate::entity::action::hereditary::*;
 use crate::entity::object::Object;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -24,23 +26,43 @@ pub enum TargetCategory {
     None,
 }
 
-/// Targets can only be adjacent to the object: north, south, east, west or the objects itself.
+impl TargetCategory {
+    pub fn name(&self) -> &str {
+        match self {
+            TargetCategory::Any => "any",
+            TargetCategory::BlockingObject => "object",
+            TargetCategory::EmptyObject => "free tile",
+            TargetCategory::None => "self",
+        }
+    }
+}
+
+/// Targets can only be adjacent to the object: any of the eight surrounding positions or the
+/// object itself.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
 pub enum Target {
     North,
-    South,
+    NorthEast,
     East,
+    SouthEast,
+    South,
+    SouthWest,
     West,
+    NorthWest,
     Center,
 }
 
 impl Target {
-    fn to_pos(&self) -> Position {
+    pub fn to_pos(&self) -> Position {
         match self {
             Target::North => Position::new(0, -1),
-            Target::South => Position::new(0, 1),
+            Target::NorthEast => Position::new(1, -1),
             Target::East => Position::new(1, 0),
+            Target::SouthEast => Position::new(1, 1),
+            Target::South => Position::new(0, 1),
+            Target::SouthWest => Position::new(-1, 1),
             Target::West => Position::new(-1, 0),
+            Target::NorthWest => Position::new(-1, -1),
             Target::Center => Position::new(0, 0),
         }
     }
@@ -49,9 +71,13 @@ impl Target {
     pub fn from_pos(p1: &Position, p2: &Position) -> Target {
         match p1.offset(p2) {
             (0, -1) => Target::North,
-            (0, 1) => Target::South,
+            (1, -1) => Target::NorthEast,
             (1, 0) => Target::East,
+            (1, 1) => Target::SouthEast,
+            (0, 1) => Target::South,
+            (-1, 1) => Target::SouthWest,
             (-1, 0) => Target::West,
+            (-1, -1) => Target::NorthWest,
             (0, 0) => Target::Center,
             _ => panic!("calling from_xy on non-adjacent target"),
         }
@@ -85,10 +111,24 @@ pub trait Action: ActionClone + Debug {
 
     fn set_target(&mut self, t: Target);
 
+    /// Target the action is aimed at, if it takes one. Shown by the AI debug overlay.
+    fn get_target(&self) -> Option<Target> {
+        None
+    }
+
     fn set_level(&mut self, lvl: i32);
 
     fn get_target_category(&self) -> TargetCategory;
 
+    /// Distance up to which the action has an effect, 0 for actions that only affect the
+    /// performer. Targeted actions reach the adjacent tiles.
+    fn get_range(&self) -> i32 {
+        match self.get_target_category() {
+            TargetCategory::None => 0,
+            _ => 1,
+        }
+    }
+
     fn get_level(&self) -> i32;
 
     fn get_identifier(&self) -> String;
@@ -116,14 +156,3 @@ impl Clone for Box<dyn Action> {
         self.clone_action()
     }
 }
-
-pub fn action_from_string(action_descriptor: &str) -> Result<Box<dyn Action>, String> {
-    match action_descriptor {
-        "ActPass" => Ok(Box::new(ActPass::default())),
-        "ActMove" => Ok(Box::new(ActMove::new())),
-        "ActMetabolise" => Ok(Box::new(ActMetabolise::new())),
-        "ActAttack" => Ok(Box::new(ActAttack::new())),
-        "ActEditGenome" => Ok(Box::new(ActEditGenome::new())),
-        _ => Err(format!("cannot find action for {}", action_descriptor)),
-    }
-}
@@ -2,21 +2,38 @@
 
 use crate::{
     core::{
+        encyclopedia::Topic,
+        events::GameEvent,
         game_objects::GameObjects,
         game_state::{GameState, MessageLog, MsgClass, ObjectFeedback},
+        host::HostCondition,
+        innit_env,
         position::Position,
     },
     entity::{
         action::{Action, ActionResult, Target, TargetCategory},
         ai::AiForceVirusProduction,
         ai::AiVirus,
+        bark::{bark, BarkTrigger},
+        combat::Hit,
         control::Controller,
+        faction::Stance,
+        genetics::is_within_dna_cap,
         genetics::DnaType,
         genetics::TraitFamily,
-        object::Object,
+        genetics::REPAIR_COST,
+        object::{Attachment, Object},
+        signal::{emit, SignalKind, SIGNAL_RADIUS},
     },
-    ui::{palette, register_particle},
+    game::{WORLD_HEIGHT, WORLD_WIDTH},
+    ui::{
+        palette, register_damage_effects, register_heal_effects, register_hp_change,
+        register_particle,
+    },
+    util::game_rng::{RngExtended, RngStream},
 };
+use rand::seq::SliceRandom;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 /// Dummy action for passing the turn.
@@ -92,11 +109,29 @@ impl Action for ActPass {
     }
 }
 
+/// Ways of moving around. Walking moves a single tile, bursting moves two tiles at once for
+/// extra energy.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub enum MoveMode {
+    Walk,
+    Burst,
+}
+
+impl MoveMode {
+    fn steps(&self) -> i32 {
+        match self {
+            MoveMode::Walk => 1,
+            MoveMode::Burst => 2,
+        }
+    }
+}
+
 /// Move an object
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ActMove {
     lvl: i32,
     direction: Target,
+    mode: MoveMode,
 }
 
 impl ActMove {
@@ -105,39 +140,1280 @@ impl ActMove {
         ActMove {
             lvl: 0,
             direction: Target::Center,
+            mode: MoveMode::Walk,
         }
     }
+
+    pub fn set_mode(&mut self, mode: MoveMode) {
+        self.mode = mode;
+    }
 }
 
 #[typetag::serde]
 impl Action for ActMove {
     fn perform(
         &self,
-        _state: &mut GameState,
+        state: &mut GameState,
         objects: &mut GameObjects,
         owner: &mut Object,
     ) -> ActionResult {
-        let target_pos = owner.pos.get_translated(&self.direction.to_pos());
-        if owner.physics.is_visible {
-            debug!(
-                "target position {:#?}, blocked: {}",
-                target_pos,
-                &objects.is_pos_blocked(&target_pos)
-            );
+        let mut has_moved = false;
+        // bursts stop early if the second step is blocked
+        for _ in 0..self.mode.steps() {
+            let target_pos = owner.pos.get_translated(&self.direction.to_pos());
+            if owner.physics.is_visible {
+                debug!(
+                    "target position {:#?}, blocked: {}",
+                    target_pos,
+                    &objects.is_pos_blocked(&target_pos)
+                );
+            }
+            if objects.is_pos_blocked(&target_pos)
+                && !push_back_claimant(state, objects, owner, target_pos)
+            {
+                break;
+            }
+            objects.leave_scent(&owner.pos);
+            if !owner.is_player() {
+                state
+                    .tile_claims
+                    .claim(state.turn, state.obj_idx, owner.pos, target_pos);
+            }
+            owner.move_to(target_pos);
+            has_moved = true;
         }
-        if !&objects.is_pos_blocked(&target_pos) {
-            owner.pos.set(target_pos.x, target_pos.y);
+
+        let target_pos = owner.pos.get_translated(&self.direction.to_pos());
+        if has_moved {
+            // moving away lets go of any wall or host
+            owner.attachment = None;
             ActionResult::Success {
                 callback: ObjectFeedback::Render,
             }
-        } else {
-            info!("object {} blocked!", owner.visual.name);
-            ActionResult::Failure // this might cause infinite loops of failure
+        } else if can_open_barrier(objects, owner, &target_pos) {
+            let mut open = ActOpenBarrier::new();
+            open.set_target(self.direction.clone());
+            open.set_level(self.lvl);
+            ActionResult::Consequence {
+                callback: ObjectFeedback::NoAction,
+                follow_up: Box::new(open),
+            }
+        } else if let Some(mut follow_up) = make_way(state, objects, owner, &target_pos) {
+            follow_up.set_target(self.direction.clone());
+            follow_up.set_level(self.lvl);
+            ActionResult::Consequence {
+                callback: ObjectFeedback::NoAction,
+                follow_up,
+            }
+        } else {
+            info!("object {} blocked!", owner.visual.name);
+            if owner.is_player() {
+                let key = objects.get_barrier(&target_pos).and_then(|b| b.key);
+                if let Some(key) = key {
+                    state.log.add(
+                        format!("The valve only opens for organisms with the {} gene.", key),
+                        MsgClass::Info,
+                    );
+                    state.discover(Topic::Mechanic, "barriers");
+                }
+            }
+            ActionResult::Failure // this might cause infinite loops of failure
+        }
+    }
+
+    fn set_target(&mut self, target: Target) {
+        self.direction = target;
+    }
+
+    fn get_target(&self) -> Option<Target> {
+        Some(self.direction.clone())
+    }
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::EmptyObject
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "move".to_string()
+    }
+
+    /// Bursts cost twice the energy of walking plus one for the extra effort.
+    fn get_energy_cost(&self) -> i32 {
+        match self.mode {
+            MoveMode::Walk => self.lvl,
+            MoveMode::Burst => 2 * self.lvl + 1,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        match self.mode {
+            MoveMode::Walk => format!("move to {:?}", self.direction),
+            MoveMode::Burst => format!("burst to {:?}", self.direction),
+        }
+    }
+}
+
+/// Open a closed barrier, such as a sphincter or a membrane pore, with pili or the gene it's keyed
+/// to. It follows from a move into the barrier.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActOpenBarrier {
+    lvl: i32,
+    direction: Target,
+}
+
+impl ActOpenBarrier {
+    pub fn new() -> Self {
+        ActOpenBarrier {
+            lvl: 0,
+            direction: Target::Center,
+        }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActOpenBarrier {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let pos = owner.pos.get_translated(&self.direction.to_pos());
+        let barrier = match objects.get_barrier(&pos) {
+            Some(barrier) if can_open_barrier(objects, owner, &pos) => barrier,
+            _ => return ActionResult::Failure,
+        };
+        objects.open_barrier(&pos);
+        if owner.is_player() {
+            let message = match &barrier.key {
+                Some(key) => format!("Your {} opens the {}.", key, barrier.kind.name()),
+                None => format!("You pry the {} open.", barrier.kind.name()),
+            };
+            state.log.add(message, MsgClass::Info);
+            state.discover(Topic::Mechanic, "barriers");
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
+        }
+    }
+
+    fn set_target(&mut self, target: Target) {
+        self.direction = target;
+    }
+
+    fn get_target(&self) -> Option<Target> {
+        Some(self.direction.clone())
+    }
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::BlockingObject
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "open barrier".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        format!("open barrier to {:?}", self.direction)
+    }
+}
+
+/// Check whether there is a closed barrier at `pos` and the owner can open it: with the gene it's
+/// keyed to, or otherwise with pili.
+fn can_open_barrier(objects: &GameObjects, owner: &Object, pos: &Position) -> bool {
+    match objects.get_barrier(pos) {
+        Some(barrier) if !barrier.is_open() => match &barrier.key {
+            Some(key) => expresses_gene(owner, key),
+            None => owner
+                .actuators
+                .actions
+                .iter()
+                .any(|a| a.get_identifier() == "attach"),
+        },
+        _ => false,
+    }
+}
+
+/// Check whether the owner carries an active copy of the gene with the given name.
+fn expresses_gene(owner: &Object, gene: &str) -> bool {
+    owner
+        .dna
+        .simplified
+        .iter()
+        .any(|g| g.trait_name == gene && !g.silenced)
+}
+
+/// Energy that pushing another organism aside costs on top of the move.
+pub const PUSH_COST: i32 = 2;
+
+/// Push a smaller organism that isn't hostile out of the way and take its place. It follows from a
+/// blocked move and costs `PUSH_COST` extra energy, so that crowds in narrow passages don't
+/// deadlock.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActPush {
+    lvl: i32,
+    direction: Target,
+}
+
+impl ActPush {
+    pub fn new() -> Self {
+        ActPush {
+            lvl: 0,
+            direction: Target::Center,
+        }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActPush {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        if owner.processors.energy < PUSH_COST {
+            return ActionResult::Failure;
+        }
+        let pos = owner.pos.get_translated(&self.direction.to_pos());
+        let idx = match find_pushee(state, objects, owner, &pos) {
+            Some(idx) => idx,
+            None => return ActionResult::Failure,
+        };
+        // straight ahead if possible, otherwise the free tile furthest away from the pusher
+        let is_free = |p: &Position| {
+            (0..WORLD_WIDTH).contains(&p.x)
+                && (0..WORLD_HEIGHT).contains(&p.y)
+                && !objects.is_pos_blocked(p)
+        };
+        let ahead = pos.get_translated(&self.direction.to_pos());
+        let destination = if is_free(&ahead) {
+            Some(ahead)
+        } else {
+            (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| Position::new(pos.x + dx, pos.y + dy)))
+                .filter(|p| *p != pos && *p != owner.pos && is_free(p))
+                .max_by_key(|p| {
+                    let (dx, dy) = (p.x - owner.pos.x, p.y - owner.pos.y);
+                    // ties go to the first tile in reading order
+                    (dx * dx + dy * dy, -(p.y * WORLD_WIDTH + p.x))
+                })
+        };
+        let destination = match destination {
+            Some(destination) => destination,
+            None => return ActionResult::Failure,
+        };
+
+        owner.processors.energy -= PUSH_COST;
+        if let Some(pushee) = objects[idx].as_mut() {
+            pushee.move_to(destination);
+            if owner.is_player() {
+                state.log.add(
+                    format!("You push {} aside.", pushee.visual.name),
+                    MsgClass::Info,
+                );
+            }
+        }
+        objects.leave_scent(&owner.pos);
+        owner.move_to(pos);
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
+        }
+    }
+
+    fn set_target(&mut self, target: Target) {
+        self.direction = target;
+    }
+
+    fn get_target(&self) -> Option<Target> {
+        Some(self.direction.clone())
+    }
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::BlockingObject
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "push".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        PUSH_COST
+    }
+
+    fn to_text(&self) -> String {
+        format!("push to {:?}", self.direction)
+    }
+}
+
+/// Swap places with an organism that isn't hostile, instead of blocking each other. It follows
+/// from a blocked move, at no extra cost.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActSwap {
+    lvl: i32,
+    direction: Target,
+}
+
+impl ActSwap {
+    pub fn new() -> Self {
+        ActSwap {
+            lvl: 0,
+            direction: Target::Center,
+        }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActSwap {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let pos = owner.pos.get_translated(&self.direction.to_pos());
+        let idx = match find_swap_partner(state, objects, owner, &pos) {
+            Some(idx) => idx,
+            None => return ActionResult::Failure,
+        };
+        if let Some(partner) = objects[idx].as_mut() {
+            partner.move_to(owner.pos);
+            if owner.is_player() {
+                state.log.add(
+                    format!("You swap places with {}.", partner.visual.name),
+                    MsgClass::Info,
+                );
+                state.events.publish(GameEvent::PlacesSwapped {
+                    name: partner.visual.name.clone(),
+                });
+            }
+        }
+        objects.leave_scent(&owner.pos);
+        owner.move_to(pos);
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
+        }
+    }
+
+    fn set_target(&mut self, target: Target) {
+        self.direction = target;
+    }
+
+    fn get_target(&self) -> Option<Target> {
+        Some(self.direction.clone())
+    }
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::BlockingObject
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "swap".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        format!("swap to {:?}", self.direction)
+    }
+}
+
+/// The action that gets a blocked move past the organism at `pos`, if any: allies swap places,
+/// smaller neutral organisms are pushed aside and bigger ones swap places as well.
+fn make_way(
+    state: &GameState,
+    objects: &GameObjects,
+    owner: &Object,
+    pos: &Position,
+) -> Option<Box<dyn Action>> {
+    let blocker = objects[blocker_at(objects, pos)?].as_ref()?;
+    match state.relations.stance(blocker, owner) {
+        Stance::Neutral if find_pushee(state, objects, owner, pos).is_some() => {
+            Some(Box::new(ActPush::new()))
+        }
+        Stance::Hostile => None,
+        _ if find_swap_partner(state, objects, owner, pos).is_some() => {
+            Some(Box::new(ActSwap::new()))
+        }
+        _ => None,
+    }
+}
+
+/// Index of the only blocking object at `pos`, if it's an organism that can be moved out of the
+/// way: alive, not anchored, not made up of several segments and not the player.
+fn blocker_at(objects: &GameObjects, pos: &Position) -> Option<usize> {
+    let mut blockers = objects.get_vector().iter().enumerate().filter(|(_, o)| {
+        o.as_ref()
+            .is_some_and(|o| o.physics.is_blocking && o.occupies(pos))
+    });
+    let (idx, blocker) = blockers.next()?;
+    let blocker = blocker.as_ref()?;
+    // walls or other blockers on the same tile don't move along
+    let is_movable = blockers.next().is_none()
+        && blocker.tile.is_none()
+        && blocker.pos == *pos
+        && blocker.alive
+        && blocker.item.is_none()
+        && blocker.control.is_some()
+        && !blocker.is_player()
+        && blocker.segments.is_empty()
+        && !blocker.is_anchored(objects);
+    if is_movable {
+        Some(idx)
+    } else {
+        None
+    }
+}
+
+/// Index of the organism at `pos` that the owner can push aside: a smaller one that isn't hostile
+/// towards the owner.
+fn find_pushee(
+    state: &GameState,
+    objects: &GameObjects,
+    owner: &Object,
+    pos: &Position,
+) -> Option<usize> {
+    let idx = blocker_at(objects, pos)?;
+    let pushee = objects[idx].as_ref()?;
+    if pushee.actuators.biomass < owner.actuators.biomass
+        && state.relations.stance(pushee, owner) != Stance::Hostile
+    {
+        Some(idx)
+    } else {
+        None
+    }
+}
+
+/// Index of the organism at `pos` that the owner can swap places with: one that isn't hostile
+/// towards the owner and fits into the owner's tile once it's vacated.
+fn find_swap_partner(
+    state: &GameState,
+    objects: &GameObjects,
+    owner: &Object,
+    pos: &Position,
+) -> Option<usize> {
+    let idx = blocker_at(objects, pos)?;
+    let partner = objects[idx].as_ref()?;
+    if owner.segments.is_empty()
+        && !objects.is_pos_blocked(&owner.pos)
+        && state.relations.stance(partner, owner) != Stance::Hostile
+    {
+        Some(idx)
+    } else {
+        None
+    }
+}
+
+/// Settle a move into a tile that another organism moved into earlier this turn, see
+/// `core::claims`. If the owner wins, the claimant is pushed back to where it came from and `true`
+/// is returned.
+fn push_back_claimant(
+    state: &GameState,
+    objects: &mut GameObjects,
+    owner: &Object,
+    pos: Position,
+) -> bool {
+    if owner.is_player() {
+        return false;
+    }
+    let (idx, from) = match state.tile_claims.contest(state.turn, state.obj_idx, pos) {
+        Some((idx, from)) if from != owner.pos && !objects.is_pos_blocked(&from) => (idx, from),
+        _ => return false,
+    };
+    // organisms with segments would have to be untangled, they keep their tile
+    let claimant = objects
+        .get_vector_mut()
+        .get_mut(idx)
+        .and_then(|o| o.as_mut());
+    match claimant {
+        Some(claimant)
+            if claimant.tile.is_none()
+                && claimant.alive
+                && claimant.physics.is_blocking
+                && claimant.pos == pos
+                && claimant.segments.is_empty()
+                && !claimant.is_player() =>
+        {
+            debug!("{} pushes {} back", owner.visual.name, claimant.visual.name);
+            claimant.move_to(from);
+        }
+        _ => return false,
+    }
+    !objects.is_pos_blocked(&pos)
+}
+
+/// Focus on increased energy production for this turn.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActMetabolise {
+    lvl: i32,
+}
+
+impl ActMetabolise {
+    pub fn new() -> Self {
+        ActMetabolise { lvl: 0 }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActMetabolise {
+    fn perform(
+        &self,
+        _state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        owner.processors.energy += self.lvl;
+        if owner.physics.is_visible {
+            register_particle(
+                owner.pos,
+                (50, 255, 50),
+                palette().world_bg_ground_fov_true,
+                owner.visual.glyph,
+                150.0,
+            )
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::NoFeedback,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "metabolize".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        "increase metabolism momentarily".to_string()
+    }
+}
+
+/// Loss of standing between the factions of victim and attacker with each attack.
+const ATTACK_RESENTMENT: i32 = 5;
+
+/// Attack another object.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActAttack {
+    lvl: i32,
+    target: Target,
+}
+
+impl ActAttack {
+    pub fn new() -> Self {
+        ActAttack {
+            lvl: 0,
+            target: Target::Center,
+        }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActAttack {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        // get coords of self position plus direction
+        // find any objects that are at that position and blocking
+        // assert that there is only one available
+        // return
+        let target_pos: Position = owner.pos.get_translated(&self.target.to_pos());
+        let valid_target: Option<&mut Object> = objects
+            .get_vector_mut()
+            .iter_mut()
+            .flatten()
+            .find(|o| o.physics.is_blocking && o.occupies(&target_pos));
+
+        match valid_target {
+            Some(t) => {
+                if owner.is_player() {
+                    bark(state, t, BarkTrigger::Interacted);
+                }
+                let mut hit = Hit::resolve(owner, t, self.lvl);
+                let damage = hit.damage;
+                // the victim's faction resents the attacker's
+                if t.faction != owner.faction {
+                    state
+                        .relations
+                        .shift(t.faction, owner.faction, -ATTACK_RESENTMENT);
+                }
+                // segments take the damage on their own and may be severed
+                if let Some(idx) = t.segment_at(&target_pos) {
+                    hit.segment = Some(t.damage_segment(idx, damage));
+                    if t.physics.is_visible {
+                        register_hp_change(target_pos, -damage);
+                    }
+                } else {
+                    let was_alive = t.actuators.hp > 0;
+                    t.actuators.hp -= damage;
+                    register_damage_effects(t, damage);
+                    if owner.is_player()
+                        && was_alive
+                        && t.actuators.hp <= 0
+                        && t.offensive_strength() > 0
+                    {
+                        state.events.publish(GameEvent::PredatorKilled {
+                            name: t.visual.name.clone(),
+                        });
+                    }
+                    debug!("target hp: {}/{}", t.actuators.hp, t.actuators.max_hp);
+                }
+                let detail = innit_env().combat_detail;
+                state
+                    .log
+                    .add_at(hit.describe(detail), MsgClass::Info, target_pos);
+                // show particle effect
+                if t.physics.is_visible {
+                    register_particle(
+                        target_pos,
+                        (200, 10, 10),
+                        palette().world_bg_ground_fov_true,
+                        'x',
+                        250.0,
+                    )
+                }
+                ActionResult::Success {
+                    // TODO: Add particle to emphasise something happened!
+                    callback: ObjectFeedback::NoFeedback,
+                }
+            }
+            None => {
+                state.log.add("Nothing to attack here", MsgClass::Info);
+                ActionResult::Failure
+            }
+        }
+    }
+
+    fn set_target(&mut self, target: Target) {
+        self.target = target;
+    }
+
+    fn get_target(&self) -> Option<Target> {
+        Some(self.target.clone())
+    }
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::BlockingObject
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "attack".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        self.lvl
+    }
+
+    fn to_text(&self) -> String {
+        format!("attack {:?}", self.target)
+    }
+}
+
+/// Sniff out pheromone trails within sensing range and highlight them in the field of view.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActFollowScent {
+    lvl: i32,
+}
+
+impl ActFollowScent {
+    pub fn new() -> Self {
+        ActFollowScent { lvl: 0 }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActFollowScent {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let range = owner.sensors.sensing_range as f32;
+        let trails: Vec<(Position, i32)> = objects
+            .get_tiles()
+            .iter()
+            .flatten()
+            .filter(|t| t.physics.is_visible && owner.pos.distance(&t.pos) <= range)
+            .map(|t| (t.pos, objects.get_scent(&t.pos)))
+            .filter(|(_, scent)| *scent > 0)
+            .collect();
+
+        if owner.is_player() {
+            if trails.is_empty() {
+                state.log.add("You don't pick up any scent", MsgClass::Info);
+            } else {
+                state.log.add("You pick up a scent", MsgClass::Info);
+            }
+        }
+
+        if owner.physics.is_visible {
+            let bg = palette().world_bg_ground_fov_true;
+            for (pos, scent) in trails {
+                // fresher trails glow longer
+                let fg = palette().hud_fg_dna_sensor;
+                register_particle(pos, fg, bg, '~', 150.0 * scent as f32);
+            }
+        }
+
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "follow scent".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        self.lvl
+    }
+
+    fn to_text(&self) -> String {
+        "follow scent".to_string()
+    }
+}
+
+// TODO: Add actions for
+// - inserting genome into another cell
+// - immobilising and manipulating another cell
+// - producing stuff
+
+/// Attach to a wall or host cell. Attached objects don't drift along currents and take less
+/// damage, but let go as soon as they move.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActAttach {
+    lvl: i32,
+    target: Target,
+}
+
+impl ActAttach {
+    pub fn new() -> Self {
+        ActAttach {
+            lvl: 0,
+            target: Target::Center,
+        }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActAttach {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let target_pos: Position = owner.pos.get_translated(&self.target.to_pos());
+        let target = objects
+            .get_vector_mut()
+            .iter_mut()
+            .flatten()
+            .find(|o| o.physics.is_blocking && o.occupies(&target_pos));
+
+        match target {
+            Some(t) => {
+                if owner.is_player() {
+                    bark(state, t, BarkTrigger::Interacted);
+                }
+                let name = t.visual.name.clone();
+                owner.attachment = Some(Attachment {
+                    pos: target_pos,
+                    strength: self.lvl,
+                });
+                if owner.is_player() || owner.physics.is_visible {
+                    state.log.add_at(
+                        format!("{} attached to {}", owner.visual.name, name),
+                        MsgClass::Info,
+                        target_pos,
+                    );
+                }
+                ActionResult::Success {
+                    callback: ObjectFeedback::NoFeedback,
+                }
+            }
+            None => {
+                if owner.is_player() {
+                    state.log.add("Nothing to attach to here", MsgClass::Info);
+                }
+                ActionResult::Failure
+            }
+        }
+    }
+
+    fn set_target(&mut self, target: Target) {
+        self.target = target;
+    }
+
+    fn get_target(&self) -> Option<Target> {
+        Some(self.target.clone())
+    }
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::BlockingObject
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "attach".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        self.lvl
+    }
+
+    fn to_text(&self) -> String {
+        format!("attach to {:?}", self.target)
+    }
+}
+
+/// Let go of whatever this object is attached to.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActDetach;
+
+#[typetag::serde]
+impl Action for ActDetach {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        if owner.attachment.take().is_some() {
+            if owner.is_player() || owner.physics.is_visible {
+                state
+                    .log
+                    .add(format!("{} detached", owner.visual.name), MsgClass::Info);
+            }
+            ActionResult::Success {
+                callback: ObjectFeedback::NoFeedback,
+            }
+        } else {
+            if owner.is_player() {
+                state.log.add("You are not attached", MsgClass::Info);
+            }
+            ActionResult::Failure
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, _lvl: i32) {}
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        0
+    }
+
+    fn get_identifier(&self) -> String {
+        "detach".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        "detach".to_string()
+    }
+}
+
+/// Retreat into a dormant spore. Dormant organisms pass their turns at no energy upkeep and take
+/// less damage, until they choose to do anything but passing.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActDormancy {
+    lvl: i32,
+}
+
+impl ActDormancy {
+    pub fn new() -> Self {
+        ActDormancy { lvl: 0 }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActDormancy {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        if owner.dormant {
+            return ActionResult::Failure;
+        }
+        owner.dormant = true;
+        if owner.is_player() {
+            state.log.add(
+                "You go dormant. Do anything but passing to awaken.",
+                MsgClass::Info,
+            );
+        } else if owner.physics.is_visible {
+            state.log.add_at(
+                format!("{} went dormant", owner.visual.name),
+                MsgClass::Info,
+                owner.pos,
+            );
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "go dormant".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        "go dormant".to_string()
+    }
+}
+
+/// Hp that resting repairs per repair gene, on top of the passive repair during metabolism.
+const REST_REPAIR: i32 = 2;
+
+/// Rest for a turn to turn stored energy into repairs, reserve first. The more repair genes the
+/// organism carries, the more hp it mends in one go.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActRest {
+    lvl: i32,
+}
+
+impl ActRest {
+    pub fn new() -> Self {
+        ActRest { lvl: 0 }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActRest {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let budget = owner.processors.reserve + owner.processors.energy;
+        let repaired = owner.repair(REST_REPAIR * self.lvl.max(1), budget);
+        if repaired == 0 {
+            if owner.is_player() {
+                let reason = if owner.actuators.hp < owner.actuators.max_hp {
+                    "You lack the energy to repair yourself."
+                } else {
+                    "There is nothing to repair."
+                };
+                state.log.add(reason, MsgClass::Info);
+            }
+            return ActionResult::Failure;
+        }
+
+        register_heal_effects(owner, repaired);
+        let cost = repaired * REPAIR_COST;
+        let from_reserve = cost.min(owner.processors.reserve);
+        owner.processors.reserve -= from_reserve;
+        owner.processors.energy -= cost - from_reserve;
+        if owner.is_player() {
+            state.log.add(
+                format!("You rest and repair {} hp", repaired),
+                MsgClass::Action,
+            );
+        } else if owner.physics.is_visible {
+            state.log.add_at(
+                format!("{} rests and repairs {} hp", owner.visual.name, repaired),
+                MsgClass::Info,
+                owner.pos,
+            );
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::UpdateHud,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "rest".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        format!("rest and repair up to {} hp", REST_REPAIR * self.lvl.max(1))
+    }
+}
+
+/// Concentration of toxins that a toxin gland secretes per gene.
+const TOXIN_SECRETION: i32 = 4;
+
+/// Secrete toxins onto the tile of the organism and all tiles around it. The toxins linger for a
+/// few turns and hurt every organism that lacks antitoxins.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActSecreteToxin {
+    lvl: i32,
+}
+
+impl ActSecreteToxin {
+    pub fn new() -> Self {
+        ActSecreteToxin { lvl: 0 }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActSecreteToxin {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let amount = TOXIN_SECRETION * self.lvl.max(1);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                objects.secrete_toxin(&owner.pos.get_translated(&Position::new(dx, dy)), amount);
+            }
+        }
+        if owner.is_player() {
+            state
+                .log
+                .add("You secrete a cloud of toxins", MsgClass::Action);
+        } else if owner.physics.is_visible {
+            state.log.add_at(
+                format!("{} secretes toxins", owner.visual.name),
+                MsgClass::Info,
+                owner.pos,
+            );
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_range(&self) -> i32 {
+        1
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "secrete toxin".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        self.lvl
+    }
+
+    fn to_text(&self) -> String {
+        "secrete toxin".to_string()
+    }
+}
+
+/// Energy that an organism gains from the nutrients of a digested wall.
+const DIGESTION_ENERGY: i32 = 3;
+
+/// Digest an adjacent wall of the host's tissue bit by bit. Each gene dissolves one more unit of
+/// the wall per turn, until it gives way and the organism absorbs its nutrients.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActDigestWall {
+    lvl: i32,
+    target: Target,
+}
+
+impl ActDigestWall {
+    pub fn new() -> Self {
+        ActDigestWall {
+            lvl: 0,
+            target: Target::Center,
+        }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActDigestWall {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let target_pos = owner.pos.get_translated(&self.target.to_pos());
+        match objects.digest_wall(&target_pos, self.lvl.max(1)) {
+            None => {
+                if owner.is_player() {
+                    state
+                        .log
+                        .add("There is no wall you could digest", MsgClass::Info);
+                }
+                ActionResult::Failure
+            }
+            Some(0) => {
+                owner.store_energy(DIGESTION_ENERGY);
+                if owner.is_player() {
+                    state
+                        .log
+                        .add("You digest your way through the wall", MsgClass::Action);
+                } else if owner.physics.is_visible {
+                    state.log.add_at(
+                        format!("{} digests its way through a wall", owner.visual.name),
+                        MsgClass::Info,
+                        target_pos,
+                    );
+                }
+                ActionResult::Success {
+                    callback: ObjectFeedback::Render,
+                }
+            }
+            Some(integrity) => {
+                if owner.is_player() {
+                    state.log.add(
+                        format!("You digest the wall, {} more to go", integrity),
+                        MsgClass::Action,
+                    );
+                }
+                ActionResult::Success {
+                    callback: ObjectFeedback::NoFeedback,
+                }
+            }
         }
     }
 
     fn set_target(&mut self, target: Target) {
-        self.direction = target;
+        self.target = target;
+    }
+
+    fn get_target(&self) -> Option<Target> {
+        Some(self.target.clone())
     }
 
     fn set_level(&mut self, lvl: i32) {
@@ -145,7 +1421,7 @@ impl Action for ActMove {
     }
 
     fn get_target_category(&self) -> TargetCategory {
-        TargetCategory::EmptyObject
+        TargetCategory::BlockingObject
     }
 
     fn get_level(&self) -> i32 {
@@ -153,7 +1429,7 @@ impl Action for ActMove {
     }
 
     fn get_identifier(&self) -> String {
-        "move".to_string()
+        "digest wall".to_string()
     }
 
     fn get_energy_cost(&self) -> i32 {
@@ -161,42 +1437,64 @@ impl Action for ActMove {
     }
 
     fn to_text(&self) -> String {
-        format!("move to {:?}", self.direction)
+        format!("digest wall {:?}", self.target)
     }
 }
 
-/// Focus on increased energy production for this turn.
+/// Number of turns that peace pheromones last at level 0.
+const PEACE_DURATION: i32 = 6;
+/// Improvement of the receivers' attitude towards the emitter's faction.
+const PEACE_STANDING: i32 = 40;
+/// Number of turns that a threat display lasts at level 0.
+const THREAT_DURATION: i32 = 3;
+/// Improvement of weaker receivers' attitude, who are intimidated by the display.
+const THREAT_DETERRENCE: i32 = 60;
+/// Worsening of stronger receivers' attitude, who are provoked by the display.
+const THREAT_PROVOCATION: i32 = -30;
+
+fn log_signal(state: &mut GameState, owner: &Object, kind: SignalKind, receivers: usize) {
+    if owner.is_player() {
+        state.log.add(
+            format!("You emit {}, {} organisms respond", kind.name(), receivers),
+            MsgClass::Info,
+        );
+    } else if owner.physics.is_visible {
+        state.log.add_at(
+            format!("{} emits {}", owner.visual.name, kind.name()),
+            MsgClass::Info,
+            owner.pos,
+        );
+    }
+}
+
+/// Release pheromones that calm down nearby organisms with a matching receptor, making them less
+/// hostile towards the emitter's faction for a while.
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ActMetabolise {
+pub struct ActPeacePheromone {
     lvl: i32,
 }
 
-impl ActMetabolise {
+impl ActPeacePheromone {
     pub fn new() -> Self {
-        ActMetabolise { lvl: 0 }
+        ActPeacePheromone { lvl: 0 }
     }
 }
 
 #[typetag::serde]
-impl Action for ActMetabolise {
+impl Action for ActPeacePheromone {
     fn perform(
         &self,
-        _state: &mut GameState,
-        _objects: &mut GameObjects,
+        state: &mut GameState,
+        objects: &mut GameObjects,
         owner: &mut Object,
     ) -> ActionResult {
-        owner.processors.energy += self.lvl;
-        if owner.physics.is_visible {
-            register_particle(
-                owner.pos,
-                (50, 255, 50),
-                palette().world_bg_ground_fov_true,
-                owner.visual.glyph,
-                150.0,
-            )
-        }
+        let turns = PEACE_DURATION + self.lvl;
+        let receivers = emit(objects, owner, SignalKind::Peace, turns, |_, _| {
+            PEACE_STANDING
+        });
+        log_signal(state, owner, SignalKind::Peace, receivers);
         ActionResult::Success {
-            callback: ObjectFeedback::NoFeedback,
+            callback: ObjectFeedback::Render,
         }
     }
 
@@ -210,102 +1508,80 @@ impl Action for ActMetabolise {
         TargetCategory::None
     }
 
+    fn get_range(&self) -> i32 {
+        SIGNAL_RADIUS as i32
+    }
+
     fn get_level(&self) -> i32 {
         self.lvl
     }
 
     fn get_identifier(&self) -> String {
-        "metabolize".to_string()
+        "emit peace pheromone".to_string()
     }
 
     fn get_energy_cost(&self) -> i32 {
-        0
+        2
     }
 
     fn to_text(&self) -> String {
-        "increase metabolism momentarily".to_string()
+        "emit peace pheromone".to_string()
     }
 }
 
-/// Attack another object.
+/// Put on a show of strength. Nearby organisms with a matching receptor that are weaker than the
+/// emitter back off, while stronger ones take offence.
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ActAttack {
+pub struct ActThreatDisplay {
     lvl: i32,
-    target: Target,
 }
 
-impl ActAttack {
+impl ActThreatDisplay {
     pub fn new() -> Self {
-        ActAttack {
-            lvl: 0,
-            target: Target::Center,
-        }
+        ActThreatDisplay { lvl: 0 }
     }
 }
 
 #[typetag::serde]
-impl Action for ActAttack {
+impl Action for ActThreatDisplay {
     fn perform(
         &self,
         state: &mut GameState,
         objects: &mut GameObjects,
         owner: &mut Object,
     ) -> ActionResult {
-        // get coords of self position plus direction
-        // find any objects that are at that position and blocking
-        // assert that there is only one available
-        // return
-        let target_pos: Position = owner.pos.get_translated(&self.target.to_pos());
-        let valid_target: Option<&mut Object> = objects
-            .get_vector_mut()
-            .iter_mut()
-            .flatten()
-            .find(|o| o.physics.is_blocking && o.pos.is_equal(&target_pos));
-
-        match valid_target {
-            Some(t) => {
-                // deal damage
-                t.actuators.hp -= self.lvl;
-                debug!("target hp: {}/{}", t.actuators.hp, t.actuators.max_hp);
-                state.log.add(
-                    format!(
-                        "{} attacked {} for {} damage",
-                        &owner.visual.name, &t.visual.name, self.lvl
-                    ),
-                    MsgClass::Info,
-                );
-                // show particle effect
-                if t.physics.is_visible {
-                    register_particle(
-                        t.pos,
-                        (200, 10, 10),
-                        palette().world_bg_ground_fov_true,
-                        'x',
-                        250.0,
-                    )
-                }
-                ActionResult::Success {
-                    // TODO: Add particle to emphasise something happened!
-                    callback: ObjectFeedback::NoFeedback,
+        let turns = THREAT_DURATION + self.lvl;
+        let receivers = emit(
+            objects,
+            owner,
+            SignalKind::Threat,
+            turns,
+            |emitter, receiver| {
+                if receiver.offensive_strength() < emitter.offensive_strength() {
+                    THREAT_DETERRENCE
+                } else {
+                    THREAT_PROVOCATION
                 }
-            }
-            None => {
-                state.log.add("Nothing to attack here", MsgClass::Info);
-                ActionResult::Failure
-            }
+            },
+        );
+        log_signal(state, owner, SignalKind::Threat, receivers);
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
         }
     }
 
-    fn set_target(&mut self, target: Target) {
-        self.target = target;
-    }
+    fn set_target(&mut self, _t: Target) {}
 
     fn set_level(&mut self, lvl: i32) {
         self.lvl = lvl;
     }
 
     fn get_target_category(&self) -> TargetCategory {
-        TargetCategory::BlockingObject
+        TargetCategory::None
+    }
+
+    fn get_range(&self) -> i32 {
+        SIGNAL_RADIUS as i32
     }
 
     fn get_level(&self) -> i32 {
@@ -313,108 +1589,18 @@ impl Action for ActAttack {
     }
 
     fn get_identifier(&self) -> String {
-        "attack".to_string()
+        "threat display".to_string()
     }
 
     fn get_energy_cost(&self) -> i32 {
-        self.lvl
+        2
     }
 
     fn to_text(&self) -> String {
-        format!("attack {:?}", self.target)
+        "threat display".to_string()
     }
 }
 
-// TODO: Add actions for
-// - attaching to another cell
-// - inserting genome into another cell
-// - immobilising and manipulating another cell
-// - producing stuff
-
-// /// Attach to another object.
-// #[derive(Debug, Serialize, Deserialize, Clone)]
-// pub struct ActAttach {
-//     lvl: i32,
-//     target: Target,
-// }
-//
-// impl ActAttach {
-//     pub fn new() -> Self {
-//         ActAttach {
-//             lvl: 0,
-//             target: Target::Center,
-//         }
-//     }
-// }
-//
-// #[typetag::serde]
-// impl Action for ActAttach {
-//     fn perform(
-//         &self,
-//         state: &mut GameState,
-//         objects: &mut GameObjects,
-//         owner: &mut Object,
-//     ) -> ActionResult {
-//         // get coords of self position plus direction
-//         // find any objects that are at that position and blocking
-//         // assert that there is only one available
-//         // return
-//         let target_pos: Position = owner.pos.get_translated(&self.target.to_pos());
-//         let valid_target: Option<&mut Object> = objects
-//             .get_vector_mut()
-//             .iter_mut()
-//             .flatten()
-//             .find(|o| o.physics.is_blocking && o.pos.is_equal(&target_pos));
-//
-//         match valid_target {
-//             Some(t) => {
-//                 // deal damage
-//                 t.actuators.hp -= self.lvl;
-//                 debug!("target hp: {}/{}", t.actuators.hp, t.actuators.max_hp);
-//                 state.log.add(
-//                     format!(
-//                         "{} attacked {} for {} damage",
-//                         &owner.visual.name, &t.visual.name, self.lvl
-//                     ),
-//                     MsgClass::Info,
-//                 );
-//                 ActionResult::Success {
-//                     // TODO: Add particle to emphasise something happened!
-//                     callback: ObjectFeedback::NoFeedback,
-//                 }
-//             }
-//             None => {
-//                 state.log.add("Nothing to attack here", MsgClass::Info);
-//                 ActionResult::Failure
-//             }
-//         }
-//     }
-//
-//     fn set_target(&mut self, target: Target) {
-//         self.target = target;
-//     }
-//
-//     fn set_level(&mut self, lvl: i32) {
-//         self.lvl = lvl;
-//     }
-//
-//     fn get_target_category(&self) -> TargetCategory {
-//         TargetCategory::BlockingObject
-//     }
-//
-//     fn get_identifier(&self) -> String {
-//         "attack".to_string()
-//     }
-//
-//     fn get_energy_cost(&self) -> i32 {
-//         self.lvl
-//     }
-//
-//     fn to_text(&self) -> String {
-//         format!("attack {:?}", self.target)
-//     }
-// }
-
 /// A virus' sole purpose is to go forth and multiply.
 /// This action corresponds to the virus trait which is located at the beginning of virus DNA.
 /// RNA viruses inject their RNA into a host cell and force them to replicate the virus WITHOUT
@@ -465,12 +1651,13 @@ impl Action for ActInjectRnaVirus {
                     || owner.is_player()
                     || owner.physics.is_visible
                 {
-                    state.log.add(
+                    state.log.add_at(
                         format!(
                             "{0} has infected {1} with virus RNA. {1} is forced to produce virions",
                             owner.visual.name, target.visual.name
                         ),
                         MsgClass::Alert,
+                        target.pos,
                     );
                 }
                 // TODO: Add forced production for retroviruses
@@ -620,6 +1807,17 @@ impl Action for ActInjectRetrovirus {
                     let bg = palette().world_bg_ground_fov_true;
                     register_particle(owner.pos.into(), fg, bg, '?', 150.0);
                 }
+            } else if !is_within_dna_cap(target.dna.raw.len() + owner.dna.raw.len()) {
+                // the cell could no longer replicate a genome any longer than this
+                if owner.physics.is_visible {
+                    state.log.add(
+                        format!(
+                            "A virus has tried to infect {} but its genome is too long already!",
+                            target.visual.name
+                        ),
+                        MsgClass::Info,
+                    );
+                }
             } else if target
                 .processors
                 .receptors
@@ -629,9 +1827,14 @@ impl Action for ActInjectRetrovirus {
                 // target and  owner must have matching receptor
                 let mut new_dna = target.dna.raw.clone();
                 new_dna.append(&mut owner.dna.raw.clone());
-                let (s, p, a, d) = state
-                    .gene_library
-                    .dna_to_traits(target.dna.dna_type, new_dna.as_ref());
+                let (s, p, a, d) = state.gene_library.dna_to_marked_traits(
+                    target.dna.dna_type,
+                    new_dna.as_ref(),
+                    &target.dna.silenced,
+                );
+                target
+                    .mutations
+                    .record(state.turn, &target.dna, &d, &state.gene_library);
                 target.change_genome(s, p, a, d);
 
                 // The virus becomes an empty shell after successfully transmitting its dna.
@@ -640,9 +1843,10 @@ impl Action for ActInjectRetrovirus {
                 owner.alive = false;
                 // ..because it's still debated as to whether viruses are alive to begin with.
                 if owner.physics.is_visible {
-                    state.log.add(
+                    state.log.add_at(
                         format!("A virus has infected {}!", target.visual.name),
                         MsgClass::Alert,
+                        target.pos,
                     );
                     // play a little particle effect
                     let fg = palette().hud_fg_bar_health;
@@ -663,6 +1867,10 @@ impl Action for ActInjectRetrovirus {
         self.target = t
     }
 
+    fn get_target(&self) -> Option<Target> {
+        Some(self.target.clone())
+    }
+
     fn set_level(&mut self, lvl: i32) {
         self.lvl = lvl;
     }
@@ -716,6 +1924,16 @@ impl Action for ActProduceVirion {
         _objects: &mut GameObjects,
         owner: &mut Object,
     ) -> ActionResult {
+        // the host's medication keeps viruses from multiplying
+        if state.host.has_condition(HostCondition::Medication) {
+            if owner.is_player() {
+                state.log.add(
+                    "The medication keeps you from producing virions.",
+                    MsgClass::Info,
+                );
+            }
+            return ActionResult::Failure;
+        }
         match &self.virus_rna {
             Some(dna) => {
                 debug!("#{} produces virion", owner.visual.name);
@@ -854,6 +2072,191 @@ impl Action for ActEditGenome {
     }
 }
 
+/// Transposons carried by some plasmids jump into junk stretches of the genome and turn them into
+/// working genes again. The level is the number of junk genes reactivated at once.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActReactivateJunk {
+    lvl: i32,
+}
+
+impl ActReactivateJunk {
+    pub fn new() -> Self {
+        ActReactivateJunk { lvl: 1 }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActReactivateJunk {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let reactivated = owner.reactivate_junk(state, self.lvl.max(1) as usize);
+        if owner.is_player() {
+            if reactivated {
+                state.log.add(
+                    "The transposon reactivates dormant stretches of your junk DNA!",
+                    MsgClass::Story,
+                );
+            } else {
+                state.log.add(
+                    "The transposon finds no junk DNA to reactivate.",
+                    MsgClass::Info,
+                );
+            }
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "reactivate junk".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        self.lvl
+    }
+
+    fn to_text(&self) -> String {
+        "reactivate junk DNA".to_string()
+    }
+}
+
+/// Chance that splicing in a foreign gene also mutates one of the cell's own genes, on top of the
+/// cell's own genetic instability.
+const ABSORB_RISK: f64 = 0.2;
+
+/// Absorb the organic debris left behind on the owner's tile by a dead organism. One randomly
+/// chosen gene of the remains is spliced into the owner's genome, at the risk of a mutation.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActAbsorbDna {
+    lvl: i32,
+}
+
+impl ActAbsorbDna {
+    pub fn new() -> Self {
+        ActAbsorbDna { lvl: 3 }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActAbsorbDna {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let debris = match objects.take_debris(&owner.pos) {
+            Some(debris) => debris,
+            None => {
+                if owner.is_player() {
+                    state
+                        .log
+                        .add("There are no remains here to absorb.", MsgClass::Info);
+                }
+                return ActionResult::Failure;
+            }
+        };
+        let rng = state.rng_streams.get(RngStream::Mutation);
+        let gene = match debris.genes.choose(rng) {
+            Some(gene) => gene.clone(),
+            None => return ActionResult::Failure,
+        };
+
+        // splicing in foreign DNA may damage the cell's own genes
+        let mut genes = owner.dna.simplified.clone();
+        let risk = innit_env()
+            .difficulty
+            .mutation_chance(ABSORB_RISK + 1.0 - owner.gene_stability);
+        let mut mutated = None;
+        if !genes.is_empty() && rng.flip_with_prob(risk) {
+            let idx = rng.gen_range(0..genes.len());
+            let mut mutation = state
+                .gene_library
+                .mutate_gene(rng, owner.dna.dna_type, &genes[idx]);
+            mutation.position = genes[idx].position;
+            mutated = Some(genes[idx].trait_name.clone());
+            genes[idx] = mutation;
+        }
+        genes.push(gene.clone());
+        let raw_dna = state.gene_library.g_traits_to_dna(&genes);
+        if !is_within_dna_cap(raw_dna.len()) {
+            if owner.is_player() {
+                state.log.add(
+                    "Your genome is too long to take up another gene.",
+                    MsgClass::Info,
+                );
+            }
+            objects.leave_debris(&owner.pos, debris);
+            return ActionResult::Failure;
+        }
+        owner.express_dna(state, &raw_dna);
+
+        if owner.is_player() {
+            state.log.add(
+                format!(
+                    "You absorb the remains of {} and splice {} into your genome.",
+                    debris.source, gene.trait_name
+                ),
+                MsgClass::Story,
+            );
+            if let Some(name) = mutated {
+                state.log.add(
+                    format!("Your {} gene mutates in the process!", name),
+                    MsgClass::Alert,
+                );
+            }
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::Render,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "absorb dna".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        self.lvl
+    }
+
+    fn to_text(&self) -> String {
+        "absorb DNA from remains".to_string()
+    }
+}
+
 /// Ability for a cell to trigger its own killswitch. It can also trigger someone else's killswitch
 /// provided that cell also has a killswitch and a matching receptor.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -922,6 +2325,10 @@ impl Action for ActKillSwitch {
         self.target = t;
     }
 
+    fn get_target(&self) -> Option<Target> {
+        Some(self.target.clone())
+    }
+
     fn set_level(&mut self, lvl: i32) {
         self.lvl = lvl;
     }
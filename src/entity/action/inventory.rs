@@ -7,6 +7,7 @@ use crate::{
     },
     entity::{
         action::{Action, ActionResult, Target, TargetCategory},
+        inventory::{identify, is_identified, item_name},
         object::Object,
     },
 };
@@ -27,25 +28,31 @@ impl Action for ActPickUpItem {
         if let Some((index, Some(target_obj))) = objects.extract_item_by_pos(&owner.pos) {
             // do stuff with object
             if target_obj.item.is_some() {
-                if owner.inventory.items.len() < owner.actuators.volume as usize {
-                    // only add object if it has in item tag
-                    state.log.add(
-                        format!(
-                            "{} picked up a {}",
-                            owner.visual.name, &target_obj.visual.name
-                        ),
-                        MsgClass::Info,
-                    );
-                    owner.add_to_inventory(state, target_obj);
-
-                    // keep the object vector neat and tidy
-                    objects.get_vector_mut().remove(index);
-
-                    return ActionResult::Success {
-                        callback: ObjectFeedback::NoFeedback,
-                    };
-                } else {
-                    state.log.add("Your inventory is full!", MsgClass::Info);
+                match owner.check_capacity(&target_obj) {
+                    Ok(()) => {
+                        // only add object if it has in item tag
+                        state.log.add(
+                            format!(
+                                "{} picked up a {}",
+                                owner.visual.name,
+                                item_name(state, &target_obj)
+                            ),
+                            MsgClass::Info,
+                        );
+                        owner.add_to_inventory(state, target_obj);
+
+                        // keep the object vector neat and tidy
+                        objects.get_vector_mut().remove(index);
+
+                        return ActionResult::Success {
+                            callback: ObjectFeedback::NoFeedback,
+                        };
+                    }
+                    Err(msg) => {
+                        if owner.is_player() {
+                            state.log.add(msg, MsgClass::Info);
+                        }
+                    }
                 }
             }
             //else {
@@ -107,7 +114,11 @@ impl Action for ActDropItem {
         if owner.inventory.items.len() > self.lvl as usize {
             let mut item: Object = owner.remove_from_inventory(state, self.lvl as usize);
             state.log.add(
-                format!("{} dropped a {}", owner.visual.name, &item.visual.name),
+                format!(
+                    "{} dropped a {}",
+                    owner.visual.name,
+                    item_name(state, &item)
+                ),
                 MsgClass::Info,
             );
             // set the item to be dropped at the same position as the player
@@ -154,3 +165,142 @@ impl Action for ActDropItem {
         "drop item".to_string()
     }
 }
+
+/// Energy it takes to analyze an item.
+const ANALYZE_COST: i32 = 2;
+
+/// Analyze the first unidentified item in the owner's inventory, which reveals the genes of all
+/// items of the same kind.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActAnalyze;
+
+#[typetag::serde]
+impl Action for ActAnalyze {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let item = match owner
+            .inventory
+            .items
+            .iter()
+            .find(|item| !is_identified(state, item))
+        {
+            Some(item) => item,
+            None => {
+                if owner.is_player() {
+                    state
+                        .log
+                        .add("You carry nothing left to analyze.", MsgClass::Info);
+                }
+                return ActionResult::Failure;
+            }
+        };
+        let vague_name = item_name(state, item);
+        identify(state, item);
+        if owner.is_player() {
+            let genes: Vec<&str> = item
+                .dna
+                .simplified
+                .iter()
+                .map(|g| g.trait_name.as_str())
+                .collect();
+            state.log.add(
+                format!(
+                    "You analyze the {}: it's a {} with {}",
+                    vague_name,
+                    item.visual.name,
+                    genes.join(", ")
+                ),
+                MsgClass::Action,
+            );
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::UpdateHud,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, _lvl: i32) {}
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        0
+    }
+
+    fn get_identifier(&self) -> String {
+        "analyze item".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        ANALYZE_COST
+    }
+
+    fn to_text(&self) -> String {
+        "analyze item".to_string()
+    }
+}
+
+/// Take in the energy held by an item. The action level determines the amount of energy.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActAbsorbEnergy {
+    lvl: i32,
+}
+
+impl ActAbsorbEnergy {
+    pub fn new(lvl: i32) -> Self {
+        ActAbsorbEnergy { lvl }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActAbsorbEnergy {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        owner.store_energy(self.lvl);
+        if owner.is_player() {
+            state
+                .log
+                .add(format!("You absorb {} energy.", self.lvl), MsgClass::Action);
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::UpdateHud,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "absorb energy".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        format!("absorb {} energy", self.lvl)
+    }
+}
@@ -0,0 +1,154 @@
+//! This module contains the actions that move organelles in and out of an organism's internal
+//! slots.
+
+use crate::{
+    core::{
+        game_objects::GameObjects,
+        game_state::{GameState, MessageLog, MsgClass, ObjectFeedback},
+    },
+    entity::{
+        action::{Action, ActionResult, Target, TargetCategory},
+        object::Object,
+        organelle::Organelle,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Take up an organelle into a free internal slot. The organelle item has already been taken out
+/// of the inventory when this action is used, it's put back if all slots are taken.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActEquipOrganelle {
+    organelle: Organelle,
+}
+
+impl ActEquipOrganelle {
+    pub fn new(organelle: Organelle) -> Self {
+        ActEquipOrganelle { organelle }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActEquipOrganelle {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        if owner.organelles.equip(self.organelle) {
+            owner.refresh_organelles(state);
+            if owner.is_player() {
+                state.log.add(
+                    format!("You take up the {}.", self.organelle.name()),
+                    MsgClass::Action,
+                );
+            }
+            ActionResult::Success {
+                callback: ObjectFeedback::UpdateHud,
+            }
+        } else {
+            if owner.is_player() {
+                state
+                    .log
+                    .add("All of your organelle slots are taken.", MsgClass::Info);
+            }
+            let item = self.organelle.to_item(owner.pos.x, owner.pos.y);
+            owner.add_to_inventory(state, item);
+            ActionResult::Failure
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, _lvl: i32) {}
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        0
+    }
+
+    fn get_identifier(&self) -> String {
+        "equip organelle".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        format!("equip {}", self.organelle.name())
+    }
+}
+
+/// Take an organelle out of the owner's internal slots and carry it in the inventory, or drop it
+/// if the inventory is full. The action level determines the organelle slot.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActUnequipOrganelle {
+    lvl: i32,
+}
+
+impl ActUnequipOrganelle {
+    pub fn new(lvl: i32) -> Self {
+        ActUnequipOrganelle { lvl }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActUnequipOrganelle {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let organelle = match owner.organelles.unequip(self.lvl as usize) {
+            Some(organelle) => organelle,
+            None => return ActionResult::Failure,
+        };
+        owner.refresh_organelles(state);
+        let item = organelle.to_item(owner.pos.x, owner.pos.y);
+        if owner.check_capacity(&item).is_ok() {
+            owner.add_to_inventory(state, item);
+        } else {
+            objects.get_vector_mut().push(Some(item));
+        }
+        if owner.is_player() {
+            state.log.add(
+                format!("You give up the {}.", organelle.name()),
+                MsgClass::Action,
+            );
+        }
+        ActionResult::Success {
+            callback: ObjectFeedback::UpdateHud,
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, lvl: i32) {
+        self.lvl = lvl;
+    }
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        self.lvl
+    }
+
+    fn get_identifier(&self) -> String {
+        "unequip organelle".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        "unequip organelle".to_string()
+    }
+}
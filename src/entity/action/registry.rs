@@ -0,0 +1,136 @@
+//! The action registry maps the name of an action type to a constructor, so that actions can be
+//! created at runtime, e.g. from raws, a debug console or key bindings.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::action::hereditary::*;
+use crate::entity::action::inventory::{ActAbsorbEnergy, ActAnalyze, ActDropItem, ActPickUpItem};
+use crate::entity::action::organelle::{ActEquipOrganelle, ActUnequipOrganelle};
+use crate::entity::action::{Action, Target};
+use crate::entity::organelle::Organelle;
+use crate::raws::object_template::DnaTemplate;
+
+/// Optional parameters for constructing an action. Anything not given keeps the action's default.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ActionParams {
+    pub level: Option<i32>,
+    pub target: Option<Target>,
+    /// Genetic payload as hexadecimal bytes, for actions that carry DNA or RNA.
+    pub dna: Option<String>,
+    /// Organelle to take up, for actions that equip one.
+    pub organelle: Option<Organelle>,
+}
+
+impl ActionParams {
+    fn parse_dna(&self) -> Result<Vec<u8>, String> {
+        match &self.dna {
+            Some(dna) => DnaTemplate::parse_dna(dna),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+type ActionConstructor = fn(&ActionParams) -> Result<Box<dyn Action>, String>;
+
+pub struct ActionRegistry {
+    constructors: HashMap<&'static str, ActionConstructor>,
+}
+
+impl ActionRegistry {
+    fn new() -> Self {
+        let mut registry = ActionRegistry {
+            constructors: HashMap::new(),
+        };
+        registry.register("ActPass", |_| Ok(Box::new(ActPass::default())));
+        registry.register("ActMove", |_| Ok(Box::new(ActMove::new())));
+        registry.register("ActPush", |_| Ok(Box::new(ActPush::new())));
+        registry.register("ActSwap", |_| Ok(Box::new(ActSwap::new())));
+        registry.register("ActOpenBarrier", |_| Ok(Box::new(ActOpenBarrier::new())));
+        registry.register("ActMetabolise", |_| Ok(Box::new(ActMetabolise::new())));
+        registry.register("ActAttack", |_| Ok(Box::new(ActAttack::new())));
+        registry.register("ActAttach", |_| Ok(Box::new(ActAttach::new())));
+        registry.register("ActDetach", |_| Ok(Box::new(ActDetach)));
+        registry.register("ActFollowScent", |_| Ok(Box::new(ActFollowScent::new())));
+        registry.register("ActEditGenome", |_| Ok(Box::new(ActEditGenome::new())));
+        registry.register("ActReactivateJunk", |_| {
+            Ok(Box::new(ActReactivateJunk::new()))
+        });
+        registry.register("ActAbsorbDna", |_| Ok(Box::new(ActAbsorbDna::new())));
+        registry.register("ActKillSwitch", |_| Ok(Box::new(ActKillSwitch::new())));
+        registry.register("ActDormancy", |_| Ok(Box::new(ActDormancy::new())));
+        registry.register("ActRest", |_| Ok(Box::new(ActRest::new())));
+        registry.register("ActSecreteToxin", |_| Ok(Box::new(ActSecreteToxin::new())));
+        registry.register("ActDigestWall", |_| Ok(Box::new(ActDigestWall::new())));
+        registry.register("ActPeacePheromone", |_| {
+            Ok(Box::new(ActPeacePheromone::new()))
+        });
+        registry.register("ActThreatDisplay", |_| {
+            Ok(Box::new(ActThreatDisplay::new()))
+        });
+        registry.register("ActInjectRnaVirus", |p| {
+            let target = p.target.clone().unwrap_or(Target::Center);
+            Ok(Box::new(ActInjectRnaVirus::new(target, p.parse_dna()?)))
+        });
+        registry.register("ActProduceVirion", |p| {
+            let rna = p.dna.as_ref().map(|_| p.parse_dna()).transpose()?;
+            Ok(Box::new(ActProduceVirion::new(rna)))
+        });
+        registry.register("ActPickUpItem", |_| Ok(Box::new(ActPickUpItem)));
+        registry.register("ActAnalyze", |_| Ok(Box::new(ActAnalyze)));
+        registry.register("ActAbsorbEnergy", |p| {
+            Ok(Box::new(ActAbsorbEnergy::new(p.level.unwrap_or(0))))
+        });
+        registry.register("ActDropItem", |p| {
+            Ok(Box::new(ActDropItem::new(p.level.unwrap_or(0))))
+        });
+        registry.register("ActEquipOrganelle", |p| {
+            let organelle = p.organelle.unwrap_or(Organelle::Mitochondrion);
+            Ok(Box::new(ActEquipOrganelle::new(organelle)))
+        });
+        registry.register("ActUnequipOrganelle", |p| {
+            Ok(Box::new(ActUnequipOrganelle::new(p.level.unwrap_or(0))))
+        });
+        registry
+    }
+
+    fn register(&mut self, name: &'static str, constructor: ActionConstructor) {
+        self.constructors.insert(name, constructor);
+    }
+
+    /// Construct the action registered under `name` and apply the given parameters.
+    pub fn create(&self, name: &str, params: &ActionParams) -> Result<Box<dyn Action>, String> {
+        let constructor = self.constructors.get(name).ok_or_else(|| {
+            format!(
+                "cannot find action for {}, known actions are {}",
+                name,
+                self.names().join(", ")
+            )
+        })?;
+        let mut action = constructor(params)?;
+        if let Some(level) = params.level {
+            action.set_level(level);
+        }
+        if let Some(target) = &params.target {
+            action.set_target(target.clone());
+        }
+        Ok(action)
+    }
+
+    /// Names of all registered actions, in alphabetical order.
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self.constructors.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+lazy_static! {
+    static ref ACTION_REGISTRY: ActionRegistry = ActionRegistry::new();
+}
+
+pub fn action_registry() -> &'static ActionRegistry {
+    &ACTION_REGISTRY
+}
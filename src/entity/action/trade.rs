@@ -0,0 +1,140 @@
+//! This module contains the actions that exchange goods between organisms.
+
+use crate::{
+    core::{
+        game_objects::GameObjects,
+        game_state::{GameState, MessageLog, MsgClass, ObjectFeedback},
+    },
+    entity::{
+        action::{Action, ActionResult, Target, TargetCategory},
+        bark::{bark, BarkTrigger},
+        inventory::{identify, item_name},
+        object::Object,
+        trade::{gene_fragment, is_willing, TradeGoods, TradeOffer},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// Improvement of the standing between the factions of both parties after each trade.
+const TRADE_GOODWILL: i32 = 2;
+
+/// Buy goods from an adjacent organism. The price is paid as the energy cost of the action and
+/// handed over to the seller, or refunded if the trade falls through.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActTrade {
+    target: Target,
+    offer: TradeOffer,
+}
+
+impl ActTrade {
+    pub fn new(target: Target, offer: TradeOffer) -> Self {
+        ActTrade { target, offer }
+    }
+}
+
+#[typetag::serde]
+impl Action for ActTrade {
+    fn perform(
+        &self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        let target_pos = owner.pos.get_translated(&self.target.to_pos());
+        let (index, mut partner) = match objects.extract_entity_by_pos(&target_pos) {
+            Some((index, Some(partner))) => (index, partner),
+            _ => {
+                owner.processors.energy += self.offer.price;
+                return ActionResult::Failure;
+            }
+        };
+
+        if owner.is_player() {
+            bark(state, &mut partner, BarkTrigger::Interacted);
+        }
+        let goods = if !is_willing(state, &partner, owner) {
+            None
+        } else {
+            match &self.offer.goods {
+                TradeGoods::Plasmid { slot } if *slot < partner.inventory.items.len() => {
+                    Some(partner.remove_from_inventory(state, *slot))
+                }
+                TradeGoods::GeneFragment { gene } => partner
+                    .dna
+                    .simplified
+                    .iter()
+                    .find(|g| g.trait_name == *gene)
+                    .map(|g| gene_fragment(state, &partner, g)),
+                _ => None,
+            }
+        };
+
+        let result = if let Some(goods) = goods {
+            // the partner tells what's in the fragments it buds off
+            if matches!(self.offer.goods, TradeGoods::GeneFragment { .. }) {
+                identify(state, &goods);
+            }
+            if owner.is_player() {
+                state.log.add(
+                    format!(
+                        "You trade {} energy with {} for {}",
+                        self.offer.price,
+                        partner.visual.name,
+                        item_name(state, &goods)
+                    ),
+                    MsgClass::Action,
+                );
+            }
+            partner.store_energy(self.offer.price);
+            owner.add_to_inventory(state, goods);
+            state
+                .relations
+                .shift(owner.faction, partner.faction, TRADE_GOODWILL);
+            ActionResult::Success {
+                callback: ObjectFeedback::UpdateHud,
+            }
+        } else {
+            // nothing changes hands, so the price is refunded
+            owner.processors.energy += self.offer.price;
+            if owner.is_player() {
+                state.log.add(
+                    format!("{} refuses to trade", partner.visual.name),
+                    MsgClass::Info,
+                );
+            }
+            ActionResult::Failure
+        };
+        objects.replace(index, partner);
+        result
+    }
+
+    fn set_target(&mut self, target: Target) {
+        self.target = target;
+    }
+
+    fn get_target(&self) -> Option<Target> {
+        Some(self.target.clone())
+    }
+
+    fn set_level(&mut self, _lvl: i32) {}
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::BlockingObject
+    }
+
+    fn get_level(&self) -> i32 {
+        0
+    }
+
+    fn get_identifier(&self) -> String {
+        "trade".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        self.offer.price
+    }
+
+    fn to_text(&self) -> String {
+        format!("trade for {}", self.offer.description)
+    }
+}
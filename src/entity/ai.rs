@@ -6,19 +6,42 @@
 
 use crate::entity::control::{Ai, Controller};
 use crate::entity::object::Object;
+use crate::{core::game_state::GameState, entity::action::hereditary::ActProduceVirion};
 use crate::{
-    core::game_objects::GameObjects,
+    core::{game_objects::GameObjects, position::Position},
     entity::action::hereditary::{ActInjectRnaVirus, ActPass},
 };
-use crate::{core::game_state::GameState, entity::action::hereditary::ActProduceVirion};
 use crate::{
     entity::action::{hereditary::ActMove, Action, Target, TargetCategory},
-    util::game_rng::RngExtended,
+    util::game_rng::{RngExtended, RngStream},
 };
 use rand::seq::{IteratorRandom, SliceRandom};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
+/// Create an AI by the name of its type, as used in the object templates.
+pub fn ai_from_name(name: &str) -> Result<Box<dyn Ai>, String> {
+    match name {
+        "AiPassive" => Ok(Box::new(AiPassive)),
+        "AiRandom" => Ok(Box::new(AiRandom::new())),
+        "AiRandomWalk" => Ok(Box::new(AiRandomWalk)),
+        "AiTrailFollower" => Ok(Box::new(AiTrailFollower::new())),
+        "AiVirus" => Ok(Box::new(AiVirus::new())),
+        s => Err(format!("Unknown controller type '{}'", s)),
+    }
+}
+
+/// What an NPC decided in its latest turn. Only recorded in debug mode, for the AI debug overlay.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AiTrace {
+    /// Kind of AI and the action it picked.
+    pub label: String,
+    /// Position the picked action is aimed at, if it takes a target.
+    pub target: Option<Position>,
+    /// Positions the AI intends to move through next.
+    pub path: Vec<Position>,
+}
+
 /// As the name suggests this AI passes its turn forever.
 /// This might actually be replaced with [Object.control](crate::entity::object::Object) == None, which save some more CPU cycles.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -69,7 +92,7 @@ impl Ai for AiRandom {
             .iter()
             .flatten()
             .filter(|obj| {
-                owner.pos.is_adjacent(&obj.pos)
+                owner.pos.is_neighbour(&obj.pos)
                     && (obj.physics.is_blocking || !objects.is_pos_occupied(&obj.pos))
             })
             // .filter_map(|o| o.as_ref())
@@ -110,7 +133,7 @@ impl Ai for AiRandom {
             .filter(|a| valid_targets.contains(&(*a).get_target_category()))
             .collect();
 
-        if let Some(a) = possible_actions.choose(&mut state.rng) {
+        if let Some(a) = possible_actions.choose(state.rng_for(RngStream::Ai)) {
             let mut boxed_action = a.clone_action();
             match boxed_action.get_target_category() {
                 TargetCategory::None => boxed_action.set_target(Target::Center),
@@ -118,7 +141,7 @@ impl Ai for AiRandom {
                     if let Some(target_obj) = adjacent_targets
                         .iter()
                         .filter(|at| at.physics.is_blocking)
-                        .choose(&mut state.rng)
+                        .choose(state.rng_for(RngStream::Ai))
                     {
                         boxed_action.set_target(Target::from_pos(&owner.pos, &target_obj.pos))
                     }
@@ -127,13 +150,14 @@ impl Ai for AiRandom {
                     if let Some(target_obj) = adjacent_targets
                         .iter()
                         .filter(|at| !at.physics.is_blocking)
-                        .choose(&mut state.rng)
+                        .choose(state.rng_for(RngStream::Ai))
                     {
                         boxed_action.set_target(Target::from_pos(&owner.pos, &target_obj.pos))
                     }
                 }
                 TargetCategory::Any => {
-                    if let Some(target_obj) = adjacent_targets.choose(&mut state.rng) {
+                    if let Some(target_obj) = adjacent_targets.choose(state.rng_for(RngStream::Ai))
+                    {
                         boxed_action.set_target(Target::from_pos(&owner.pos, &target_obj.pos))
                     }
                 }
@@ -162,12 +186,12 @@ impl Ai for AiRandomWalk {
             .iter()
             .flatten()
             .filter(|obj| {
-                owner.pos.is_adjacent(&obj.pos)
+                owner.pos.is_neighbour(&obj.pos)
                     && (obj.physics.is_blocking || !objects.is_pos_occupied(&obj.pos))
             })
             // .filter_map(|o| o.as_ref())
             .collect::<Vec<&Object>>()
-            .choose(&mut state.rng)
+            .choose(state.rng_for(RngStream::Ai))
         {
             let mut action = Box::new(ActMove::new());
             action.set_target(Target::from_pos(&owner.pos, &t.pos));
@@ -178,6 +202,125 @@ impl Ai for AiRandomWalk {
     }
 }
 
+/// Chance of a predator to overlook a dormant organism right next to it.
+const DORMANT_OVERLOOK_CHANCE: f64 = 0.75;
+/// Number of steps along the scent trail shown by the AI debug overlay.
+const TRAIL_PREVIEW: usize = 8;
+
+/// Predator that sniffs out pheromone trails and follows them towards their freshest end, attacking
+/// whatever hostile organism it finds there. Organisms without a chemoreceptor gene cannot smell anything and
+/// wander around randomly instead.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AiTrailFollower {
+    previous_pos: Option<Position>,
+}
+
+impl AiTrailFollower {
+    pub fn new() -> Self {
+        AiTrailFollower { previous_pos: None }
+    }
+}
+
+#[typetag::serde]
+impl Ai for AiTrailFollower {
+    fn act(
+        &mut self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> Box<dyn Action> {
+        // attack any prey that is right next to us
+        if let Some(attack) = owner
+            .actuators
+            .actions
+            .iter()
+            .find(|a| a.get_identifier() == "attack")
+        {
+            // only hostile organisms are prey, dormant spores look like inert matter and are
+            // mostly overlooked
+            let (relations, rng) = (&state.relations, state.rng_streams.get(RngStream::Ai));
+            let prey: Vec<&Object> = objects
+                .get_non_tiles()
+                .iter()
+                .flatten()
+                .filter(|o| o.alive && o.physics.is_blocking && owner.pos.is_neighbour(&o.pos))
+                .filter(|o| relations.is_hostile_towards(owner, o))
+                .filter(|o| !o.dormant || !rng.flip_with_prob(DORMANT_OVERLOOK_CHANCE))
+                .collect();
+            if let Some(prey) = prey.choose(rng) {
+                let mut action = attack.clone_action();
+                action.set_target(Target::from_pos(&owner.pos, &prey.pos));
+                return action;
+            }
+        }
+
+        let can_smell = owner
+            .sensors
+            .actions
+            .iter()
+            .any(|a| a.get_identifier() == "follow scent");
+
+        // Walk to the adjacent free tile with the strongest scent, but don't trace back our own
+        // steps. If there is no scent, pick a free tile at random.
+        let free_tiles: Vec<&Object> = objects
+            .get_tiles()
+            .iter()
+            .flatten()
+            .filter(|t| {
+                owner.pos.is_neighbour(&t.pos)
+                    && !t.physics.is_blocking
+                    && !objects.is_pos_occupied(&t.pos)
+                    && self.previous_pos != Some(t.pos)
+            })
+            .collect();
+        let scented = if can_smell {
+            free_tiles
+                .iter()
+                .filter(|t| objects.get_scent(&t.pos) > 0)
+                .max_by_key(|t| objects.get_scent(&t.pos))
+        } else {
+            None
+        };
+
+        if let Some(t) = scented.or_else(|| free_tiles.choose(state.rng_for(RngStream::Ai))) {
+            self.previous_pos = Some(owner.pos);
+            let mut action = Box::new(ActMove::new());
+            action.set_target(Target::from_pos(&owner.pos, &t.pos));
+            action
+        } else {
+            self.previous_pos = None;
+            Box::new(ActPass::default())
+        }
+    }
+
+    /// Follow the scent uphill from the current position, as far as it goes.
+    fn planned_path(&self, owner: &Object, objects: &GameObjects) -> Vec<Position> {
+        let mut path: Vec<Position> = Vec::new();
+        let mut pos = owner.pos;
+        while path.len() < TRAIL_PREVIEW {
+            let scent = objects.get_scent(&pos);
+            let next = objects
+                .get_tiles()
+                .iter()
+                .flatten()
+                .filter(|t| {
+                    pos.is_neighbour(&t.pos) && !t.physics.is_blocking && !path.contains(&t.pos)
+                })
+                .map(|t| t.pos)
+                .filter(|p| objects.get_scent(p) > 0 && objects.get_scent(p) >= scent)
+                .max_by_key(|p| objects.get_scent(p));
+            match next {
+                Some(next) => {
+                    path.push(next);
+                    pos = next;
+                }
+                None => break,
+            }
+        }
+        path
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AiVirus;
 
@@ -195,21 +338,23 @@ impl Ai for AiVirus {
         objects: &mut GameObjects,
         owner: &mut Object,
     ) -> Box<dyn Action> {
-        // if there is an adjacent cell, attempt to infect it
+        // if there is an adjacent hostile cell, attempt to infect it
+        let relations = &state.relations;
         if let Some(target) = objects
             .get_vector()
             .iter()
             .flatten()
             .filter(|obj| {
-                owner.pos.is_adjacent(&obj.pos)
+                owner.pos.is_neighbour(&obj.pos)
                     && (obj.physics.is_blocking)
+                    && relations.is_hostile_towards(owner, obj)
                     && obj
                         .processors
                         .receptors
                         .iter()
                         .any(|e| owner.processors.receptors.contains(e))
             })
-            .choose(&mut state.rng)
+            .choose(state.rng_streams.get(RngStream::Ai))
         {
             assert!(!owner.dna.raw.is_empty());
             return Box::new(ActInjectRnaVirus::new(
@@ -218,16 +363,37 @@ impl Ai for AiVirus {
             ));
         }
 
-        // if there is no target to infect, try a random walk instead
-        if state.rng.flip_with_prob(0.1) {
+        // if there is no target to infect, colonize the vessel walls and hold on for a while
+        if owner.attachment.is_some() {
+            if state.rng_for(RngStream::Ai).flip_with_prob(0.9) {
+                return Box::new(ActPass::default());
+            }
+        } else if let Some(attach) = owner.match_action("attach") {
+            if let Some(wall) = objects
+                .get_tiles()
+                .iter()
+                .flatten()
+                .filter(|t| t.physics.is_blocking && owner.pos.is_neighbour(&t.pos))
+                .choose(state.rng_for(RngStream::Ai))
+            {
+                let mut action = attach;
+                action.set_target(Target::from_pos(&owner.pos, &wall.pos));
+                return action;
+            }
+        }
+
+        // otherwise try a random walk instead
+        if state.rng_for(RngStream::Ai).flip_with_prob(0.1) {
             if let Some(t) = objects
                 .get_vector()
                 .iter()
                 .flatten()
-                .filter(|obj| owner.pos.is_adjacent(&obj.pos) && !objects.is_pos_occupied(&obj.pos))
+                .filter(|obj| {
+                    owner.pos.is_neighbour(&obj.pos) && !objects.is_pos_occupied(&obj.pos)
+                })
                 // .filter_map(|o| o.as_ref())
                 .collect::<Vec<&Object>>()
-                .choose(&mut state.rng)
+                .choose(state.rng_for(RngStream::Ai))
             {
                 let mut action = Box::new(ActMove::new());
                 action.set_target(Target::from_pos(&owner.pos, &t.pos));
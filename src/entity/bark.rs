@@ -0,0 +1,52 @@
+//! Barks are one-off lines of story that organisms utter when the player first comes across them.
+//! They are defined per object template in the raws and give a run some narrative texture. Each
+//! line is told only once per run, no matter how many organisms carry it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::{GameState, MessageLog, MsgClass};
+use crate::entity::object::Object;
+
+/// Occasion on which an organism barks.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarkTrigger {
+    /// The organism comes into sight of the player.
+    Seen,
+    /// The player attacks, attaches to or trades with the organism.
+    Interacted,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Bark {
+    pub trigger: BarkTrigger,
+    pub text: String,
+}
+
+/// Let the object tell all of its barks for the given trigger, unless they have been told in this
+/// run already. Told barks are gone for good.
+pub fn bark(state: &mut GameState, object: &mut Object, trigger: BarkTrigger) {
+    if object.barks.is_empty() {
+        return;
+    }
+    let (due, rest): (Vec<Bark>, Vec<Bark>) =
+        object.barks.drain(..).partition(|b| b.trigger == trigger);
+    object.barks = rest;
+    for bark in due {
+        if state.told_barks.insert(bark.text.clone()) {
+            state.log.add_at(bark.text, MsgClass::Story, object.pos);
+        }
+    }
+}
+
+/// Let all organisms in sight of the player bark for the first time.
+pub fn bark_in_sight(state: &mut GameState, objects: &mut GameObjects) {
+    for object in objects
+        .get_vector_mut()
+        .iter_mut()
+        .flatten()
+        .filter(|o| o.physics.is_visible && !o.is_player())
+    {
+        bark(state, object, BarkTrigger::Seen);
+    }
+}
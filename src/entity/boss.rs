@@ -0,0 +1,156 @@
+//! Bosses are unique, named organisms that guard their own arena. They fight in phases: whenever
+//! their health drops below the threshold of the next phase they swap their AI and unleash a
+//! special ability. Defeating a boss is recorded as a victory in the game state.
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::{GameState, MessageLog, MsgClass};
+use crate::core::host::antibody;
+use crate::core::position::Position;
+use crate::core::world::object_from_template;
+use crate::entity::action::Action;
+use crate::entity::ai::ai_from_name;
+use crate::entity::control::{Ai, Controller};
+use crate::entity::object::Object;
+use crate::raws::boss_template::BossTemplate;
+use crate::raws::object_template::ObjectTemplate;
+use serde::{Deserialize, Serialize};
+
+/// Special moves that bosses perform when entering a new phase.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum BossAbility {
+    /// Restore the given amount of hit points.
+    Regenerate { hp: i32 },
+    /// Call antibodies to the free positions around the boss.
+    Summon { count: usize },
+    /// Raise the level of the boss' attacks.
+    Enrage { attack: i32 },
+}
+
+impl BossAbility {
+    fn perform(&self, state: &mut GameState, objects: &mut GameObjects, owner: &mut Object) {
+        match self {
+            BossAbility::Regenerate { hp } => {
+                owner.actuators.hp = (owner.actuators.hp + hp).min(owner.actuators.max_hp);
+            }
+            BossAbility::Summon { count } => {
+                let free: Vec<Position> = (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| Position::new(dx, dy)))
+                    .map(|offset| owner.pos.get_translated(&offset))
+                    .filter(|p| !objects.is_pos_blocked(p) && !objects.is_pos_occupied(p))
+                    .take(*count)
+                    .collect();
+                for pos in free {
+                    objects.push(antibody(&state.gene_library, pos));
+                }
+            }
+            BossAbility::Enrage { attack } => {
+                for action in owner
+                    .actuators
+                    .actions
+                    .iter_mut()
+                    .filter(|a| a.get_identifier() == "attack")
+                {
+                    let level = action.get_level();
+                    action.set_level(level + attack);
+                }
+            }
+        }
+    }
+}
+
+/// A stage of a boss fight, which starts once the boss' health drops below the given fraction.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BossPhase {
+    pub below_health: f32,
+    /// Name of the AI that takes over in this phase, the current one stays if there is none.
+    #[serde(default)]
+    pub ai: Option<String>,
+    pub message: String,
+    #[serde(default)]
+    pub ability: Option<BossAbility>,
+}
+
+/// Drives a boss by delegating to the AI of its current phase.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AiBoss {
+    name: String,
+    phases: Vec<BossPhase>,
+    /// Index of the next phase to enter.
+    next_phase: usize,
+    ai: Box<dyn Ai>,
+}
+
+impl AiBoss {
+    pub fn new(name: &str, phases: Vec<BossPhase>, ai: Box<dyn Ai>) -> Self {
+        AiBoss {
+            name: name.to_string(),
+            phases,
+            next_phase: 0,
+            ai,
+        }
+    }
+}
+
+#[typetag::serde]
+impl Ai for AiBoss {
+    fn act(
+        &mut self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> Box<dyn Action> {
+        let health = owner.actuators.hp as f32 / owner.actuators.max_hp.max(1) as f32;
+        while let Some(phase) = self.phases.get(self.next_phase) {
+            if health >= phase.below_health {
+                break;
+            }
+            debug!("{} enters phase {}", self.name, self.next_phase + 1);
+            if let Some(ai) = &phase.ai {
+                match ai_from_name(ai) {
+                    Ok(ai) => self.ai = ai,
+                    Err(msg) => error!("{}", msg),
+                }
+            }
+            if owner.physics.is_visible {
+                state
+                    .log
+                    .add_at(phase.message.clone(), MsgClass::Story, owner.pos);
+            }
+            if let Some(ability) = &phase.ability {
+                ability.perform(state, objects, owner);
+            }
+            self.next_phase += 1;
+        }
+        self.ai.act(state, objects, owner)
+    }
+
+    fn boss_name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn planned_path(&self, owner: &Object, objects: &GameObjects) -> Vec<Position> {
+        self.ai.planned_path(owner, objects)
+    }
+}
+
+/// Create a boss from its definition and the object template of its species.
+pub fn boss_from_template(
+    state: &mut GameState,
+    boss: &BossTemplate,
+    template: &ObjectTemplate,
+    pos: Position,
+) -> Result<Object, String> {
+    let mut object = object_from_template(state, template, pos.x, pos.y)?;
+    object.visual.name = boss.name.clone();
+    object.actuators.max_hp += boss.bonus_hp;
+    object.actuators.hp += boss.bonus_hp;
+    let ai = match object.control.take() {
+        Some(Controller::Npc(ai)) => ai,
+        _ => return Err(format!("boss {} needs an AI controller", boss.name)),
+    };
+    Ok(object.control(Controller::Npc(Box::new(AiBoss::new(
+        &boss.name,
+        boss.phases.clone(),
+        ai,
+    )))))
+}
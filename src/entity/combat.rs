@@ -0,0 +1,83 @@
+//! Attacks are resolved into hit reports that record how the damage came about. The message log
+//! and visual effects are derived from these reports, so that each of them can present as much
+//! of a hit as it sees fit.
+
+use crate::core::game_env::CombatDetail;
+use crate::entity::object::Object;
+
+/// Everything that went into a single hit of one organism on another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub attacker: String,
+    pub target: String,
+    /// Damage of the attack before the target's defences.
+    pub attack: i32,
+    /// Damage held off by the target's attachment to the tissue.
+    pub attachment: i32,
+    /// Damage shrugged off by the target's dormant spore.
+    pub dormancy: i32,
+    /// Damage actually dealt.
+    pub damage: i32,
+    /// Whether only a segment of the target was hit, and how many segments were severed.
+    pub segment: Option<usize>,
+    /// Hp of the target after the hit.
+    pub hp_left: i32,
+}
+
+impl Hit {
+    /// Work out how much damage an attack of the given strength deals to `target`. Attached
+    /// targets hold on and take less of it, dormant spores are hardened and shrug off half of
+    /// the rest. Any hit deals at least one point of damage.
+    pub fn resolve(attacker: &Object, target: &Object, attack: i32) -> Self {
+        let held = match target.attachment {
+            Some(a) => (attack - a.strength).max(1),
+            None => attack,
+        };
+        let damage = if target.dormant {
+            (held / 2).max(1)
+        } else {
+            held
+        };
+        Hit {
+            attacker: attacker.visual.name.clone(),
+            target: target.visual.name.clone(),
+            attack,
+            attachment: (attack - held).max(0),
+            dormancy: (held - damage).max(0),
+            damage,
+            segment: None,
+            hp_left: target.actuators.hp - damage,
+        }
+    }
+
+    /// Describe the hit for the message log.
+    pub fn describe(&self, detail: CombatDetail) -> String {
+        let mut text = match self.segment {
+            Some(_) => format!(
+                "{} attacked a segment of {} for {} damage",
+                self.attacker, self.target, self.damage
+            ),
+            None => format!(
+                "{} attacked {} for {} damage",
+                self.attacker, self.target, self.damage
+            ),
+        };
+        if let Some(severed) = self.segment.filter(|s| *s > 0) {
+            text.push_str(&format!(", severing {} segment(s)", severed));
+        }
+        if detail == CombatDetail::Full {
+            let mut breakdown = vec![format!("attack {}", self.attack)];
+            if self.attachment > 0 {
+                breakdown.push(format!("attachment -{}", self.attachment));
+            }
+            if self.dormancy > 0 {
+                breakdown.push(format!("dormancy -{}", self.dormancy));
+            }
+            if self.segment.is_none() {
+                breakdown.push(format!("{} hp left", self.hp_left.max(0)));
+            }
+            text.push_str(&format!(" ({})", breakdown.join(", ")));
+        }
+        text
+    }
+}
@@ -1,5 +1,6 @@
 use crate::core::game_objects::GameObjects;
 use crate::core::game_state::GameState;
+use crate::core::position::Position;
 use crate::entity::action::Action;
 use crate::entity::object::Object;
 use crate::entity::player::PlayerCtrl;
@@ -20,4 +21,21 @@ pub trait Ai: Debug {
         objects: &mut GameObjects,
         owner: &mut Object,
     ) -> Box<dyn Action>;
+
+    /// Name of the kind of AI, which tells AIs apart in the telemetry.
+    fn kind(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// Name of the boss that this AI drives, if it belongs to one.
+    fn boss_name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Positions the AI intends to move through next, for the AI debug overlay. AIs without any
+    /// plan beyond their next action return an empty path.
+    fn planned_path(&self, _owner: &Object, _objects: &GameObjects) -> Vec<Position> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,123 @@
+//! Automated actions of the player, i.e. travelling to a distant position and holding down a
+//! movement key, go on without further input. All of them keep the same danger watch, which
+//! interrupts them as soon as something needs the player's attention.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::{GameState, MsgClass};
+use crate::entity::faction::Stance;
+use crate::entity::object::Object;
+
+/// Reasons for interrupting an automated action.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Danger {
+    /// The player lost hit points.
+    Hurt,
+    /// An alert was logged.
+    Alert,
+    /// A hostile organism of the given name came into sight.
+    Hostile(String),
+    /// The threat in sight increased.
+    Threat,
+}
+
+impl Danger {
+    /// Message that tells the player why they stopped. Alerts speak for themselves.
+    pub fn message(&self) -> Option<String> {
+        match self {
+            Danger::Hurt => Some("You stop, you have been hurt!".to_string()),
+            Danger::Alert => None,
+            Danger::Hostile(name) => Some(format!("You stop, {} comes into view.", name)),
+            Danger::Threat => Some("You stop, something dangerous is nearby.".to_string()),
+        }
+    }
+}
+
+/// What the player had to be wary of when an automated action began. Anything beyond that is a
+/// danger.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DangerWatch {
+    /// Hit points of the player.
+    hp: i32,
+    /// Threat in sight, as estimated by the hud.
+    threat: f32,
+    /// Names of the hostile organisms in sight, sorted.
+    hostiles: Vec<String>,
+    /// Number of log messages.
+    log_len: usize,
+}
+
+impl DangerWatch {
+    /// Take note of the player's current situation, given the threat in sight.
+    pub fn new(state: &GameState, objects: &GameObjects, threat: f32) -> Self {
+        DangerWatch {
+            hp: player_of(state, objects).map_or(0, |p| p.actuators.hp),
+            threat,
+            hostiles: hostiles_in_sight(state, objects),
+            log_len: state.log.messages.len(),
+        }
+    }
+
+    /// Check whether anything happened since the watch began that the player should know about.
+    pub fn check(&self, state: &GameState, objects: &GameObjects, threat: f32) -> Option<Danger> {
+        if player_of(state, objects).is_some_and(|p| p.actuators.hp < self.hp) {
+            return Some(Danger::Hurt);
+        }
+        let is_alerted = state
+            .log
+            .messages
+            .iter()
+            .skip(self.log_len)
+            .any(|m| matches!(m.class, MsgClass::Alert));
+        if is_alerted {
+            return Some(Danger::Alert);
+        }
+        // any hostile organism beyond those already in sight is a newcomer
+        let mut known = self.hostiles.clone();
+        for name in hostiles_in_sight(state, objects) {
+            match known.iter().position(|k| *k == name) {
+                Some(idx) => {
+                    known.remove(idx);
+                }
+                None => return Some(Danger::Hostile(name)),
+            }
+        }
+        if threat > self.threat {
+            return Some(Danger::Threat);
+        }
+        None
+    }
+}
+
+fn player_of<'a>(state: &GameState, objects: &'a GameObjects) -> Option<&'a Object> {
+    objects
+        .get_vector()
+        .get(state.player_idx)
+        .and_then(|p| p.as_ref())
+}
+
+/// Names of the visible organisms that are hostile towards the player, sorted.
+fn hostiles_in_sight(state: &GameState, objects: &GameObjects) -> Vec<String> {
+    let player = match player_of(state, objects) {
+        Some(player) => player,
+        None => return Vec::new(),
+    };
+    let mut hostiles: Vec<String> = objects
+        .get_vector()
+        .iter()
+        .flatten()
+        .filter(|o| {
+            o.alive
+                && o.physics.is_visible
+                && !o.is_player()
+                && o.control.is_some()
+                && o.tile.is_none()
+                && o.item.is_none()
+                && state.relations.stance(o, player) == Stance::Hostile
+        })
+        .map(|o| o.visual.name.clone())
+        .collect();
+    hostiles.sort();
+    hostiles
+}
@@ -0,0 +1,149 @@
+//! Factions group organisms by their allegiance within the host. How factions stand towards each
+//! other is kept in a relationship matrix, which the AI consults to decide whom to attack. The
+//! standing shifts during the game, e.g. when members of one faction attack another.
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::object::Object;
+
+/// Standing below which organisms of two factions attack each other on sight.
+pub const HOSTILE_STANDING: i32 = -25;
+/// Standing above which organisms of two factions support each other.
+pub const FRIENDLY_STANDING: i32 = 25;
+/// Limits of the standing between two factions.
+const MAX_STANDING: i32 = 100;
+/// Improvement of the gut flora's attitude towards an organism per symbiosis gene it carries.
+const SYMBIOSIS_STANDING: i32 = 30;
+
+const NUM_FACTIONS: usize = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Faction {
+    /// Tissue, items and anything else without an allegiance.
+    #[default]
+    Unaligned,
+    /// The player and their offspring.
+    Lineage,
+    /// Commensal bacteria that belong to the host.
+    GutFlora,
+    Pathogens,
+    ImmuneSystem,
+}
+
+impl Faction {
+    pub fn name(&self) -> &str {
+        match self {
+            Faction::Unaligned => "unaligned",
+            Faction::Lineage => "your lineage",
+            Faction::GutFlora => "gut flora",
+            Faction::Pathogens => "pathogens",
+            Faction::ImmuneSystem => "immune system",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// How an organism stands towards another one at a glance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stance {
+    Hostile,
+    Neutral,
+    Allied,
+}
+
+/// Symmetric matrix of the standing between every pair of factions, from -100 (sworn enemies) to
+/// 100 (allies).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Relations {
+    standing: [[i32; NUM_FACTIONS]; NUM_FACTIONS],
+}
+
+impl Default for Relations {
+    fn default() -> Self {
+        use Faction::*;
+        let mut relations = Relations {
+            standing: [[0; NUM_FACTIONS]; NUM_FACTIONS],
+        };
+        for faction in &[Lineage, GutFlora, Pathogens, ImmuneSystem] {
+            relations.set(*faction, *faction, MAX_STANDING);
+        }
+        relations.set(ImmuneSystem, Pathogens, -MAX_STANDING);
+        relations.set(ImmuneSystem, Lineage, -75);
+        relations.set(GutFlora, Pathogens, -50);
+        relations.set(GutFlora, Lineage, -30);
+        relations.set(ImmuneSystem, GutFlora, 20);
+        relations
+    }
+}
+
+impl Relations {
+    pub fn new() -> Self {
+        Relations::default()
+    }
+
+    pub fn standing(&self, a: Faction, b: Faction) -> i32 {
+        self.standing[a.index()][b.index()]
+    }
+
+    fn set(&mut self, a: Faction, b: Faction, standing: i32) {
+        let standing = standing.clamp(-MAX_STANDING, MAX_STANDING);
+        self.standing[a.index()][b.index()] = standing;
+        self.standing[b.index()][a.index()] = standing;
+    }
+
+    /// Improve or worsen the standing between two factions. Unaligned organisms hold no grudges.
+    pub fn shift(&mut self, a: Faction, b: Faction, delta: i32) {
+        if a != Faction::Unaligned && b != Faction::Unaligned {
+            self.set(a, b, self.standing(a, b) + delta);
+        }
+    }
+
+    /// How members of the given faction regard the organism `other`. Gut flora tolerates
+    /// organisms that carry symbiosis genes.
+    pub fn attitude(&self, faction: Faction, other: &Object) -> i32 {
+        let mut attitude = self.standing(faction, other.faction);
+        if faction == Faction::GutFlora && other.faction != Faction::GutFlora {
+            attitude += SYMBIOSIS_STANDING * other.processors.symbiosis;
+        }
+        attitude.min(MAX_STANDING)
+    }
+
+    /// Check whether members of the given faction attack `other` on sight.
+    pub fn is_hostile(&self, faction: Faction, other: &Object) -> bool {
+        self.attitude(faction, other) < HOSTILE_STANDING
+    }
+
+    /// Check whether the organism `owner` attacks `other` on sight, taking into account the
+    /// chemical signals it has received.
+    pub fn is_hostile_towards(&self, owner: &Object, other: &Object) -> bool {
+        self.attitude(owner.faction, other) + owner.signal_standing(other.faction)
+            < HOSTILE_STANDING
+    }
+
+    /// How the organism `owner` stands towards `other`, taking into account the chemical signals
+    /// it has received.
+    pub fn stance(&self, owner: &Object, other: &Object) -> Stance {
+        let attitude = self.attitude(owner.faction, other) + owner.signal_standing(other.faction);
+        if attitude < HOSTILE_STANDING {
+            Stance::Hostile
+        } else if attitude > FRIENDLY_STANDING {
+            Stance::Allied
+        } else {
+            Stance::Neutral
+        }
+    }
+}
+
+/// Describe an attitude in a single word.
+pub fn describe_attitude(attitude: i32) -> &'static str {
+    if attitude < HOSTILE_STANDING {
+        "hostile"
+    } else if attitude > FRIENDLY_STANDING {
+        "friendly"
+    } else {
+        "neutral"
+    }
+}
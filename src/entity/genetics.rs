@@ -29,13 +29,19 @@
 // Should attributes be fix on trait level or full-on generic as list of attribute objects?
 // How to best model synergies and anti-synergies across traits?
 
+use crate::core::host::AntibioticClass;
+use crate::core::{innit_env, telemetry};
 use crate::entity::action::{
-    hereditary::{ActAttack, ActKillSwitch, ActMetabolise, ActMove},
+    hereditary::{
+        ActAttach, ActAttack, ActDetach, ActDigestWall, ActDormancy, ActFollowScent, ActKillSwitch,
+        ActMetabolise, ActMove, ActPeacePheromone, ActRest, ActSecreteToxin, ActThreatDisplay,
+    },
     inventory::ActPickUpItem,
     Action,
 };
 use crate::entity::genetics::DnaType::Nucleoid;
-use crate::util::game_rng::GameRng;
+use crate::raws::object_template::DnaTemplate;
+use crate::util::game_rng::{GameRng, RngExtended};
 use crate::util::generate_gray_code;
 use core::fmt;
 use rand::{
@@ -45,10 +51,32 @@ use rand::{
 };
 use serde::{Deserialize, Serialize};
 use std::cmp;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::{Display, Formatter};
 
 pub const GENE_LEN: usize = 30;
+/// Amount of biomass that grants one extra point of hp and energy storage.
+pub const BIOMASS_PER_BONUS: i32 = 10;
+/// Amount of biomass that makes movement one energy point more expensive.
+pub const BIOMASS_PER_MOVE_COST: i32 = 20;
+/// Minimal biomass an organism needs to block others from moving through it.
+pub const BIOMASS_BLOCKING: i32 = 3;
+/// Organisms with at least this much biomass are considered large.
+pub const BIOMASS_LARGE: i32 = 25;
+/// Amount of biomass that costs one energy point per turn to sustain.
+pub const BIOMASS_PER_UPKEEP: i32 = 30;
+/// Amount of biomass that lets an organism carry one more point of weight.
+pub const BIOMASS_PER_CARRY: i32 = 5;
+/// Weight that even the tiniest organism can carry.
+pub const BASE_CARRY_WEIGHT: i32 = 2;
+/// Surplus energy each energy store organelle can hold in reserve.
+pub const RESERVE_PER_STORE: i32 = 3;
+/// Stored energy it takes to repair one point of hp.
+pub const REPAIR_COST: i32 = 2;
+/// Default cap on the length in [bytes] of raw DNA. Every byte has to be copied each time the DNA
+/// is replicated, beyond this length replicating the genome costs more than its genes could ever
+/// earn back. The cap can be changed with `--max-dna`.
+pub const MAX_DNA_LEN: usize = 192;
 
 /// All traits belong to one of three major categories, called trait families.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
@@ -79,11 +107,56 @@ pub enum TraitAttribute {
     Volume,
     Metabolism,
     Storage,
+    /// Energy store organelles add to the energy storage and keep a reserve of surplus energy.
+    Reserve,
     // TODO: Determine receptor kind by position on DNA
     Receptor,
+    Anchor,
+    /// Resistance genes protect against a class of antibiotics.
+    Resistance(AntibioticClass),
+    /// Tolerance genes let organisms cope with hot or acidic tissue.
+    HeatTolerance,
+    PhTolerance,
+    /// Symbiosis genes make the gut flora accept the organism as one of their own.
+    Symbiosis,
+    /// Repair genes mend damage from stored energy while the organism metabolizes.
+    Repair,
+    /// Antitoxins protect against the toxins that some organisms secrete.
+    ToxinResistance,
+    /// Producer genes harvest energy from light or from nutrient-rich tissue without eating.
+    Photosynthesis,
+    Chemosynthesis,
     None,
 }
 
+impl TraitAttribute {
+    /// Describe how each gene with this attribute changes the stats of its organism.
+    pub fn describe(&self) -> Option<String> {
+        let effect = match self {
+            TraitAttribute::SensingRange => "+1 sensing range".to_string(),
+            TraitAttribute::Hp => "+1 max hp".to_string(),
+            TraitAttribute::Volume => "+1 volume".to_string(),
+            TraitAttribute::Metabolism => "+1 metabolism".to_string(),
+            TraitAttribute::Storage => "+1 storage".to_string(),
+            TraitAttribute::Reserve => format!("+1 storage, +{} reserve", RESERVE_PER_STORE),
+            TraitAttribute::Receptor => "+1 receptor".to_string(),
+            TraitAttribute::Anchor => "+1 anchor".to_string(),
+            TraitAttribute::Resistance(antibiotic) => format!("resists {}", antibiotic.name()),
+            TraitAttribute::HeatTolerance => "+1 heat tolerance".to_string(),
+            TraitAttribute::PhTolerance => "+1 pH tolerance".to_string(),
+            TraitAttribute::Symbiosis => "+1 symbiosis".to_string(),
+            TraitAttribute::Repair => "+1 hp repair per turn".to_string(),
+            TraitAttribute::ToxinResistance => "resists toxins".to_string(),
+            TraitAttribute::Photosynthesis => "+1 energy per turn on lit tiles".to_string(),
+            TraitAttribute::Chemosynthesis => {
+                "+1 energy per turn on nutrient-rich tiles".to_string()
+            }
+            TraitAttribute::None => return None,
+        };
+        Some(effect)
+    }
+}
+
 /// Genetic traits are linked to actions and attributes.
 /// Actions are supposed to be linked to key inputs.
 /// Relationships:
@@ -99,6 +172,9 @@ pub struct GeneticTrait {
     pub attribute: TraitAttribute,       // Vec<TraitAttribute>
     pub action: Option<Box<dyn Action>>, // TraitActions
     pub position: u32,                   // position of the gene within the genome
+    /// Whether an epigenetic mark keeps the gene from being expressed.
+    #[serde(default)]
+    pub silenced: bool,
 }
 
 impl GeneticTrait {
@@ -114,9 +190,16 @@ impl GeneticTrait {
             attribute,
             action,
             position: 0,
+            silenced: false,
         }
     }
 
+    /// Whether the gene adds to the biomass, and with it to the upkeep, of the organism. Junk and
+    /// markers are too short to weigh anything.
+    pub fn adds_biomass(&self) -> bool {
+        !matches!(self.trait_family, TraitFamily::Junk(_) | TraitFamily::Ltr)
+    }
+
     fn junk(value: u8) -> Self {
         GeneticTrait {
             trait_name: "Junk".to_string(),
@@ -124,11 +207,13 @@ impl GeneticTrait {
             attribute: TraitAttribute::None,
             action: None,
             position: 0,
+            silenced: false,
         }
     }
 }
 
-fn create_trait_list() -> Vec<GeneticTrait> {
+/// All traits that genes can code for, in the order of their codes.
+pub(crate) fn create_trait_list() -> Vec<GeneticTrait> {
     // use TraitAttribute::*;
     use TraitFamily::*;
     vec![
@@ -152,10 +237,22 @@ fn create_trait_list() -> Vec<GeneticTrait> {
             TraitAttribute::SensingRange,
             None,
         ),
+        GeneticTrait::new(
+            "Chemoreceptor",
+            Sensing,
+            TraitAttribute::None,
+            Some(Box::new(ActFollowScent::new())),
+        ),
         // enzymes are stand-ins for metabolism for now
         // TODO: separate into catabolism and anabolism
         GeneticTrait::new("Enzyme", Processing, TraitAttribute::Metabolism, None),
-        GeneticTrait::new("Energy Store", Processing, TraitAttribute::None, None),
+        // energy stores also allow to retreat into a dormant spore and live off the reserve
+        GeneticTrait::new(
+            "Energy Store",
+            Processing,
+            TraitAttribute::Reserve,
+            Some(Box::new(ActDormancy::new())),
+        ),
         GeneticTrait::new(
             "Metabolism",
             Processing,
@@ -169,7 +266,98 @@ fn create_trait_list() -> Vec<GeneticTrait> {
             TraitAttribute::None,
             Some(Box::new(ActKillSwitch::new())),
         ),
+        // adhesins let cells stick to the tissue and resist currents
+        GeneticTrait::new("Adhesin", Actuating, TraitAttribute::Anchor, None),
+        // pili are hair-like appendages that let cells grab onto walls and hosts
+        GeneticTrait::new(
+            "Pili",
+            Actuating,
+            TraitAttribute::None,
+            Some(Box::new(ActAttach::new())),
+        ),
+        // resistance genes, mostly passed around on plasmids
+        GeneticTrait::new(
+            "Beta-Lactamase",
+            Processing,
+            TraitAttribute::Resistance(AntibioticClass::BetaLactam),
+            None,
+        ),
+        GeneticTrait::new(
+            "Efflux Pump",
+            Processing,
+            TraitAttribute::Resistance(AntibioticClass::Tetracycline),
+            None,
+        ),
+        // heat shock proteins keep other proteins folded in a fever
+        GeneticTrait::new(
+            "Heat Shock Protein",
+            Processing,
+            TraitAttribute::HeatTolerance,
+            None,
+        ),
+        // proton pumps keep the inside of the cell neutral in acidic or alkaline tissue
+        GeneticTrait::new("Proton Pump", Processing, TraitAttribute::PhTolerance, None),
+        // symbiosis factors signal the gut flora that the cell is harmless
+        GeneticTrait::new(
+            "Symbiosis Factor",
+            Processing,
+            TraitAttribute::Symbiosis,
+            None,
+        ),
+        // signaling glands let cells negotiate with organisms that share a receptor
+        GeneticTrait::new(
+            "Pheromone Gland",
+            Processing,
+            TraitAttribute::None,
+            Some(Box::new(ActPeacePheromone::new())),
+        ),
+        GeneticTrait::new(
+            "Warning Signal",
+            Actuating,
+            TraitAttribute::None,
+            Some(Box::new(ActThreatDisplay::new())),
+        ),
+        // repair enzymes mend the cell wall and let the cell rest to heal faster
+        GeneticTrait::new(
+            "Repair Enzyme",
+            Processing,
+            TraitAttribute::Repair,
+            Some(Box::new(ActRest::new())),
+        ),
+        // toxin glands poison the surrounding tissue, their owners are immune to it
+        GeneticTrait::new(
+            "Toxin Gland",
+            Actuating,
+            TraitAttribute::ToxinResistance,
+            Some(Box::new(ActSecreteToxin::new())),
+        ),
+        GeneticTrait::new(
+            "Antitoxin",
+            Processing,
+            TraitAttribute::ToxinResistance,
+            None,
+        ),
+        // proteases break down the tissue walls of the host
+        GeneticTrait::new(
+            "Protease",
+            Actuating,
+            TraitAttribute::None,
+            Some(Box::new(ActDigestWall::new())),
+        ),
         GeneticTrait::new("LTR marker", TraitFamily::Ltr, TraitAttribute::None, None),
+        // producers live off light or the chemistry of the tissue instead of hunting
+        GeneticTrait::new(
+            "Chlorophyll",
+            Processing,
+            TraitAttribute::Photosynthesis,
+            None,
+        ),
+        GeneticTrait::new(
+            "Sulfur Oxidase",
+            Processing,
+            TraitAttribute::Chemosynthesis,
+            None,
+        ),
     ]
 }
 
@@ -208,7 +396,37 @@ pub struct Processors {
     pub metabolism: i32,     // energy production per turn
     pub energy_storage: i32, // maximum energy store
     pub energy: i32,
+    /// Surplus energy kept in storage organelles, tapped when the upkeep can't be paid otherwise.
+    #[serde(default)]
+    pub reserve: i32,
+    #[serde(default)]
+    pub reserve_capacity: i32,
+    /// Energy consumed each turn to sustain the organism's biomass.
+    #[serde(default)]
+    pub upkeep: i32,
+    /// Number of consecutive turns the organism couldn't pay its upkeep.
+    #[serde(default)]
+    pub starvation: i32,
     pub receptors: Vec<Receptor>,
+    /// Classes of antibiotics the organism is resistant to.
+    #[serde(default)]
+    pub resistances: Vec<AntibioticClass>,
+    #[serde(default)]
+    pub heat_tolerance: i32,
+    #[serde(default)]
+    pub ph_tolerance: i32,
+    #[serde(default)]
+    pub symbiosis: i32,
+    /// Hp that the organism repairs per turn of metabolism, as long as it has energy to spare.
+    #[serde(default)]
+    pub repair: i32,
+    #[serde(default)]
+    pub toxin_resistance: i32,
+    /// Energy harvested per turn on lit and on nutrient-rich tiles respectively.
+    #[serde(default)]
+    pub photosynthesis: i32,
+    #[serde(default)]
+    pub chemosynthesis: i32,
 }
 
 impl Processors {
@@ -218,7 +436,19 @@ impl Processors {
             metabolism: 1,
             energy_storage: 1,
             energy: 0,
+            reserve: 0,
+            reserve_capacity: 0,
+            upkeep: 0,
+            starvation: 0,
             receptors: Vec::new(),
+            resistances: Vec::new(),
+            heat_tolerance: 0,
+            ph_tolerance: 0,
+            symbiosis: 0,
+            repair: 0,
+            toxin_resistance: 0,
+            photosynthesis: 0,
+            chemosynthesis: 0,
         }
     }
 }
@@ -232,6 +462,8 @@ pub struct Receptor {
 /// Actuators contain:
 /// - attributes:
 ///   - speed, a modifier of the energy cost of the functions
+///   - anchoring, which keeps the object from drifting along currents
+///   - biomass, the amount of expressed genes, which determines the overall size
 /// - functions:
 ///   - move
 ///   - attack
@@ -242,6 +474,15 @@ pub struct Actuators {
     pub max_hp: i32,
     pub hp: i32,
     pub volume: i32,
+    pub anchor: i32,
+    pub biomass: i32,
+    /// Total weight of the items the organism can carry in its inventory.
+    #[serde(default = "base_carry_weight")]
+    pub carry_weight: i32,
+}
+
+fn base_carry_weight() -> i32 {
+    BASE_CARRY_WEIGHT
 }
 
 impl Actuators {
@@ -251,6 +492,9 @@ impl Actuators {
             max_hp: 1,
             hp: 1,
             volume: 5,
+            anchor: 0,
+            biomass: 0,
+            carry_weight: BASE_CARRY_WEIGHT,
         }
     }
 }
@@ -285,11 +529,17 @@ impl Default for DnaType {
 /// For now objects hold DNA either contained in an organelle (Nucleus), free floating in the cell
 /// (Nucleoid) or in form of a ring structure that can be exchanged or picked up by certain other
 /// objects (Plasmid). This is indicated by the `dna_type`.
+///
+/// On top of the DNA sit epigenetic marks, which switch single genes off without touching the raw
+/// DNA. Silenced genes are still part of the genome, but don't contribute to the phenotype.
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Dna {
     pub dna_type: DnaType,
     pub raw: Vec<u8>,
     pub simplified: Vec<GeneticTrait>,
+    /// Indices of the silenced genes within `simplified`.
+    #[serde(default)]
+    pub silenced: BTreeSet<usize>,
 }
 
 impl Dna {
@@ -298,8 +548,56 @@ impl Dna {
             dna_type: Nucleoid,
             raw: Vec::new(),
             simplified: Vec::new(),
+            silenced: BTreeSet::new(),
         }
     }
+
+    /// Break the upkeep of the organism down by trait. Every gene that adds to the biomass adds
+    /// its share to the upkeep, so that the shares of all traits sum up to the biomass divided by
+    /// `BIOMASS_PER_UPKEEP`. The traits are ordered from the most to the least costly.
+    pub fn upkeep_by_trait(&self) -> Vec<UpkeepShare> {
+        let mut genes: BTreeMap<&str, usize> = BTreeMap::new();
+        for g_trait in self.simplified.iter().filter(|g| g.adds_biomass()) {
+            *genes.entry(&g_trait.trait_name).or_insert(0) += 1;
+        }
+        let mut shares: Vec<UpkeepShare> = genes
+            .into_iter()
+            .map(|(trait_name, genes)| UpkeepShare {
+                trait_name: trait_name.to_string(),
+                genes,
+                upkeep: genes as f32 / BIOMASS_PER_UPKEEP as f32,
+            })
+            .collect();
+        // stable sort, so that equally costly traits stay in alphabetical order
+        shares.sort_by_key(|share| std::cmp::Reverse(share.genes));
+        shares
+    }
+
+    /// The epigenetic marks as given by the genes' own flags, e.g. after the genes were
+    /// rearranged.
+    pub fn marks_from_genes(&self) -> BTreeSet<usize> {
+        self.simplified
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.silenced)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+/// Check whether raw DNA of the given length in [bytes] can still be replicated.
+pub fn is_within_dna_cap(len: usize) -> bool {
+    len <= innit_env().max_dna_len
+}
+
+/// Part of an organism's upkeep that is spent on expressing all genes of one trait.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpkeepShare {
+    pub trait_name: String,
+    /// Number of genes of the trait in the genome.
+    pub genes: usize,
+    /// Energy per turn the genes cost.
+    pub upkeep: f32,
 }
 
 /// The gene library lets the user define genes.
@@ -316,10 +614,10 @@ impl Dna {
 pub struct GeneLibrary {
     /// Traits are now supposed to be generic, so enums are no longer the way to go.
     trait_vec: Vec<GeneticTrait>,
-    /// Traits are encoded in gray code.
-    gray_to_trait: HashMap<u8, String>,
+    /// Traits are encoded in gray code. Ordered maps keep saved games byte for byte the same.
+    gray_to_trait: BTreeMap<u8, String>,
     /// Reverse mapping for encoding traits into dna
-    trait_to_gray: HashMap<String, u8>,
+    trait_to_gray: BTreeMap<String, u8>,
     /// Vector of gray code with index corresponding to its binary representation
     gray_code: Vec<u8>,
     /// Count the number of traits we have, sort of as a running id.
@@ -332,14 +630,14 @@ impl GeneLibrary {
 
         let trait_vec: Vec<GeneticTrait> = create_trait_list();
         let trait_count = trait_vec.len();
-        let gray_code = generate_gray_code(4);
-        let gray_to_trait: HashMap<u8, String> = trait_vec
+        let gray_code = generate_gray_code(5);
+        let gray_to_trait: BTreeMap<u8, String> = trait_vec
             .iter()
             .enumerate()
             .map(|(code, gene_trait)| (gray_code[code + 1], gene_trait.trait_name.clone()))
             .collect();
         debug!("gray to trait map: {:#?}", gray_to_trait);
-        let trait_to_gray: HashMap<String, u8> = trait_vec
+        let trait_to_gray: BTreeMap<String, u8> = trait_vec
             .iter()
             .enumerate()
             .map(|(code, gene_trait)| (gene_trait.trait_name.clone(), gray_code[code + 1]))
@@ -355,6 +653,11 @@ impl GeneLibrary {
         }
     }
 
+    /// All traits known to the library, in the order of their codes.
+    pub fn traits(&self) -> &[GeneticTrait] {
+        &self.trait_vec
+    }
+
     // TODO: Add parameters to control distribution of sense, process and actuate!
     // TODO: Use above parameters for NPC definitions, readable from datafiles!
     pub fn new_dna(&self, rng: &mut GameRng, has_ltr: bool, avg_genome_len: usize) -> Vec<u8> {
@@ -399,6 +702,18 @@ impl GeneLibrary {
         dna
     }
 
+    /// Return the byte that encodes the given trait in the DNA.
+    pub fn trait_code(&self, g_trait: &GeneticTrait) -> u8 {
+        match g_trait.trait_family {
+            TraitFamily::Junk(code) => code,
+            _ => self
+                .trait_to_gray
+                .get(&g_trait.trait_name)
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+
     // TODO: Take care of the case where `traits` contains junk, literally.
     /// Encode genetic traits into binary DNA code.
     pub fn g_traits_to_dna(&self, traits: &[GeneticTrait]) -> Vec<u8> {
@@ -461,26 +776,130 @@ impl GeneLibrary {
         dna
     }
 
-    pub fn trait_strs_to_dna(&self, rng: &mut GameRng, traits: &[String]) -> Vec<u8> {
+    /// Let a random bit of the given gene flip. Junk genes buffer such point mutations, as they
+    /// aren't expressed anyway a flip in them changes nothing. Returns the mutated gene.
+    pub fn mutate_gene(
+        &self,
+        rng: &mut GameRng,
+        dna_type: DnaType,
+        g_trait: &GeneticTrait,
+    ) -> GeneticTrait {
+        if matches!(g_trait.trait_family, TraitFamily::Junk(_)) {
+            return g_trait.clone();
+        }
+        let mut gene_bits = self.g_trait_refs_to_dna(&[g_trait]);
+        let random_bit = rng.gen_range(0..gene_bits.len());
+        gene_bits[random_bit] ^= rng.random_bit();
+        self.dna_to_traits(dna_type, &gene_bits)
+            .3
+            .simplified
+            .first()
+            .cloned()
+            .unwrap_or_else(|| g_trait.clone())
+    }
+
+    /// Turn up to `count` randomly chosen junk genes of a genome into random functional genes.
+    /// Returns the new raw DNA, or `None` if the genome has no junk to reactivate.
+    pub fn reactivate_junk(&self, rng: &mut GameRng, dna: &Dna, count: usize) -> Option<Vec<u8>> {
+        let junk: Vec<usize> = dna
+            .simplified
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| matches!(t.trait_family, TraitFamily::Junk(_)))
+            .map(|(idx, _)| idx)
+            .collect();
+        if junk.is_empty() {
+            return None;
+        }
+        let functional: Vec<&GeneticTrait> = self
+            .trait_vec
+            .iter()
+            .filter(|t| t.trait_family != TraitFamily::Ltr)
+            .collect();
+        let mut genes = dna.simplified.clone();
+        for idx in junk.choose_multiple(rng, count) {
+            let mut reactivated = (*functional.choose(rng)?).clone();
+            reactivated.position = genes[*idx].position;
+            genes[*idx] = reactivated;
+        }
+        Some(self.g_traits_to_dna(&genes))
+    }
+
+    /// Strip the junk that is never expressed from a genome. Junk in front of a receptor gene is
+    /// kept, because the receptor's type depends on its position in the genome. This way the
+    /// organism keeps its phenotype and epigenetic marks, only its DNA gets shorter. Returns the
+    /// decoded compacted genome, or `None` if there is nothing to strip.
+    pub fn compact_dna(&self, dna: &Dna) -> Option<(Sensors, Processors, Actuators, Dna)> {
+        let spacer_len = dna
+            .simplified
+            .iter()
+            .rposition(|t| t.attribute == TraitAttribute::Receptor)
+            .map_or(0, |idx| idx + 1);
+        let expressed: Vec<&GeneticTrait> = dna
+            .simplified
+            .iter()
+            .enumerate()
+            .filter(|(idx, t)| *idx < spacer_len || !matches!(t.trait_family, TraitFamily::Junk(_)))
+            .map(|(_, t)| t)
+            .collect();
+        let compacted = self.g_trait_refs_to_dna(&expressed);
+        if compacted.is_empty() || compacted.len() >= dna.raw.len() {
+            return None;
+        }
+        let marks = expressed
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.silenced)
+            .map(|(idx, _)| idx)
+            .collect();
+        Some(self.dna_to_marked_traits(dna.dna_type, &compacted, &marks))
+    }
+
+    /// Translate trait names into raw DNA, one gene per name. Fails on names that the gene
+    /// library doesn't know.
+    pub fn trait_strs_to_dna(&self, traits: &[String]) -> Result<Vec<u8>, String> {
         let mut dna: Vec<u8> = Vec::new();
         for t in traits {
-            // push 0x00 first as the genome start symbol
-            dna.push(0);
-            // add length
-            dna.push(1);
-            if let Some(gray) = self.trait_to_gray.get(t) {
-                dna.push(*gray);
-            } else {
-                let defined_range = self.trait_to_gray.len() as u8;
-                dna.push(rng.gen_range(defined_range..=255));
-                panic!("unknown genetic trait: {}", t);
-            }
-            //
-            // // add random attribute value
-            // dna.push(game_rng.gen_range(0, 16) as u8);
+            let gray = self
+                .trait_to_gray
+                .get(t)
+                .ok_or_else(|| format!("unknown genetic trait: {}", t))?;
+            // push 0x00 first as the genome start symbol, then the length
+            dna.extend_from_slice(&[0, 1, *gray]);
         }
-        // debug!("new dna generated: {:?}", dna);
-        dna
+        Ok(dna)
+    }
+
+    /// Create raw DNA as described by a species template from the raws. Fails on unknown trait
+    /// names and on explicit DNA that isn't made of hexadecimal bytes.
+    pub fn dna_from_template(
+        &self,
+        rng: &mut GameRng,
+        template: &DnaTemplate,
+        has_ltr: bool,
+    ) -> Result<Vec<u8>, String> {
+        let dna = match template {
+            DnaTemplate::Random { genome_len } => self.new_dna(rng, has_ltr, *genome_len),
+            DnaTemplate::Distributed {
+                s_rate,
+                p_rate,
+                a_rate,
+                genome_len,
+            } => self.dna_from_distribution(
+                rng,
+                &[*s_rate, *p_rate, *a_rate],
+                &[
+                    TraitFamily::Sensing,
+                    TraitFamily::Processing,
+                    TraitFamily::Actuating,
+                ],
+                has_ltr,
+                *genome_len,
+            ),
+            DnaTemplate::Defined { traits } => self.trait_strs_to_dna(traits)?,
+            DnaTemplate::Explicit { dna } => DnaTemplate::parse_dna(dna)?,
+        };
+        Ok(dna)
     }
 
     pub fn dna_from_distribution(
@@ -526,11 +945,24 @@ impl GeneLibrary {
         &self,
         dna_type: DnaType,
         raw_dna: &[u8],
+    ) -> (Sensors, Processors, Actuators, Dna) {
+        self.dna_to_marked_traits(dna_type, raw_dna, &BTreeSet::new())
+    }
+
+    /// Decode DNA whose genes at the given indices are silenced by epigenetic marks. Silenced
+    /// genes are part of the resulting DNA, but contribute neither attributes nor actions.
+    pub fn dna_to_marked_traits(
+        &self,
+        dna_type: DnaType,
+        raw_dna: &[u8],
+        silenced: &BTreeSet<usize>,
     ) -> (Sensors, Processors, Actuators, Dna) {
         assert!(!raw_dna.is_empty());
+        telemetry().record_decode();
         let mut start_ptr: usize = 0;
         let mut end_ptr: usize = raw_dna.len();
         let mut trait_builder: TraitBuilder = TraitBuilder::new(dna_type, raw_dna);
+        trait_builder.dna.silenced = silenced.clone();
         let mut position: u32 = 0;
 
         while start_ptr < raw_dna.len() - 2 {
@@ -601,14 +1033,19 @@ impl GeneLibrary {
                     trace!("found genetic trait {}", genetic_trait.trait_name);
                     let mut this_trait = genetic_trait.clone();
                     this_trait.position = position;
-                    trait_builder.add_action(&this_trait);
-                    trait_builder.add_attribute(&this_trait);
+                    this_trait.silenced = trait_builder.is_next_silenced();
+                    if !this_trait.silenced {
+                        trait_builder.add_action(&this_trait);
+                        trait_builder.add_attribute(&this_trait);
+                    }
                     trait_builder.record_trait(this_trait);
                 } else {
                     error!("no trait for id {}", trait_name);
                 }
             } else {
-                trait_builder.record_trait(GeneticTrait::junk(dna[i]));
+                let mut junk = GeneticTrait::junk(dna[i]);
+                junk.silenced = trait_builder.is_next_silenced();
+                trait_builder.record_trait(junk);
             }
         }
 
@@ -624,10 +1061,11 @@ struct TraitBuilder {
     sensors: Sensors,
     processors: Processors,
     actuators: Actuators,
-    // accumulated traits, mapping trait to count
-    sensor_action_count: HashMap<String, i32>,
-    processor_action_count: HashMap<String, i32>,
-    actuator_action_count: HashMap<String, i32>,
+    // accumulated traits, mapping trait to count; ordered by name so that organisms with the
+    // same genome always end up with their actions in the same order
+    sensor_action_count: BTreeMap<String, i32>,
+    processor_action_count: BTreeMap<String, i32>,
+    actuator_action_count: BTreeMap<String, i32>,
     dna: Dna,
 }
 
@@ -638,13 +1076,14 @@ impl TraitBuilder {
             sensors: Sensors::new(),
             processors: Processors::new(),
             actuators: Actuators::new(),
-            sensor_action_count: HashMap::new(),
-            processor_action_count: HashMap::new(),
-            actuator_action_count: HashMap::new(),
+            sensor_action_count: BTreeMap::new(),
+            processor_action_count: BTreeMap::new(),
+            actuator_action_count: BTreeMap::new(),
             dna: Dna {
                 dna_type,
                 raw: raw_dna.to_vec(),
                 simplified: Vec::new(),
+                silenced: BTreeSet::new(),
             },
         }
     }
@@ -667,11 +1106,44 @@ impl TraitBuilder {
             TraitAttribute::Storage => {
                 self.processors.energy_storage += 1;
             }
+            TraitAttribute::Reserve => {
+                self.processors.energy_storage += 1;
+                self.processors.reserve_capacity += RESERVE_PER_STORE;
+            }
             TraitAttribute::Receptor => {
                 self.processors.receptors.push(Receptor {
                     typ: g_trait.position,
                 });
             }
+            TraitAttribute::Anchor => {
+                self.actuators.anchor += 1;
+            }
+            TraitAttribute::HeatTolerance => {
+                self.processors.heat_tolerance += 1;
+            }
+            TraitAttribute::PhTolerance => {
+                self.processors.ph_tolerance += 1;
+            }
+            TraitAttribute::Symbiosis => {
+                self.processors.symbiosis += 1;
+            }
+            TraitAttribute::Repair => {
+                self.processors.repair += 1;
+            }
+            TraitAttribute::ToxinResistance => {
+                self.processors.toxin_resistance += 1;
+            }
+            TraitAttribute::Photosynthesis => {
+                self.processors.photosynthesis += 1;
+            }
+            TraitAttribute::Chemosynthesis => {
+                self.processors.chemosynthesis += 1;
+            }
+            TraitAttribute::Resistance(antibiotic) => {
+                if !self.processors.resistances.contains(&antibiotic) {
+                    self.processors.resistances.push(antibiotic);
+                }
+            }
             TraitAttribute::None => {}
         }
     }
@@ -706,7 +1178,15 @@ impl TraitBuilder {
         }
     }
 
+    /// Whether the gene that is recorded next is silenced by an epigenetic mark.
+    fn is_next_silenced(&self) -> bool {
+        self.dna.silenced.contains(&self.dna.simplified.len())
+    }
+
     pub fn record_trait(&mut self, g_trait: GeneticTrait) {
+        if g_trait.adds_biomass() {
+            self.actuators.biomass += 1;
+        }
         self.dna.simplified.push(g_trait);
     }
 
@@ -772,12 +1252,32 @@ impl TraitBuilder {
             .collect();
 
         // Space for 'post-processing'
+        // marks of genes that didn't make it into the genome are meaningless
+        let gene_count = self.dna.simplified.len();
+        self.dna.silenced.retain(|idx| *idx < gene_count);
+        // More biomass makes for a sturdier organism with more room for energy.
+        let bonus = self.actuators.biomass / BIOMASS_PER_BONUS;
+        self.actuators.max_hp += bonus;
+        self.actuators.hp += bonus;
+        self.processors.energy_storage += bonus;
+        self.processors.upkeep = self.actuators.biomass / BIOMASS_PER_UPKEEP;
+        self.actuators.carry_weight += self.actuators.biomass / BIOMASS_PER_CARRY;
+
         // Add equip function for anything but viruses and plasmids
         if matches!(self.dna.dna_type, DnaType::Nucleoid)
             || matches!(self.dna.dna_type, DnaType::Nucleus)
         {
             self.actuators.actions.push(Box::new(ActPickUpItem))
         }
+        // anything that can attach itself can also let go again
+        if self
+            .actuators
+            .actions
+            .iter()
+            .any(|a| a.get_identifier() == "attach")
+        {
+            self.actuators.actions.push(Box::new(ActDetach))
+        }
 
         (self.sensors, self.processors, self.actuators, self.dna)
     }
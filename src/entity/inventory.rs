@@ -1,6 +1,50 @@
+use crate::core::game_state::GameState;
+use crate::entity::genetics::DnaType;
 use crate::entity::{action::Action, object::Object};
 use serde::{Deserialize, Serialize};
 
+/// Weight of an item that carries no genes.
+const BASE_ITEM_WEIGHT: i32 = 1;
+/// Number of genes that make a carried item one point heavier.
+const GENES_PER_WEIGHT: usize = 4;
+
+/// Weight of an item in the inventory. Plasmids get heavier with every few genes they carry.
+pub fn item_weight(item: &Object) -> i32 {
+    BASE_ITEM_WEIGHT + (item.dna.simplified.len() / GENES_PER_WEIGHT) as i32
+}
+
+/// Plasmids with at most this many genes appear small before they're identified.
+const SMALL_PLASMID_GENES: usize = 2;
+/// Plasmids with at least this many genes appear large before they're identified.
+const LARGE_PLASMID_GENES: usize = 6;
+
+/// Check whether the player knows the genes that the item carries. Items without genes have
+/// nothing to hide, plasmids and gene fragments are identified per kind for the whole run.
+pub fn is_identified(state: &GameState, item: &Object) -> bool {
+    item.dna.dna_type != DnaType::Plasmid || state.identified_items.contains(&item.visual.name)
+}
+
+/// Learn the genes of all items of the same kind. Returns whether they were unknown so far.
+pub fn identify(state: &mut GameState, item: &Object) -> bool {
+    !is_identified(state, item) && state.identified_items.insert(item.visual.name.clone())
+}
+
+/// Name of the item as far as the player knows it. Unidentified plasmids only give away their
+/// rough size.
+pub fn item_name(state: &GameState, item: &Object) -> String {
+    if is_identified(state, item) {
+        return item.visual.name.clone();
+    }
+    let genes = item.dna.simplified.len();
+    if genes <= SMALL_PLASMID_GENES {
+        "small unknown plasmid".to_string()
+    } else if genes >= LARGE_PLASMID_GENES {
+        "large unknown plasmid".to_string()
+    } else {
+        "unknown plasmid".to_string()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Inventory {
     /// A list of items contained in this inventory.
@@ -17,4 +61,9 @@ impl Inventory {
             inv_actions: Vec::new(),
         }
     }
+
+    /// Total weight of all items in the inventory.
+    pub fn weight(&self) -> i32 {
+        self.items.iter().map(item_weight).sum()
+    }
 }
@@ -0,0 +1,85 @@
+//! Dead organisms leave behind what they were made of: fragments of their genes and a globule of
+//! the energy they had stored. Hunting down a species is the way to get hold of its abilities.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::game_state::GameState;
+use crate::entity::action::inventory::ActAbsorbEnergy;
+use crate::entity::genetics::GeneticTrait;
+use crate::entity::object::{InventoryItem, Object};
+use crate::entity::trade::{gene_fragment, is_tradeable};
+use crate::util::game_rng::{RngExtended, RngStream};
+
+/// How much of itself an organism leaves behind when it dies. Given per species in the raws.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct DropRates {
+    /// Chance for each of the organism's traits to be left behind as a gene fragment.
+    pub gene_chance: f64,
+    /// Most gene fragments a single organism leaves behind.
+    pub max_fragments: usize,
+    /// Share of the stored energy and reserve that is left behind as an energy globule.
+    pub energy_share: f64,
+}
+
+impl Default for DropRates {
+    fn default() -> Self {
+        DropRates {
+            gene_chance: 0.25,
+            max_fragments: 2,
+            energy_share: 0.5,
+        }
+    }
+}
+
+/// Items that the dead organism leaves behind at its position, derived from its genome and
+/// energy stores.
+pub fn drop_loot(state: &mut GameState, dead: &Object) -> Vec<Object> {
+    let rates = dead.drops;
+    let gene_chance = rates.gene_chance.clamp(0.0, 1.0);
+
+    // each trait counts once, no matter how many copies of it the genome holds
+    let mut traits: Vec<&GeneticTrait> = Vec::new();
+    for gene in dead.dna.simplified.iter().filter(|g| is_tradeable(g)) {
+        if !traits.iter().any(|t| t.trait_name == gene.trait_name) {
+            traits.push(gene);
+        }
+    }
+    let mut dropped: Vec<&GeneticTrait> = Vec::new();
+    for gene in traits {
+        if dropped.len() >= rates.max_fragments {
+            break;
+        }
+        if state
+            .rng_streams
+            .get(RngStream::Loot)
+            .flip_with_prob(gene_chance)
+        {
+            dropped.push(gene);
+        }
+    }
+    let mut loot: Vec<Object> = dropped
+        .into_iter()
+        .map(|gene| gene_fragment(state, dead, gene))
+        .collect();
+
+    let stored = dead.processors.energy.max(0) + dead.processors.reserve.max(0);
+    let energy = (stored as f64 * rates.energy_share.max(0.0)) as i32;
+    if energy > 0 {
+        loot.push(energy_globule(dead, energy));
+    }
+    loot
+}
+
+/// Energy left behind by a dead organism, taken in when the globule is used.
+fn energy_globule(dead: &Object, energy: i32) -> Object {
+    Object::new()
+        .position(dead.pos.x, dead.pos.y)
+        .living(true)
+        .visualize("Energy Globule", '%', (255, 200, 60))
+        .physical(false, false, false)
+        .itemize(Some(InventoryItem::new(
+            format!("{} energy left behind by {}.", energy, dead.visual.name),
+            Some(Box::new(ActAbsorbEnergy::new(energy))),
+        )))
+}
@@ -0,0 +1,119 @@
+//! Organisms remember how their genome changed over time, be it by editing, infection or picking
+//! up plasmids, so that the player can look back on how they ended up the way they are.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::genetics::{Dna, GeneLibrary, GeneticTrait};
+
+/// Maximum number of mutation events each object remembers.
+pub const MUTATION_HISTORY_LEN: usize = 24;
+
+/// A single changed gene. Inserted genes have no old trait, deleted genes no new one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MutationEvent {
+    pub turn: u128,
+    /// Index of the gene within the genome.
+    pub gene_idx: usize,
+    pub old: Option<GeneCode>,
+    pub new: Option<GeneCode>,
+}
+
+/// Encoded byte of a gene and the trait it expresses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeneCode {
+    pub byte: u8,
+    pub trait_name: String,
+}
+
+impl GeneCode {
+    fn new(gene_library: &GeneLibrary, g_trait: &GeneticTrait) -> Self {
+        GeneCode {
+            byte: gene_library.trait_code(g_trait),
+            trait_name: g_trait.trait_name.clone(),
+        }
+    }
+}
+
+impl MutationEvent {
+    pub fn to_text(&self) -> String {
+        let describe = |code: &GeneCode| format!("{} ({:#04x})", code.trait_name, code.byte);
+        let change = match (&self.old, &self.new) {
+            (Some(old), Some(new)) => format!("{} -> {}", describe(old), describe(new)),
+            (None, Some(new)) => format!("+ {}", describe(new)),
+            (Some(old), None) => format!("- {}", describe(old)),
+            (None, None) => "no change".to_string(),
+        };
+        format!("turn {:<6} gene {:<3} {}", self.turn, self.gene_idx, change)
+    }
+}
+
+/// Bounded buffer of the most recent mutation events, oldest first.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct MutationHistory {
+    events: VecDeque<MutationEvent>,
+}
+
+impl MutationHistory {
+    pub fn new() -> Self {
+        MutationHistory {
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &MutationEvent> {
+        self.events.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Index of the gene that changed most recently, unless that change removed it.
+    pub fn latest_gene(&self) -> Option<usize> {
+        self.events
+            .back()
+            .filter(|e| e.new.is_some())
+            .map(|e| e.gene_idx)
+    }
+
+    fn push(&mut self, event: MutationEvent) {
+        if self.events.len() == MUTATION_HISTORY_LEN {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Record the differences between two versions of a genome. Genes that are the same at the
+    /// start and end of both genomes are skipped, the genes in between are either replaced one by
+    /// one or, if the genome grew or shrank, inserted or deleted.
+    pub fn record(&mut self, turn: u128, old: &Dna, new: &Dna, gene_library: &GeneLibrary) {
+        let same = |a: &GeneticTrait, b: &GeneticTrait| {
+            gene_library.trait_code(a) == gene_library.trait_code(b)
+        };
+        let (old, new) = (&old.simplified, &new.simplified);
+        let prefix = old
+            .iter()
+            .zip(new.iter())
+            .take_while(|(a, b)| same(a, b))
+            .count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| same(a, b))
+            .count();
+        let old_changed = &old[prefix..old.len() - suffix];
+        let new_changed = &new[prefix..new.len() - suffix];
+
+        for i in 0..old_changed.len().max(new_changed.len()) {
+            self.push(MutationEvent {
+                turn,
+                gene_idx: prefix + i,
+                old: old_changed.get(i).map(|g| GeneCode::new(gene_library, g)),
+                new: new_changed.get(i).map(|g| GeneCode::new(gene_library, g)),
+            });
+        }
+    }
+}
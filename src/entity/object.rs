@@ -1,14 +1,33 @@
+use crate::core::events::GameEvent;
 use crate::core::position::Position;
-use crate::core::world::Tile;
+use crate::core::world::{Debris, Environment, Region, Tile};
+use crate::core::{innit_env, telemetry};
 use crate::entity::action::*;
+use crate::entity::ai::AiTrace;
+use crate::entity::bark::Bark;
 use crate::entity::control::*;
-use crate::entity::genetics::{Actuators, Dna, DnaType, Processors, Sensors};
-use crate::entity::inventory::Inventory;
-use crate::ui::hud::ToolTip;
-use crate::{core::game_objects::GameObjects, entity::action::hereditary::ActPass};
+use crate::entity::faction::Faction;
+use crate::entity::genetics::{
+    is_within_dna_cap, Actuators, Dna, DnaType, GeneticTrait, Processors, Sensors, TraitFamily,
+    BIOMASS_BLOCKING, BIOMASS_LARGE, BIOMASS_PER_MOVE_COST, REPAIR_COST,
+};
+use crate::entity::inventory::{item_weight, Inventory};
+use crate::entity::loot::{drop_loot, DropRates};
+use crate::entity::mutation::MutationHistory;
+use crate::entity::organelle::Organelles;
+use crate::entity::signal::{Aura, ReceivedSignal};
+use crate::entity::trade::is_tradeable;
+use crate::ui::hud::{faction_color, ToolTip};
+use crate::ui::{palette, register_heal_effects};
+use crate::util::game_rng::RngStream;
+use crate::util::timer::Timer;
+use crate::{
+    core::game_objects::GameObjects,
+    entity::action::hereditary::{ActAbsorbDna, ActPass},
+};
 use crate::{
     core::game_state::{GameState, Log, MessageLog, MsgClass},
-    entity::action::inventory::ActDropItem,
+    entity::action::inventory::{ActAnalyze, ActDropItem},
 };
 use serde::{Deserialize, Serialize};
 use std::cmp::min;
@@ -43,6 +62,57 @@ pub struct Object {
     pub actuators: Actuators,
     pub inventory: Inventory,
     pub item: Option<InventoryItem>,
+    pub attachment: Option<Attachment>,
+    /// Dormant organisms pass their turns without energy upkeep and take less damage.
+    #[serde(default)]
+    pub dormant: bool,
+    /// Most recent changes to the genome.
+    #[serde(default)]
+    pub mutations: MutationHistory,
+    /// Id of the player's bookmark for this organism, if there is one.
+    #[serde(default)]
+    pub bookmark: Option<u32>,
+    /// Trailing body parts of multi-tile organisms, closest to the head first.
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    #[serde(default)]
+    pub faction: Faction,
+    /// Chemical signals picked up from other organisms that currently sway this one's attitude.
+    #[serde(default)]
+    pub signals: Vec<ReceivedSignal>,
+    /// Signal this organism is currently emitting.
+    #[serde(default)]
+    pub aura: Option<Aura>,
+    /// Lines of story the organism has yet to tell.
+    #[serde(default)]
+    pub barks: Vec<Bark>,
+    /// Organelles held in the internal slots, they work independently of the genome.
+    #[serde(default)]
+    pub organelles: Organelles,
+    /// How much of itself the organism leaves behind when it dies.
+    #[serde(default)]
+    pub drops: DropRates,
+    /// Decision of the NPC in its latest turn, only recorded in debug mode.
+    #[serde(skip)]
+    pub ai_trace: Option<AiTrace>,
+}
+
+/// A body part of a multi-tile organism, e.g. a cell in a bacterial chain or a section of a
+/// fungal hypha. Segments follow the head of the organism and can be severed on their own.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub pos: Position,
+    pub hp: i32,
+}
+
+/// Objects can attach themselves to walls or host cells, which keeps them from drifting along
+/// currents and makes them harder to damage.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct Attachment {
+    /// Position of the wall or host this object holds on to.
+    pub pos: Position,
+    /// How firmly this object holds on, reduces incoming damage.
+    pub strength: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -130,6 +200,18 @@ impl Object {
             actuators: Actuators::new(),
             inventory: Inventory::new(),
             item: None,
+            attachment: None,
+            dormant: false,
+            mutations: MutationHistory::new(),
+            bookmark: None,
+            segments: Vec::new(),
+            faction: Faction::Unaligned,
+            signals: Vec::new(),
+            aura: None,
+            barks: Vec::new(),
+            organelles: Organelles::default(),
+            drops: DropRates::default(),
+            ai_trace: None,
         }
     }
 
@@ -180,15 +262,50 @@ impl Object {
         self
     }
 
+    /// Set the faction the object belongs to. Part of the builder pattern.
+    pub fn faction(mut self, faction: Faction) -> Object {
+        self.faction = faction;
+        self
+    }
+
+    /// Give the object trailing segments, each with the given hit points. They start out coiled
+    /// up at the object's position and unfold as it moves. Part of the builder pattern.
+    pub fn segmented(mut self, count: usize, hp: i32) -> Object {
+        self.segments = vec![Segment { pos: self.pos, hp }; count];
+        self
+    }
+
     /// Turn the object into a collectible item. Part of the builder pattern.
     pub fn inventory_item(mut self, item: InventoryItem) -> Object {
         self.item = Some(item);
         self
     }
 
+    /// Give the object lines of story to tell. Part of the builder pattern.
+    pub fn barks(mut self, barks: Vec<Bark>) -> Object {
+        self.barks = barks;
+        self
+    }
+
+    /// Set what the organism leaves behind when it dies. Part of the builder pattern.
+    pub fn drops(mut self, drops: DropRates) -> Object {
+        self.drops = drops;
+        self
+    }
+
     /// Transform the object into a tile. Part of the builder pattern.
     pub fn tile_explored(mut self, is_explored: bool) -> Object {
-        self.tile = Some(Tile { is_explored });
+        self.tile = Some(Tile {
+            is_explored,
+            scent: 0,
+            current: Target::Center,
+            environment: Environment::default(),
+            toxin: 0,
+            digested: 0,
+            region: Region::default(),
+            debris: None,
+            barrier: None,
+        });
         self
     }
 
@@ -210,19 +327,95 @@ impl Object {
     }
 
     /// Perform necessary actions when object dies.
-    pub fn die(&mut self, _state: &mut GameState, objects: &mut GameObjects) {
+    pub fn die(&mut self, state: &mut GameState, objects: &mut GameObjects) {
         self.alive = false;
+        // decaying organisms poison the host
+        if self.tile.is_none() {
+            state.host.release_toxins(self.actuators.biomass);
+        }
         // empty inventory into this objects' current position
         for mut o in self.inventory.items.drain(..) {
             o.pos.set(self.pos.x, self.pos.y);
             objects.push(o);
         }
+        // organisms leave behind fragments of their genome and what's left of their energy
+        if self.tile.is_none() && self.item.is_none() && !self.is_player() {
+            for o in drop_loot(state, self) {
+                objects.push(o);
+            }
+            // the rest of the genome stays behind as debris, to be absorbed by others
+            let genes: Vec<GeneticTrait> = self
+                .dna
+                .simplified
+                .iter()
+                .filter(|g| is_tradeable(g))
+                .cloned()
+                .collect();
+            if !genes.is_empty() {
+                objects.leave_debris(
+                    &self.pos,
+                    Debris {
+                        source: self.visual.name.clone(),
+                        genes,
+                    },
+                );
+            }
+        }
         // take this object out of the world
         if self.is_player() {
             self.visual.name = "your remains".to_string();
         }
     }
 
+    /// Move the object to a new position and pull its segments along after it.
+    pub fn move_to(&mut self, pos: Position) {
+        let mut previous = self.pos;
+        for segment in self.segments.iter_mut() {
+            std::mem::swap(&mut segment.pos, &mut previous);
+        }
+        self.pos = pos;
+    }
+
+    /// Check whether the object or any of its segments covers the given position.
+    pub fn occupies(&self, p: &Position) -> bool {
+        self.pos.is_equal(p) || self.segments.iter().any(|s| s.pos.is_equal(p))
+    }
+
+    /// Return the index of the segment at the given position, unless the head is there too.
+    pub fn segment_at(&self, p: &Position) -> Option<usize> {
+        if self.pos.is_equal(p) {
+            None
+        } else {
+            self.segments.iter().position(|s| s.pos.is_equal(p))
+        }
+    }
+
+    /// Damage a single segment. A destroyed segment is severed together with all segments behind
+    /// it. Returns the number of severed segments.
+    pub fn damage_segment(&mut self, idx: usize, damage: i32) -> usize {
+        match self.segments.get_mut(idx) {
+            Some(segment) => {
+                segment.hp -= damage;
+                if segment.hp <= 0 {
+                    let severed = self.segments.len() - idx;
+                    self.segments.truncate(idx);
+                    severed
+                } else {
+                    0
+                }
+            }
+            None => 0,
+        }
+    }
+
+    /// Name of the boss this object is, if it is one.
+    pub fn boss_name(&self) -> Option<String> {
+        match &self.control {
+            Some(Controller::Npc(ai)) => ai.boss_name().map(String::from),
+            _ => None,
+        }
+    }
+
     pub fn is_player(&self) -> bool {
         if let Some(Controller::Player(_)) = self.control {
             true
@@ -254,11 +447,83 @@ impl Object {
         self.control = Some(controller);
     }
 
-    pub fn metabolize(&mut self) {
+    /// Convert nutrients into energy. `nutrients` is the percentage of the usual amount that the
+    /// host has available, but metabolism always yields at least one point of energy.
+    /// Organisms with repair genes spend surplus and reserve energy on mending their damage.
+    pub fn metabolize(&mut self, nutrients: i32) {
+        let gained = (self.processors.metabolism * nutrients / 100).max(1);
+        let produced = self.processors.energy + gained;
+        self.processors.energy = min(produced, self.processors.energy_storage);
+        let spare = produced - self.processors.energy + self.processors.reserve;
+        let repaired = self.repair(self.processors.repair, spare);
+        if repaired > 0 {
+            register_heal_effects(self, repaired);
+        }
+        // any surplus overflows into the storage organelles
+        self.processors.reserve = min(
+            spare - repaired * REPAIR_COST,
+            self.processors.reserve_capacity,
+        );
+    }
+
+    /// Harvest energy from the conditions of the tile, if the organism has producer genes. Returns
+    /// the energy gained, which never exceeds the energy storage.
+    pub fn produce(&mut self, env: &Environment) -> i32 {
+        let produced = env.production(
+            self.processors.photosynthesis,
+            self.processors.chemosynthesis,
+        );
+        let energy = min(
+            self.processors.energy + produced,
+            self.processors.energy_storage,
+        );
+        let gained = (energy - self.processors.energy).max(0);
+        self.processors.energy += gained;
+        gained
+    }
+
+    /// Repair up to `amount` points of missing hp at `REPAIR_COST` per point, spending no more
+    /// than `budget` energy. Returns the number of repaired hp, paying for them is up to the
+    /// caller.
+    pub fn repair(&mut self, amount: i32, budget: i32) -> i32 {
+        let missing = self.actuators.max_hp - self.actuators.hp;
+        let repaired = amount.min(missing).min(budget / REPAIR_COST).max(0);
+        self.actuators.hp += repaired;
+        repaired
+    }
+
+    /// Take in energy from an outside source. What doesn't fit into the energy storage is kept in
+    /// reserve as far as possible.
+    pub fn store_energy(&mut self, amount: i32) {
+        let surplus = (self.processors.energy + amount - self.processors.energy_storage).max(0);
         self.processors.energy = min(
-            self.processors.energy + self.processors.metabolism,
+            self.processors.energy + amount,
             self.processors.energy_storage,
-        )
+        );
+        self.processors.reserve = min(
+            self.processors.reserve + surplus,
+            self.processors.reserve_capacity,
+        );
+    }
+
+    /// Pay the energy upkeep of the organism's biomass, tapping into the reserve if need be.
+    /// Returns whether the organism is starving, i.e. couldn't pay in full.
+    pub fn pay_upkeep(&mut self) -> bool {
+        let mut upkeep = self.processors.upkeep;
+        let from_energy = min(upkeep, self.processors.energy);
+        self.processors.energy -= from_energy;
+        upkeep -= from_energy;
+        let from_reserve = min(upkeep, self.processors.reserve);
+        self.processors.reserve -= from_reserve;
+        upkeep -= from_reserve;
+
+        if upkeep > 0 {
+            self.processors.starvation += 1;
+            true
+        } else {
+            self.processors.starvation = 0;
+            false
+        }
     }
 
     /// Set the object's current dna and resulting super traits.
@@ -273,6 +538,24 @@ impl Object {
         self.processors = processors;
         self.actuators = actuators;
         self.dna = dna;
+        self.organelles
+            .express(&mut self.processors, &mut self.actuators);
+
+        // tiny organisms can be passed by, plasmids never block anyway
+        if self.tile.is_none() && self.dna.dna_type != DnaType::Plasmid {
+            self.physics.is_blocking = self.actuators.biomass >= BIOMASS_BLOCKING;
+        }
+
+        // only the player cares about what the items it finds are made of, and about absorbing
+        // the genes of others
+        if self.is_player() {
+            if self.match_action("analyze item").is_none() {
+                self.sensors.actions.push(Box::new(ActAnalyze));
+            }
+            if self.match_action("absorb dna").is_none() {
+                self.actuators.actions.push(Box::new(ActAbsorbDna::new()));
+            }
+        }
 
         // update default action
         if let Some(Controller::Player(ref mut ctrl)) = &mut self.control {
@@ -295,11 +578,8 @@ impl Object {
                 .iter()
                 .find(|a| a.as_ref().get_identifier() == "pick up item")
             {
-                ctrl.quick1_action = def_action.clone_action();
-                debug!(
-                    "{} new quick action: {:#?}",
-                    self.visual.name, ctrl.primary_action
-                );
+                ctrl.set_hotbar_action(0, def_action.clone_action());
+                debug!("{} new hotbar action: {:#?}", self.visual.name, def_action);
             }
         }
     }
@@ -315,7 +595,20 @@ impl Object {
         let next_action;
         match controller {
             Some(Controller::Npc(ref mut boxed_ai)) => {
-                next_action = Some(boxed_ai.act(state, objects, self));
+                let mut timer = Timer::new("ai decision");
+                let action = boxed_ai.act(state, objects, self);
+                let elapsed = timer.stop_silent();
+                telemetry().record_decision(boxed_ai.kind(), elapsed);
+                if innit_env().debug_mode {
+                    self.ai_trace = Some(AiTrace {
+                        label: format!("{}: {}", boxed_ai.kind(), action.get_identifier()),
+                        target: action
+                            .get_target()
+                            .map(|t| self.pos.get_translated(&t.to_pos())),
+                        path: boxed_ai.planned_path(self, objects),
+                    });
+                }
+                next_action = Some(action);
             }
             Some(Controller::Player(ref mut player_ctrl)) => {
                 next_action = player_ctrl.next_action.take();
@@ -354,18 +647,10 @@ impl Object {
         self.control = controller;
     }
 
-    pub fn set_quick1_action(&mut self, new_quick1_action: Box<dyn Action>) {
+    pub fn set_hotbar_action(&mut self, slot: usize, new_hotbar_action: Box<dyn Action>) {
         let mut controller = self.control.take();
         if let Some(Controller::Player(ref mut ctrl)) = controller {
-            ctrl.quick1_action = new_quick1_action;
-        }
-        self.control = controller;
-    }
-
-    pub fn set_quick2_action(&mut self, new_quick2_action: Box<dyn Action>) {
-        let mut controller = self.control.take();
-        if let Some(Controller::Player(ref mut ctrl)) = controller {
-            ctrl.quick2_action = new_quick2_action;
+            ctrl.set_hotbar_action(slot, new_hotbar_action);
         }
         self.control = controller;
     }
@@ -383,9 +668,7 @@ impl Object {
     pub fn get_primary_action(&self, target: Target) -> Box<dyn Action> {
         // Some(def_action.clone())
         if let Some(Controller::Player(ctrl)) = &self.control {
-            let mut action_clone = ctrl.primary_action.clone();
-            action_clone.set_target(target);
-            action_clone
+            ctrl.prepare_action(ctrl.primary_action.as_ref(), target)
         } else {
             Box::new(ActPass::default())
         }
@@ -394,25 +677,15 @@ impl Object {
     pub fn get_secondary_action(&self, target: Target) -> Box<dyn Action> {
         // Some(def_action.clone())
         if let Some(Controller::Player(ctrl)) = &self.control {
-            let mut action_clone = ctrl.secondary_action.clone();
-            action_clone.set_target(target);
-            action_clone
-        } else {
-            Box::new(ActPass::default())
-        }
-    }
-
-    pub fn get_quick1_action(&self) -> Box<dyn Action> {
-        if let Some(Controller::Player(ctrl)) = &self.control {
-            ctrl.quick1_action.clone()
+            ctrl.prepare_action(ctrl.secondary_action.as_ref(), target)
         } else {
             Box::new(ActPass::default())
         }
     }
 
-    pub fn get_quick2_action(&self) -> Box<dyn Action> {
+    pub fn get_hotbar_action(&self, slot: usize) -> Box<dyn Action> {
         if let Some(Controller::Player(ctrl)) = &self.control {
-            ctrl.quick2_action.clone()
+            ctrl.get_hotbar_action(slot)
         } else {
             Box::new(ActPass::default())
         }
@@ -428,6 +701,28 @@ impl Object {
             .cloned()
     }
 
+    /// Check whether the item fits into the inventory, both by number of items and by weight.
+    /// Returns the reason if it doesn't.
+    pub fn check_capacity(&self, item: &Object) -> Result<(), String> {
+        if self.inventory.items.len() as i32 >= self.actuators.volume {
+            return Err("Your inventory is full!".to_string());
+        }
+        let spare_weight = self.actuators.carry_weight - self.inventory.weight();
+        if item_weight(item) > spare_weight {
+            return Err(format!(
+                "That's too heavy, you can carry only {} more weight.",
+                spare_weight.max(0)
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check whether the object carries more items or weight than it can hold.
+    pub fn is_overloaded(&self) -> bool {
+        self.inventory.items.len() as i32 > self.actuators.volume
+            || self.inventory.weight() > self.actuators.carry_weight
+    }
+
     pub fn add_to_inventory(&mut self, state: &mut GameState, o: Object) {
         let reread_dna = o.dna.dna_type == DnaType::Plasmid;
         let new_idx = self.inventory.items.len();
@@ -468,58 +763,302 @@ impl Object {
             .collect();
         let mut complete_dna = self.dna.raw.clone();
         complete_dna.append(&mut combined);
-        let (s, p, a, d) = state
-            .gene_library
-            .dna_to_traits(self.dna.dna_type, &complete_dna);
+        // plasmid genes beyond the cap on the genome's length are never expressed
+        if !is_within_dna_cap(complete_dna.len()) {
+            complete_dna.truncate(innit_env().max_dna_len.max(self.dna.raw.len()));
+            if self.is_player() {
+                state.log.add(
+                    "Your genome is too long to express all of your plasmids.",
+                    MsgClass::Info,
+                );
+            }
+        }
+        let (s, p, a, d) = state.gene_library.dna_to_marked_traits(
+            self.dna.dna_type,
+            &complete_dna,
+            &self.dna.silenced,
+        );
+        self.mutations
+            .record(state.turn, &self.dna, &d, &state.gene_library);
+        self.change_genome(s, p, a, d);
+        if self.is_player() {
+            state.events.publish(GameEvent::GenomeChanged {
+                genes: self.dna.simplified.len(),
+            });
+        }
+    }
+
+    /// Express the genome anew after the organelles changed. Hit points and energy are kept, as far
+    /// as the new attributes allow.
+    pub fn refresh_organelles(&mut self, state: &mut GameState) {
+        // without a genome there's nothing to express
+        if self.dna.raw.is_empty() {
+            return;
+        }
+        let (hp, energy, reserve) = (
+            self.actuators.hp,
+            self.processors.energy,
+            self.processors.reserve,
+        );
+        let (s, p, a, d) = state.gene_library.dna_to_marked_traits(
+            self.dna.dna_type,
+            &self.dna.raw,
+            &self.dna.silenced,
+        );
+        self.change_genome(s, p, a, d);
+        self.actuators.hp = hp.min(self.actuators.max_hp);
+        self.processors.energy = energy.min(self.processors.energy_storage);
+        self.processors.reserve = reserve.min(self.processors.reserve_capacity);
+    }
+
+    /// Replace the genome with the given raw DNA and express it. Epigenetic marks carry over, hit
+    /// points and energy are kept as far as the new attributes allow.
+    pub fn express_dna(&mut self, state: &mut GameState, raw_dna: &[u8]) {
+        let (hp, energy) = (self.actuators.hp, self.processors.energy);
+        let (s, p, a, d) =
+            state
+                .gene_library
+                .dna_to_marked_traits(self.dna.dna_type, raw_dna, &self.dna.silenced);
+        self.mutations
+            .record(state.turn, &self.dna, &d, &state.gene_library);
+        self.change_genome(s, p, a, d);
+        self.actuators.hp = hp.min(self.actuators.max_hp);
+        self.processors.energy = energy.min(self.processors.energy_storage);
+        if self.is_player() {
+            state.events.publish(GameEvent::GenomeChanged {
+                genes: self.dna.simplified.len(),
+            });
+        }
+    }
+
+    /// Turn up to `count` of this object's junk genes into random functional genes. Returns
+    /// whether there was any junk to reactivate.
+    pub fn reactivate_junk(&mut self, state: &mut GameState, count: usize) -> bool {
+        let raw_dna = match state.gene_library.reactivate_junk(
+            state.rng_streams.get(RngStream::Mutation),
+            &self.dna,
+            count,
+        ) {
+            Some(raw_dna) => raw_dna,
+            None => return false,
+        };
+        let (s, p, a, d) = state.gene_library.dna_to_marked_traits(
+            self.dna.dna_type,
+            &raw_dna,
+            &self.dna.silenced,
+        );
+        self.mutations
+            .record(state.turn, &self.dna, &d, &state.gene_library);
         self.change_genome(s, p, a, d);
+        if self.is_player() {
+            state.events.publish(GameEvent::GenomeChanged {
+                genes: self.dna.simplified.len(),
+            });
+        }
+        true
+    }
+
+    /// Return the energy this object has to spend on the given action under the given local
+    /// conditions. Moving gets more expensive the more biomass there is to move.
+    pub fn action_cost(&self, action: &dyn Action, env: &Environment) -> i32 {
+        let cost = if action.get_identifier() == "move" {
+            action.get_energy_cost() + self.actuators.biomass / BIOMASS_PER_MOVE_COST
+        } else {
+            action.get_energy_cost()
+        };
+        if cost > 0 {
+            (cost + self.environment_modifier(env)).max(1)
+        } else {
+            cost
+        }
+    }
+
+    /// Extra energy that actions cost under the given conditions. Organisms whose tolerance genes
+    /// let them shrug off conditions that would stress others thrive there and save energy.
+    pub fn environment_modifier(&self, env: &Environment) -> i32 {
+        let stress = env.stress(self.processors.heat_tolerance, self.processors.ph_tolerance);
+        if stress == 0 && env.stress(0, 0) > 0 {
+            -1
+        } else {
+            stress
+        }
+    }
+
+    /// Return the glyph to render this object with. Large organisms are shown in upper case.
+    pub fn display_glyph(&self) -> char {
+        if self.tile.is_none() && self.actuators.biomass >= BIOMASS_LARGE {
+            self.visual.glyph.to_ascii_uppercase()
+        } else {
+            self.visual.glyph
+        }
+    }
+
+    /// Return the foreground color to render this object with. Dormant organisms appear dimmed.
+    pub fn display_color(&self) -> (u8, u8, u8) {
+        let (r, g, b) = self.visual.fg_color;
+        if self.dormant {
+            (r / 2, g / 2, b / 2)
+        } else {
+            (r, g, b)
+        }
+    }
+
+    /// Check whether this object resists currents, either by its genome or by being attached to a
+    /// wall or host that is still right next to it.
+    pub fn is_anchored(&self, objects: &GameObjects) -> bool {
+        self.actuators.anchor > 0
+            || self
+                .attachment
+                .is_some_and(|a| self.pos.is_neighbour(&a.pos) && objects.is_pos_blocked(&a.pos))
+    }
+
+    /// Return whether this object and the other one share at least one receptor type.
+    pub fn has_matching_receptor(&self, other: &Object) -> bool {
+        self.processors
+            .receptors
+            .iter()
+            .any(|e1| other.processors.receptors.iter().any(|e2| e1.typ == e2.typ))
+    }
+
+    /// Sum up how the received signals change this object's attitude towards the given faction.
+    pub fn signal_standing(&self, faction: Faction) -> i32 {
+        self.signals
+            .iter()
+            .filter(|s| s.faction == faction)
+            .map(|s| s.standing)
+            .sum()
+    }
+
+    /// Let received signals and the own aura fade by one turn.
+    pub fn decay_signals(&mut self) {
+        for signal in &mut self.signals {
+            signal.turns -= 1;
+        }
+        self.signals.retain(|s| s.turns > 0);
+        if let Some(aura) = &mut self.aura {
+            aura.turns -= 1;
+            if aura.turns <= 0 {
+                self.aura = None;
+            }
+        }
+    }
+
+    /// Sum up the strength of all actions that can harm other objects.
+    pub fn offensive_strength(&self) -> i32 {
+        self.actuators
+            .actions
+            .iter()
+            .chain(self.processors.actions.iter())
+            .chain(self.sensors.actions.iter())
+            .filter(|a| a.get_identifier() == "attack")
+            .map(|a| a.get_level())
+            .sum()
+    }
+
+    /// Estimate how dangerous the `other` object is to this one by comparing their genomes.
+    /// Attacks are weighted against our own hit points, while viruses that can infect us count as
+    /// deadly as losing all hit points. Returns 0 for objects that pose no danger at all, including
+    /// allies of our own faction.
+    pub fn assess_threat(&self, other: &Object) -> f32 {
+        let is_ally = self.faction != Faction::Unaligned && other.faction == self.faction;
+        if !other.alive
+            || other.tile.is_some()
+            || is_ally
+            || !matches!(other.control, Some(Controller::Npc(_)))
+        {
+            return 0.0;
+        }
+
+        let defense = self.actuators.hp.max(1) as f32;
+        let mut threat = other.offensive_strength() as f32 / defense;
+        if other.dna.dna_type == DnaType::Rna && other.has_matching_receptor(self) {
+            threat += 1.0;
+        }
+        threat
     }
 
     pub fn generate_tooltip(&self, other: &Object) -> ToolTip {
-        // tiles don't need a header
-        if self.tile.is_some() {
+        // tiles don't need a header, open ones show the local conditions
+        if let Some(tile) = &self.tile {
             return if !self.physics.is_blocking {
-                ToolTip::no_header(vec![])
+                ToolTip::no_header(vec![
+                    (
+                        "temperature:".to_string(),
+                        format!("{:.1}°C", tile.environment.temperature),
+                    ),
+                    ("pH:".to_string(), format!("{:.1}", tile.environment.ph)),
+                    (
+                        "nutrients:".to_string(),
+                        format!("{:.0}%", tile.environment.nutrients * 100.0),
+                    ),
+                    (
+                        "light:".to_string(),
+                        format!("{:.0}%", tile.environment.light * 100.0),
+                    ),
+                ])
             } else {
                 ToolTip::header_only(self.visual.name.clone())
             };
         }
 
         // show whether both objects have matching receptors
-        let receptor_match = if self
-            .processors
-            .receptors
-            .iter()
-            .any(|e1| other.processors.receptors.iter().any(|e2| e1.typ == e2.typ))
-        {
+        let receptor_match = if self.has_matching_receptor(other) {
             "match".to_string()
         } else {
             "no match".to_string()
         };
 
+        // count the genes of each family for a short summary of the genome
+        let mut genes = [0; 3];
+        for g_trait in &self.dna.simplified {
+            match g_trait.trait_family {
+                TraitFamily::Sensing => genes[0] += 1,
+                TraitFamily::Processing => genes[1] += 1,
+                TraitFamily::Actuating => genes[2] += 1,
+                TraitFamily::Ltr | TraitFamily::Junk(_) => {}
+            }
+        }
+
         let header = self.visual.name.clone();
         let attributes: Vec<(String, String)> = vec![
             (
                 "position".to_string(),
                 format!("{}, {}", self.pos.x, self.pos.y),
             ),
+            ("faction:".to_string(), self.faction.name().to_string()),
             (
-                "hp:".to_string(),
-                format!("{}/{}", self.actuators.hp, self.actuators.max_hp).to_string(),
-            ),
-            (
-                "energy:".to_string(),
-                format!(
-                    "{}/{}",
-                    self.processors.energy, self.processors.energy_storage
-                )
-                .to_string(),
+                "genes:".to_string(),
+                format!("S{} P{} A{}", genes[0], genes[1], genes[2]),
             ),
             (
                 "sense range:".to_string(),
                 self.sensors.sensing_range.to_string(),
             ),
+            ("biomass:".to_string(), self.actuators.biomass.to_string()),
+            (
+                "dormant:".to_string(),
+                if self.dormant { "yes" } else { "no" }.to_string(),
+            ),
             ("receptors:".to_string(), receptor_match),
         ];
-        ToolTip::new(header, attributes)
+        let mut tooltip = ToolTip::new(header, attributes).with_color(faction_color(self.faction));
+        // items and other lifeless things have neither hp nor energy to speak of
+        if self.actuators.max_hp > 0 {
+            tooltip = tooltip.with_bar(
+                "hp",
+                self.actuators.hp,
+                self.actuators.max_hp,
+                palette().hud_fg_bar_health,
+            );
+        }
+        if self.processors.energy_storage > 0 {
+            tooltip = tooltip.with_bar(
+                "energy",
+                self.processors.energy,
+                self.processors.energy_storage,
+                palette().hud_fg_bar_energy,
+            );
+        }
+        tooltip
     }
 }
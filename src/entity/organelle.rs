@@ -0,0 +1,134 @@
+//! Organelles are compartments that an organism takes up from its surroundings and carries around
+//! inside. Unlike genes they aren't encoded in the DNA, so they keep working no matter how the
+//! genome is edited. Each one occupies one of a few internal slots and can be taken out again.
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::action::hereditary::ActMove;
+use crate::entity::action::organelle::ActEquipOrganelle;
+use crate::entity::genetics::{Actuators, Processors};
+use crate::entity::object::{InventoryItem, Object};
+
+/// Number of organelles an organism can hold at once.
+pub const ORGANELLE_SLOTS: usize = 2;
+
+/// Energy production per turn added by a mitochondrion.
+const MITOCHONDRION_METABOLISM: i32 = 1;
+/// Energy storage added by a mitochondrion.
+const MITOCHONDRION_STORAGE: i32 = 10;
+/// Energy reserve capacity added by a vacuole.
+const VACUOLE_RESERVE: i32 = 15;
+/// Inventory slots added by a vacuole.
+const VACUOLE_VOLUME: i32 = 1;
+/// Carry weight added by a vacuole.
+const VACUOLE_CARRY_WEIGHT: i32 = 3;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Organelle {
+    /// Produces and stores extra energy.
+    Mitochondrion,
+    /// Lets even organisms without any genes for it move around.
+    Flagellum,
+    /// Stores reserve energy and carried items.
+    Vacuole,
+}
+
+impl Organelle {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Organelle::Mitochondrion => "Mitochondrion",
+            Organelle::Flagellum => "Flagellum",
+            Organelle::Vacuole => "Vacuole",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Organelle::Mitochondrion => "+1 metabolism, +10 energy storage",
+            Organelle::Flagellum => "move, even without genes for it",
+            Organelle::Vacuole => "+15 energy reserve, +1 item slot, +3 carry weight",
+        }
+    }
+
+    fn color(&self) -> (u8, u8, u8) {
+        match self {
+            Organelle::Mitochondrion => (230, 120, 60),
+            Organelle::Flagellum => (200, 200, 90),
+            Organelle::Vacuole => (90, 200, 230),
+        }
+    }
+
+    /// Add the bonuses of this organelle to the freshly expressed attributes of an organism.
+    fn express(&self, processors: &mut Processors, actuators: &mut Actuators) {
+        match self {
+            Organelle::Mitochondrion => {
+                processors.metabolism += MITOCHONDRION_METABOLISM;
+                processors.energy_storage += MITOCHONDRION_STORAGE;
+            }
+            Organelle::Flagellum => {
+                if !actuators
+                    .actions
+                    .iter()
+                    .any(|a| a.get_identifier() == "move")
+                {
+                    actuators.actions.push(Box::new(ActMove::new()));
+                }
+            }
+            Organelle::Vacuole => {
+                processors.reserve_capacity += VACUOLE_RESERVE;
+                actuators.volume += VACUOLE_VOLUME;
+                actuators.carry_weight += VACUOLE_CARRY_WEIGHT;
+            }
+        }
+    }
+
+    /// Pack the organelle into an item that can be picked up, carried and equipped.
+    pub fn to_item(self, x: i32, y: i32) -> Object {
+        Object::new()
+            .position(x, y)
+            .living(true)
+            .visualize(self.name(), 'o', self.color())
+            .physical(false, false, false)
+            .itemize(Some(InventoryItem::new(
+                format!("Organelle: {}.", self.description()),
+                Some(Box::new(ActEquipOrganelle::new(self))),
+            )))
+    }
+}
+
+/// The organelles an organism holds in its internal slots.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Organelles {
+    pub equipped: Vec<Organelle>,
+}
+
+impl Organelles {
+    pub fn is_full(&self) -> bool {
+        self.equipped.len() >= ORGANELLE_SLOTS
+    }
+
+    /// Put the organelle into a free slot. Returns `false` if all slots are taken.
+    pub fn equip(&mut self, organelle: Organelle) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.equipped.push(organelle);
+        true
+    }
+
+    /// Take the organelle out of the given slot, if there is one.
+    pub fn unequip(&mut self, slot: usize) -> Option<Organelle> {
+        if slot < self.equipped.len() {
+            Some(self.equipped.remove(slot))
+        } else {
+            None
+        }
+    }
+
+    /// Add the bonuses of all held organelles to the freshly expressed attributes of an organism.
+    pub fn express(&self, processors: &mut Processors, actuators: &mut Actuators) {
+        for organelle in &self.equipped {
+            organelle.express(processors, actuators);
+        }
+    }
+}
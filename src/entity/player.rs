@@ -1,17 +1,56 @@
 //! Game settings pertaining to the player.
 //! This defines player actions, key bindings and more.
 
-use crate::entity::action::{hereditary::ActPass, Action};
+use crate::core::position::Position;
+use crate::entity::action::{
+    hereditary::{ActMove, ActPass, MoveMode},
+    Action, Target,
+};
+use crate::entity::danger::DangerWatch;
 use serde::{Deserialize, Serialize};
 
 pub const PLAYER: usize = 0; // player object reference, index of the object vector
+
+/// Number of slots in the action hotbar, each activated by one of the number keys.
+pub const HOTBAR_SIZE: usize = 9;
+
+/// A journey of the player to a distant position, taking one step per turn.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Travel {
+    /// Remaining steps, the next step comes first.
+    pub path: Vec<Position>,
+    /// Situation when setting out, taken with the first step. Any danger interrupts the journey.
+    #[serde(default)]
+    pub watch: Option<DangerWatch>,
+}
+
+impl Travel {
+    pub fn new(path: Vec<Position>) -> Self {
+        Travel { path, watch: None }
+    }
+
+    /// The move from `pos` to the next position of the path. Journeys always walk, so that they
+    /// keep to their path whatever movement mode the player picked.
+    pub fn step_action(&self, move_action: &dyn Action, pos: &Position) -> Option<Box<dyn Action>> {
+        let next = self.path.first()?;
+        let mut step = ActMove::new();
+        step.set_level(move_action.get_level());
+        step.set_target(Target::from_pos(pos, next));
+        Some(Box::new(step))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PlayerCtrl {
     pub primary_action: Box<dyn Action>,
     pub secondary_action: Box<dyn Action>,
-    pub quick1_action: Box<dyn Action>,
-    pub quick2_action: Box<dyn Action>,
+    /// Un-targeted actions that can be triggered directly via number keys.
+    #[serde(default)]
+    pub hotbar: Vec<Box<dyn Action>>,
     pub next_action: Option<Box<dyn Action>>,
+    pub move_mode: MoveMode,
+    #[serde(default)]
+    pub travel: Option<Travel>,
 }
 
 impl PlayerCtrl {
@@ -19,9 +58,54 @@ impl PlayerCtrl {
         PlayerCtrl {
             primary_action: Box::new(ActPass::default()),
             secondary_action: Box::new(ActPass::default()),
-            quick1_action: Box::new(ActPass::default()),
-            quick2_action: Box::new(ActPass::default()),
+            hotbar: (0..HOTBAR_SIZE)
+                .map(|_| Box::new(ActPass::default()) as Box<dyn Action>)
+                .collect(),
             next_action: None,
+            move_mode: MoveMode::Walk,
+            travel: None,
+        }
+    }
+
+    /// Put an action into the given hotbar slot. Slots beyond the hotbar size are ignored.
+    pub fn set_hotbar_action(&mut self, slot: usize, action: Box<dyn Action>) {
+        if slot >= HOTBAR_SIZE {
+            return;
+        }
+        while self.hotbar.len() <= slot {
+            self.hotbar.push(Box::new(ActPass::default()));
+        }
+        self.hotbar[slot] = action;
+    }
+
+    /// Return the action in the given hotbar slot, or passing if the slot is empty.
+    pub fn get_hotbar_action(&self, slot: usize) -> Box<dyn Action> {
+        match self.hotbar.get(slot) {
+            Some(action) => action.clone(),
+            None => Box::new(ActPass::default()),
         }
     }
+
+    /// Switch between walking and bursting and return the new movement mode.
+    pub fn toggle_move_mode(&mut self) -> MoveMode {
+        self.move_mode = match self.move_mode {
+            MoveMode::Walk => MoveMode::Burst,
+            MoveMode::Burst => MoveMode::Walk,
+        };
+        self.move_mode
+    }
+
+    /// Turn the given action into a targeted action. Move actions adopt the current movement mode.
+    pub fn prepare_action(&self, action: &dyn Action, target: Target) -> Box<dyn Action> {
+        let mut prepared = if action.get_identifier() == "move" {
+            let mut move_action = ActMove::new();
+            move_action.set_level(action.get_level());
+            move_action.set_mode(self.move_mode);
+            Box::new(move_action)
+        } else {
+            action.clone_action()
+        };
+        prepared.set_target(target);
+        prepared
+    }
 }
@@ -0,0 +1,84 @@
+//! Organisms negotiate with chemical signals. Emitting a signal leaves an aura around the emitter
+//! and temporarily changes how nearby organisms regard the emitter's faction, but only those that
+//! carry a receptor matching one of the emitter's can pick it up.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::game_objects::GameObjects;
+use crate::entity::faction::Faction;
+use crate::entity::object::Object;
+
+/// Distance up to which emitted signals are received.
+pub const SIGNAL_RADIUS: f32 = 4.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SignalKind {
+    /// Pheromones that calm down other organisms.
+    Peace,
+    /// A show of strength that deters weaker organisms and provokes stronger ones.
+    Threat,
+}
+
+impl SignalKind {
+    pub fn name(&self) -> &str {
+        match self {
+            SignalKind::Peace => "peace pheromones",
+            SignalKind::Threat => "a threat display",
+        }
+    }
+}
+
+/// A signal an organism has picked up, which changes its attitude towards the emitter's faction
+/// for a number of turns.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct ReceivedSignal {
+    pub kind: SignalKind,
+    pub faction: Faction,
+    pub standing: i32,
+    pub turns: i32,
+}
+
+/// A signal an organism is currently emitting, shown around it in the world.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Aura {
+    pub kind: SignalKind,
+    pub turns: i32,
+}
+
+/// Broadcast a signal from `emitter` to all organisms in range that can receive it. The standing
+/// change for each receiver is decided by `standing`. Returns the number of receivers.
+pub fn emit<F>(
+    objects: &mut GameObjects,
+    emitter: &mut Object,
+    kind: SignalKind,
+    turns: i32,
+    standing: F,
+) -> usize
+where
+    F: Fn(&Object, &Object) -> i32,
+{
+    emitter.aura = Some(Aura { kind, turns });
+    let mut receivers = 0;
+    for receiver in objects
+        .get_vector_mut()
+        .iter_mut()
+        .flatten()
+        .filter(|o| o.tile.is_none() && o.alive && o.control.is_some())
+        .filter(|o| o.pos.distance(&emitter.pos) <= SIGNAL_RADIUS)
+        .filter(|o| o.has_matching_receptor(emitter))
+    {
+        let signal = ReceivedSignal {
+            kind,
+            faction: emitter.faction,
+            standing: standing(emitter, receiver),
+            turns,
+        };
+        // a fresh signal of the same kind from the same faction replaces the old one
+        receiver
+            .signals
+            .retain(|s| s.kind != kind || s.faction != emitter.faction);
+        receiver.signals.push(signal);
+        receivers += 1;
+    }
+    receivers
+}
@@ -0,0 +1,123 @@
+//! Organisms that don't regard the player as an enemy are open to trade. They hand over plasmids
+//! from their inventory or bud off fragments of their own genome in exchange for energy.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::game_state::GameState;
+use crate::entity::action::hereditary::ActEditGenome;
+use crate::entity::faction::FRIENDLY_STANDING;
+use crate::entity::genetics::{DnaType, GeneticTrait, TraitFamily};
+use crate::entity::inventory::item_name;
+use crate::entity::object::{InventoryItem, Object};
+
+/// Energy a plasmid costs, on top of one energy per gene it carries.
+const PLASMID_PRICE: i32 = 2;
+/// Energy a gene fragment costs.
+const FRAGMENT_PRICE: i32 = 2;
+/// Energy that friendly organisms knock off the price.
+const FRIENDLY_DISCOUNT: i32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum TradeGoods {
+    /// The plasmid in the given slot of the partner's inventory.
+    Plasmid { slot: usize },
+    /// A copy of one of the partner's genes, packed into a plasmid.
+    GeneFragment { gene: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TradeOffer {
+    pub goods: TradeGoods,
+    pub description: String,
+    /// Energy the buyer pays.
+    pub price: i32,
+}
+
+/// Check whether `partner` is willing to trade with `buyer`, i.e. doesn't consider it an enemy.
+pub fn is_willing(state: &GameState, partner: &Object, buyer: &Object) -> bool {
+    partner.alive
+        && partner.tile.is_none()
+        && partner.item.is_none()
+        && !partner.is_player()
+        && partner.control.is_some()
+        && !state.relations.is_hostile_towards(partner, buyer)
+}
+
+/// List everything `partner` has to offer to `buyer`: the plasmids it carries and one fragment
+/// of each gene that the buyer's genome lacks.
+pub fn offers(state: &GameState, partner: &Object, buyer: &Object) -> Vec<TradeOffer> {
+    let attitude =
+        state.relations.attitude(partner.faction, buyer) + partner.signal_standing(buyer.faction);
+    let discount = if attitude > FRIENDLY_STANDING {
+        FRIENDLY_DISCOUNT
+    } else {
+        0
+    };
+
+    let plasmids = partner
+        .inventory
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.dna.dna_type == DnaType::Plasmid)
+        .map(|(slot, item)| TradeOffer {
+            goods: TradeGoods::Plasmid { slot },
+            description: format!(
+                "{} ({} genes)",
+                item_name(state, item),
+                item.dna.simplified.len()
+            ),
+            price: PLASMID_PRICE + item.dna.simplified.len() as i32,
+        });
+
+    let mut genes: Vec<&GeneticTrait> = Vec::new();
+    for gene in partner.dna.simplified.iter().filter(|g| is_tradeable(g)) {
+        let is_known = buyer
+            .dna
+            .simplified
+            .iter()
+            .chain(genes.iter().copied())
+            .any(|g| g.trait_name == gene.trait_name);
+        if !is_known {
+            genes.push(gene);
+        }
+    }
+    let fragments = genes.into_iter().map(|gene| TradeOffer {
+        goods: TradeGoods::GeneFragment {
+            gene: gene.trait_name.clone(),
+        },
+        description: format!("{} fragment", gene.trait_name),
+        price: FRAGMENT_PRICE,
+    });
+
+    plasmids
+        .chain(fragments)
+        .map(|mut offer| {
+            offer.price = (offer.price - discount).max(1);
+            offer
+        })
+        .collect()
+}
+
+/// Check whether the gene can be packed into a fragment, i.e. is neither a marker nor junk.
+pub fn is_tradeable(gene: &GeneticTrait) -> bool {
+    !matches!(gene.trait_family, TraitFamily::Ltr | TraitFamily::Junk(_))
+}
+
+/// Pack a copy of the given gene into a plasmid that can be carried in the inventory.
+pub fn gene_fragment(state: &GameState, owner: &Object, gene: &GeneticTrait) -> Object {
+    let raw = state.gene_library.g_trait_refs_to_dna(&[gene]);
+    Object::new()
+        .position(owner.pos.x, owner.pos.y)
+        .living(true)
+        .visualize(&format!("{} Fragment", gene.trait_name), 'p', (0, 130, 255))
+        .physical(false, false, false)
+        .genome(
+            0.99,
+            state.gene_library.dna_to_traits(DnaType::Plasmid, &raw),
+        )
+        .itemize(Some(InventoryItem::new(
+            format!("A fragment of {}'s genome.", owner.visual.name),
+            Some(Box::new(ActEditGenome::new())),
+        )))
+}
@@ -1,39 +1,83 @@
 //! The top level representation of the game. Here the major game components are constructed and
 //! the game loop is executed.
 
+use crate::core::achievements::Achievements;
+use crate::core::bug_report::{create_bug_report, InputRecorder};
+use crate::core::daily::{daily_seed, today};
+use crate::core::ecosystem::{Ecosystem, SpawnRaws};
+use crate::core::encyclopedia::entries;
+use crate::core::events::GameEvent;
+use crate::core::game_env::{GameEnv, GameMode, GameSpeed};
+use crate::core::game_error::GameError;
 use crate::core::game_objects::GameObjects;
 use crate::core::game_state::{GameState, MessageLog, MsgClass, ObjectFeedback};
+use crate::core::highscores::{Highscores, Score};
+use crate::core::position::Position;
+use crate::core::run_code::RunCode;
+use crate::core::save::{PlatformSave, SaveBackend, CHECKPOINT_INTERVAL, RESPAWN_PENALTY};
+use crate::core::snapshot::export_snapshot;
+use crate::core::story::{reach, Milestone};
+use crate::core::telemetry::Telemetry;
+use crate::core::world::prefab::PrefabRegistry;
 use crate::core::world::world_gen_organic::OrganicsWorldGenerator;
 use crate::core::world::WorldGen;
+use crate::core::{innit_env, telemetry};
 use crate::entity::action::hereditary::ActPass;
 use crate::entity::action::inventory::ActDropItem;
 use crate::entity::action::{Action, Target, TargetCategory};
 use crate::entity::control::Controller;
+use crate::entity::danger::DangerWatch;
+use crate::entity::faction::Faction;
 use crate::entity::genetics::{DnaType, GENE_LEN};
+use crate::entity::inventory::identify;
 use crate::entity::object::Object;
-use crate::entity::player::PlayerCtrl;
-use crate::raws::{load_object_templates, load_spawns};
+use crate::entity::player::{PlayerCtrl, Travel};
+use crate::entity::trade::{is_willing, offers};
+use crate::raws::spawn::from_dungeon_level;
+use crate::raws::{
+    check_raws, load_boss_templates, load_ecosystem, load_object_templates, load_palette,
+    load_story, load_terrain, TISSUE_TEMPLATE,
+};
+use crate::ui::animations::AnimationClock;
 use crate::ui::custom::genome_editor::{GenomeEditingState, GenomeEditor};
+use crate::ui::custom::sandbox::Sandbox;
+use crate::ui::dialog::bookmarks::bookmarks_screen;
 use crate::ui::dialog::character::character_screen;
 use crate::ui::dialog::controls::controls_screen;
+use crate::ui::dialog::encounter::encounter_screen;
+use crate::ui::dialog::encyclopedia::EncyclopediaDialog;
+use crate::ui::dialog::error::error_screen;
+use crate::ui::dialog::mutations::mutation_screen;
+use crate::ui::dialog::organelles::OrganelleDialog;
+use crate::ui::dialog::raws_errors::raws_errors_screen;
+use crate::ui::dialog::rename::RenameDialog;
+use crate::ui::dialog::run_code::RunCodeDialog;
+use crate::ui::dialog::story::StoryDialog;
+use crate::ui::dialog::trade::TradeDialog;
 use crate::ui::dialog::InfoBox;
 use crate::ui::frontend::render_world;
-use crate::ui::game_input::{read_input, PlayerInput, UiAction};
-use crate::ui::hud::{render_gui, Hud};
+use crate::ui::game_input::{read_input, KeyRepeat, PlayerAction, PlayerInput, UiAction};
+use crate::ui::hud::{render_gui, render_telemetry, Hud};
+use crate::ui::layers::{DirtyLayers, Layer};
 use crate::ui::menu::choose_action_menu::{choose_action_menu, ActionCategory, ActionItem};
+use crate::ui::menu::difficulty_menu::DifficultyMenuItem;
+use crate::ui::menu::display_menu::DisplayMenuItem;
 use crate::ui::menu::game_over_menu::{game_over_menu, GameOverMenuItem};
 use crate::ui::menu::main_menu::{main_menu, MainMenuItem};
 use crate::ui::menu::{Menu, MenuItem};
 use crate::ui::palette;
 use crate::ui::particles;
+use crate::ui::register_particle;
 use crate::ui::rex_assets::RexAssets;
+use crate::ui::spectator::{Spectator, SPECTATOR_RANGE};
+use crate::ui::window::WindowTracker;
+use crate::util::game_rng::RngStream;
+use crate::util::platform;
 use crate::util::timer::{time_from, Timer};
 use core::fmt;
-use rltk::{ColorPair, DrawBatch, GameState as Rltk_GameState, Rltk};
-use std::error::Error;
+use rltk::{ColorPair, DrawBatch, GameState as Rltk_GameState, Rltk, VirtualKeyCode};
+use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
-use std::fs::{self, File};
-use std::io::{Read, Write};
 
 // environment constraints
 // game window
@@ -52,30 +96,90 @@ pub const PAR_CON: usize = 2;
 
 pub const MENU_WIDTH: i32 = 20;
 
+/// Time in [ms] each visible NPC action is shown at animated game speed.
+const ANIMATION_STEP_MS: f32 = 80.0;
+/// Time in [ns] that objects may take their turns per frame. Once it's used up, the remaining
+/// objects go on in the next frame, so that the screen keeps animating and input is still read
+/// while hundreds of NPCs act.
+const PROCESSING_BUDGET_NS: u128 = 5_000_000;
+
 #[derive(Debug)]
 pub enum RunState {
     MainMenu(Menu<MainMenuItem>),
+    DisplaySettings(Menu<DisplayMenuItem>),
+    /// Pick the difficulty of a new game.
+    ChooseDifficulty(Menu<DifficultyMenuItem>),
     NewGame,
+    /// Start today's daily run.
+    NewDailyRun,
+    /// Start a run from a code shared by another player.
+    NewSharedRun(RunCode),
     LoadGame,
     ChooseActionMenu(Menu<ActionItem>),
     GameOver(Menu<GameOverMenuItem>),
     InfoBox(InfoBox),
+    ErrorDialog(InfoBox),
     GenomeEditing(GenomeEditor),
+    RenameDialog(RenameDialog),
+    RunCodeDialog(RunCodeDialog),
+    Trading(TradeDialog),
+    Organelles(OrganelleDialog),
+    Encyclopedia(EncyclopediaDialog),
+    Story(StoryDialog),
+    Spectating(Spectator),
+    Sandbox(Sandbox),
     Ticking,
     CheckInput,
     ToggleDarkLightMode,
 }
 
+impl RunState {
+    /// Check whether the state draws a menu or dialog onto the overlay.
+    pub fn has_overlay(&self) -> bool {
+        matches!(
+            self,
+            RunState::MainMenu(_)
+                | RunState::DisplaySettings(_)
+                | RunState::ChooseDifficulty(_)
+                | RunState::ChooseActionMenu(_)
+                | RunState::GameOver(_)
+                | RunState::InfoBox(_)
+                | RunState::ErrorDialog(_)
+                | RunState::GenomeEditing(_)
+                | RunState::RenameDialog(_)
+                | RunState::RunCodeDialog(_)
+                | RunState::Trading(_)
+                | RunState::Organelles(_)
+                | RunState::Encyclopedia(_)
+                | RunState::Story(_)
+                | RunState::Sandbox(_)
+        )
+    }
+}
+
 impl Display for RunState {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             RunState::MainMenu(_) => write!(f, "MainMenu"),
+            RunState::DisplaySettings(_) => write!(f, "DisplaySettings"),
+            RunState::ChooseDifficulty(_) => write!(f, "ChooseDifficulty"),
             RunState::NewGame => write!(f, "NewGame"),
+            RunState::NewDailyRun => write!(f, "NewDailyRun"),
+            RunState::NewSharedRun(_) => write!(f, "NewSharedRun"),
             RunState::LoadGame => write!(f, "LoadGame"),
             RunState::ChooseActionMenu(_) => write!(f, "ChooseActionMenu"),
             RunState::GameOver(_) => write!(f, "GameOver"),
             RunState::InfoBox(_) => write!(f, "InfoBox"),
+            RunState::ErrorDialog(_) => write!(f, "ErrorDialog"),
             RunState::GenomeEditing(_) => write!(f, "GenomeEditing"),
+            RunState::RenameDialog(_) => write!(f, "RenameDialog"),
+            RunState::RunCodeDialog(_) => write!(f, "RunCodeDialog"),
+            RunState::Trading(_) => write!(f, "Trading"),
+            RunState::Organelles(_) => write!(f, "Organelles"),
+            RunState::Encyclopedia(_) => write!(f, "Encyclopedia"),
+            RunState::Story(_) => write!(f, "Story"),
+            RunState::Spectating(_) => write!(f, "Spectating"),
+            RunState::Sandbox(_) => write!(f, "Sandbox"),
             RunState::Ticking => write!(f, "Ticking"),
             RunState::CheckInput => write!(f, "CheckInput"),
             RunState::ToggleDarkLightMode => write!(f, "ToggleDarkLightMode"),
@@ -90,7 +194,18 @@ pub struct Game {
     // object_templates: Vec<ObjectTemplate>,
     run_state: Option<RunState>,
     hud: Hud,
-    re_render: bool,
+    /// Layers of the screen that need to be drawn anew.
+    dirty: DirtyLayers,
+    /// Whether anything was drawn onto the overlay in the previous frame.
+    overlay_shown: bool,
+    /// While paused, NPCs don't act and the player can only look around or advance single turns.
+    paused: bool,
+    /// Turn up to which the world goes on despite being paused.
+    advance_to: Option<u128>,
+    /// Time in [ms] that the last animated NPC action is still shown.
+    animation_delay: f32,
+    /// Time of all animations and visual effects.
+    clock: AnimationClock,
     is_dark_color_palette: bool,
     rex_assets: RexAssets,
     /// This workaround is required because each mouse click is registered twice (press & release),
@@ -98,6 +213,29 @@ pub struct Game {
     mouse_workaround: bool,
     /// Keep track of the time to warn if the game runs too slow.
     slowest_tick: u128,
+    /// Problems with the raws that have already been reported to the player.
+    raws_errors: Vec<String>,
+    save_backend: Box<dyn SaveBackend>,
+    /// Whether the player can respawn at a checkpoint after dying in the current run.
+    can_respawn: bool,
+    /// Achievements unlocked across all runs.
+    achievements: Achievements,
+    /// Milestones of the story, as defined in the raws.
+    milestones: Vec<Milestone>,
+    /// Milestones reached during the latest turns whose vignettes are yet to be shown.
+    pending_milestones: VecDeque<Milestone>,
+    /// Introductions of species first seen during the latest turns, yet to be shown.
+    pending_encounters: VecDeque<InfoBox>,
+    /// The player's own settings, set aside while they are locked for a daily run.
+    own_settings: Option<GameEnv>,
+    /// Recent inputs of the player, to be included in bug reports.
+    input_recorder: InputRecorder,
+    /// Held movement keys that keep the player going.
+    key_repeat: KeyRepeat,
+    /// Whether the telemetry of the scheduler is shown in the debug overlay.
+    show_telemetry: bool,
+    /// Size and position of the window, remembered for the next session.
+    window: WindowTracker,
 }
 
 impl Game {
@@ -105,61 +243,387 @@ impl Game {
         let state = GameState::new(0);
         let objects = GameObjects::new();
 
+        // problems with the raws are reported once at startup
+        let raws_errors = check_raws();
+        if let Some(custom_palette) = load_palette(&mut Vec::new()) {
+            *palette() = custom_palette;
+        }
+
+        let milestones = load_story(&mut Vec::new());
+
+        let (rex_assets, asset_error) = RexAssets::new();
+        let run_state = match asset_error {
+            Some(e) => RunState::ErrorDialog(error_screen(&e)),
+            None if !raws_errors.is_empty() => {
+                RunState::ErrorDialog(raws_errors_screen(&raws_errors))
+            }
+            None => RunState::MainMenu(main_menu()),
+        };
+
         Game {
             state,
             objects,
             // spawns: load_spawns(),
             // object_templates: load_object_templates(),
-            run_state: Some(RunState::MainMenu(main_menu())),
+            run_state: Some(run_state),
             hud: Hud::new(),
-            re_render: false,
+            dirty: DirtyLayers::new(),
+            overlay_shown: false,
+            paused: false,
+            advance_to: None,
+            animation_delay: 0.0,
+            clock: AnimationClock::new(),
             is_dark_color_palette: true,
-            rex_assets: RexAssets::new(),
+            rex_assets,
             mouse_workaround: false,
             slowest_tick: 0,
+            raws_errors,
+            save_backend: Box::new(PlatformSave::new()),
+            can_respawn: false,
+            achievements: Achievements::load(),
+            milestones,
+            pending_milestones: VecDeque::new(),
+            pending_encounters: VecDeque::new(),
+            own_settings: None,
+            input_recorder: InputRecorder::new(),
+            key_repeat: KeyRepeat::new(),
+            show_telemetry: false,
+            window: WindowTracker::new(),
         }
     }
 
+    /// Unlock the achievements fulfilled by the latest game events and announce them. Story
+    /// milestones reached by the events and newly encountered species are queued up to be shown.
+    fn check_achievements(&mut self) {
+        let events = self.state.events.drain();
+        self.pending_milestones
+            .extend(reach(&mut self.state, &self.milestones, &events));
+        if innit_env().use_encounter_popups {
+            for event in &events {
+                if let GameEvent::SpeciesEncountered {
+                    name,
+                    threat,
+                    genes,
+                } = event
+                {
+                    self.pending_encounters
+                        .push_back(encounter_screen(name, *threat, genes));
+                }
+            }
+        }
+        for achievement in self.achievements.process(&events) {
+            self.state.log.add(
+                format!(
+                    "Achievement unlocked: {}. {}",
+                    achievement.name, achievement.description
+                ),
+                MsgClass::Story,
+            );
+            self.hud
+                .show_toast(format!("Achievement unlocked: {}", achievement.name));
+        }
+    }
+
+    /// Take the next step of the player's journey, if there is one. Any input, danger or obstacle
+    /// ends the journey.
+    fn next_travel_step(&mut self, ctx: &Rltk) -> Option<Box<dyn Action>> {
+        let threat = self.hud.threat;
+        let player = self.objects[self.state.player_idx].as_ref()?;
+        let mut travel = match &player.control {
+            Some(Controller::Player(ctrl)) => ctrl.travel.clone()?,
+            _ => return None,
+        };
+        let danger = travel
+            .watch
+            .get_or_insert_with(|| DangerWatch::new(&self.state, &self.objects, threat))
+            .check(&self.state, &self.objects, threat);
+        let is_blocked = !travel
+            .path
+            .first()
+            .is_some_and(|p| player.pos.is_neighbour(p) && !self.objects.is_pos_blocked(p));
+        let move_action = player.match_action("move");
+
+        // any input by the player is processed as usual
+        let has_input = ctx.key.is_some() || ctx.left_click;
+        let must_stop = danger.is_some() || is_blocked || move_action.is_none();
+        let reason = match danger {
+            Some(danger) => danger.message(),
+            None => Some("You stop, your way is blocked.".to_string()),
+        };
+
+        let player = self.objects[self.state.player_idx].as_mut()?;
+        let pos = player.pos;
+        if let Some(Controller::Player(ctrl)) = &mut player.control {
+            ctrl.travel = None;
+            if must_stop {
+                if let Some(msg) = reason {
+                    self.state.log.add(msg, MsgClass::Info);
+                }
+                return None;
+            }
+            if has_input {
+                return None;
+            }
+            let action = travel.step_action(move_action?.as_ref(), &pos)?;
+            travel.path.remove(0);
+            if !travel.path.is_empty() {
+                ctrl.travel = Some(travel);
+            }
+            return Some(action);
+        }
+        None
+    }
+
+    /// Let the game engine process objects until we have to re-render the world or UI.
+    fn process_objects(&mut self) -> RunState {
+        let speed = innit_env().game_speed;
+        let mut feedback;
+        let mut is_npc_turn;
+        let mut deferred_render = false;
+        // Re-rendering is necessary either because the world changed or messages need to
+        // be printed to the log. At instant speed the NPCs' actions are only rendered once all of
+        // them had their turn, or once the processing budget of this frame is used up.
+        let mut budget = Timer::new("process objects");
+        let mut actions = 0;
+        'processing: loop {
+            is_npc_turn = !self.state.is_players_turn();
+            feedback = self.state.process_object(&mut self.objects);
+            actions += 1;
+            let is_deferrable = matches!(
+                feedback,
+                ObjectFeedback::NoFeedback | ObjectFeedback::Render | ObjectFeedback::UpdateHud
+            );
+            if speed == GameSpeed::Instant && is_npc_turn && is_deferrable {
+                deferred_render |= feedback != ObjectFeedback::NoFeedback;
+            } else if feedback != ObjectFeedback::NoFeedback || self.state.log.is_changed {
+                break 'processing;
+            }
+            if budget.elapsed() > PROCESSING_BUDGET_NS {
+                trace!("processing budget used up, continue next frame");
+                break 'processing;
+            }
+        }
+        budget.stop_silent();
+        telemetry().record_frame(actions);
+        let run_state =
+            self.process_visual_feedback(feedback, is_npc_turn && speed == GameSpeed::Animated);
+        if deferred_render {
+            self.dirty.mark_all();
+        }
+        run_state
+    }
+
+    /// Decide how to go on after an object's turn. Animated NPC actions are shown for a moment
+    /// before the next object takes its turn.
+    fn process_visual_feedback(&mut self, feedback: ObjectFeedback, is_animated: bool) -> RunState {
+        trace!("process feedback in RunState::Ticking: {:#?}", feedback);
+        match feedback {
+            ObjectFeedback::GameOver => self.end_run(),
+            ObjectFeedback::Render => {
+                self.dirty.mark_all();
+                if is_animated {
+                    self.animation_delay = ANIMATION_STEP_MS;
+                }
+                RunState::Ticking
+            }
+            ObjectFeedback::GenomeManipulator => {
+                if let Some(genome_editor) =
+                    create_genome_manipulator(&mut self.state, &mut self.objects)
+                {
+                    RunState::GenomeEditing(genome_editor)
+                } else {
+                    RunState::CheckInput
+                }
+            }
+            ObjectFeedback::UpdateHud => {
+                self.hud.require_refresh = true;
+                RunState::Ticking
+            }
+            // if there is no reason to re-render, check whether we're waiting on user input
+            _ => {
+                if self.state.is_players_turn() && self.state.player_energy_full(&self.objects) {
+                    RunState::CheckInput
+                } else {
+                    RunState::Ticking
+                }
+            }
+        }
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.advance_to = None;
+        if self.paused {
+            self.state.log.add(
+                "Game paused. Press P to resume or . to advance a single turn.",
+                MsgClass::Info,
+            );
+        } else {
+            self.state.log.add("Game resumed.", MsgClass::Info);
+        }
+    }
+
+    /// Let the world go on until the current turn is over, passing the player's turn if needed.
+    fn advance_turn(&mut self) -> RunState {
+        if self.state.is_players_turn() {
+            if let Some(ref mut player) = self.objects[self.state.player_idx] {
+                if !player.has_next_action() {
+                    player.set_next_action(Some(Box::new(ActPass::default())));
+                }
+            }
+        }
+        self.advance_to = Some(self.state.turn + 1);
+        RunState::Ticking
+    }
+
     fn reset(&mut self, state: GameState, objects: GameObjects) {
+        self.lock_settings(state.daily.is_some());
+        innit_env().set_difficulty(state.difficulty.clone());
         self.state = state;
         self.objects = objects;
+        self.paused = false;
+        self.advance_to = None;
+        self.input_recorder.clear();
+        self.pending_milestones.clear();
+        self.pending_encounters.clear();
+        self.state.visibility.update(&mut self.objects);
+        *telemetry() = Telemetry::new();
 
         if let Some(player) = &self.objects[self.state.player_idx] {
-            self.hud.update_ui_items(player);
+            self.hud.update_ui_items(&self.state, player);
+        };
+    }
+
+    /// Hide the world and the side panel behind the menu background, for menus outside of a run.
+    fn show_menu_background(&mut self, ctx: &mut Rltk) {
+        self.state.log.is_changed = false;
+        self.hud.require_refresh = false;
+        self.dirty.clear();
+        particles().particles.clear();
+        ctx.set_active_console(HUD_CON);
+        ctx.cls();
+        ctx.set_active_console(WORLD_CON);
+        ctx.cls();
+        if let Some(menu) = &self.rex_assets.menu {
+            ctx.render_xp_sprite(menu, 0, 0);
+        }
+    }
+
+    /// Daily runs are played with the default settings, so that all players face the same
+    /// conditions. The player's own settings are set aside meanwhile and restored afterwards.
+    fn lock_settings(&mut self, locked: bool) {
+        if locked && self.own_settings.is_none() {
+            let own_settings = innit_env().clone();
+            self.own_settings = Some(own_settings);
+            innit_env().lock();
+        } else if !locked {
+            if let Some(own_settings) = self.own_settings.take() {
+                *innit_env() = own_settings;
+            }
+        }
+    }
+
+    /// Enter the player's result into the highscore list once the game is over.
+    fn record_score(&mut self) {
+        let penalty = self.state.respawns as u128 * RESPAWN_PENALTY;
+        let score = Score {
+            turns: self.state.turn.saturating_sub(penalty),
+            bosses: self.state.defeated_bosses.len(),
+            daily: self.state.daily.clone(),
+            difficulty: self.state.difficulty.name.clone(),
         };
+        let mut highscores = Highscores::load();
+        if let Some(rank) = highscores.insert(score) {
+            highscores.save();
+            self.state.log.add(
+                format!("Your run ranks #{} in the highscore list.", rank + 1),
+                MsgClass::Story,
+            );
+        }
     }
 
-    /// Create a new game by instantiating the game engine, game state and object vector.
-    fn new_game() -> (GameState, GameObjects) {
+    /// End the run after the player died. Runs with permadeath lose their save game, whereas runs
+    /// in checkpoint mode are charged a respawn at their last checkpoint.
+    fn end_run(&mut self) -> RunState {
+        self.record_score();
+        self.can_respawn = match self.state.game_mode {
+            GameMode::Permadeath => {
+                if let Err(e) = self.save_backend.delete() {
+                    error!("cannot delete save game: {}", e);
+                }
+                false
+            }
+            GameMode::Checkpoint => match self.save_backend.charge_respawn() {
+                Ok(()) => true,
+                Err(e) => {
+                    error!("cannot respawn at checkpoint: {}", e);
+                    false
+                }
+            },
+        };
+        RunState::GameOver(game_over_menu(
+            innit_env().spectator_turns > 0,
+            self.can_respawn,
+        ))
+    }
+
+    /// Save a checkpoint for the player to respawn at, once enough turns have passed since the
+    /// last one.
+    fn update_checkpoint(&mut self, force: bool) {
+        let is_due = self.state.turn >= self.state.last_checkpoint + CHECKPOINT_INTERVAL;
+        if self.state.game_mode != GameMode::Checkpoint || !(force || is_due) {
+            return;
+        }
+        self.state.last_checkpoint = self.state.turn;
+        match self.save_backend.save_game(&self.state, &self.objects) {
+            Ok(()) => self.state.log.add("Checkpoint reached.", MsgClass::Info),
+            Err(e) => error!("cannot save checkpoint: {}", e),
+        }
+    }
+
+    /// Create a new game by instantiating the game engine, game state and object vector. Daily
+    /// runs generate the world from the date given in `daily`.
+    pub fn new_game(raws_errors: &mut Vec<String>, seed: Option<u64>) -> (GameState, GameObjects) {
         // create game state holding game-relevant information
         let level = 1;
-        let mut state = GameState::new(level);
+        let mut state = match seed {
+            Some(seed) => GameState::with_seed(level, seed),
+            None => GameState::new(level),
+        };
+        state.difficulty = innit_env().difficulty.clone();
+        state.game_mode = innit_env().game_mode;
+        state.ecosystem = Ecosystem::new(from_dungeon_level(&load_ecosystem(raws_errors), level));
 
         // initialise game object vector
         let mut objects = GameObjects::new();
         objects.blank_world();
 
         // load spawn and object templates from raw files
-        let spawns = load_spawns();
-        let object_templates = load_object_templates();
+        let raws = SpawnRaws::load(raws_errors);
 
         // generate world terrain
         // let mut world_generator = RogueWorldGenerator::new();
-        let mut world_generator = OrganicsWorldGenerator::new();
-        world_generator.make_world(&mut state, &mut objects, &spawns, &object_templates, level);
-        // objects.set_tile_dna_random(&mut state.rng, &state.gene_library);
-        objects.set_tile_dna(
-            &mut state.rng,
-            vec![
-                "Cell Membrane".to_string(),
-                "Cell Membrane".to_string(),
-                "Cell Membrane".to_string(),
-                "Energy Store".to_string(),
-                "Energy Store".to_string(),
-                "Receptor".to_string(),
-            ],
-            &state.gene_library,
+        let prefabs = PrefabRegistry::load(raws_errors);
+        let terrain = load_terrain(raws_errors);
+        let bosses = load_boss_templates(raws_errors);
+        let mut world_generator = OrganicsWorldGenerator::new(prefabs, terrain, bosses);
+        world_generator.make_world(
+            &mut state,
+            &mut objects,
+            &raws.spawns,
+            &raws.templates,
+            level,
         );
+        // objects.set_tile_dna_random(&mut state.rng, &state.gene_library);
+        if let Some(tissue) = raws.templates.iter().find(|t| t.npc == TISSUE_TEMPLATE) {
+            objects.set_tile_dna(
+                state.rng_streams.get(RngStream::WorldGen),
+                tissue,
+                &state.gene_library,
+            );
+        } else {
+            error!("No object template found for '{}'", TISSUE_TEMPLATE);
+        }
 
         // create object representing the player
         let (new_x, new_y) = world_generator.get_player_start_pos();
@@ -168,12 +632,16 @@ impl Game {
             .living(true)
             .visualize("You", '@', (255, 255, 255))
             .physical(true, false, true)
+            .faction(Faction::Lineage)
             .control(Controller::Player(PlayerCtrl::new()))
             .genome(
                 0.99,
-                state
-                    .gene_library
-                    .new_genetics(&mut state.rng, DnaType::Nucleus, false, GENE_LEN),
+                state.gene_library.new_genetics(
+                    state.rng_streams.get(RngStream::WorldGen),
+                    DnaType::Nucleus,
+                    false,
+                    GENE_LEN,
+                ),
             );
 
         trace!("created player object {}", player);
@@ -186,6 +654,9 @@ impl Game {
             player.get_primary_action(Target::Center).to_text()
         );
 
+        state
+            .discovered_regions
+            .insert(objects.get_region(&player.pos));
         objects.set_player(player);
 
         // a warm welcoming message
@@ -194,47 +665,11 @@ impl Game {
             MsgClass::Story,
         );
 
+        state.spawn_raws = Some(raws);
         (state, objects)
     }
 }
 
-/// Load an existing savegame and instantiates GameState & Objects
-/// from which the game is resumed in the game loop.
-pub fn load_game() -> Result<(GameState, GameObjects), Box<dyn Error>> {
-    // TODO: Add proper UI error output if any of this fails!
-    if let Some(mut save_file) = dirs::data_local_dir() {
-        save_file.push("innit");
-        save_file.push("savegame");
-        let mut file = File::open(save_file)?;
-        let mut json_save_state = String::new();
-        file.read_to_string(&mut json_save_state)?;
-        let result = serde_json::from_str::<(GameState, GameObjects)>(&json_save_state)?;
-        Ok(result)
-    } else {
-        error!("CANNOT ACCESS SYSTEM DATA DIR");
-        panic!("CANNOT ACCESS SYSTEM DATA DIR");
-    }
-}
-
-/// Serialize and store GameState and Objects into a JSON file.
-pub fn save_game(state: &GameState, objects: &GameObjects) -> Result<(), Box<dyn Error>> {
-    if let Some(mut env_data) = dirs::data_local_dir() {
-        env_data.push("innit");
-        fs::create_dir_all(&env_data)?;
-        env_data.push("savegame");
-
-        let mut save_file = File::create(env_data)?;
-        let save_data = serde_json::to_string(&(state, objects))?;
-        save_file.write_all(save_data.as_bytes())?;
-        debug!("SAVED GAME TO FILE");
-        Ok(())
-    } else {
-        // TODO: Create dialog_s with error message!
-        error!("CANNOT CREATE SAVE FILE!");
-        Ok(())
-    }
-}
-
 impl Rltk_GameState for Game {
     /// Central function of the game.
     /// - process player input
@@ -242,6 +677,8 @@ impl Rltk_GameState for Game {
     /// - let NPCs take their turn
     fn tick(&mut self, ctx: &mut Rltk) {
         let mut timer = Timer::new("game loop");
+        self.clock.tick(ctx.frame_time_ms);
+        self.window.update(ctx);
         // mouse workaround
         if ctx.left_click {
             if self.mouse_workaround {
@@ -250,62 +687,85 @@ impl Rltk_GameState for Game {
             self.mouse_workaround = !self.mouse_workaround;
         }
 
-        // Render world and world only if there is any new information, otherwise save the
-        // computation.
-        if self.re_render || self.hud.require_refresh || self.state.log.is_changed {
-            // println!(
-            //     "{}, {}, {}",
-            //     self.re_render, self.hud.require_refresh, self.state.log.is_changed
-            // );
-            ctx.set_active_console(HUD_CON);
-            ctx.cls();
-
-            if self.re_render || self.hud.require_refresh {
-                ctx.set_active_console(WORLD_CON);
-                ctx.cls();
-                render_world(&mut self.objects, ctx);
-            }
+        self.check_achievements();
+        self.hud.update_toast(&self.clock);
+        self.key_repeat.check_release();
 
+        // Draw only the layers with new information on them, the others keep their content.
+        let mut render_timer = Timer::new("render");
+        if self.hud.require_refresh || self.state.log.is_changed {
+            self.dirty.mark(Layer::Hud);
+        }
+        if self.dirty.take(Layer::World) {
+            ctx.set_active_console(WORLD_CON);
+            ctx.cls();
+            render_world(&self.objects, &self.state, ctx);
+        }
+        if self.dirty.take(Layer::Hud) {
             ctx.set_active_console(HUD_CON);
+            ctx.cls();
             let player = self
                 .objects
                 .extract_by_index(self.state.player_idx)
                 .unwrap();
+            self.hud.update_threat(&player, &self.objects);
+            self.hud.environment = self.objects.get_environment(&player.pos);
+            self.hud.update_map(&self.objects, &player);
+            self.hud.paused = self.paused;
             render_gui(&self.state, &mut self.hud, ctx, &player);
             self.objects.replace(self.state.player_idx, player);
 
             // switch off any triggers
-            self.re_render = false;
             self.state.log.is_changed = false;
             self.hud.require_refresh = false
         }
 
+        // Particles, dialogs and menus share the overlay. It's drawn as long as any of them is
+        // shown and once more afterwards to clear it.
+        let has_overlay = !particles().particles.is_empty()
+            || self.show_telemetry
+            || self.run_state.as_ref().is_some_and(RunState::has_overlay);
+        if has_overlay || self.overlay_shown {
+            self.dirty.mark(Layer::Overlay);
+        }
+        self.overlay_shown = has_overlay;
+        if self.dirty.take(Layer::Overlay) {
+            ctx.set_active_console(PAR_CON);
+            ctx.cls();
+            let mut draw_batch = DrawBatch::new();
+            draw_batch.target(PAR_CON);
+            for particle in &particles().particles {
+                draw_batch.print_color(
+                    particle.render_pos(),
+                    particle.glyph,
+                    ColorPair::new(particle.col_fg, particle.col_bg),
+                );
+            }
+            if self.show_telemetry {
+                render_telemetry(&telemetry(), &mut draw_batch);
+            }
+            // TODO: Use constants for z_order!
+            draw_batch.submit(10000).unwrap();
+        }
+        self.dirty.record_frame(render_timer.stop_silent());
+
         // The particles need to be queried each cycle to activate and cull them in time.
         trace!("updating particles");
-        ctx.set_active_console(PAR_CON);
-        ctx.cls();
-        let mut draw_batch = DrawBatch::new();
-        for particle in &particles().particles {
-            draw_batch.print_color(
-                particle.pos.into(),
-                particle.glyph,
-                ColorPair::new(particle.col_fg, particle.col_bg),
-            );
+        if particles().update(&self.clock) {
+            self.dirty.mark(Layer::World);
+        }
+
+        // shake the world and everything floating above it
+        let (dx, dy) = particles().shake_offset();
+        for console in &[WORLD_CON, PAR_CON] {
+            ctx.set_active_console(*console);
+            ctx.set_offset(dx, dy);
         }
-        // TODO: Use constants for z_order!
-        draw_batch.submit(10000).unwrap();
-        self.re_render = particles().update(ctx);
 
         let mut new_run_state = self.run_state.take().unwrap();
         new_run_state = match new_run_state {
             RunState::MainMenu(ref mut instance) => {
-                self.state.log.is_changed = false;
-                self.hud.require_refresh = false;
-                self.re_render = false;
-                particles().particles.clear();
-                ctx.set_active_console(WORLD_CON);
-                ctx.cls();
-                ctx.render_xp_sprite(&self.rex_assets.menu, 0, 0);
+                self.show_menu_background(ctx);
                 match instance.display(ctx) {
                     Some(option) => {
                         MainMenuItem::process(&mut self.state, &mut self.objects, instance, &option)
@@ -313,17 +773,60 @@ impl Rltk_GameState for Game {
                     None => RunState::MainMenu(instance.clone()),
                 }
             }
+            RunState::DisplaySettings(ref mut instance) => {
+                self.show_menu_background(ctx);
+                let fg = palette().hud_fg_dna_sensor;
+                let bg = palette().hud_bg;
+                ctx.print_color_centered_at(SCREEN_WIDTH / 2, 1, fg, bg, "DISPLAY SETTINGS");
+                let fg = palette().hud_fg;
+                ctx.print_color_centered_at(
+                    SCREEN_WIDTH / 2,
+                    3,
+                    fg,
+                    bg,
+                    "Changes take effect after a restart",
+                );
+                match instance.display(ctx) {
+                    Some(option) => DisplayMenuItem::process(
+                        &mut self.state,
+                        &mut self.objects,
+                        instance,
+                        &option,
+                    ),
+                    None => RunState::DisplaySettings(instance.clone()),
+                }
+            }
+            RunState::ChooseDifficulty(ref mut instance) => {
+                self.show_menu_background(ctx);
+                let fg = palette().hud_fg_dna_sensor;
+                let bg = palette().hud_bg;
+                ctx.print_color_centered_at(SCREEN_WIDTH / 2, 1, fg, bg, "NEW GAME");
+                match instance.display(ctx) {
+                    Some(option) => DifficultyMenuItem::process(
+                        &mut self.state,
+                        &mut self.objects,
+                        instance,
+                        &option,
+                    ),
+                    None => RunState::ChooseDifficulty(instance.clone()),
+                }
+            }
             RunState::GameOver(ref mut instance) => {
-                self.state.log.is_changed = false;
-                self.hud.require_refresh = false;
-                self.re_render = false;
-                particles().particles.clear();
-                ctx.set_active_console(WORLD_CON);
-                ctx.cls();
-                ctx.render_xp_sprite(&self.rex_assets.menu, 0, 0);
+                self.show_menu_background(ctx);
                 let fg = palette().hud_fg_dna_sensor;
                 let bg = palette().hud_bg;
                 ctx.print_color_centered_at(SCREEN_WIDTH / 2, 1, fg, bg, "GAME OVER");
+                let fg = palette().hud_fg;
+                let mut summary = format!(
+                    "Survived {} turns at {} difficulty, {} bosses defeated",
+                    self.state.turn,
+                    self.state.difficulty.name,
+                    self.state.defeated_bosses.len()
+                );
+                if self.state.game_mode == GameMode::Checkpoint {
+                    summary.push_str(&format!(", {} respawns", self.state.respawns));
+                }
+                ctx.print_color_centered_at(SCREEN_WIDTH / 2, 3, fg, bg, &summary);
                 match instance.display(ctx) {
                     Some(option) => GameOverMenuItem::process(
                         &mut self.state,
@@ -342,133 +845,336 @@ impl Rltk_GameState for Game {
             },
             RunState::Ticking => {
                 trace!("enter RunState::Ticking {}", self.state.log.is_changed);
-                let mut feedback;
-                // Let the game engine process objects until we have to re-render the world or UI.
-                // Re-rendering is necessary either because the world changed or messages need to
-                // be printed to the log.
-                'processing: loop {
-                    feedback = self.state.process_object(&mut self.objects);
-                    if feedback != ObjectFeedback::NoFeedback || self.state.log.is_changed {
-                        break 'processing;
-                    }
+                // the world can be paused at any time, even in the middle of the NPCs' turns
+                if let (Some(VirtualKeyCode::P), false) = (ctx.key, ctx.control) {
+                    self.toggle_pause();
                 }
-
-                trace!("process feedback in RunState::Ticking: {:#?}", feedback);
-                match feedback {
-                    ObjectFeedback::GameOver => RunState::GameOver(game_over_menu()),
-                    ObjectFeedback::Render => {
-                        self.re_render = true;
-                        RunState::Ticking
+                if self.advance_to.is_some_and(|turn| self.state.turn >= turn) {
+                    self.advance_to = None;
+                }
+                if let Some(milestone) = self.pending_milestones.pop_front() {
+                    RunState::Story(StoryDialog::new(milestone))
+                } else if let Some(encounter) = self.pending_encounters.pop_front() {
+                    RunState::InfoBox(encounter)
+                } else if self.paused && self.advance_to.is_none() {
+                    RunState::CheckInput
+                } else if self.animation_delay > 0.0 {
+                    self.animation_delay -= self.clock.delta();
+                    RunState::Ticking
+                } else {
+                    self.process_objects()
+                }
+            }
+            RunState::CheckInput => {
+                self.update_checkpoint(false);
+                let step = if self.paused {
+                    None
+                } else {
+                    self.next_travel_step(ctx)
+                };
+                if let Some(step) = step {
+                    if let Some(ref mut player) = self.objects[self.state.player_idx] {
+                        player.set_next_action(Some(step));
                     }
-                    ObjectFeedback::GenomeManipulator => {
-                        if let Some(genome_editor) =
-                            create_genome_manipulator(&mut self.state, &mut self.objects)
-                        {
-                            RunState::GenomeEditing(genome_editor)
-                        } else {
+                    RunState::Ticking
+                } else {
+                    let input = read_input(
+                        &mut self.state,
+                        &mut self.objects,
+                        &mut self.hud,
+                        &mut self.key_repeat,
+                        ctx,
+                    );
+                    self.input_recorder.record(self.state.turn, &input);
+                    match input {
+                        PlayerInput::MetaInput(UiAction::ToggleInputRecording) => {
+                            let msg = if self.input_recorder.toggle() {
+                                "Input recording on"
+                            } else {
+                                "Input recording off"
+                            };
+                            self.state.log.add(msg, MsgClass::Info);
                             RunState::CheckInput
                         }
-                    }
-                    ObjectFeedback::UpdateHud => {
-                        self.hud.require_refresh = true;
-                        RunState::Ticking
-                    }
-                    // if there is no reason to re-render, check whether we're waiting on user input
-                    _ => {
-                        if self.state.is_players_turn()
-                            && self.state.player_energy_full(&self.objects)
-                        {
+                        PlayerInput::MetaInput(UiAction::ToggleTelemetry) => {
+                            if innit_env().debug_mode {
+                                self.show_telemetry = !self.show_telemetry;
+                            }
                             RunState::CheckInput
-                        } else {
-                            self.re_render = false;
-                            RunState::Ticking
                         }
-                    }
-                }
-            }
-            RunState::CheckInput => {
-                match read_input(&mut self.state, &mut self.objects, &mut self.hud, ctx) {
-                    PlayerInput::MetaInput(meta_action) => {
-                        trace!("process meta action: {:#?}", meta_action);
-                        handle_meta_actions(&mut self.state, &mut self.objects, ctx, meta_action)
-                    }
-                    PlayerInput::PlayInput(in_game_action) => {
-                        trace!("inject in-game action {:#?} to player", in_game_action);
-                        if let Some(ref mut player) = self.objects[self.state.player_idx] {
-                            use crate::ui::game_input::PlayerAction::*;
-                            let a: Option<Box<dyn Action>> = match in_game_action {
-                                PrimaryAction(dir) => Some(player.get_primary_action(dir)),
-                                SecondaryAction(dir) => Some(player.get_secondary_action(dir)),
-                                Quick1Action => Some(player.get_quick1_action()),
-                                Quick2Action => Some(player.get_quick2_action()),
-                                UseInventoryItem(idx) => {
-                                    trace!("PlayInput USE_ITEM");
-                                    let inventory_object = &player.inventory.items.remove(idx);
-                                    player.inventory.inv_actions.retain(|a| {
-                                        a.get_identifier() != "drop item"
-                                            || a.get_level() == idx as i32
-                                    });
-                                    if let Some(item) = &inventory_object.item {
-                                        item.use_action.clone()
-                                    } else {
-                                        None
-                                    }
-                                }
-                                DropItem(idx) => {
-                                    trace!("PlayInput DROP_ITEM");
-                                    if player.inventory.items.len() > idx {
-                                        Some(Box::new(ActDropItem::new(idx as i32)))
-                                    } else {
-                                        None
-                                    }
+                        PlayerInput::MetaInput(UiAction::ToggleAiTraces) => {
+                            if innit_env().debug_mode {
+                                let show_ai_traces = !innit_env().show_ai_traces;
+                                innit_env().show_ai_traces = show_ai_traces;
+                                self.dirty.mark_all();
+                            }
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::CycleHeatMap) => {
+                            if innit_env().debug_mode {
+                                let heat_map = innit_env().heat_map.next();
+                                innit_env().heat_map = heat_map;
+                                self.state
+                                    .log
+                                    .add(format!("Heat map: {}", heat_map.name()), MsgClass::Info);
+                                self.dirty.mark_all();
+                            }
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::BugReport) => {
+                            match create_bug_report(
+                                &self.state,
+                                &self.objects,
+                                &self.input_recorder,
+                            ) {
+                                Ok(name) => self.state.log.add(
+                                    format!(
+                                        "Seed, recent inputs, log and save written to {}",
+                                        name
+                                    ),
+                                    MsgClass::Info,
+                                ),
+                                Err(e) => {
+                                    error!("cannot create bug report: {}", e);
+                                    self.state.log.add("Bug report failed!", MsgClass::Alert);
                                 }
-                                PassTurn => Some(Box::new(ActPass::default())),
+                            }
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::SelectTab(tab)) => {
+                            self.hud.select_tab(tab);
+                            self.dirty.mark_all();
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::CycleTab(step)) => {
+                            self.hud
+                                .select_tab(self.hud.settings.sidebar_tab.cycle(step));
+                            self.dirty.mark_all();
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::ScrollPanel(step)) => {
+                            self.hud.scroll_panel(step);
+                            self.dirty.mark_all();
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::ToggleFactionBadges) => {
+                            let use_faction_badges = !innit_env().use_faction_badges;
+                            innit_env().set_faction_badges(use_faction_badges);
+                            let status = if use_faction_badges {
+                                "on: hostile organisms are inverted, allies shaded"
+                            } else {
+                                "off"
                             };
-                            player.set_next_action(a);
-                            RunState::Ticking
-                        } else {
+                            self.state
+                                .log
+                                .add(format!("Faction badges turned {}", status), MsgClass::Info);
+                            self.dirty.mark_all();
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::ToggleVisualEffects) => {
+                            let status = if self.hud.toggle_effects() {
+                                "on"
+                            } else {
+                                "off"
+                            };
+                            self.state
+                                .log
+                                .add(format!("Visual effects turned {}", status), MsgClass::Info);
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::ToggleActions) => {
+                            self.hud.toggle_actions();
+                            self.dirty.mark_all();
+                            RunState::CheckInput
+                        }
+                        PlayerInput::MetaInput(UiAction::TogglePause) => {
+                            self.toggle_pause();
                             RunState::Ticking
                         }
+                        PlayerInput::MetaInput(UiAction::AdvanceTurn) => self.advance_turn(),
+                        PlayerInput::MetaInput(meta_action) => {
+                            trace!("process meta action: {:#?}", meta_action);
+                            handle_meta_actions(
+                                &mut self.state,
+                                &mut self.objects,
+                                self.save_backend.as_mut(),
+                                ctx,
+                                meta_action,
+                            )
+                        }
+                        PlayerInput::PlayInput(_) if self.paused => {
+                            self.state.log.add(
+                                "The game is paused. Press P to resume or . to advance a turn.",
+                                MsgClass::Info,
+                            );
+                            RunState::CheckInput
+                        }
+                        PlayerInput::PlayInput(in_game_action) => {
+                            play_input(&mut self.state, &mut self.objects, in_game_action)
+                        }
+                        PlayerInput::Undefined => RunState::CheckInput,
                     }
-                    PlayerInput::Undefined => RunState::CheckInput,
                 }
             }
             RunState::GenomeEditing(genome_editor) => match genome_editor.state {
                 GenomeEditingState::Done => {
                     if let Some(ref mut player) = self.objects[self.state.player_idx] {
-                        player.set_dna(genome_editor.player_dna);
+                        player.mutations.record(
+                            self.state.turn,
+                            &player.dna,
+                            &genome_editor.player_dna,
+                            &self.state.gene_library,
+                        );
+                        let dna = genome_editor.player_dna;
+                        if dna.raw.is_empty() {
+                            player.set_dna(dna);
+                        } else {
+                            // express the edited genome, including any silenced genes
+                            let (s, p, a, d) = self.state.gene_library.dna_to_marked_traits(
+                                dna.dna_type,
+                                &dna.raw,
+                                &dna.silenced,
+                            );
+                            player.change_genome(s, p, a, d);
+                        }
+                        self.state.events.publish(GameEvent::GenomeChanged {
+                            genes: player.dna.simplified.len(),
+                        });
                     }
-                    self.re_render = true;
+                    self.dirty.mark_all();
                     RunState::CheckInput
                 }
 
                 _ => genome_editor.display(&mut self.state, ctx),
             },
+            RunState::Spectating(mut spectator) => {
+                if let Some(VirtualKeyCode::Escape) = ctx.key {
+                    RunState::GameOver(game_over_menu(false, self.can_respawn))
+                } else {
+                    if let Some(key) = ctx.key {
+                        if spectator.move_camera(key, ctx.shift) {
+                            self.dirty.mark_all();
+                        }
+                    }
+                    if self.state.visibility.camera() != Some(spectator.camera) {
+                        let camera = (spectator.camera, SPECTATOR_RANGE);
+                        self.state.visibility.set_camera(Some(camera));
+                        self.state.visibility.update(&mut self.objects);
+                        self.dirty.mark_all();
+                    }
+                    if spectator.advance(&self.clock) {
+                        self.state.simulate_turn(&mut self.objects);
+                        self.dirty.mark_all();
+                    }
+                    if spectator.turns_left == 0 {
+                        RunState::GameOver(game_over_menu(false, self.can_respawn))
+                    } else {
+                        RunState::Spectating(spectator)
+                    }
+                }
+            }
+            RunState::Sandbox(mut sandbox) => {
+                if let Some(VirtualKeyCode::Escape) = ctx.key {
+                    self.dirty.mark_all();
+                    RunState::Ticking
+                } else {
+                    match sandbox.update(&mut self.state, &mut self.objects, ctx) {
+                        ObjectFeedback::GameOver => RunState::GameOver(game_over_menu(
+                            innit_env().spectator_turns > 0,
+                            false,
+                        )),
+                        ObjectFeedback::Render | ObjectFeedback::UpdateHud => {
+                            self.dirty.mark_all();
+                            RunState::Sandbox(sandbox)
+                        }
+                        _ => RunState::Sandbox(sandbox),
+                    }
+                }
+            }
+            RunState::RenameDialog(dialog) => dialog.display(&mut self.objects, ctx),
+            RunState::RunCodeDialog(dialog) => dialog.display(ctx),
+            RunState::Trading(dialog) => {
+                dialog.display(&mut self.objects, self.state.player_idx, ctx)
+            }
+            RunState::Organelles(dialog) => {
+                dialog.display(&mut self.objects, self.state.player_idx, ctx)
+            }
+            RunState::Encyclopedia(dialog) => dialog.display(ctx),
+            RunState::Story(dialog) => {
+                let run_state = dialog.display(&mut self.state, &mut self.objects, ctx);
+                self.dirty.mark_all();
+                run_state
+            }
             RunState::InfoBox(infobox) => match infobox.display(ctx) {
                 Some(infobox) => RunState::InfoBox(infobox),
                 None => RunState::Ticking,
             },
+            RunState::ErrorDialog(infobox) => match infobox.display(ctx) {
+                Some(infobox) => RunState::ErrorDialog(infobox),
+                None => RunState::MainMenu(main_menu()),
+            },
             RunState::ToggleDarkLightMode => {
                 self.is_dark_color_palette = !self.is_dark_color_palette;
-                self.re_render = true;
+                self.dirty.mark_all();
                 RunState::Ticking
             }
-            RunState::NewGame => {
-                // start new game
-                let (new_state, new_objects) = Game::new_game();
+            RunState::NewGame | RunState::NewDailyRun | RunState::NewSharedRun(_) => {
+                // start new game, daily runs need the settings locked before generating the world
+                let (seed, daily, shared) = match &new_run_state {
+                    RunState::NewDailyRun => {
+                        let date = today();
+                        (Some(daily_seed(&date)), Some(date), None)
+                    }
+                    RunState::NewSharedRun(code) => (Some(code.seed), None, Some(*code)),
+                    _ => (None, None, None),
+                };
+                self.lock_settings(daily.is_some());
+                let mut raws_errors = Vec::new();
+                let (mut new_state, new_objects) = Game::new_game(&mut raws_errors, seed);
+                new_state.daily = daily;
                 self.reset(new_state, new_objects);
-                self.re_render = true;
-                RunState::Ticking
+                self.update_checkpoint(true);
+                self.dirty.mark_all();
+                if let Some(date) = &self.state.daily {
+                    self.state.log.add(
+                        format!("Daily run of {}, good luck!", date),
+                        MsgClass::Story,
+                    );
+                }
+                if let Some(code) = shared {
+                    self.state
+                        .log
+                        .add(format!("Shared run {}", code.encode()), MsgClass::Story);
+                    if !code.matches_settings() {
+                        self.state.log.add(
+                            "This run was shared with different settings, the game may play out differently",
+                            MsgClass::Alert,
+                        );
+                    }
+                }
+                // only report problems with raws that changed since the last report
+                raws_errors.retain(|e| !self.raws_errors.contains(e));
+                if raws_errors.is_empty() {
+                    RunState::Ticking
+                } else {
+                    let infobox = raws_errors_screen(&raws_errors);
+                    self.raws_errors.extend(raws_errors);
+                    RunState::InfoBox(infobox)
+                }
             }
             RunState::LoadGame => {
                 // load game from file
-                match load_game() {
+                match self.save_backend.load_game() {
                     Ok((state, objects)) => {
                         self.reset(state, objects);
-                        self.re_render = true;
+                        self.dirty.mark_all();
                         RunState::Ticking
                     }
-                    Err(_e) => RunState::MainMenu(main_menu()),
+                    Err(e) => {
+                        error!("cannot load game: {}", e);
+                        let error = GameError::LoadFailed(e.to_string());
+                        RunState::ErrorDialog(error_screen(&error))
+                    }
                 }
             }
         };
@@ -482,6 +1188,8 @@ impl Rltk_GameState for Game {
             (0, 0, 0),
             &format!("FPS: {}", ctx.fps),
         );
+        // menus and dialogs are drawn onto the overlay
+        ctx.set_active_console(PAR_CON);
 
         // keep time and emit warning if a tick takes longer than half a second
         let tick_elapsed = timer.stop_silent();
@@ -494,17 +1202,71 @@ impl Rltk_GameState for Game {
     }
 }
 
+/// Hand the action chosen by the player to their organism.
+pub fn play_input(
+    state: &mut GameState,
+    objects: &mut GameObjects,
+    in_game_action: PlayerAction,
+) -> RunState {
+    trace!("inject in-game action {:#?} to player", in_game_action);
+    if let Some(ref mut player) = objects[state.player_idx] {
+        use crate::ui::game_input::PlayerAction::*;
+        let a: Option<Box<dyn Action>> = match in_game_action {
+            PrimaryAction(dir) => Some(player.get_primary_action(dir)),
+            SecondaryAction(dir) => Some(player.get_secondary_action(dir)),
+            HotbarAction(slot) => Some(player.get_hotbar_action(slot)),
+            UseInventoryItem(idx) => {
+                trace!("PlayInput USE_ITEM");
+                let inventory_object = &player.inventory.items.remove(idx);
+                player
+                    .inventory
+                    .inv_actions
+                    .retain(|a| a.get_identifier() != "drop item" || a.get_level() == idx as i32);
+                // using an item reveals what it's made of
+                if identify(state, inventory_object) {
+                    state.log.add(
+                        format!("It was a {}.", inventory_object.visual.name),
+                        MsgClass::Info,
+                    );
+                }
+                if let Some(item) = &inventory_object.item {
+                    item.use_action.clone()
+                } else {
+                    None
+                }
+            }
+            DropItem(idx) => {
+                trace!("PlayInput DROP_ITEM");
+                if player.inventory.items.len() > idx {
+                    Some(Box::new(ActDropItem::new(idx as i32)))
+                } else {
+                    None
+                }
+            }
+            PassTurn => Some(Box::new(ActPass::default())),
+        };
+        player.set_next_action(a);
+        RunState::Ticking
+    } else {
+        RunState::Ticking
+    }
+}
+
 pub fn handle_meta_actions(
     state: &mut GameState,
     objects: &mut GameObjects,
-    _ctx: &mut Rltk,
+    save_backend: &mut dyn SaveBackend,
+    ctx: &mut Rltk,
     action: UiAction,
 ) -> RunState {
     debug!("received action {:?}", action);
     match action {
         UiAction::ExitGameLoop => {
-            let result = save_game(&state, &objects);
-            result.unwrap();
+            if let Err(e) = save_backend.save_game(state, objects) {
+                error!("cannot save game: {}", e);
+                let error = GameError::SaveFailed(e.to_string());
+                return RunState::ErrorDialog(error_screen(&error));
+            }
             RunState::MainMenu(main_menu())
         }
         UiAction::ToggleDarkLightMode => {
@@ -512,7 +1274,151 @@ pub fn handle_meta_actions(
             // RunState::Ticking(true)
             RunState::ToggleDarkLightMode
         }
-        UiAction::CharacterScreen => RunState::InfoBox(character_screen(state, objects)),
+        UiAction::ToggleAmbience => {
+            let use_ambience = !innit_env().use_ambience;
+            innit_env().set_ambience(use_ambience);
+            let status = if use_ambience { "on" } else { "off" };
+            state.log.add(
+                format!("Ambient animation turned {}", status),
+                MsgClass::Info,
+            );
+            RunState::CheckInput
+        }
+        UiAction::CycleCombatDetail => {
+            let combat_detail = innit_env().combat_detail.next();
+            innit_env().set_combat_detail(combat_detail);
+            state.log.add(
+                format!("Combat log detail set to {}", combat_detail.name()),
+                MsgClass::Info,
+            );
+            RunState::CheckInput
+        }
+        UiAction::CycleGameSpeed => {
+            let game_speed = innit_env().game_speed.next();
+            innit_env().set_game_speed(game_speed);
+            state.log.add(
+                format!("Game speed set to {}", game_speed.name()),
+                MsgClass::Info,
+            );
+            RunState::CheckInput
+        }
+        UiAction::ToggleMoveMode => {
+            if let Some(Some(Controller::Player(ctrl))) = objects[state.player_idx]
+                .as_mut()
+                .map(|p| p.control.as_mut())
+            {
+                let mode = ctrl.toggle_move_mode();
+                state
+                    .log
+                    .add(format!("Movement mode: {:?}", mode), MsgClass::Info);
+            }
+            RunState::CheckInput
+        }
+        UiAction::CharacterScreen => match character_screen(state, objects) {
+            Ok(infobox) => RunState::InfoBox(infobox),
+            Err(e) => RunState::ErrorDialog(error_screen(&e)),
+        },
+        UiAction::MutationHistory => match mutation_screen(state, objects) {
+            Ok(infobox) => RunState::InfoBox(infobox),
+            Err(e) => RunState::ErrorDialog(error_screen(&e)),
+        },
+        UiAction::RenameOrganism => {
+            let mouse = Position::from(ctx.mouse_point());
+            match objects.organism_index_at(&mouse) {
+                Some(idx) => {
+                    let name = objects[idx]
+                        .as_ref()
+                        .map_or(String::new(), |o| o.visual.name.clone());
+                    RunState::RenameDialog(RenameDialog::new(idx, &name))
+                }
+                None => {
+                    state
+                        .log
+                        .add("Point at an organism to rename it.", MsgClass::Info);
+                    RunState::CheckInput
+                }
+            }
+        }
+        UiAction::Trade => {
+            let mouse = Position::from(ctx.mouse_point());
+            match (
+                objects.organism_index_at(&mouse),
+                objects[state.player_idx].as_ref(),
+            ) {
+                (Some(idx), Some(player)) if idx != state.player_idx => {
+                    let partner = objects[idx].as_ref().unwrap();
+                    if !player.pos.is_neighbour(&partner.pos) {
+                        state
+                            .log
+                            .add("Move next to an organism to trade with it.", MsgClass::Info);
+                        RunState::CheckInput
+                    } else if !is_willing(state, partner, player) {
+                        state.log.add(
+                            format!("{} won't trade with you.", partner.visual.name),
+                            MsgClass::Info,
+                        );
+                        RunState::CheckInput
+                    } else {
+                        let offers = offers(state, partner, player);
+                        if offers.is_empty() {
+                            state.log.add(
+                                format!("{} has nothing to offer.", partner.visual.name),
+                                MsgClass::Info,
+                            );
+                            RunState::CheckInput
+                        } else {
+                            RunState::Trading(TradeDialog::new(
+                                &partner.visual.name,
+                                Target::from_pos(&player.pos, &partner.pos),
+                                offers,
+                                player.processors.energy,
+                            ))
+                        }
+                    }
+                }
+                _ => {
+                    state
+                        .log
+                        .add("Point at an organism to trade with it.", MsgClass::Info);
+                    RunState::CheckInput
+                }
+            }
+        }
+        UiAction::Organelles => match objects[state.player_idx].as_ref() {
+            Some(player) => RunState::Organelles(OrganelleDialog::new(&player.organelles)),
+            None => RunState::CheckInput,
+        },
+        UiAction::Encyclopedia => {
+            let templates = load_object_templates(&mut Vec::new());
+            RunState::Encyclopedia(EncyclopediaDialog::new(entries(
+                &state.discoveries,
+                &state.gene_library,
+                &templates,
+            )))
+        }
+        UiAction::ToggleBookmark => {
+            let mouse = Position::from(ctx.mouse_point());
+            if let Some(organism) = objects
+                .organism_index_at(&mouse)
+                .and_then(|idx| objects[idx].as_mut())
+            {
+                let msg = if state.bookmarks.toggle(organism) {
+                    format!("Bookmarked {}.", organism.visual.name)
+                } else {
+                    format!("Removed the bookmark of {}.", organism.visual.name)
+                };
+                state.log.add(msg, MsgClass::Info);
+            } else {
+                state
+                    .log
+                    .add("Point at an organism to bookmark it.", MsgClass::Info);
+            }
+            RunState::CheckInput
+        }
+        UiAction::Bookmarks => {
+            state.bookmarks.update(objects);
+            RunState::InfoBox(bookmarks_screen(state))
+        }
         UiAction::ChoosePrimaryAction => {
             if let Some(ref mut player) = objects[state.player_idx] {
                 let action_items = get_available_actions(
@@ -525,7 +1431,7 @@ pub fn handle_meta_actions(
                 );
                 if !action_items.is_empty() {
                     RunState::ChooseActionMenu(choose_action_menu(
-                        action_items,
+                        &action_items,
                         ActionCategory::Primary,
                     ))
                 } else {
@@ -551,7 +1457,7 @@ pub fn handle_meta_actions(
                 );
                 if !action_items.is_empty() {
                     RunState::ChooseActionMenu(choose_action_menu(
-                        action_items,
+                        &action_items,
                         ActionCategory::Secondary,
                     ))
                 } else {
@@ -565,32 +1471,13 @@ pub fn handle_meta_actions(
                 RunState::Ticking
             }
         }
-        UiAction::ChooseQuick1Action => {
+        UiAction::ChooseHotbarAction(slot) => {
             if let Some(ref mut player) = objects[state.player_idx] {
                 let action_items = get_available_actions(player, &[TargetCategory::None]);
                 if !action_items.is_empty() {
                     RunState::ChooseActionMenu(choose_action_menu(
-                        action_items,
-                        ActionCategory::Quick1,
-                    ))
-                } else {
-                    state.log.add(
-                        "You have no actions available! Try modifying your genome.",
-                        MsgClass::Alert,
-                    );
-                    RunState::Ticking
-                }
-            } else {
-                RunState::Ticking
-            }
-        }
-        UiAction::ChooseQuick2Action => {
-            if let Some(ref mut player) = objects[state.player_idx] {
-                let action_items = get_available_actions(player, &[TargetCategory::None]);
-                if !action_items.is_empty() {
-                    RunState::ChooseActionMenu(choose_action_menu(
-                        action_items,
-                        ActionCategory::Quick2,
+                        &action_items,
+                        ActionCategory::Hotbar(slot),
                     ))
                 } else {
                     state.log.add(
@@ -611,17 +1498,108 @@ pub fn handle_meta_actions(
             }
         }
         UiAction::Help => RunState::InfoBox(controls_screen()),
+        UiAction::ShowRunCode => RunState::InfoBox(InfoBox::new(
+            "Run Code".to_string(),
+            vec![
+                RunCode::new(state.seed).encode(),
+                "".to_string(),
+                "Share this code to let others start the same run.".to_string(),
+            ],
+        )),
+        UiAction::Snapshot => {
+            match export_snapshot(state, objects) {
+                Ok(name) => {
+                    match platform::data_file_path(&format!("{}.png", name)) {
+                        Ok(path) => ctx.screenshot(path.display()),
+                        Err(e) => warn!("cannot take screenshot: {}", e),
+                    }
+                    state
+                        .log
+                        .add(format!("Snapshot saved as {}", name), MsgClass::Info);
+                }
+                Err(e) => {
+                    error!("cannot export snapshot: {}", e);
+                    state.log.add("Snapshot failed!", MsgClass::Alert);
+                }
+            }
+            RunState::CheckInput
+        }
+        UiAction::ExportTelemetry => {
+            if innit_env().debug_mode {
+                match telemetry().export(state.turn) {
+                    Ok(name) => state
+                        .log
+                        .add(format!("Telemetry saved as {}", name), MsgClass::Info),
+                    Err(e) => {
+                        error!("cannot export telemetry: {}", e);
+                        state.log.add("Telemetry export failed!", MsgClass::Alert);
+                    }
+                }
+            }
+            RunState::CheckInput
+        }
+        // pausing, input recording, bug reports, telemetry, badges and the sidebar are up to the
+        // game loop
+        UiAction::TogglePause
+        | UiAction::AdvanceTurn
+        | UiAction::ToggleInputRecording
+        | UiAction::ToggleTelemetry
+        | UiAction::ToggleAiTraces
+        | UiAction::CycleHeatMap
+        | UiAction::BugReport
+        | UiAction::SelectTab(_)
+        | UiAction::CycleTab(_)
+        | UiAction::ScrollPanel(_)
+        | UiAction::ToggleFactionBadges
+        | UiAction::ToggleActions
+        | UiAction::ToggleVisualEffects => RunState::CheckInput,
+        UiAction::Sandbox => {
+            if innit_env().debug_mode {
+                let mut raws_errors = Vec::new();
+                let object_templates = load_object_templates(&mut raws_errors);
+                for e in raws_errors {
+                    error!("{}", e);
+                }
+                RunState::Sandbox(Sandbox::new(object_templates))
+            } else {
+                RunState::CheckInput
+            }
+        }
+        UiAction::TravelTo(pos) => {
+            let path = objects[state.player_idx]
+                .as_ref()
+                .and_then(|player| objects.find_path(&player.pos, &pos));
+            match (path, &mut objects[state.player_idx]) {
+                (Some(path), Some(player)) if !path.is_empty() => {
+                    if let Some(Controller::Player(ctrl)) = &mut player.control {
+                        ctrl.travel = Some(Travel::new(path));
+                    }
+                    RunState::CheckInput
+                }
+                _ => {
+                    state.log.add("You don't know a way there.", MsgClass::Info);
+                    RunState::CheckInput
+                }
+            }
+        }
+        UiAction::ShowLogOrigin(pos) => {
+            // mark the position of the logged event with a short-lived marker
+            let fg = palette().hud_fg_highlight;
+            let bg = palette().world_bg_ground_fov_true;
+            register_particle(pos, fg, bg, 'X', 1000.0);
+            RunState::CheckInput
+        }
     }
 }
 
-fn get_available_actions(obj: &mut Object, targets: &[TargetCategory]) -> Vec<String> {
+fn get_available_actions(obj: &mut Object, targets: &[TargetCategory]) -> Vec<Box<dyn Action>> {
     obj.actuators
         .actions
         .iter()
         .chain(obj.processors.actions.iter())
         .chain(obj.sensors.actions.iter())
         .filter(|a| targets.contains(&a.get_target_category()))
-        .map(|a| a.get_identifier())
+        .cloned()
         .collect()
 }
 
@@ -2,6 +2,7 @@
 extern crate log;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(not(target_arch = "wasm32"))]
 extern crate pretty_env_logger;
 extern crate rand;
 extern crate rand_core;
@@ -10,6 +11,7 @@ extern crate rltk;
 extern crate serde;
 extern crate serde_json;
 
+mod assets;
 mod core;
 mod entity;
 mod game;
@@ -20,10 +22,17 @@ mod util;
 
 use crate::game::{SCREEN_HEIGHT, SCREEN_WIDTH};
 use crate::raws::object_template::ObjectTemplate;
+use crate::ui::settings::{DisplaySettings, UiSettings};
+use crate::ui::window;
 // use crate::raws::object_template::ObjectTemplate;
 // use crate::raws::spawn::Spawn;
-use crate::{core::game_env::GameEnv, game::Game};
-use std::env;
+use crate::{
+    core::{
+        game_env::{GameEnv, GameSpeed},
+        innit_env,
+    },
+    game::Game,
+};
 
 // For game testing run with `RUST_LOG=innit=trace RUST_BACKTRACE=1 cargo run`.
 // Check [https://nnethercote.github.io/perf-book/title-page.html] for optimisation strategies.
@@ -41,21 +50,13 @@ pub fn main() -> rltk::BError {
     "#
     );
 
-    // init logger
-    pretty_env_logger::init();
     let mut env: GameEnv = GameEnv::new();
-
-    // parse program arguments
-    let args: Vec<String> = env::args().collect();
-    println!("args: {:?}", args);
-    for arg in args {
-        if arg.eq("-d") || arg.eq("--debug") {
-            env.set_debug_mode(true);
-        }
-        if arg.eq("-s") || arg.eq("--seeding") {
-            env.set_rng_seeding(true);
-        }
+    configure_native(&mut env);
+    // visual effects stay off if the player switched them off, `--no-effects` turns them off anyway
+    if !UiSettings::load().effects {
+        env.set_visual_effects(false);
     }
+    *innit_env() = env;
 
     // let spawn_str: String = serde_json::to_string(&Spawn::example()).unwrap();
     // println!("{}", spawn_str);
@@ -65,20 +66,119 @@ pub fn main() -> rltk::BError {
 
     // build engine and launch the game
     use rltk::RltkBuilder;
-    // let font = "fonts/rex_paint_10x10.png";
-    let font = "fonts/rex_paint_8x8.png";
-    let mut context = RltkBuilder::simple(SCREEN_WIDTH, SCREEN_HEIGHT)
-        .unwrap()
+    // the font is given by its full path, either of a user provided file or the embedded one
+    let display = DisplaySettings::load();
+    let font = display
+        .font
+        .asset()
+        .resolve()
+        .expect("the fonts are embedded into the game")
+        .path();
+    let glyph_size = display.font.glyph_size();
+    let tile_size = display.tile_size();
+    let builder = RltkBuilder::new()
+        .with_dimensions(SCREEN_WIDTH, SCREEN_HEIGHT)
+        .with_tile_dimensions(tile_size, tile_size)
         .with_advanced_input(true)
-        .with_font(font, 8, 8)
-        .with_sparse_console(SCREEN_WIDTH, SCREEN_HEIGHT, font) // hud layer
-        .with_sparse_console(SCREEN_WIDTH, SCREEN_HEIGHT, font) // particles
+        .with_resource_path("")
+        .with_font(&font, glyph_size, glyph_size)
+        .with_simple_console(SCREEN_WIDTH, SCREEN_HEIGHT, &font) // world layer
+        .with_sparse_console(SCREEN_WIDTH, SCREEN_HEIGHT, &font) // hud layer
+        .with_sparse_console(SCREEN_WIDTH, SCREEN_HEIGHT, &font) // particles
         .with_title("Innit alpha v0.0.4")
         .with_vsync(false)
         .with_fps_cap(60.0)
-        .with_automatic_console_resize(false)
-        .build()?;
+        .with_automatic_console_resize(false);
+    let context = window::configure(builder, &display).build()?;
+    window::restore(&display);
 
-    context.set_active_font(1, false);
     rltk::main_loop(context, Game::new())
 }
+
+/// Set up logging and read the environment and program arguments. None of this is available in the
+/// browser, where the game always runs with the default environment.
+#[cfg(not(target_arch = "wasm32"))]
+fn configure_native(env: &mut GameEnv) {
+    use std::env;
+
+    // init logger
+    pretty_env_logger::init();
+
+    // data and mod directories can also be given via environment variables, the arguments take
+    // precedence
+    if let Ok(data_dir) = env::var("INNIT_DATA_DIR") {
+        env.set_data_dir(data_dir.into());
+    }
+    if let Ok(mod_dir) = env::var("INNIT_MOD_DIR") {
+        env.set_mod_dir(mod_dir.into());
+    }
+
+    // parse program arguments
+    let args: Vec<String> = env::args().collect();
+    println!("args: {:?}", args);
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg.eq("-d") || arg.eq("--debug") {
+            env.set_debug_mode(true);
+        }
+        if arg.eq("-s") || arg.eq("--seeding") {
+            env.set_rng_seeding(true);
+        }
+        if arg.eq("-n") || arg.eq("--no-effects") {
+            env.set_visual_effects(false);
+        }
+        if arg.eq("--no-ambience") {
+            env.set_ambience(false);
+        }
+        if arg.eq("--faction-badges") {
+            env.set_faction_badges(true);
+        }
+        if arg.eq("--no-encounters") {
+            env.set_encounter_popups(false);
+        }
+        if arg.eq("-l") || arg.eq("--log-messages") {
+            env.set_log_messages(true);
+        }
+        if arg.eq("--data-dir") {
+            match args.next() {
+                Some(data_dir) => env.set_data_dir(data_dir.into()),
+                None => error!("--data-dir requires a path"),
+            }
+        }
+        if arg.eq("--mod-dir") {
+            match args.next() {
+                Some(mod_dir) => env.set_mod_dir(mod_dir.into()),
+                None => error!("--mod-dir requires a path"),
+            }
+        }
+        if arg.eq("--max-dna") {
+            match args.next().map(|len| len.parse::<usize>()) {
+                Some(Ok(len)) => env.set_max_dna_len(len),
+                _ => error!("--max-dna requires a length in bytes"),
+            }
+        }
+        if arg.eq("--spectate") {
+            match args.next().map(|turns| turns.parse::<u32>()) {
+                Some(Ok(turns)) => env.set_spectator_turns(turns),
+                _ => error!("--spectate requires a number of turns"),
+            }
+        }
+        if arg.eq("--speed") {
+            match args.next().and_then(|speed| GameSpeed::from_name(&speed)) {
+                Some(speed) => env.set_game_speed(speed),
+                None => error!("--speed requires one of instant, fast or animated"),
+            }
+        }
+        if arg.eq("--key-repeat") {
+            let delay = args.next().map(|delay| delay.parse::<u32>());
+            let rate = args.next().map(|rate| rate.parse::<u32>());
+            match (delay, rate) {
+                (Some(Ok(delay)), Some(Ok(rate))) => env.set_key_repeat(delay, rate),
+                _ => error!("--key-repeat requires a delay and a rate in milliseconds"),
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn configure_native(_env: &mut GameEnv) {}
@@ -1,37 +1,219 @@
+pub(crate) mod boss_template;
+pub(crate) mod difficulty;
+pub(crate) mod ecosystem;
 pub(crate) mod object_template;
+pub(crate) mod prefab_template;
 pub(crate) mod spawn;
+pub(crate) mod terrain;
 
-use spawn::Spawn;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use spawn::{Spawn, Transition};
 
+use crate::assets::{self, Asset};
+use crate::core::story::Milestone;
+use crate::raws::boss_template::BossTemplate;
+use crate::raws::difficulty::Difficulty;
+use crate::raws::ecosystem::EcosystemBounds;
 use crate::raws::object_template::ObjectTemplate;
+use crate::raws::prefab_template::PrefabTemplate;
+use crate::raws::terrain::TerrainParams;
+use crate::ui::color_palette::ColorPalette;
 
-rltk::embedded_resource!(SPAWN_RAW, "../raws/spawns.json");
-rltk::embedded_resource!(OBJECT_RAW, "../raws/objects.json");
-
-pub fn load_spawns() -> Vec<Spawn> {
-    rltk::link_resource!(SPAWN_RAW, "../raws/spawns.json");
-
-    // Retrieve the raw data as an array of u8 (8-bit unsigned chars)
-    let raw_data = rltk::embedding::EMBED
-        .lock()
-        // .unwrap()
-        .get_resource("../raws/spawns.json".to_string())
-        .unwrap();
-    let raw_string =
-        std::str::from_utf8(&raw_data).expect("Unable to convert to a valid UTF-8 string.");
-    serde_json::from_str(&raw_string).expect("Unable to parse JSON")
-}
-
-pub fn load_object_templates() -> Vec<ObjectTemplate> {
-    rltk::link_resource!(OBJECT_RAW, "../raws/objects.json");
-
-    // Retrieve the raw data as an array of u8 (8-bit unsigned chars)
-    let raw_data = rltk::embedding::EMBED
-        .lock()
-        // .unwrap()
-        .get_resource("../raws/objects.json".to_string())
-        .unwrap();
-    let raw_string =
-        std::str::from_utf8(&raw_data).expect("Unable to convert to a valid UTF-8 string.");
-    serde_json::from_str(&raw_string).expect("Unable to parse JSON")
+/// Name of the object template that defines the genome of the world's tissue, i.e. the tiles.
+pub const TISSUE_TEMPLATE: &str = "Tissue";
+
+/// Load the spawn tables. Problems with the entries are appended to `errors`.
+pub fn load_spawns(errors: &mut Vec<String>) -> Vec<Spawn> {
+    load_raw_list(&assets::SPAWNS, errors)
+}
+
+/// Load the object templates. Problems with the entries are appended to `errors`.
+pub fn load_object_templates(errors: &mut Vec<String>) -> Vec<ObjectTemplate> {
+    load_raw_list(&assets::OBJECTS, errors)
+}
+
+/// Load the text prefabs. Problems with the entries are appended to `errors`.
+pub fn load_prefab_templates(errors: &mut Vec<String>) -> Vec<PrefabTemplate> {
+    load_raw_list(&assets::PREFABS, errors)
+}
+
+/// Load the terrain parameters per dungeon level. Problems with the entries are appended to
+/// `errors`.
+pub fn load_terrain(errors: &mut Vec<String>) -> Vec<Transition<TerrainParams>> {
+    load_raw_list(&assets::TERRAIN, errors)
+}
+
+/// Load the bosses and their arenas. Problems with the entries are appended to `errors`.
+pub fn load_boss_templates(errors: &mut Vec<String>) -> Vec<BossTemplate> {
+    load_raw_list(&assets::BOSSES, errors)
+}
+
+/// Load the milestones of the story. Problems with the entries are appended to `errors`.
+pub fn load_story(errors: &mut Vec<String>) -> Vec<Milestone> {
+    load_raw_list(&assets::STORY, errors)
+}
+
+/// Load the difficulty presets that new games can be played at. Problems with the entries are
+/// appended to `errors`.
+pub fn load_difficulties(errors: &mut Vec<String>) -> Vec<Difficulty> {
+    load_raw_list(&assets::DIFFICULTY, errors)
+}
+
+/// Load the bounds of the ecosystem regulator per dungeon level. Problems with the entries are
+/// appended to `errors`.
+pub fn load_ecosystem(errors: &mut Vec<String>) -> Vec<Transition<EcosystemBounds>> {
+    load_raw_list(&assets::ECOSYSTEM, errors)
+}
+
+/// Load a custom color palette from the user data or mod directory, if there is one.
+pub fn load_palette(errors: &mut Vec<String>) -> Option<ColorPalette> {
+    assets::PALETTE.load(
+        errors,
+        |origin, bytes, errors| match serde_json::from_slice(bytes) {
+            Ok(palette) => Some(palette),
+            Err(e) => {
+                error!("failed to load {}: {}", origin, e);
+                errors.push(format!("{}: {}", origin, e));
+                None
+            }
+        },
+    )
+}
+
+/// Load all raws once, to find any problems with them right at the start. Returns the problems.
+pub fn check_raws() -> Vec<String> {
+    let mut errors = Vec::new();
+    load_palette(&mut errors);
+    load_spawns(&mut errors);
+    load_object_templates(&mut errors);
+    load_prefab_templates(&mut errors);
+    load_terrain(&mut errors);
+    load_boss_templates(&mut errors);
+    load_story(&mut errors);
+    load_difficulties(&mut errors);
+    load_ecosystem(&mut errors);
+    errors
+}
+
+/// Load a list of entries from the first source of the asset that provides a valid list, see
+/// [`assets`]. Entries that cannot be parsed are skipped, the others are kept.
+fn load_raw_list<T: DeserializeOwned>(asset: &Asset, errors: &mut Vec<String>) -> Vec<T> {
+    asset
+        .load(errors, |origin, bytes, errors| {
+            parse_entries(origin, &String::from_utf8_lossy(bytes), errors)
+        })
+        .unwrap_or_default()
+}
+
+/// Parse a JSON list entry by entry. Each entry that cannot be parsed is reported in `errors` with
+/// the file, its index and line and the offending field, if known. Returns `None` if the text is
+/// no JSON list at all.
+pub(crate) fn parse_entries<T: DeserializeOwned>(
+    file: &str,
+    raw_string: &str,
+    errors: &mut Vec<String>,
+) -> Option<Vec<T>> {
+    let entries = match split_entries(raw_string) {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("failed to load {}: {}", file, e);
+            errors.push(format!("{}: {}, all of it is skipped", file, e));
+            return None;
+        }
+    };
+    let mut parsed = Vec::new();
+    for (idx, (start_line, text)) in entries.into_iter().enumerate() {
+        match serde_json::from_str(text) {
+            Ok(entry) => parsed.push(entry),
+            Err(e) => {
+                let line = start_line + e.line() - 1;
+                let message = e.to_string();
+                let message = message.split(" at line ").next().unwrap_or_default();
+                let field = text
+                    .lines()
+                    .nth(e.line() - 1)
+                    .and_then(field_name)
+                    .map_or(String::new(), |f| format!("field `{}`: ", f));
+                let error = format!(
+                    "{}:{}: skipped entry #{}{}, {}{}",
+                    file,
+                    line,
+                    idx,
+                    entry_name(text),
+                    field,
+                    message
+                );
+                error!("{}", error);
+                errors.push(error);
+            }
+        }
+    }
+    Some(parsed)
+}
+
+/// Split a JSON list into the text of its entries, each with the line it starts on.
+fn split_entries(raw_string: &str) -> Result<Vec<(usize, &str)>, String> {
+    // let serde find any syntax errors, so that the entries can be taken apart safely
+    serde_json::from_str::<Vec<Value>>(raw_string).map_err(|e| e.to_string())?;
+
+    let mut entries = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut is_escaped = false;
+    let mut start = None;
+    let mut line = 1;
+    let mut start_line = 1;
+    for (i, c) in raw_string.char_indices() {
+        if c == '\n' {
+            line += 1;
+        }
+        if in_string {
+            match c {
+                _ if is_escaped => is_escaped = false,
+                '\\' => is_escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        if depth == 1 && (c == ',' || c == ']') {
+            if let Some(s) = start.take() {
+                entries.push((start_line, raw_string[s..i].trim_end()));
+            }
+        } else if depth >= 1 && start.is_none() && !c.is_whitespace() {
+            start = Some(i);
+            start_line = line;
+        }
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            '"' => in_string = true,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// Name of a raw entry for error messages, taken from the fields that usually identify it.
+fn entry_name(text: &str) -> String {
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|entry| {
+            ["name", "npc", "id"]
+                .iter()
+                .find_map(|key| entry.get(key).and_then(Value::as_str).map(str::to_string))
+        })
+        .map_or(String::new(), |name| format!(" ({})", name))
+}
+
+/// Key of the field defined on the given line of JSON text, if any.
+fn field_name(line: &str) -> Option<&str> {
+    let key = line.trim_start().strip_prefix('"')?;
+    let end = key.find('"')?;
+    if key[end + 1..].trim_start().starts_with(':') {
+        Some(&key[..end])
+    } else {
+        None
+    }
 }
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::boss::BossPhase;
+
+/// A boss and the arena it guards. The boss is an organism of the species given by `npc`, made
+/// tougher by `bonus_hp`. Its arena is drawn like a text prefab, with `B` marking the boss'
+/// position.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BossTemplate {
+    pub name: String,
+    pub npc: String,
+    /// Dungeon level the boss appears on.
+    pub level: u32,
+    #[serde(default)]
+    pub bonus_hp: i32,
+    pub phases: Vec<BossPhase>,
+    pub arena: Vec<String>,
+}
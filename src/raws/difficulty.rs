@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Name of the difficulty that new games are played at unless the player picks another one.
+pub const STANDARD_DIFFICULTY: &str = "standard";
+
+/// A set of parameters that scale the simulation for a whole run. Each factor is relative to the
+/// usual rules, so that the default of 1 leaves them unchanged.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Difficulty {
+    pub name: String,
+    pub description: String,
+    /// Factor of the chance that genes mutate.
+    pub mutation_rate: f64,
+    /// Factor of how often the host's immune system sends out antibodies.
+    pub immune_frequency: f64,
+    /// Factor of the nutrients that organisms can metabolise from the host.
+    pub nutrient_abundance: f64,
+    /// Factors of the spawn weights per NPC type, types that aren't listed spawn as usual.
+    pub spawn_weights: BTreeMap<String, f64>,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty {
+            name: STANDARD_DIFFICULTY.to_string(),
+            description: String::new(),
+            mutation_rate: 1.0,
+            immune_frequency: 1.0,
+            nutrient_abundance: 1.0,
+            spawn_weights: BTreeMap::new(),
+        }
+    }
+}
+
+impl Difficulty {
+    /// Scale the spawn weight of the given NPC type.
+    pub fn spawn_weight(&self, npc: &str, weight: u32) -> u32 {
+        let factor = self.spawn_weights.get(npc).copied().unwrap_or(1.0);
+        (weight as f64 * factor.max(0.0)).round() as u32
+    }
+
+    /// Scale the chance of a mutation, keeping it a valid probability.
+    pub fn mutation_chance(&self, chance: f64) -> f64 {
+        (chance * self.mutation_rate).clamp(0.0, 1.0)
+    }
+
+    /// Scale the nutrients given in percent of the usual supply.
+    pub fn nutrients(&self, nutrients: i32) -> i32 {
+        (nutrients as f64 * self.nutrient_abundance.max(0.0)).round() as i32
+    }
+
+    /// Stretch or shorten an interval between two immune responses, it never drops below 1.
+    pub fn immune_interval(&self, interval: u32) -> u32 {
+        let frequency = self.immune_frequency.max(0.01);
+        ((interval as f64 / frequency).round() as u32).max(1)
+    }
+}
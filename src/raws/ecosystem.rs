@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Bounds within which the ecosystem regulator keeps the population of a world. They are given
+/// per dungeon level as a transition table, the default suits the first level.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct EcosystemBounds {
+    /// Number of turns between two counts of the population.
+    pub census_interval: u32,
+    /// Population below which the ecosystem is about to collapse.
+    pub min_population: usize,
+    /// Population above which the ecosystem is about to explode.
+    pub max_population: usize,
+    /// Organisms spawned per census at the usual spawn rate, rounded. Below 0.5 nothing spawns
+    /// until the population has been too small for a few censuses.
+    pub spawns_per_census: f64,
+    /// Change of the rates per census, the smaller the gentler.
+    pub step: f64,
+    /// Range of the factor on the number of organisms spawned per census.
+    pub min_spawn_rate: f64,
+    pub max_spawn_rate: f64,
+    /// Range of the factor on the nutrients that organisms can metabolise.
+    pub min_nutrient_rate: f64,
+    pub max_nutrient_rate: f64,
+}
+
+impl Default for EcosystemBounds {
+    fn default() -> Self {
+        EcosystemBounds {
+            census_interval: 50,
+            min_population: 5,
+            max_population: 40,
+            spawns_per_census: 0.4,
+            step: 0.1,
+            min_spawn_rate: 0.0,
+            max_spawn_rate: 5.0,
+            min_nutrient_rate: 0.7,
+            max_nutrient_rate: 1.3,
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 
-use crate::entity::genetics::DnaType;
+use crate::entity::action::registry::ActionParams;
+use crate::entity::bark::{Bark, BarkTrigger};
+use crate::entity::faction::Faction;
+use crate::entity::genetics::{create_trait_list, DnaType};
+use crate::entity::loot::DropRates;
 use crate::entity::object::Physics;
 /// Struct for spawning objects that requires an internal state.
 #[derive(Serialize, Deserialize, Clone)]
@@ -14,6 +18,17 @@ pub struct ObjectTemplate {
     pub dna_type: DnaType,
     pub dna_template: DnaTemplate,
     pub stability: f64,
+    /// Number of trailing segments of multi-tile organisms.
+    #[serde(default)]
+    pub segments: usize,
+    #[serde(default)]
+    pub faction: Faction,
+    /// Lines of story that organisms of this kind tell the player.
+    #[serde(default)]
+    pub barks: Vec<Bark>,
+    /// How much of themselves organisms of this kind leave behind when they die.
+    #[serde(default)]
+    pub drops: DropRates,
     // pub dna_transitions: Vec<Transition<DnaTemplate>>,
 }
 
@@ -29,14 +44,58 @@ pub enum DnaTemplate {
         genome_len: usize,
     },
     Defined {
+        #[serde(deserialize_with = "checked_traits")]
         traits: Vec<String>,
     },
+    /// Explicit DNA, given as a string of whitespace separated hexadecimal bytes, e.g. "00 01 0c".
+    Explicit {
+        #[serde(deserialize_with = "checked_dna")]
+        dna: String,
+    },
+}
+
+/// Deserialize explicit DNA and check right away that it consists of hexadecimal bytes, so that
+/// templates with broken DNA are reported and skipped when the raws are loaded.
+fn checked_dna<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let dna = String::deserialize(deserializer)?;
+    DnaTemplate::parse_dna(&dna).map_err(de::Error::custom)?;
+    Ok(dna)
+}
+
+/// Deserialize the trait names of defined DNA and check right away that each one is a known
+/// trait, so that templates with unknown traits are reported and skipped when the raws are loaded.
+fn checked_traits<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let traits = Vec::<String>::deserialize(deserializer)?;
+    let known = create_trait_list();
+    if let Some(unknown) = traits
+        .iter()
+        .find(|t| !known.iter().any(|k| &k.trait_name == *t))
+    {
+        return Err(de::Error::custom(format!(
+            "unknown genetic trait: {}",
+            unknown
+        )));
+    }
+    Ok(traits)
+}
+
+impl DnaTemplate {
+    /// Parse the hexadecimal bytes of an explicit DNA string.
+    pub fn parse_dna(dna: &str) -> Result<Vec<u8>, String> {
+        dna.split_whitespace()
+            .map(|byte| {
+                u8::from_str_radix(byte, 16).map_err(|e| format!("invalid dna '{}': {}", byte, e))
+            })
+            .collect()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct InvItem {
     pub name: String,
     pub action: String,
+    #[serde(default)]
+    pub action_params: ActionParams,
 }
 
 impl ObjectTemplate {
@@ -56,6 +115,13 @@ impl ObjectTemplate {
             dna_type: DnaType::Rna,
             dna_template: DnaTemplate::Random { genome_len: 10 },
             stability: 0.75,
+            segments: 0,
+            faction: Faction::Pathogens,
+            barks: vec![Bark {
+                trigger: BarkTrigger::Seen,
+                text: "A virus drifts by, looking for a cell to hijack.".to_string(),
+            }],
+            drops: DropRates::default(),
         }]
     }
 }
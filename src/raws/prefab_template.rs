@@ -0,0 +1,14 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A handcrafted map chunk drawn as text. `#` marks walls, `.` open tissue and spaces leave the
+/// generated map untouched. Any other character is a spawn marker and must be listed in
+/// `markers` together with the name of the object template to spawn there.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PrefabTemplate {
+    pub name: String,
+    pub rows: Vec<String>,
+    #[serde(default)]
+    pub markers: HashMap<char, String>,
+}
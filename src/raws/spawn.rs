@@ -13,11 +13,27 @@ pub struct Transition<T> {
     pub value: T,
 }
 
+/// Where in the world objects of a species may be placed.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum SpawnLocation {
+    /// Any position, including inside walls.
+    #[default]
+    Anywhere,
+    /// Any open floor tile.
+    Open,
+    /// Open floor tiles adjacent to a wall.
+    Wall,
+    /// Open floor tiles with a current flowing through them.
+    Current,
+}
+
 /// Struct for spawning objects that requires an internal state.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Spawn {
     pub npc: String,
     pub spawn_transitions: Vec<Transition<u32>>,
+    #[serde(default)]
+    pub location: SpawnLocation,
     // pub dna_transitions: Vec<Transition<DnaTemplate>>,
 }
 
@@ -36,6 +52,7 @@ impl Spawn {
                         value: 21,
                     },
                 ],
+                location: SpawnLocation::Open,
                 // dna_transitions: vec![
                 //     Transition {
                 //         level: 1,
@@ -69,6 +86,7 @@ impl Spawn {
                         value: 21,
                     },
                 ],
+                location: SpawnLocation::Open,
                 // dna_transitions: vec![
                 //     Transition {
                 //         level: 1,
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Parameters of the noise that varies the terrain of organic worlds. They are given per dungeon
+/// level as a transition table, the default leaves the terrain unvaried.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TerrainParams {
+    /// Scale of the noise, lower values give larger patches.
+    pub frequency: f32,
+    /// How much the noise thins out or thickens the tissue walls, from 0 (not at all) to 1.
+    pub wall_variation: f32,
+    /// How much the nutrient richness deviates from the host's supply, from 0 (not at all) to 1.
+    pub nutrient_variation: f32,
+    /// Share of the world, from 0 to 1, that is covered by hazardous patches.
+    pub hazard_coverage: f32,
+    /// Temperature rise in °C at the core of a hazardous patch, which also acidifies by a quarter
+    /// of that in pH.
+    pub hazard_strength: f32,
+}
+
+impl Default for TerrainParams {
+    fn default() -> Self {
+        TerrainParams {
+            frequency: 0.08,
+            wall_variation: 0.0,
+            nutrient_variation: 0.0,
+            hazard_coverage: 0.0,
+            hazard_strength: 0.0,
+        }
+    }
+}
@@ -1,4 +1,80 @@
+#[cfg(test)]
+mod achievement;
+#[cfg(test)]
+mod action;
 mod ai;
 #[cfg(test)]
+mod ambience;
+#[cfg(test)]
+mod animations;
+#[cfg(test)]
+mod assets;
+#[cfg(test)]
+mod bark;
+#[cfg(test)]
+mod boss;
+#[cfg(test)]
+mod bug_report;
+#[cfg(test)]
+mod clock;
+#[cfg(test)]
+mod combat;
+#[cfg(test)]
+mod connectivity;
+#[cfg(test)]
+mod daily;
+#[cfg(test)]
+mod danger;
+#[cfg(test)]
+mod determinism;
+#[cfg(test)]
+mod ecosystem;
+#[cfg(test)]
+mod encyclopedia;
+#[cfg(test)]
+mod faction;
+#[cfg(test)]
+mod game_loop;
+#[cfg(test)]
 mod genetics;
+#[cfg(test)]
+mod host;
+#[cfg(test)]
+mod hud;
+#[cfg(test)]
+mod layers;
+#[cfg(test)]
+mod loot;
+#[cfg(test)]
+mod mutation;
+#[cfg(test)]
+mod organelle;
 mod position;
+#[cfg(test)]
+mod prefab;
+#[cfg(test)]
+mod raws;
+#[cfg(test)]
+mod region;
+#[cfg(test)]
+mod rng;
+#[cfg(test)]
+mod run_code;
+#[cfg(test)]
+mod save;
+#[cfg(test)]
+mod segment;
+#[cfg(test)]
+mod settings;
+#[cfg(test)]
+mod snapshot;
+#[cfg(test)]
+mod story;
+#[cfg(test)]
+mod telemetry;
+#[cfg(test)]
+mod trade;
+#[cfg(test)]
+mod visibility;
+#[cfg(test)]
+mod widget;
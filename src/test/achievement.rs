@@ -0,0 +1,20 @@
+use crate::core::achievements::Achievements;
+use crate::core::events::GameEvent;
+use crate::core::host::AntibioticClass;
+
+#[test]
+fn test_achievement_unlocks() {
+    let mut achievements = Achievements::new();
+
+    let unlocked = achievements.unlock(&GameEvent::GenomeChanged { genes: 25 });
+    let ids: Vec<&str> = unlocked.iter().map(|a| a.id).collect();
+    assert_eq!(ids, vec!["first_mutation", "long_genome"]);
+
+    // achievements are only unlocked once
+    assert!(achievements
+        .unlock(&GameEvent::GenomeChanged { genes: 30 })
+        .is_empty());
+    assert!(!achievements.is_unlocked("antibiotic_survivor"));
+    achievements.unlock(&GameEvent::TreatmentSurvived(AntibioticClass::BetaLactam));
+    assert!(achievements.is_unlocked("antibiotic_survivor"));
+}
@@ -0,0 +1,470 @@
+use crate::core::events::GameEvent;
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::{GameState, ObjectFeedback, MAX_CONSEQUENCES};
+use crate::core::position::Position;
+use crate::core::world::{
+    object_from_template, toxin_damage, BarrierKind, Tile, TOXIN_MAX, WALL_INTEGRITY,
+};
+use crate::entity::action::hereditary::{
+    ActAbsorbDna, ActAttach, ActDigestWall, ActMove, ActRest, ActSecreteToxin, PUSH_COST,
+};
+use crate::entity::action::inventory::{ActAnalyze, ActPickUpItem};
+use crate::entity::action::registry::{action_registry, ActionParams};
+use crate::entity::action::{Action, ActionResult, Target, TargetCategory};
+use crate::entity::ai::AiPassive;
+use crate::entity::control::Controller;
+use crate::entity::genetics::{BASE_CARRY_WEIGHT, REPAIR_COST};
+use crate::entity::inventory::{is_identified, item_name};
+use crate::entity::object::{Attachment, Object};
+use crate::entity::organelle::Organelle;
+use crate::entity::player::{PlayerCtrl, Travel};
+use crate::entity::signal::SIGNAL_RADIUS;
+use crate::raws::load_object_templates;
+use rltk::{Algorithm2D, BaseMap};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_action_registry() {
+    for name in action_registry().names() {
+        let action = action_registry().create(name, &ActionParams::default());
+        assert!(action.is_ok(), "cannot create {}", name);
+    }
+
+    let params = ActionParams {
+        level: Some(3),
+        target: Some(Target::East),
+        dna: None,
+        organelle: None,
+    };
+    let action = action_registry().create("ActAttack", &params).unwrap();
+    assert_eq!(action.get_level(), 3);
+
+    assert!(action_registry()
+        .create("ActUnknown", &ActionParams::default())
+        .is_err());
+}
+
+#[test]
+fn test_rest_and_repair() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    let mut cell = Object::new();
+    cell.actuators.max_hp = 10;
+    cell.actuators.hp = 2;
+    cell.processors.energy_storage = 2;
+    cell.processors.reserve_capacity = 6;
+    cell.processors.reserve = 6;
+
+    // without repair genes metabolism doesn't heal
+    cell.metabolize(100);
+    assert_eq!(cell.actuators.hp, 2);
+
+    // each repair gene mends one hp per turn of metabolism, paid from spare energy
+    cell.processors.repair = 1;
+    cell.metabolize(100);
+    assert_eq!(cell.actuators.hp, 3);
+    assert_eq!(cell.processors.reserve, 6 - REPAIR_COST);
+
+    // resting spends the reserve first and scales with the level
+    let mut rest = ActRest::new();
+    rest.set_level(1);
+    let reserve = cell.processors.reserve;
+    let result = rest.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Success { .. }));
+    assert_eq!(cell.actuators.hp, 5);
+    assert_eq!(cell.processors.reserve, reserve - 2 * REPAIR_COST);
+
+    // an organism can't repair beyond its max hp
+    cell.actuators.hp = 10;
+    let result = rest.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Failure));
+}
+
+/// Pickups beyond the carry weight are rejected and leave the item where it is.
+#[test]
+fn test_pick_up_capacity() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let mut cell = Object::new().position(5, 5);
+    assert!(cell.actuators.volume > BASE_CARRY_WEIGHT);
+
+    for _ in 0..=BASE_CARRY_WEIGHT {
+        objects.push(Organelle::Vacuole.to_item(5, 5));
+    }
+    for _ in 0..BASE_CARRY_WEIGHT {
+        let result = ActPickUpItem.perform(&mut state, &mut objects, &mut cell);
+        assert!(matches!(result, ActionResult::Success { .. }));
+    }
+    let result = ActPickUpItem.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Failure));
+    assert_eq!(cell.inventory.items.len(), BASE_CARRY_WEIGHT as usize);
+    assert_eq!(cell.inventory.weight(), BASE_CARRY_WEIGHT);
+    assert!(objects.extract_item_by_pos(&Position::new(5, 5)).is_some());
+    assert!(!cell.is_overloaded());
+}
+
+/// Analyzing a plasmid identifies all plasmids of its kind for the rest of the run.
+#[test]
+fn test_analyze_item() {
+    let mut errors = Vec::new();
+    let templates = load_object_templates(&mut errors);
+    let template = templates.iter().find(|t| t.npc == "R-Plasmid").unwrap();
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    let plasmid = object_from_template(&mut state, template, 5, 5).unwrap();
+    let other = object_from_template(&mut state, template, 6, 5).unwrap();
+    assert!(!is_identified(&state, &plasmid));
+    assert!(item_name(&state, &plasmid).contains("unknown plasmid"));
+
+    let mut cell = Object::new().position(5, 5);
+    cell.inventory.items.push(plasmid);
+    let result = ActAnalyze.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Success { .. }));
+    assert!(is_identified(&state, &other));
+    assert_eq!(item_name(&state, &other), "R-Plasmid");
+
+    // nothing left to analyze
+    let result = ActAnalyze.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Failure));
+}
+
+/// Absorbing the remains of a dead organism splices one of its genes into the genome.
+#[test]
+fn test_absorb_dna() {
+    let mut errors = Vec::new();
+    let templates = load_object_templates(&mut errors);
+    let template = templates.iter().find(|t| t.npc == "Streptococcus").unwrap();
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let mut cell = object_from_template(&mut state, template, 5, 5).unwrap();
+    let mut prey = object_from_template(&mut state, template, 5, 5).unwrap();
+    prey.die(&mut state, &mut objects);
+    let genes = cell.dna.simplified.len();
+
+    let absorb = ActAbsorbDna::new();
+    let result = absorb.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Success { .. }));
+    assert_eq!(cell.dna.simplified.len(), genes + 1);
+
+    // the remains are used up
+    let result = absorb.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Failure));
+}
+
+#[test]
+fn test_secrete_toxin() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let mut cell = Object::new().position(5, 5);
+
+    let mut secrete = ActSecreteToxin::new();
+    secrete.set_level(2);
+    let result = secrete.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Success { .. }));
+    assert_eq!(objects.get_toxin(&Position::new(4, 6)), 8);
+    assert_eq!(objects.get_toxin(&Position::new(7, 5)), 0);
+    assert_eq!(toxin_damage(objects.get_toxin(&Position::new(5, 5))), 2);
+
+    // toxins pile up to a limit and fade over time
+    secrete.perform(&mut state, &mut objects, &mut cell);
+    assert_eq!(objects.get_toxin(&Position::new(5, 5)), TOXIN_MAX);
+    objects.decay_chemicals();
+    assert_eq!(objects.get_toxin(&Position::new(5, 5)), TOXIN_MAX - 1);
+}
+
+#[test]
+fn test_digest_wall() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let mut cell = Object::new().position(4, 5);
+    cell.processors.energy_storage = 10;
+
+    let mut digest = ActDigestWall::new();
+    digest.set_level(2);
+    digest.set_target(Target::East);
+    for _ in 0..WALL_INTEGRITY / 2 {
+        assert!(objects.is_pos_blocked(&Position::new(5, 5)));
+        let result = digest.perform(&mut state, &mut objects, &mut cell);
+        assert!(matches!(result, ActionResult::Success { .. }));
+    }
+    assert!(!objects.is_pos_blocked(&Position::new(5, 5)));
+    assert!(cell.processors.energy > 0);
+
+    // there is nothing left to digest and the boundary of the world holds
+    let result = digest.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Failure));
+    assert_eq!(
+        objects.digest_wall(&Position::new(0, 5), WALL_INTEGRITY),
+        None
+    );
+}
+
+#[test]
+fn test_action_range() {
+    let range = |name: &str| {
+        action_registry()
+            .create(name, &ActionParams::default())
+            .unwrap()
+            .get_range()
+    };
+    assert_eq!(range("ActAttack"), 1);
+    assert_eq!(range("ActRest"), 0);
+    assert_eq!(range("ActSecreteToxin"), 1);
+    assert_eq!(range("ActPeacePheromone"), SIGNAL_RADIUS as i32);
+}
+
+/// Test action that triggers itself until `remaining` runs out, counting its calls in the
+/// performer's energy.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ActChain {
+    remaining: usize,
+}
+
+#[typetag::serde]
+impl Action for ActChain {
+    fn perform(
+        &self,
+        _state: &mut GameState,
+        _objects: &mut GameObjects,
+        owner: &mut Object,
+    ) -> ActionResult {
+        owner.processors.energy += 1;
+        match self.remaining {
+            0 => ActionResult::Success {
+                callback: ObjectFeedback::UpdateHud,
+            },
+            remaining => ActionResult::Consequence {
+                callback: ObjectFeedback::Render,
+                follow_up: Box::new(ActChain {
+                    remaining: remaining - 1,
+                }),
+            },
+        }
+    }
+
+    fn set_target(&mut self, _t: Target) {}
+
+    fn set_level(&mut self, _lvl: i32) {}
+
+    fn get_target_category(&self) -> TargetCategory {
+        TargetCategory::None
+    }
+
+    fn get_level(&self) -> i32 {
+        0
+    }
+
+    fn get_identifier(&self) -> String {
+        "chain".to_string()
+    }
+
+    fn get_energy_cost(&self) -> i32 {
+        0
+    }
+
+    fn to_text(&self) -> String {
+        "chain".to_string()
+    }
+}
+
+#[test]
+fn test_consequences() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    let mut cell = Object::new();
+
+    // the highest ranking feedback of the chain wins
+    let feedback =
+        state.process_action(&mut objects, &mut cell, Box::new(ActChain { remaining: 3 }));
+    assert_eq!(feedback, ObjectFeedback::UpdateHud);
+    assert_eq!(cell.processors.energy, 4);
+
+    // endless chains are cut off
+    cell.processors.energy = 0;
+    let feedback = state.process_action(
+        &mut objects,
+        &mut cell,
+        Box::new(ActChain { remaining: 100 }),
+    );
+    assert_eq!(feedback, ObjectFeedback::Render);
+    assert_eq!(cell.processors.energy, MAX_CONSEQUENCES as i32 + 1);
+}
+
+/// A blocked move pushes a smaller organism aside, unless it's anchored.
+#[test]
+fn test_push() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    for x in 4..=6 {
+        objects.replace_tile(&Position::new(x, 5), Tile::empty(x, 5, false));
+    }
+    let mut cell = Object::new().position(4, 5);
+    cell.actuators.biomass = 10;
+    cell.processors.energy = PUSH_COST;
+    let mut other = Object::new()
+        .position(5, 5)
+        .physical(true, false, false)
+        .control(Controller::Npc(Box::new(AiPassive)))
+        .living(true);
+    other.actuators.biomass = 5;
+    other.actuators.anchor = 1;
+    objects.push(other);
+    let mut walk = ActMove::new();
+    walk.set_level(1);
+    walk.set_target(Target::East);
+
+    let feedback = state.process_action(&mut objects, &mut cell, Box::new(walk.clone()));
+    assert_eq!(feedback, ObjectFeedback::NoAction);
+    assert_eq!(cell.pos, Position::new(4, 5));
+
+    let other = objects
+        .get_vector_mut()
+        .last_mut()
+        .unwrap()
+        .as_mut()
+        .unwrap();
+    other.actuators.anchor = 0;
+    let feedback = state.process_action(&mut objects, &mut cell, Box::new(walk.clone()));
+    assert_eq!(feedback, ObjectFeedback::Render);
+    assert_eq!(cell.pos, Position::new(5, 5));
+    assert_eq!(cell.processors.energy, 0);
+    let other = objects.get_vector().last().unwrap().as_ref().unwrap();
+    assert_eq!(other.pos, Position::new(6, 5));
+}
+
+/// Organisms hold on to what they're attached to until they actually move away.
+#[test]
+fn test_blocked_move_keeps_attachment() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    for x in 3..=4 {
+        objects.replace_tile(&Position::new(x, 5), Tile::empty(x, 5, false));
+    }
+    let mut cell = Object::new().position(4, 5);
+    cell.attachment = Some(Attachment {
+        pos: Position::new(5, 5),
+        strength: 1,
+    });
+    let mut walk = ActMove::new();
+    walk.set_level(1);
+
+    walk.set_target(Target::East);
+    let result = walk.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Failure));
+    assert!(cell.attachment.is_some());
+
+    walk.set_target(Target::West);
+    walk.perform(&mut state, &mut objects, &mut cell);
+    assert_eq!(cell.pos, Position::new(3, 5));
+    assert!(cell.attachment.is_none());
+}
+
+/// Journeys keep to their path one tile at a time, even while the player bursts.
+#[test]
+fn test_travel_walks_in_burst_mode() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    for x in 4..=7 {
+        objects.replace_tile(&Position::new(x, 5), Tile::empty(x, 5, false));
+    }
+    let mut ctrl = PlayerCtrl::new();
+    ctrl.toggle_move_mode();
+    let mut cell = Object::new().position(4, 5);
+    let mut walk = ActMove::new();
+    walk.set_level(1);
+    let travel = Travel::new(vec![Position::new(5, 5), Position::new(6, 5)]);
+
+    // a regular move would burst past the next step of the path
+    let burst = ctrl.prepare_action(&walk, Target::East);
+    let mut burster = Object::new().position(4, 5);
+    burst.perform(&mut state, &mut objects, &mut burster);
+    assert_eq!(burster.pos, Position::new(6, 5));
+
+    let step = travel.step_action(&walk, &cell.pos).unwrap();
+    step.perform(&mut state, &mut objects, &mut cell);
+    assert_eq!(cell.pos, Position::new(5, 5));
+}
+
+/// A blocked move swaps places with a bigger organism that isn't hostile, and tells the player.
+#[test]
+fn test_swap() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    for x in 4..=5 {
+        objects.replace_tile(&Position::new(x, 5), Tile::empty(x, 5, false));
+    }
+    let mut player = Object::new()
+        .position(4, 5)
+        .control(Controller::Player(PlayerCtrl::new()));
+    player.actuators.biomass = 10;
+    let mut other = Object::new()
+        .position(5, 5)
+        .physical(true, false, false)
+        .control(Controller::Npc(Box::new(AiPassive)))
+        .living(true);
+    other.actuators.biomass = 20;
+    objects.push(other);
+    let mut walk = ActMove::new();
+    walk.set_level(1);
+    walk.set_target(Target::East);
+
+    let feedback = state.process_action(&mut objects, &mut player, Box::new(walk));
+    assert_eq!(feedback, ObjectFeedback::Render);
+    assert_eq!(player.pos, Position::new(5, 5));
+    let other = objects.get_vector().last().unwrap().as_ref().unwrap();
+    assert_eq!(other.pos, Position::new(4, 5));
+    assert!(matches!(
+        state.events.drain().as_slice(),
+        [GameEvent::PlacesSwapped { .. }]
+    ));
+}
+
+/// Organisms with pili pry barriers open, which close again once their time is up and nothing is
+/// in the way.
+#[test]
+fn test_open_barrier() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let pore = Position::new(5, 5);
+    objects.replace_tile(&Position::new(4, 5), Tile::empty(4, 5, false));
+    objects.replace_tile(&pore, Tile::barrier(5, 5, BarrierKind::Pore, false));
+    assert!(objects.is_pos_blocked(&pore));
+    assert!(!objects.is_opaque(objects.point2d_to_index(pore.into())));
+    let mut cell = Object::new().position(4, 5);
+    let mut walk = ActMove::new();
+    walk.set_level(1);
+    walk.set_target(Target::East);
+
+    // without pili the barrier stays shut
+    let feedback = state.process_action(&mut objects, &mut cell, Box::new(walk.clone()));
+    assert_eq!(feedback, ObjectFeedback::NoAction);
+
+    cell.actuators.actions.push(Box::new(ActAttach::new()));
+    let feedback = state.process_action(&mut objects, &mut cell, Box::new(walk.clone()));
+    assert_eq!(feedback, ObjectFeedback::Render);
+    assert!(!objects.is_pos_blocked(&pore));
+    assert_eq!(cell.pos, Position::new(4, 5));
+
+    // an organism in the way keeps it open
+    let feedback = state.process_action(&mut objects, &mut cell, Box::new(walk));
+    assert_eq!(feedback, ObjectFeedback::Render);
+    let cell = cell.physical(true, false, false);
+    objects.push(cell);
+    for _ in 0..BarrierKind::Pore.open_turns() {
+        objects.update_barriers();
+    }
+    assert!(objects.get_barrier(&pore).unwrap().is_open());
+    objects.get_vector_mut().pop();
+    objects.update_barriers();
+    assert!(!objects.get_barrier(&pore).unwrap().is_open());
+    assert!(objects.is_pos_blocked(&pore));
+}
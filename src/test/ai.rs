@@ -12,6 +12,7 @@ fn test_random_ai() {
     use crate::entity::object::Object;
     use crate::entity::player::PLAYER;
     use crate::ui::palette;
+    use crate::util::game_rng::RngStream;
 
     let ((p_x, p_y), mut state, mut objects) = _create_minimal_world();
 
@@ -37,9 +38,12 @@ fn test_random_ai() {
         // TODO: Pull genome create out of here. It's not the same for every NPC.
         .genome(
             0.75,
-            state
-                .gene_library
-                .new_genetics(&mut state.rng, DnaType::Rna, true, GENE_LEN),
+            state.gene_library.new_genetics(
+                state.rng_streams.get(RngStream::WorldGen),
+                DnaType::Rna,
+                true,
+                GENE_LEN,
+            ),
         )
         .control(Controller::Npc(Box::new(AiVirus::new())));
 
@@ -51,9 +55,12 @@ fn test_random_ai() {
         // TODO: Pull genome create out of here. It's not the same for every NPC.
         .genome(
             0.75,
-            state
-                .gene_library
-                .new_genetics(&mut state.rng, DnaType::Rna, true, GENE_LEN),
+            state.gene_library.new_genetics(
+                state.rng_streams.get(RngStream::WorldGen),
+                DnaType::Rna,
+                true,
+                GENE_LEN,
+            ),
         )
         .control(Controller::Npc(Box::new(AiVirus::new())));
 
@@ -65,9 +72,12 @@ fn test_random_ai() {
         // TODO: Pull genome create out of here. It's not the same for every NPC.
         .genome(
             0.75,
-            state
-                .gene_library
-                .new_genetics(&mut state.rng, DnaType::Rna, true, GENE_LEN),
+            state.gene_library.new_genetics(
+                state.rng_streams.get(RngStream::WorldGen),
+                DnaType::Rna,
+                true,
+                GENE_LEN,
+            ),
         )
         .control(Controller::Npc(Box::new(AiVirus::new())));
 
@@ -95,9 +105,12 @@ fn test_random_ai() {
         // TODO: Pull genome create out of here. It's not the same for every NPC.
         .genome(
             0.75,
-            state
-                .gene_library
-                .new_genetics(&mut state.rng, DnaType::Rna, true, GENE_LEN),
+            state.gene_library.new_genetics(
+                state.rng_streams.get(RngStream::WorldGen),
+                DnaType::Rna,
+                true,
+                GENE_LEN,
+            ),
         )
         .control(Controller::Npc(Box::new(AiVirus::new())));
 
@@ -163,6 +176,9 @@ fn _create_minimal_world() -> ((i32, i32), GameState, GameObjects) {
                     max_hp: 1,
                     hp: 1,
                     volume: 1,
+                    anchor: 0,
+                    biomass: 0,
+                    carry_weight: 0,
                 },
                 Dna::default(),
             ),
@@ -173,3 +189,24 @@ fn _create_minimal_world() -> ((i32, i32), GameState, GameObjects) {
 
     ((p_x, p_y), state, objects)
 }
+
+/// Trail followers plan to go uphill along the scent, which the AI debug overlay shows.
+#[test]
+fn test_trail_follower_plan() {
+    use crate::core::position::Position;
+    use crate::entity::ai::AiTrailFollower;
+    use crate::entity::control::Ai;
+    use crate::entity::object::Object;
+
+    let ((p_x, p_y), _state, mut objects) = _create_minimal_world();
+    let east = Position::new(p_x + 1, p_y);
+    let north = Position::new(p_x, p_y - 1);
+    objects.leave_scent(&north);
+    objects.leave_scent(&east);
+    objects.decay_chemicals();
+    objects.leave_scent(&east);
+
+    let follower = AiTrailFollower::new();
+    let owner = Object::new().position(p_x, p_y);
+    assert_eq!(follower.planned_path(&owner, &objects), vec![east]);
+}
@@ -0,0 +1,34 @@
+use crate::core::position::Position;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::ui::ambience::Ambience;
+use crate::ui::animations::AnimationClock;
+
+#[test]
+fn test_ambience_frames() {
+    let mut ambience = Ambience::new();
+    let mut clock = AnimationClock::new();
+    let speck_count = |ambience: &Ambience| {
+        (0..WORLD_HEIGHT)
+            .flat_map(|y| (0..WORLD_WIDTH).map(move |x| Position::new(x, y)))
+            .filter(|p| ambience.has_speck(p))
+            .count()
+    };
+    assert_eq!(speck_count(&ambience), 0);
+
+    // a new frame is only due after some time has passed
+    clock.tick(40.0);
+    assert!(!ambience.update(&clock));
+    clock.tick(70.0);
+    assert!(ambience.update(&clock));
+    assert!(speck_count(&ambience) > 0);
+    assert!(!ambience.has_speck(&Position::new(-1, 0)));
+    assert!(!ambience.has_speck(&Position::new(WORLD_WIDTH, WORLD_HEIGHT)));
+
+    for _ in 0..100 {
+        clock.tick(100.0);
+        ambience.update(&clock);
+        let shimmer = ambience.shimmer(&Position::new(3, 4));
+        assert!((0.0..=1.0).contains(&shimmer));
+    }
+    assert!(speck_count(&ambience) > 0);
+}
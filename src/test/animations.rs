@@ -0,0 +1,49 @@
+use crate::core::position::Position;
+use crate::ui::animations::{AnimationClock, Interval};
+use crate::ui::particle::{Particle, ParticleSystem};
+
+/// Run the particle system for a little over a second at the given frame rate. Returns the number of steps of an
+/// interval of 100 ms and the time in [ms] after which a particle with a lifetime of 500 ms
+/// expired.
+fn run_at(fps: f32) -> (u32, f32) {
+    let mut clock = AnimationClock::new();
+    let mut interval = Interval::new(100.0);
+    let mut particle_sys = ParticleSystem::new();
+    particle_sys.particles.push(Particle::new(
+        Position::new(1, 1),
+        (0, 0, 0),
+        (0, 0, 0),
+        '*',
+        500.0,
+    ));
+    let mut steps = 0;
+    let mut expired_at = None;
+    for _ in 0..(fps * 1.05).ceil() as usize {
+        clock.tick(1000.0 / fps);
+        steps += interval.advance(&clock);
+        particle_sys.update(&clock);
+        if particle_sys.particles.is_empty() && expired_at.is_none() {
+            expired_at = Some(clock.elapsed() as f32);
+        }
+    }
+    (steps, expired_at.unwrap())
+}
+
+#[test]
+fn test_animations_ignore_frame_rate() {
+    let (slow_steps, slow_expiry) = run_at(30.0);
+    let (fast_steps, fast_expiry) = run_at(144.0);
+    assert_eq!(slow_steps, 10);
+    assert_eq!(fast_steps, 10);
+    // particles expire within a frame of their lifetime
+    assert!((slow_expiry - 500.0).abs() <= 1000.0 / 30.0);
+    assert!((fast_expiry - 500.0).abs() <= 1000.0 / 144.0);
+}
+
+#[test]
+fn test_clock_caps_long_frames() {
+    let mut clock = AnimationClock::new();
+    clock.tick(5000.0);
+    assert!(clock.delta() < 5000.0);
+    assert_eq!(clock.elapsed(), clock.delta() as f64);
+}
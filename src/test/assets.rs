@@ -0,0 +1,51 @@
+use std::fs;
+
+use crate::assets::{self, AssetSource};
+use crate::ui::rex_assets::load_xp;
+
+#[test]
+fn test_sources_in_order_of_precedence() {
+    let root = std::env::temp_dir().join(format!("innit_assets_{}", std::process::id()));
+    let data_dir = root.join("data");
+    let mod_dir = root.join("mod");
+    fs::create_dir_all(data_dir.join("fonts")).unwrap();
+    fs::create_dir_all(mod_dir.join("fonts")).unwrap();
+    fs::write(mod_dir.join("fonts/rex_paint_8x8.png"), "").unwrap();
+    fs::write(mod_dir.join("palette.json"), "{}").unwrap();
+    fs::write(data_dir.join("palette.json"), "{}").unwrap();
+
+    let font = assets::FONT_8X8.sources_in(Some(data_dir.clone()), Some(mod_dir.clone()));
+    assert_eq!(
+        font,
+        vec![
+            AssetSource::Mod(mod_dir.join("fonts/rex_paint_8x8.png")),
+            AssetSource::Embedded("../resources/fonts/rex_paint_8x8.png"),
+        ]
+    );
+    let palette = assets::PALETTE.sources_in(Some(data_dir.clone()), Some(mod_dir.clone()));
+    assert_eq!(
+        palette,
+        vec![
+            AssetSource::UserData(data_dir.join("palette.json")),
+            AssetSource::Mod(mod_dir.join("palette.json")),
+        ]
+    );
+    assert!(assets::PALETTE.sources_in(None, None).is_empty());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_embedded_assets_load() {
+    let mut errors = Vec::new();
+    for asset in &[
+        assets::MENU_ART,
+        assets::CAPILLARY_PREFAB,
+        assets::ALVEOLUS_PREFAB,
+    ] {
+        assert!(load_xp(asset, &mut errors).is_some(), "{}", asset.name);
+    }
+    let font = assets::FONT_8X8.load(&mut errors, |_, bytes, _| Some(bytes.len()));
+    assert!(font.unwrap_or(0) > 0);
+    assert!(errors.is_empty(), "{:?}", errors);
+}
@@ -0,0 +1,41 @@
+use crate::core::game_state::GameState;
+use crate::entity::bark::{bark, Bark, BarkTrigger};
+use crate::entity::object::Object;
+
+#[test]
+fn test_barks_are_told_once() {
+    let mut state = GameState::with_seed(1, 7);
+    let barks = vec![
+        Bark {
+            trigger: BarkTrigger::Seen,
+            text: "Hello".to_string(),
+        },
+        Bark {
+            trigger: BarkTrigger::Interacted,
+            text: "Ouch".to_string(),
+        },
+    ];
+    let mut first = Object::new().barks(barks.clone());
+    let mut second = Object::new().barks(barks);
+    let told = |state: &GameState| {
+        state
+            .log
+            .messages
+            .iter()
+            .map(|m| m.text.clone())
+            .collect::<Vec<String>>()
+    };
+
+    bark(&mut state, &mut first, BarkTrigger::Seen);
+    assert_eq!(told(&state), vec!["Hello"]);
+    assert_eq!(first.barks.len(), 1);
+
+    // the same line isn't told again by another organism
+    bark(&mut state, &mut second, BarkTrigger::Seen);
+    bark(&mut state, &mut first, BarkTrigger::Seen);
+    assert_eq!(told(&state), vec!["Hello"]);
+
+    bark(&mut state, &mut second, BarkTrigger::Interacted);
+    assert_eq!(told(&state), vec!["Hello", "Ouch"]);
+    assert!(second.barks.is_empty());
+}
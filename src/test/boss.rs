@@ -0,0 +1,35 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::position::Position;
+use crate::entity::boss::boss_from_template;
+use crate::entity::control::Controller;
+use crate::raws::{load_boss_templates, load_object_templates};
+
+#[test]
+fn test_boss_phases() {
+    let mut errors = Vec::new();
+    let bosses = load_boss_templates(&mut errors);
+    let templates = load_object_templates(&mut errors);
+    let boss = &bosses[0];
+    let template = templates.iter().find(|t| t.npc == boss.npc).unwrap();
+
+    let mut state = GameState::new(1);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let mut macrophage =
+        boss_from_template(&mut state, boss, template, Position::new(10, 10)).unwrap();
+    assert_eq!(macrophage.boss_name().as_deref(), Some(boss.name.as_str()));
+    let attack_level = macrophage.match_action("attack").unwrap().get_level();
+
+    // dropping to a single hit point passes through all phases at once
+    macrophage.actuators.hp = 1;
+    let mut control = macrophage.control.take();
+    if let Some(Controller::Npc(ai)) = control.as_mut() {
+        ai.act(&mut state, &mut objects, &mut macrophage);
+    }
+    assert_eq!(macrophage.actuators.hp, 9);
+    assert_eq!(
+        macrophage.match_action("attack").unwrap().get_level(),
+        attack_level + 2
+    );
+}
@@ -0,0 +1,17 @@
+use crate::core::bug_report::InputRecorder;
+use crate::ui::game_input::{PlayerAction, PlayerInput};
+
+#[test]
+fn test_input_recorder() {
+    let mut recorder = InputRecorder::new();
+    for turn in 0..250 {
+        recorder.record(turn, &PlayerInput::PlayInput(PlayerAction::PassTurn));
+    }
+    recorder.record(250, &PlayerInput::Undefined);
+    assert_eq!(recorder.inputs().count(), 200);
+    assert_eq!(recorder.inputs().next().map(|i| i.turn), Some(50));
+
+    assert!(!recorder.toggle());
+    recorder.record(251, &PlayerInput::PlayInput(PlayerAction::PassTurn));
+    assert_eq!(recorder.inputs().last().map(|i| i.turn), Some(249));
+}
@@ -0,0 +1,60 @@
+use crate::core::clock::{ClockTime, IMMUNE_PATROL_INTERVAL, MINUTES_PER_TURN};
+use crate::raws::difficulty::Difficulty;
+
+#[test]
+fn test_clock_time_from_turn() {
+    let start = ClockTime::from_turn(0);
+    assert_eq!(start.to_string(), "day 1 08:00");
+    assert!(start.is_full_hour());
+    assert!(!ClockTime::from_turn(1).is_full_hour());
+
+    let turns_per_day = (24 * 60 / MINUTES_PER_TURN) as u128;
+    let next_day = ClockTime::from_turn(turns_per_day + 1);
+    assert_eq!((next_day.day, next_day.hour), (2, 8));
+    assert!(next_day > start);
+
+    // the host digests its lunch for a while
+    let lunch = ClockTime {
+        day: 1,
+        hour: 12,
+        minute: 30,
+    };
+    assert!(lunch.nutrient_cycle() > 100);
+    let morning = ClockTime {
+        day: 1,
+        hour: 10,
+        minute: 0,
+    };
+    assert_eq!(morning.nutrient_cycle(), 100);
+}
+
+/// The immune system patrols once a night, more or less often depending on the difficulty.
+#[test]
+fn test_patrol_frequency() {
+    let at = |day, hour| ClockTime {
+        day,
+        hour,
+        minute: 0,
+    };
+    let standard = Difficulty::default().immune_interval(IMMUNE_PATROL_INTERVAL);
+    assert!(at(1, 3).is_patrol_hour(standard));
+    assert!(at(2, 3).is_patrol_hour(standard));
+    assert!(!at(1, 15).is_patrol_hour(standard));
+
+    let brutal = Difficulty {
+        immune_frequency: 2.0,
+        ..Difficulty::default()
+    };
+    let interval = brutal.immune_interval(IMMUNE_PATROL_INTERVAL);
+    assert!(at(1, 3).is_patrol_hour(interval));
+    assert!(at(1, 15).is_patrol_hour(interval));
+
+    let casual = Difficulty {
+        immune_frequency: 0.5,
+        ..Difficulty::default()
+    };
+    let interval = casual.immune_interval(IMMUNE_PATROL_INTERVAL);
+    assert!(at(1, 3).is_patrol_hour(interval));
+    assert!(!at(2, 3).is_patrol_hour(interval));
+    assert!(at(3, 3).is_patrol_hour(interval));
+}
@@ -0,0 +1,28 @@
+use crate::core::game_env::CombatDetail;
+use crate::entity::combat::Hit;
+use crate::entity::object::Object;
+
+#[test]
+fn test_hit_breakdown() {
+    let attacker = Object::new().visualize("Killer Cell", 'K', (255, 255, 255));
+    let mut target = Object::new().visualize("Spore", 's', (255, 255, 255));
+    target.actuators.hp = 5;
+    target.dormant = true;
+
+    let hit = Hit::resolve(&attacker, &target, 4);
+    assert_eq!(hit.damage, 2);
+    assert_eq!(hit.dormancy, 2);
+    assert_eq!(hit.hp_left, 3);
+    assert_eq!(
+        hit.describe(CombatDetail::Summary),
+        "Killer Cell attacked Spore for 2 damage"
+    );
+    assert_eq!(
+        hit.describe(CombatDetail::Full),
+        "Killer Cell attacked Spore for 2 damage (attack 4, dormancy -2, 3 hp left)"
+    );
+
+    // any hit deals at least one point of damage
+    let hit = Hit::resolve(&attacker, &target, 0);
+    assert_eq!(hit.damage, 1);
+}
@@ -0,0 +1,137 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::position::Position;
+use crate::core::world::connectivity::{ensure_connectivity, find_unreachable};
+use crate::core::world::gates::place_gate;
+use crate::core::world::prefab::PrefabRegistry;
+use crate::core::world::world_gen_organic::OrganicsWorldGenerator;
+use crate::core::world::{Tile, WorldGen};
+use crate::raws::{load_boss_templates, load_object_templates, load_spawns, load_terrain};
+
+#[test]
+fn test_carve_corridor() {
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    for (x, y) in &[(10, 10), (11, 10), (20, 15)] {
+        objects
+            .get_tile_at(*x, *y)
+            .replace(Tile::empty(*x as i32, *y as i32, false));
+    }
+    let start = Position::new(10, 10);
+    assert_eq!(
+        find_unreachable(&mut objects, start),
+        Some(Position::new(20, 15))
+    );
+    // the shortest corridor from (20, 15) to (11, 10) takes 14 steps, ending on an open tile
+    assert_eq!(ensure_connectivity(&mut objects, start), 13);
+    assert_eq!(find_unreachable(&mut objects, start), None);
+}
+
+#[test]
+fn test_generated_worlds_connected() {
+    let mut errors = Vec::new();
+    let spawns = load_spawns(&mut errors);
+    let object_templates = load_object_templates(&mut errors);
+    let prefabs = PrefabRegistry::load(&mut errors);
+    let terrain = load_terrain(&mut errors);
+    let bosses = load_boss_templates(&mut errors);
+    let mut gates = 0;
+    for seed in 0..20 {
+        let level = 1 + (seed % 6) as u32;
+        let mut state = GameState::with_seed(level, seed);
+        let mut objects = GameObjects::new();
+        objects.blank_world();
+        let mut generator =
+            OrganicsWorldGenerator::new(prefabs.clone(), terrain.clone(), bosses.clone());
+        generator.make_world(&mut state, &mut objects, &spawns, &object_templates, level);
+        let (x, y) = generator.get_player_start_pos();
+        // the tissue behind a valve counts as connected, once its key is found
+        for tile in objects.get_vector_mut().iter_mut().flatten() {
+            if let Some(barrier) = tile.tile.as_mut().and_then(|t| t.barrier.as_mut()) {
+                gates += barrier.key.take().map_or(0, |_| 1);
+            }
+        }
+        assert_eq!(
+            find_unreachable(&mut objects, Position::new(x, y)),
+            None,
+            "world of seed {} is not connected",
+            seed
+        );
+    }
+    assert!(gates > 0, "no world is gated");
+}
+
+/// A valve seals off the room at the end of a corridor and its key is left in the other room.
+#[test]
+fn test_place_gate() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let mut open =
+        |x: i32, y: i32| objects.replace_tile(&Position::new(x, y), Tile::empty(x, y, false));
+    for y in 5..15 {
+        for x in 5..15 {
+            open(x, y);
+        }
+    }
+    for x in 15..20 {
+        open(x, 10);
+    }
+    for y in 7..13 {
+        for x in 20..26 {
+            open(x, y);
+        }
+    }
+    let start = Position::new(6, 6);
+
+    let gate = place_gate(&mut state, &mut objects, start).unwrap();
+    assert!((15..20).contains(&gate.x));
+    let key = objects.get_barrier(&gate).unwrap().key.unwrap();
+    assert_eq!(
+        find_unreachable(&mut objects, start).map(|p| p.x >= gate.x),
+        Some(true)
+    );
+    let debris = objects.get_tiles().iter().flatten().find_map(|o| {
+        o.tile
+            .as_ref()
+            .and_then(|t| t.debris.as_ref())
+            .map(|d| (o.pos, d))
+    });
+    let (pos, debris) = debris.unwrap();
+    assert!(pos.x < 15);
+    assert_eq!(debris.genes[0].trait_name, key);
+}
+
+/// Chokepoints that don't seal off enough tissue are left looking as they did before.
+#[test]
+fn test_rejected_gate_keeps_tile() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    for y in 5..15 {
+        for x in 5..15 {
+            objects.replace_tile(&Position::new(x, y), Tile::empty(x, y, false));
+        }
+    }
+    // a short dead end, too small to be worth a gate
+    for x in 15..20 {
+        let mut tile = Tile::empty(x, 10, false);
+        tile.visual.glyph = ',';
+        tile.visual.name = "mucus".to_string();
+        objects.replace_tile(&Position::new(x, 10), tile);
+    }
+
+    assert_eq!(
+        place_gate(&mut state, &mut objects, Position::new(6, 6)),
+        None
+    );
+    for x in 15..20 {
+        let tile = objects.get_tile_at(x, 10).as_ref().unwrap();
+        assert_eq!(
+            (tile.visual.glyph, tile.visual.name.as_str()),
+            (',', "mucus")
+        );
+        assert!(!tile.physics.is_blocking);
+        assert!(objects.get_barrier(&Position::new(x as i32, 10)).is_none());
+    }
+}
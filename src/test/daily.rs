@@ -0,0 +1,29 @@
+use crate::core::daily::{daily_seed, date_from_days};
+use crate::core::highscores::{Highscores, Score};
+
+#[test]
+fn test_daily_dates() {
+    assert_eq!(date_from_days(0), (1970, 1, 1));
+    assert_eq!(date_from_days(19_723), (2024, 1, 1));
+    assert_eq!(date_from_days(19_782), (2024, 2, 29));
+    assert_eq!(date_from_days(-1), (1969, 12, 31));
+
+    assert_eq!(daily_seed("2024-01-01"), daily_seed("2024-01-01"));
+    assert_ne!(daily_seed("2024-01-01"), daily_seed("2024-01-02"));
+}
+
+#[test]
+fn test_highscore_ranking() {
+    let mut highscores = Highscores::new();
+    let score = |turns, bosses| Score {
+        turns,
+        bosses,
+        daily: None,
+        difficulty: "standard".to_string(),
+    };
+    assert_eq!(highscores.insert(score(100, 0)), Some(0));
+    assert_eq!(highscores.insert(score(50, 1)), Some(0));
+    assert_eq!(highscores.insert(score(80, 0)), Some(2));
+    let turns: Vec<u128> = highscores.entries().iter().map(|s| s.turns).collect();
+    assert_eq!(turns, vec![50, 100, 80]);
+}
@@ -0,0 +1,79 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::{GameState, MessageLog, MsgClass};
+use crate::entity::ai::AiPassive;
+use crate::entity::control::Controller;
+use crate::entity::danger::{Danger, DangerWatch};
+use crate::entity::faction::Faction;
+use crate::entity::genetics::DnaType;
+use crate::entity::object::Object;
+use crate::entity::player::PlayerCtrl;
+
+/// Automated actions are interrupted by newly sighted foes, alerts and injuries, but not by foes
+/// that were already in sight when they began.
+#[test]
+fn test_danger_watch() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    let mut player = Object::new()
+        .faction(Faction::Lineage)
+        .control(Controller::Player(PlayerCtrl::new()));
+    player.actuators.hp = 10;
+    let antibody = |visible: bool| {
+        let mut antibody = Object::new()
+            .living(true)
+            .visualize("Antibody", 'y', (255, 255, 255))
+            .faction(Faction::ImmuneSystem)
+            .control(Controller::Npc(Box::new(AiPassive)));
+        antibody.physics.is_visible = visible;
+        antibody
+    };
+    objects.get_vector_mut().push(Some(player));
+    objects.get_vector_mut().push(Some(antibody(true)));
+    objects.get_vector_mut().push(Some(antibody(false)));
+    state.player_idx = 0;
+
+    let watch = DangerWatch::new(&state, &objects, 0.5);
+    assert_eq!(watch.check(&state, &objects, 0.5), None);
+    assert_eq!(watch.check(&state, &objects, 0.75), Some(Danger::Threat));
+
+    objects[2].as_mut().unwrap().physics.is_visible = true;
+    assert_eq!(
+        watch.check(&state, &objects, 0.5),
+        Some(Danger::Hostile("Antibody".to_string()))
+    );
+    objects[2].as_mut().unwrap().physics.is_visible = false;
+
+    state.log.add("Something burns!", MsgClass::Alert);
+    assert_eq!(watch.check(&state, &objects, 0.5), Some(Danger::Alert));
+
+    objects[0].as_mut().unwrap().actuators.hp = 9;
+    assert_eq!(watch.check(&state, &objects, 0.5), Some(Danger::Hurt));
+}
+
+/// Allies of the same faction are no threat, however well armed they are.
+#[test]
+fn test_allies_pose_no_threat() {
+    let state = GameState::with_seed(1, 7);
+    let traits: Vec<String> = ["Attack", "Attack", "Move"]
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+    let raw_dna = state.gene_library.trait_strs_to_dna(&traits).unwrap();
+    let cell = |x: i32, faction: Faction| {
+        Object::new()
+            .position(x, 5)
+            .living(true)
+            .faction(faction)
+            .control(Controller::Npc(Box::new(AiPassive)))
+            .genome(
+                1.0,
+                state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna),
+            )
+    };
+    let owner = cell(5, Faction::GutFlora);
+    let ally = cell(6, Faction::GutFlora);
+    let foe = cell(4, Faction::Pathogens);
+
+    assert_eq!(owner.assess_threat(&ally), 0.0);
+    assert!(owner.assess_threat(&foe) > 0.0);
+}
@@ -0,0 +1,112 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::util::game_rng::RngStream;
+use rand::Rng;
+
+use super::save::headless_game;
+
+const TURNS: u128 = 1000;
+const CHECK_INTERVAL: u128 = 100;
+
+/// Hash everything that is saved of a game. Maps are ordered by their keys when converted to a
+/// JSON value, so that equal games hash equally regardless of how their hash maps are laid out.
+fn world_hash(state: &GameState, objects: &GameObjects) -> u64 {
+    let canonical = serde_json::to_value((state, objects)).unwrap().to_string();
+    let mut hasher = DefaultHasher::new();
+    canonical.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_simulation_is_deterministic() {
+    let (mut state_a, mut objects_a) = headless_game(1234);
+    let (mut state_b, mut objects_b) = headless_game(1234);
+    assert_eq!(
+        world_hash(&state_a, &objects_a),
+        world_hash(&state_b, &objects_b),
+        "worlds differ after generation"
+    );
+    while state_a.turn < TURNS {
+        state_a.simulate_turn(&mut objects_a);
+        state_b.simulate_turn(&mut objects_b);
+        if state_a.turn % CHECK_INTERVAL == 0 {
+            assert_eq!(
+                world_hash(&state_a, &objects_a),
+                world_hash(&state_b, &objects_b),
+                "simulations diverged by turn {}",
+                state_a.turn
+            );
+        }
+    }
+    // every subsystem, loot included, keeps drawing the same numbers
+    for stream in RngStream::ALL.iter() {
+        assert_eq!(
+            state_a.rng_for(*stream).gen::<u64>(),
+            state_b.rng_for(*stream).gen::<u64>(),
+            "{:?} streams diverged",
+            stream
+        );
+    }
+}
+
+/// Two organisms move into the same tile in one turn. On odd turns the later one pushes the first
+/// back, on even turns the first one keeps the tile.
+#[test]
+fn test_contested_tile() {
+    use crate::core::position::Position;
+    use crate::core::world::Tile;
+    use crate::entity::action::hereditary::ActMove;
+    use crate::entity::action::{Action, ActionResult, Target};
+    use crate::entity::object::{InventoryItem, Object};
+
+    for (turn, is_pushed_back) in [(1, true), (2, false)] {
+        let mut state = GameState::with_seed(1, 7);
+        let mut objects = GameObjects::new();
+        objects.blank_world();
+        for x in 4..=6 {
+            objects.replace_tile(&Position::new(x, 5), Tile::empty(x, 5, false));
+        }
+        state.turn = turn;
+        // an item lying in the contested tile stays where it is
+        let mut item = Object::new().position(5, 5).living(true);
+        item.item = Some(InventoryItem::new("Plasmid", None));
+        objects.push(item);
+        let mut first = Object::new()
+            .position(4, 5)
+            .living(true)
+            .physical(true, false, false);
+        let mut second = Object::new()
+            .position(6, 5)
+            .living(true)
+            .physical(true, false, false);
+        let mut step =
+            |target: Target, idx: usize, owner: &mut Object, objects: &mut GameObjects| {
+                let mut act = ActMove::new();
+                act.set_target(target);
+                act.set_level(1);
+                state.obj_idx = idx;
+                act.perform(&mut state, objects, owner)
+            };
+
+        let first_idx = objects.get_obj_count();
+        let result = step(Target::East, first_idx, &mut first, &mut objects);
+        assert!(matches!(result, ActionResult::Success { .. }));
+        objects.push(first);
+        let result = step(Target::West, first_idx + 1, &mut second, &mut objects);
+        let item = objects[first_idx - 1].as_ref().unwrap();
+        assert_eq!(item.pos, Position::new(5, 5));
+        let first = objects.get_vector().last().unwrap().as_ref().unwrap();
+        if is_pushed_back {
+            assert!(matches!(result, ActionResult::Success { .. }));
+            assert_eq!(first.pos, Position::new(4, 5));
+            assert_eq!(second.pos, Position::new(5, 5));
+        } else {
+            assert!(matches!(result, ActionResult::Failure));
+            assert_eq!(first.pos, Position::new(5, 5));
+            assert_eq!(second.pos, Position::new(6, 5));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::core::ecosystem::Ecosystem;
+use crate::raws::ecosystem::EcosystemBounds;
+
+/// The regulator spawns more and feeds better while the population is too small, holds back while
+/// it is too large and otherwise drifts back to the usual rates.
+#[test]
+fn test_ecosystem_census() {
+    let bounds = EcosystemBounds {
+        census_interval: 10,
+        min_population: 5,
+        max_population: 20,
+        spawns_per_census: 2.0,
+        step: 0.5,
+        min_spawn_rate: 0.0,
+        max_spawn_rate: 2.0,
+        min_nutrient_rate: 0.5,
+        max_nutrient_rate: 1.5,
+    };
+    let mut ecosystem = Ecosystem::new(bounds);
+    assert!(ecosystem.is_census_due(30));
+    assert!(!ecosystem.is_census_due(35));
+
+    // a dwindling population
+    assert_eq!(ecosystem.census(2), 3);
+    assert_eq!(ecosystem.nutrients(100), 150);
+    assert_eq!(ecosystem.census(2), 4);
+    assert_eq!(ecosystem.nutrient_rate, 1.5);
+    // spawns never overshoot the upper bound
+    assert_eq!(ecosystem.census(4), 4);
+    assert_eq!(ecosystem.census(18), 2);
+
+    // a booming population
+    assert_eq!(ecosystem.census(25), 0);
+    assert_eq!(ecosystem.census(25), 0);
+    assert_eq!(ecosystem.census(25), 0);
+    assert_eq!(ecosystem.census(25), 0);
+    assert_eq!(ecosystem.spawn_rate, 0.0);
+    assert_eq!(ecosystem.nutrients(100), 50);
+
+    // back to normal
+    assert_eq!(ecosystem.census(10), 1);
+    assert_eq!(ecosystem.census(10), 2);
+    assert_eq!(ecosystem.census(10), 2);
+    assert_eq!(ecosystem.nutrient_rate, 1.0);
+}
+
+/// Once a species dies out, a new strain of it migrates in from the edge of the world.
+#[test]
+fn test_extinction_recolonized() {
+    use std::collections::BTreeMap;
+
+    use crate::core::ecosystem::recolonize;
+    use crate::core::game_objects::GameObjects;
+    use crate::core::game_state::GameState;
+    use crate::core::world::Tile;
+    use crate::raws::{load_object_templates, load_spawns};
+
+    let mut ecosystem = Ecosystem::default();
+    let lineages: BTreeMap<String, usize> = [("Lactobacillus", 2), ("Virus", 1)]
+        .iter()
+        .map(|(species, count)| (species.to_string(), *count))
+        .collect();
+    assert!(ecosystem.record_lineages(lineages.clone()).is_empty());
+    let mut survivors = lineages;
+    survivors.remove("Virus");
+    assert_eq!(ecosystem.record_lineages(survivors), vec!["Virus"]);
+
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    for (x, y) in &[(1, 5), (2, 5), (1, 6), (40, 25)] {
+        objects
+            .get_tile_at(*x, *y)
+            .replace(Tile::empty(*x as i32, *y as i32, false));
+    }
+    let mut errors = Vec::new();
+    let spawns = load_spawns(&mut errors);
+    let templates = load_object_templates(&mut errors);
+    let migrants = recolonize(
+        &mut state,
+        &mut objects,
+        &spawns,
+        &templates,
+        "Lactobacillus",
+    );
+    assert_eq!(migrants, 2);
+    let arrivals: Vec<_> = objects
+        .get_non_tiles()
+        .iter()
+        .flatten()
+        .filter(|o| o.visual.name == "Lactobacillus")
+        .map(|o| o.pos)
+        .collect();
+    assert_eq!(arrivals.len(), 2);
+    assert!(arrivals.iter().all(|p| p.x <= 2));
+
+    // organisms that aren't part of the spawn tables don't migrate
+    assert_eq!(
+        recolonize(&mut state, &mut objects, &spawns, &templates, "Antibody"),
+        0
+    );
+}
@@ -0,0 +1,71 @@
+use crate::core::encyclopedia::{discover_in_sight, entries, Topic};
+use crate::core::events::GameEvent;
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::world::object_from_template;
+use crate::entity::control::Controller;
+use crate::entity::player::PlayerCtrl;
+use crate::raws::load_object_templates;
+
+/// Entries unlock once the player carries a trait or sees a species, and stay in the save. Each
+/// species is announced the first time it comes into sight.
+#[test]
+fn test_discoveries_unlock_entries() {
+    let mut errors = Vec::new();
+    let templates = load_object_templates(&mut errors);
+    let template = templates.iter().find(|t| t.npc == "Streptococcus").unwrap();
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    let mut player = object_from_template(&mut state, template, 5, 5)
+        .unwrap()
+        .control(Controller::Player(PlayerCtrl::new()));
+    player.visual.name = "Player".to_string();
+    let mut bacterium = object_from_template(&mut state, template, 6, 5).unwrap();
+    bacterium.physics.is_visible = true;
+    let mut hidden = object_from_template(&mut state, template, 7, 5).unwrap();
+    hidden.visual.name = "Lactobacillus".to_string();
+    hidden.physics.is_visible = false;
+    let gene = player.dna.simplified[0].trait_name.clone();
+    for object in [player, bacterium, hidden] {
+        objects.get_vector_mut().push(Some(object));
+    }
+
+    let before = entries(&state.discoveries, &state.gene_library, &templates);
+    assert!(before.iter().all(|e| !e.is_unlocked));
+
+    discover_in_sight(&mut state, &objects);
+    state.discover(Topic::Mechanic, "fever");
+    let after = entries(&state.discoveries, &state.gene_library, &templates);
+    let is_unlocked = |topic: Topic, name: &str| {
+        after
+            .iter()
+            .any(|e| e.topic == topic && e.name == name && e.is_unlocked)
+    };
+    assert!(is_unlocked(Topic::Trait, &gene));
+    assert!(is_unlocked(Topic::Species, "Streptococcus"));
+    assert!(!is_unlocked(Topic::Species, "Lactobacillus"));
+    assert!(is_unlocked(Topic::Mechanic, "Fever"));
+
+    // each species is introduced only once per run
+    let encountered = |state: &mut GameState| {
+        state
+            .events
+            .drain()
+            .into_iter()
+            .filter_map(|e| match e {
+                GameEvent::SpeciesEncountered { name, genes, .. } => Some((name, genes)),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let first = encountered(&mut state);
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].0, "Streptococcus");
+    assert!(!first[0].1.is_empty());
+    discover_in_sight(&mut state, &objects);
+    assert!(encountered(&mut state).is_empty());
+
+    let saved = serde_json::to_string(&state).unwrap();
+    let loaded: GameState = serde_json::from_str(&saved).unwrap();
+    assert_eq!(loaded.discoveries, state.discoveries);
+}
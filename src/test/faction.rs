@@ -0,0 +1,85 @@
+use crate::core::game_objects::GameObjects;
+use crate::entity::ai::AiPassive;
+use crate::entity::control::Controller;
+use crate::entity::faction::{Faction, Relations, Stance};
+use crate::entity::genetics::Receptor;
+use crate::entity::object::Object;
+use crate::entity::signal::{emit, SignalKind};
+
+#[test]
+fn test_relations() {
+    let mut relations = Relations::new();
+    let mut player = Object::new().faction(Faction::Lineage);
+    let virus = Object::new().faction(Faction::Pathogens);
+
+    assert!(relations.is_hostile(Faction::ImmuneSystem, &virus));
+    assert!(!relations.is_hostile(Faction::Pathogens, &virus));
+
+    // symbiosis genes calm down the gut flora
+    assert!(relations.is_hostile(Faction::GutFlora, &player));
+    player.processors.symbiosis = 1;
+    assert!(!relations.is_hostile(Faction::GutFlora, &player));
+
+    // standing is symmetric and bounded
+    relations.shift(Faction::Lineage, Faction::GutFlora, -500);
+    assert_eq!(
+        relations.standing(Faction::GutFlora, Faction::Lineage),
+        -100
+    );
+    assert!(relations.is_hostile(Faction::GutFlora, &player));
+
+    // unaligned objects hold no grudges
+    relations.shift(Faction::Unaligned, Faction::Pathogens, -500);
+    assert_eq!(
+        relations.standing(Faction::Unaligned, Faction::Pathogens),
+        0
+    );
+}
+
+#[test]
+fn test_signals() {
+    let relations = Relations::new();
+    let mut player = Object::new().position(5, 5).faction(Faction::Lineage);
+    player.processors.receptors.push(Receptor { typ: 1 });
+    let mut flora = Object::new()
+        .position(7, 5)
+        .living(true)
+        .faction(Faction::GutFlora)
+        .control(Controller::Npc(Box::new(AiPassive)));
+    flora.processors.receptors.push(Receptor { typ: 1 });
+    let deaf = Object::new()
+        .position(6, 6)
+        .living(true)
+        .faction(Faction::GutFlora)
+        .control(Controller::Npc(Box::new(AiPassive)));
+    let mut objects = GameObjects::new();
+    objects.push(flora);
+    objects.push(deaf);
+
+    // only organisms with a matching receptor pick up the signal
+    assert_eq!(
+        emit(&mut objects, &mut player, SignalKind::Peace, 2, |_, _| 40),
+        1
+    );
+    assert!(player.aura.is_some());
+    let mut flora = objects.extract_by_index(0).unwrap();
+    assert!(!relations.is_hostile_towards(&flora, &player));
+
+    // signals wear off
+    flora.decay_signals();
+    assert!(!relations.is_hostile_towards(&flora, &player));
+    flora.decay_signals();
+    assert!(relations.is_hostile_towards(&flora, &player));
+}
+
+#[test]
+fn test_stance_towards_player() {
+    let relations = Relations::new();
+    let player = Object::new().faction(Faction::Lineage);
+    let antibody = Object::new().faction(Faction::ImmuneSystem);
+    let offspring = Object::new().faction(Faction::Lineage);
+    let plasmid = Object::new();
+    assert_eq!(relations.stance(&antibody, &player), Stance::Hostile);
+    assert_eq!(relations.stance(&offspring, &player), Stance::Allied);
+    assert_eq!(relations.stance(&plasmid, &player), Stance::Neutral);
+}
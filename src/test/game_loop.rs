@@ -0,0 +1,101 @@
+use rltk::VirtualKeyCode;
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::{GameState, ObjectFeedback};
+use crate::entity::object::Object;
+use crate::game::{play_input, Game};
+use crate::ui::game_input::{key_to_action, PlayerInput};
+
+/// Number of times the player is handed an input, each followed by the turns of all other objects.
+const TICKS: usize = 300;
+
+/// Keys the scripted player presses over and over, with `Ctrl` and `Shift` held or not. Keys
+/// that don't map to an in-game action are ignored, just like the game loop would handle them
+/// outside of the world.
+const SCRIPT: [(VirtualKeyCode, bool, bool); 10] = [
+    (VirtualKeyCode::Right, false, false),
+    (VirtualKeyCode::Right, false, false),
+    (VirtualKeyCode::Up, false, false),
+    (VirtualKeyCode::D, false, false),
+    (VirtualKeyCode::Space, false, false),
+    (VirtualKeyCode::Left, false, false),
+    (VirtualKeyCode::Key1, false, false),
+    (VirtualKeyCode::Down, false, false),
+    (VirtualKeyCode::Numpad9, false, false),
+    (VirtualKeyCode::Tab, false, false),
+];
+
+fn check_invariants(state: &GameState, objects: &GameObjects, tick: usize) {
+    let player = objects[state.player_idx]
+        .as_ref()
+        .unwrap_or_else(|| panic!("no player at index {} in tick {}", state.player_idx, tick));
+    assert!(
+        player.is_player(),
+        "player index points elsewhere in tick {}",
+        tick
+    );
+    let energy = player.processors.energy;
+    assert!(
+        (0..=player.processors.energy_storage).contains(&energy),
+        "energy {} out of bounds in tick {}",
+        energy,
+        tick
+    );
+    assert!(player.actuators.hp <= player.actuators.max_hp);
+    let violations = state.validate(objects);
+    assert!(violations.is_empty(), "tick {}: {:?}", tick, violations);
+}
+
+#[test]
+fn test_game_loop_smoke() {
+    let mut errors = Vec::new();
+    let (mut state, mut objects) = Game::new_game(&mut errors, Some(42));
+    let mut script = SCRIPT.iter().cycle();
+    'ticking: for tick in 0..TICKS {
+        let (key, ctrl, shift) = *script.next().unwrap();
+        if let PlayerInput::PlayInput(action) = key_to_action(key, ctrl, shift) {
+            play_input(&mut state, &mut objects, action);
+        }
+        // everyone else acts until it's the player's turn again
+        loop {
+            if state.process_object(&mut objects) == ObjectFeedback::GameOver {
+                break 'ticking;
+            }
+            if state.is_players_turn() {
+                break;
+            }
+        }
+        check_invariants(&state, &objects, tick);
+    }
+    assert!(state.turn > 0, "no turn was completed");
+}
+
+#[test]
+fn test_validate_reports_violations() {
+    let mut errors = Vec::new();
+    let (mut state, mut objects) = Game::new_game(&mut errors, Some(42));
+    assert!(state.validate(&objects).is_empty());
+
+    if let Some(player) = objects[state.player_idx].as_mut() {
+        player.processors.energy = player.processors.energy_storage + 1;
+    }
+    let wall = objects
+        .get_vector()
+        .iter()
+        .flatten()
+        .find(|o| o.tile.is_some() && o.physics.is_blocking)
+        .map(|o| o.pos)
+        .unwrap();
+    objects.push(
+        Object::new()
+            .position(wall.x, wall.y)
+            .physical(true, false, false),
+    );
+    state.player_idx = objects.get_obj_count();
+
+    let violations = state.validate(&objects);
+    assert_eq!(violations.len(), 3, "{:?}", violations);
+    assert!(violations[0].starts_with("player index"));
+    assert!(violations[1].contains("energy"));
+    assert!(violations[2].contains("is blocked by"));
+}
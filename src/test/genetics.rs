@@ -1,4 +1,5 @@
 use crate::entity::genetics::DnaType;
+use crate::util::game_rng::RngStream;
 use crate::{
     core::game_state::GameState,
     entity::action::{hereditary::ActMove, inventory::ActPickUpItem},
@@ -35,7 +36,19 @@ fn test_dna_encoding() {
         metabolism: 1,
         energy_storage: 1,
         energy: 0,
+        reserve: 0,
+        reserve_capacity: 0,
+        upkeep: 0,
+        starvation: 0,
         receptors: Vec::new(),
+        resistances: Vec::new(),
+        heat_tolerance: 0,
+        ph_tolerance: 0,
+        symbiosis: 0,
+        repair: 0,
+        toxin_resistance: 0,
+        photosynthesis: 0,
+        chemosynthesis: 0,
     };
 
     let a = Actuators {
@@ -43,6 +56,9 @@ fn test_dna_encoding() {
         max_hp: 1,
         hp: 1,
         volume: 1,
+        anchor: 0,
+        biomass: 0,
+        carry_weight: 0,
     };
 
     let (_s, _p, _a, _) = gene_lib.dna_to_traits(DnaType::Nucleus, &dna);
@@ -82,11 +98,209 @@ fn test_dna_encoding() {
 #[test]
 fn test_dna_de_encoding() {
     let mut state = GameState::new(0);
-    let raw_dna = state.gene_library.new_dna(&mut state.rng, false, 10);
+    let raw_dna = state
+        .gene_library
+        .new_dna(state.rng_streams.get(RngStream::WorldGen), false, 10);
     let (_, _, _, d) = state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
     let traits: Vec<String> = d.simplified.iter().map(|t| t.trait_name.clone()).collect();
-    let raw_dna_2 = state
-        .gene_library
-        .trait_strs_to_dna(&mut state.rng, &traits);
+    let raw_dna_2 = state.gene_library.trait_strs_to_dna(&traits).unwrap();
     assert_eq!(raw_dna, raw_dna_2);
 }
+
+/// Decoding the same DNA twice, even with separate gene libraries, yields identical components
+/// down to the order of their actions.
+#[test]
+fn test_dna_decoding_is_stable() {
+    use crate::entity::genetics::GeneLibrary;
+
+    let mut state = GameState::with_seed(0, 7);
+    let raw_dna = state
+        .gene_library
+        .new_dna(state.rng_streams.get(RngStream::WorldGen), false, 40);
+    let other_library = GeneLibrary::new();
+    let first = state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+    let second = other_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+    assert_eq!(
+        serde_json::to_string(&first).unwrap(),
+        serde_json::to_string(&second).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_string(&state.gene_library).unwrap(),
+        serde_json::to_string(&other_library).unwrap()
+    );
+}
+
+#[test]
+fn test_trait_attribute_description() {
+    use crate::core::host::AntibioticClass;
+    use crate::entity::genetics::{TraitAttribute, RESERVE_PER_STORE};
+
+    assert_eq!(TraitAttribute::Hp.describe(), Some("+1 max hp".to_string()));
+    assert_eq!(
+        TraitAttribute::Reserve.describe(),
+        Some(format!("+1 storage, +{} reserve", RESERVE_PER_STORE))
+    );
+    assert_eq!(
+        TraitAttribute::Resistance(AntibioticClass::BetaLactam).describe(),
+        Some(format!("resists {}", AntibioticClass::BetaLactam.name()))
+    );
+    assert_eq!(TraitAttribute::None.describe(), None);
+}
+
+/// Compacting a genome strips the junk that isn't needed to keep the receptors in place, without
+/// changing anything about the organism.
+#[test]
+fn test_compact_dna_keeps_phenotype() {
+    let state = GameState::with_seed(0, 7);
+    let receptor = state
+        .gene_library
+        .trait_strs_to_dna(&["Receptor".to_string()])
+        .unwrap();
+    let movement = state
+        .gene_library
+        .trait_strs_to_dna(&["Move".to_string()])
+        .unwrap();
+    let raw_dna: Vec<u8> = [
+        &[0x00, 0x01, 0xff][..],
+        &receptor,
+        &[0x00, 0x01, 0xfe],
+        &movement,
+        &[0x00, 0x01, 0xfd],
+    ]
+    .concat();
+
+    let (s, p, a, d) = state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+    let (s2, p2, a2, d2) = state.gene_library.compact_dna(&d).unwrap();
+    assert_eq!(d2.raw.len(), 3 * 3);
+    assert_eq!(
+        serde_json::to_string(&(s, p, a)).unwrap(),
+        serde_json::to_string(&(s2, p2, a2)).unwrap()
+    );
+
+    // nothing left to strip
+    assert!(state.gene_library.compact_dna(&d2).is_none());
+}
+
+/// Junk genes shrug off point mutations, but can be reactivated into functional genes.
+#[test]
+fn test_junk_dna() {
+    use crate::entity::genetics::TraitFamily;
+
+    let mut state = GameState::with_seed(0, 7);
+    let raw_dna = vec![0x00, 0x01, 0xff, 0x00, 0x01, 0xfe];
+    let (_, _, _, d) = state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+    let rng = state.rng_streams.get(RngStream::Mutation);
+    for _ in 0..10 {
+        let mutated = state
+            .gene_library
+            .mutate_gene(rng, DnaType::Nucleus, &d.simplified[0]);
+        assert_eq!(mutated.trait_family, TraitFamily::Junk(0xff));
+    }
+
+    let reactivated = state.gene_library.reactivate_junk(rng, &d, 1).unwrap();
+    let (_, _, _, d2) = state
+        .gene_library
+        .dna_to_traits(DnaType::Nucleus, &reactivated);
+    let junk = |d: &crate::entity::genetics::Dna| {
+        d.simplified
+            .iter()
+            .filter(|t| matches!(t.trait_family, TraitFamily::Junk(_)))
+            .count()
+    };
+    assert_eq!(junk(&d2), 1);
+    assert_eq!(d2.simplified.len(), 2);
+
+    // without any junk there's nothing to reactivate
+    let (_, _, _, d3) = state.gene_library.dna_to_traits(
+        DnaType::Nucleus,
+        &state.gene_library.reactivate_junk(rng, &d2, 1).unwrap(),
+    );
+    assert!(state.gene_library.reactivate_junk(rng, &d3, 1).is_none());
+}
+
+/// Silenced genes stay in the genome, but aren't expressed.
+#[test]
+fn test_silenced_genes() {
+    use std::collections::BTreeSet;
+
+    let state = GameState::with_seed(0, 7);
+    let raw_dna = state
+        .gene_library
+        .trait_strs_to_dna(&["Move".to_string(), "Optical Sensor".to_string()])
+        .unwrap();
+    let (s, _, a, _) = state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+    let silenced: BTreeSet<usize> = [0, 1, 9].iter().copied().collect();
+    let (s2, _, a2, d2) =
+        state
+            .gene_library
+            .dna_to_marked_traits(DnaType::Nucleus, &raw_dna, &silenced);
+
+    assert_eq!(d2.raw, raw_dna);
+    assert_eq!(d2.simplified.len(), 2);
+    assert!(d2.simplified.iter().all(|g| g.silenced));
+    assert_eq!(d2.silenced, d2.marks_from_genes());
+    assert!(a.actions.iter().any(|a| a.get_identifier() == "move"));
+    assert!(!a2.actions.iter().any(|a| a.get_identifier() == "move"));
+    assert!(s2.sensing_range < s.sensing_range);
+}
+
+/// The upkeep is attributed to the traits by their number of genes.
+#[test]
+fn test_upkeep_by_trait() {
+    use crate::entity::genetics::BIOMASS_PER_UPKEEP;
+
+    let state = GameState::with_seed(0, 7);
+    let traits: Vec<String> = ["Move", "Optical Sensor", "Move"]
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+    let mut raw_dna = state.gene_library.trait_strs_to_dna(&traits).unwrap();
+    raw_dna.extend_from_slice(&[0x00, 0x01, 0xff]);
+    let (_, _, a, d) = state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+
+    let shares = d.upkeep_by_trait();
+    assert_eq!(shares.len(), 2);
+    assert_eq!(
+        (shares[0].trait_name.as_str(), shares[0].genes),
+        ("Move", 2)
+    );
+    let total: f32 = shares.iter().map(|s| s.upkeep).sum();
+    assert!((total - a.biomass as f32 / BIOMASS_PER_UPKEEP as f32).abs() < 0.001);
+}
+
+/// Producer genes yield energy on lit and nutrient-rich tiles, but never beyond the storage.
+#[test]
+fn test_producer_traits() {
+    use crate::core::world::Environment;
+    use crate::entity::object::Object;
+
+    let state = GameState::with_seed(0, 7);
+    let traits: Vec<String> = [
+        "Chlorophyll",
+        "Chlorophyll",
+        "Sulfur Oxidase",
+        "Energy Store",
+    ]
+    .iter()
+    .map(|t| t.to_string())
+    .collect();
+    let raw_dna = state.gene_library.trait_strs_to_dna(&traits).unwrap();
+    let (s, p, a, d) = state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+    assert_eq!((p.photosynthesis, p.chemosynthesis), (2, 1));
+    let mut producer = Object::new().genome(1.0, (s, p, a, d));
+
+    let dark = Environment::default();
+    assert_eq!(producer.produce(&dark), 0);
+    let lit = Environment { light: 1.0, ..dark };
+    assert_eq!(producer.produce(&lit), 2);
+    let rich = Environment {
+        nutrients: 1.5,
+        ..lit
+    };
+    producer.processors.energy = producer.processors.energy_storage - 1;
+    assert_eq!(producer.produce(&rich), 1);
+    assert_eq!(
+        producer.processors.energy,
+        producer.processors.energy_storage
+    );
+}
@@ -0,0 +1,54 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::host::{Host, HostCondition, HostEvent};
+use crate::util::game_rng::GameRng;
+
+#[test]
+fn test_host_collapse() {
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let mut rng = GameRng::new_from_u64_seed(0);
+
+    // a healthy host has fewer nutrients to spare
+    let mut host = Host::new();
+    assert!(host.nutrients() < 100);
+    assert!(host.update(&mut rng, &objects).is_empty());
+
+    // toxins poison the host until it collapses
+    host.health = 1.0;
+    host.release_toxins(1000);
+    assert_eq!(
+        host.update(&mut rng, &objects),
+        vec![
+            HostEvent::Collapsed,
+            HostEvent::ConditionStarted(HostCondition::Inflammation)
+        ]
+    );
+    assert!(host.collapsed);
+    assert_eq!(host.nutrients(), 100);
+
+    // it collapses only once until recovered
+    assert!(host.update(&mut rng, &objects).is_empty());
+}
+
+#[test]
+fn test_host_conditions() {
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    let mut rng = GameRng::new_from_u64_seed(0);
+
+    // a declining host runs a fever, which makes actions more costly
+    let mut host = Host::new();
+    host.health = 60.0;
+    host.release_toxins(3000);
+    let events = host.update(&mut rng, &objects);
+    assert!(events.contains(&HostEvent::ConditionStarted(HostCondition::Fever)));
+    assert!(host.energy_surcharge() > 0);
+
+    // phases started again merely last longer, and end in due time
+    assert!(!host.start_condition(HostCondition::Fever, 2));
+    assert!(host.start_condition(HostCondition::Medication, 1));
+    let events = host.update(&mut rng, &objects);
+    assert!(events.contains(&HostEvent::ConditionEnded(HostCondition::Medication)));
+    assert!(!host.has_condition(HostCondition::Medication));
+    assert!(host.has_condition(HostCondition::Fever));
+}
@@ -0,0 +1,15 @@
+use crate::ui::hud::SidebarTab;
+use crate::ui::settings::UiSettings;
+
+#[test]
+fn test_sidebar_tabs() {
+    assert_eq!(SidebarTab::Status.cycle(1), SidebarTab::Dna);
+    assert_eq!(SidebarTab::Status.cycle(-1), SidebarTab::Objectives);
+    assert_eq!(SidebarTab::Objectives.cycle(1), SidebarTab::Status);
+    assert_eq!(SidebarTab::Log.cycle(5), SidebarTab::Log);
+
+    // settings stored by older versions lack newer fields
+    let settings: UiSettings = serde_json::from_str(r#"{"sidebar_tab":"Log"}"#).unwrap();
+    assert_eq!(settings.sidebar_tab, SidebarTab::Log);
+    assert!(!settings.actions_collapsed);
+}
@@ -0,0 +1,18 @@
+use crate::ui::layers::{DirtyLayers, Layer};
+
+#[test]
+fn test_only_marked_layers_are_drawn() {
+    let mut dirty = DirtyLayers::new();
+    dirty.mark(Layer::Hud);
+    assert!(!dirty.take(Layer::World));
+    assert!(dirty.take(Layer::Hud));
+    assert!(
+        !dirty.take(Layer::Hud),
+        "a drawn layer is clean until marked again"
+    );
+
+    dirty.mark_all();
+    dirty.clear();
+    assert!(!dirty.is_dirty(Layer::World));
+    assert!(!dirty.is_dirty(Layer::Overlay));
+}
@@ -0,0 +1,38 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::world::object_from_template;
+use crate::entity::loot::DropRates;
+use crate::raws::load_object_templates;
+
+/// Dead organisms leave behind fragments of their own genes and part of their energy.
+#[test]
+fn test_loot_from_genome() {
+    let mut errors = Vec::new();
+    let templates = load_object_templates(&mut errors);
+    let template = templates.iter().find(|t| t.npc == "Streptococcus").unwrap();
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    let mut bacterium = object_from_template(&mut state, template, 5, 5).unwrap();
+    bacterium.drops = DropRates {
+        gene_chance: 1.0,
+        max_fragments: 2,
+        energy_share: 0.5,
+    };
+    bacterium.processors.energy = 10;
+    bacterium.processors.reserve = 0;
+    bacterium.die(&mut state, &mut objects);
+
+    let loot: Vec<_> = objects.get_vector().iter().flatten().collect();
+    assert_eq!(loot.len(), 3);
+    for fragment in &loot[..2] {
+        let gene = fragment.visual.name.trim_end_matches(" Fragment");
+        assert!(bacterium
+            .dna
+            .simplified
+            .iter()
+            .any(|g| g.trait_name == gene));
+    }
+    assert_eq!(loot[2].visual.name, "Energy Globule");
+    let absorb = loot[2].item.as_ref().unwrap().use_action.as_ref().unwrap();
+    assert_eq!(absorb.get_level(), 5);
+}
@@ -0,0 +1,42 @@
+use crate::entity::genetics::{DnaType, GeneLibrary};
+use crate::entity::mutation::{MutationHistory, MUTATION_HISTORY_LEN};
+use crate::util::generate_gray_code;
+
+#[test]
+fn test_mutation_history() {
+    let gene_lib = GeneLibrary::new();
+    let gray_code = generate_gray_code(5);
+    let dna = |codes: &[usize]| {
+        let raw: Vec<u8> = codes
+            .iter()
+            .flat_map(|c| vec![0x00, 0x01, gray_code[*c]])
+            .collect();
+        gene_lib.dna_to_traits(DnaType::Nucleus, &raw).3
+    };
+
+    let mut history = MutationHistory::new();
+    // a single substituted gene
+    history.record(1, &dna(&[1, 2, 3]), &dna(&[1, 4, 3]), &gene_lib);
+    // an inserted gene
+    history.record(2, &dna(&[1, 4, 3]), &dna(&[1, 4, 5, 3]), &gene_lib);
+
+    let events: Vec<_> = history.events().collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].gene_idx, 1);
+    assert_eq!(events[0].old.as_ref().unwrap().byte, gray_code[2]);
+    assert_eq!(events[0].new.as_ref().unwrap().byte, gray_code[4]);
+    assert_eq!(events[1].gene_idx, 2);
+    assert!(events[1].old.is_none());
+    assert_eq!(events[1].new.as_ref().unwrap().byte, gray_code[5]);
+    assert_eq!(history.latest_gene(), Some(2));
+
+    // the history forgets the oldest events
+    for turn in 0..MUTATION_HISTORY_LEN as u128 {
+        history.record(turn, &dna(&[1]), &dna(&[2]), &gene_lib);
+    }
+    assert_eq!(history.events().count(), MUTATION_HISTORY_LEN);
+
+    // deleted genes can't be highlighted
+    history.record(30, &dna(&[1, 2]), &dna(&[1]), &gene_lib);
+    assert_eq!(history.latest_gene(), None);
+}
@@ -0,0 +1,81 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::world::object_from_template;
+use crate::entity::action::organelle::{ActEquipOrganelle, ActUnequipOrganelle};
+use crate::entity::action::{Action, ActionResult};
+use crate::entity::genetics::DnaType;
+use crate::entity::object::Object;
+use crate::entity::organelle::{Organelle, ORGANELLE_SLOTS};
+use crate::raws::load_object_templates;
+
+/// Organelles add to the attributes of the genome, survive edits of the genome and are returned to
+/// the inventory once they are taken out again.
+#[test]
+fn test_organelles() {
+    let mut state = GameState::with_seed(0, 7);
+    let raw_dna = state
+        .gene_library
+        .trait_strs_to_dna(&["Cell Membrane".to_string()])
+        .unwrap();
+    let mut cell = Object::new().position(5, 5).living(true).genome(
+        1.0,
+        state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna),
+    );
+    let metabolism = cell.processors.metabolism;
+    let storage = cell.processors.energy_storage;
+    assert!(!cell
+        .actuators
+        .actions
+        .iter()
+        .any(|a| a.get_identifier() == "move"));
+
+    let mut objects = GameObjects::new();
+    for organelle in &[Organelle::Mitochondrion, Organelle::Flagellum] {
+        let equip = ActEquipOrganelle::new(*organelle);
+        let result = equip.perform(&mut state, &mut objects, &mut cell);
+        assert!(matches!(result, ActionResult::Success { .. }));
+    }
+    assert_eq!(cell.organelles.equipped.len(), ORGANELLE_SLOTS);
+    assert_eq!(cell.processors.metabolism, metabolism + 1);
+    assert_eq!(cell.processors.energy_storage, storage + 10);
+    assert!(cell
+        .actuators
+        .actions
+        .iter()
+        .any(|a| a.get_identifier() == "move"));
+
+    // the bonuses don't depend on the genome
+    let traits = state.gene_library.dna_to_traits(DnaType::Nucleus, &raw_dna);
+    cell.change_genome(traits.0, traits.1, traits.2, traits.3);
+    assert_eq!(cell.processors.metabolism, metabolism + 1);
+
+    // without a free slot the organelle goes back into the inventory
+    let equip = ActEquipOrganelle::new(Organelle::Vacuole);
+    let result = equip.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Failure));
+    assert_eq!(cell.inventory.items.len(), 1);
+
+    let unequip = ActUnequipOrganelle::new(0);
+    let result = unequip.perform(&mut state, &mut objects, &mut cell);
+    assert!(matches!(result, ActionResult::Success { .. }));
+    assert_eq!(cell.processors.metabolism, metabolism);
+    assert_eq!(cell.processors.energy_storage, storage);
+    assert_eq!(cell.inventory.items.len(), 2);
+    assert_eq!(cell.inventory.items[1].visual.name, "Mitochondrion");
+}
+
+/// Organelles found in the world are items without a genome.
+#[test]
+fn test_organelle_template() {
+    let mut errors = Vec::new();
+    let templates = load_object_templates(&mut errors);
+    let template = templates.iter().find(|t| t.npc == "Vacuole").unwrap();
+    let mut state = GameState::new(1);
+    let vacuole = object_from_template(&mut state, template, 5, 5).unwrap();
+    assert!(vacuole.dna.raw.is_empty());
+    let item = vacuole.item.as_ref().unwrap();
+    assert_eq!(
+        item.use_action.as_ref().unwrap().get_identifier(),
+        "equip organelle"
+    );
+}
@@ -31,3 +31,19 @@ fn test_adjacent() {
     assert!(!pos_1.is_adjacent(&pos_13));
     assert!(!pos_1.is_adjacent(&pos_14));
 }
+
+#[test]
+fn test_neighbour() {
+    use crate::core::position::Position;
+
+    let center = Position::new(10, 10);
+
+    assert!(!center.is_neighbour(&Position::new(10, 10)));
+    assert!(center.is_neighbour(&Position::new(10, 9)));
+    assert!(center.is_neighbour(&Position::new(11, 11)));
+    assert!(center.is_neighbour(&Position::new(9, 9)));
+    assert!(center.is_neighbour(&Position::new(11, 9)));
+    assert!(center.is_neighbour(&Position::new(9, 11)));
+    assert!(!center.is_neighbour(&Position::new(10, 8)));
+    assert!(!center.is_neighbour(&Position::new(12, 11)));
+}
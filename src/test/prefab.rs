@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::core::game_objects::GameObjects;
+use crate::core::position::Position;
+use crate::core::world::prefab::{Prefab, PrefabCell, PrefabRegistry};
+use crate::raws::prefab_template::PrefabTemplate;
+use rltk::rex::{XpColor, XpFile};
+
+/// Broken images are rejected instead of crashing the game.
+#[test]
+fn test_broken_xp_prefab() {
+    let mut xp = XpFile::new(3, 2);
+    xp.layers[0].width = 4;
+    assert!(Prefab::from_xp("test", &xp, &HashMap::new()).is_err());
+    xp.layers.clear();
+    assert!(Prefab::from_xp("test", &xp, &HashMap::new()).is_err());
+}
+
+#[test]
+fn test_prefab_from_xp() {
+    let mut xp = XpFile::new(3, 2);
+    let layer = &mut xp.layers[0];
+    layer.get_mut(0, 0).unwrap().ch = '#' as u32;
+    layer.get_mut(1, 0).unwrap().ch = '.' as u32;
+    layer.get_mut(2, 0).unwrap().ch = '#' as u32;
+    layer.get_mut(2, 0).unwrap().bg = XpColor::TRANSPARENT;
+    let prefab = Prefab::from_xp("test", &xp, &HashMap::new()).unwrap();
+    assert_eq!((prefab.width, prefab.height), (3, 2));
+    assert_eq!(prefab.cell(0, 0), PrefabCell::Wall);
+    assert_eq!(prefab.cell(1, 0), PrefabCell::Floor);
+    assert_eq!(prefab.cell(2, 0), PrefabCell::Keep);
+    assert_eq!(prefab.cell(0, 1), PrefabCell::Keep);
+
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    prefab.stamp(&mut objects, 5, 5);
+    assert!(
+        !objects
+            .get_tile_at(6, 5)
+            .as_ref()
+            .unwrap()
+            .physics
+            .is_blocking
+    );
+    assert!(
+        objects
+            .get_tile_at(5, 5)
+            .as_ref()
+            .unwrap()
+            .physics
+            .is_blocking
+    );
+
+    assert!(!PrefabRegistry::load(&mut Vec::new()).prefabs().is_empty());
+}
+
+#[test]
+fn test_prefab_transform() {
+    let mut markers = HashMap::new();
+    markers.insert('v', "Virus".to_string());
+    let template = PrefabTemplate {
+        name: "test".to_string(),
+        rows: vec!["#.v".to_string(), "## ".to_string()],
+        markers,
+    };
+    let prefab = Prefab::from_template(&template);
+    assert_eq!(prefab.spawns.len(), 1);
+    assert_eq!(prefab.spawns[0].pos, Position::new(2, 0));
+    assert_eq!(prefab.cell(2, 0), PrefabCell::Floor);
+
+    // rotating clockwise turns the top row into the right column
+    let rotated = prefab.rotated();
+    assert_eq!((rotated.width, rotated.height), (2, 3));
+    assert_eq!(rotated.cell(1, 0), PrefabCell::Wall);
+    assert_eq!(rotated.cell(1, 1), PrefabCell::Floor);
+    assert_eq!(rotated.cell(0, 2), PrefabCell::Keep);
+    assert_eq!(rotated.spawns[0].pos, Position::new(1, 2));
+
+    let mirrored = prefab.mirrored();
+    assert_eq!(mirrored.cell(2, 0), PrefabCell::Wall);
+    assert_eq!(mirrored.cell(0, 1), PrefabCell::Keep);
+    assert_eq!(mirrored.spawns[0].pos, Position::new(0, 0));
+}
@@ -0,0 +1,99 @@
+use crate::core::game_state::GameState;
+use crate::core::story::Milestone;
+use crate::raws::object_template::DnaTemplate;
+use crate::raws::{check_raws, parse_entries};
+use crate::util::game_rng::RngStream;
+
+const STORY: &str = r#"[
+    {
+        "id": "first",
+        "trigger": "GenomeChanged",
+        "title": "First",
+        "text": ["It begins."]
+    },
+    {
+        "id": "second",
+        "trigger": "CellDivided",
+        "title": "Second",
+        "text": ["It goes on."]
+    },
+    {
+        "id": "third",
+        "trigger": "PredatorKilled",
+        "text": ["It ends."]
+    }
+]"#;
+
+#[test]
+fn test_invalid_entries_are_skipped() {
+    let mut errors = Vec::new();
+    let milestones: Vec<Milestone> = parse_entries("story.json", STORY, &mut errors).unwrap();
+    assert_eq!(milestones.len(), 1);
+    assert_eq!(milestones[0].id, "first");
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(
+        errors[0].starts_with("story.json:10: skipped entry #1 (second), field `trigger`: "),
+        "{}",
+        errors[0]
+    );
+    assert!(errors[1].starts_with("story.json:"), "{}", errors[1]);
+    assert!(
+        errors[1].contains("skipped entry #2 (third)"),
+        "{}",
+        errors[1]
+    );
+    assert!(errors[1].contains("missing field `title`"), "{}", errors[1]);
+
+    let broken: Option<Vec<Milestone>> = parse_entries("story.json", "[{}", &mut errors);
+    assert!(broken.is_none());
+    assert_eq!(errors.len(), 3);
+}
+
+/// Explicit DNA that isn't made of hexadecimal bytes is reported and skipped when loading.
+#[test]
+fn test_invalid_dna_is_skipped() {
+    let templates = r#"[
+        { "Explicit": { "dna": "00 01 0c" } },
+        { "Explicit": { "dna": "00 zz" } }
+    ]"#;
+    let mut errors = Vec::new();
+    let parsed: Vec<DnaTemplate> = parse_entries("objects.json", templates, &mut errors).unwrap();
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("invalid dna 'zz'"), "{}", errors[0]);
+}
+
+/// Defined DNA with a trait that the gene library doesn't know is reported and skipped when
+/// loading, and refused when creating DNA from it.
+#[test]
+fn test_unknown_trait_is_skipped() {
+    let templates = r#"[
+        { "Defined": { "traits": ["Move", "Cell Membrane"] } },
+        { "Defined": { "traits": ["Move", "Telekinesis"] } }
+    ]"#;
+    let mut errors = Vec::new();
+    let parsed: Vec<DnaTemplate> = parse_entries("objects.json", templates, &mut errors).unwrap();
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(errors.len(), 1);
+    assert!(
+        errors[0].contains("unknown genetic trait: Telekinesis"),
+        "{}",
+        errors[0]
+    );
+
+    let mut state = GameState::with_seed(0, 7);
+    let bogus = DnaTemplate::Defined {
+        traits: vec!["Telekinesis".to_string()],
+    };
+    let dna = state.gene_library.dna_from_template(
+        state.rng_streams.get(RngStream::WorldGen),
+        &bogus,
+        false,
+    );
+    assert_eq!(dna, Err("unknown genetic trait: Telekinesis".to_string()));
+}
+
+#[test]
+fn test_built_in_raws_are_valid() {
+    assert_eq!(check_raws(), Vec::<String>::new());
+}
@@ -0,0 +1,57 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::position::Position;
+use crate::core::world::{generate_regions, Region, Tile, BODY_PH, REGION_BLOCK};
+use crate::entity::action::Target;
+
+#[test]
+fn test_generate_regions() {
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    // open up three blocks: one with a stream, one acidic and one plain
+    for (block, ph) in [(0, BODY_PH), (1, BODY_PH - 1.0), (2, BODY_PH)] {
+        for y in 1..REGION_BLOCK {
+            for x in block * REGION_BLOCK..(block + 1) * REGION_BLOCK {
+                let mut floor = Tile::empty(x, y, false);
+                if let Some(tile) = floor.tile.as_mut() {
+                    tile.environment.ph = ph;
+                }
+                objects.get_tile_at(x as usize, y as usize).replace(floor);
+            }
+        }
+    }
+    for x in 1..6 {
+        objects.set_current(&Position::new(x, 2), Target::East);
+    }
+    generate_regions(&mut objects);
+
+    assert_eq!(
+        objects.get_region(&Position::new(3, 3)),
+        Region::CapillaryBed
+    );
+    assert_eq!(
+        objects.get_region(&Position::new(REGION_BLOCK + 3, 3)),
+        Region::StomachLining
+    );
+    assert_eq!(
+        objects.get_region(&Position::new(2 * REGION_BLOCK + 3, 3)),
+        Region::MucusLayer
+    );
+}
+
+#[test]
+fn test_enter_region() {
+    let mut state = GameState::with_seed(1, 7);
+    state.enter_region(Region::CapillaryBed);
+    state.enter_region(Region::MucusLayer);
+    state.enter_region(Region::CapillaryBed);
+    let messages: Vec<&str> = state.log.messages.iter().map(|m| m.text.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "You discover the capillary bed.",
+            "You discover the mucus layer.",
+            "You enter the capillary bed."
+        ]
+    );
+}
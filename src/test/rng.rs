@@ -0,0 +1,48 @@
+use crate::core::game_state::GameState;
+use crate::util::game_rng::RngStream;
+use rand::Rng;
+
+fn draw(state: &mut GameState, stream: RngStream) -> Vec<u32> {
+    (0..8).map(|_| state.rng_for(stream).gen()).collect()
+}
+
+#[test]
+fn test_rng_streams_are_independent() {
+    let mut state = GameState::with_seed(1, 42);
+    let mut busy_state = GameState::with_seed(1, 42);
+
+    // drawing from one stream leaves the others untouched
+    draw(&mut busy_state, RngStream::Ai);
+    assert_eq!(
+        draw(&mut state, RngStream::WorldGen),
+        draw(&mut busy_state, RngStream::WorldGen)
+    );
+    assert_ne!(
+        draw(&mut state, RngStream::Ai),
+        draw(&mut busy_state, RngStream::Ai)
+    );
+
+    // each stream of a seed yields its own numbers
+    let mut fresh_state = GameState::with_seed(1, 42);
+    let streams: Vec<Vec<u32>> = RngStream::ALL
+        .iter()
+        .map(|s| draw(&mut fresh_state, *s))
+        .collect();
+    for (i, a) in streams.iter().enumerate() {
+        assert!(streams.iter().skip(i + 1).all(|b| a != b));
+    }
+}
+
+/// Games saved before the loot stream existed load with a loot stream of their own.
+#[test]
+fn test_rng_streams_of_old_saves() {
+    use crate::util::game_rng::RngStreams;
+
+    let mut saved = serde_json::to_value(RngStreams::new(42)).unwrap();
+    saved.as_array_mut().unwrap().pop();
+    let mut a: RngStreams = serde_json::from_value(saved.clone()).unwrap();
+    let mut b: RngStreams = serde_json::from_value(saved).unwrap();
+    let loot: u64 = a.get(RngStream::Loot).gen();
+    assert_eq!(loot, b.get(RngStream::Loot).gen::<u64>());
+    assert_ne!(loot, a.get(RngStream::Host).gen::<u64>());
+}
@@ -0,0 +1,16 @@
+use crate::core::run_code::RunCode;
+
+#[test]
+fn test_run_code_round_trip() {
+    let code = RunCode::new(0xdead_beef_1234_5678);
+    let encoded = code.encode();
+    assert!(code.matches_settings());
+    assert_eq!(RunCode::decode(&encoded), Ok(code));
+
+    // codes are read regardless of case, dashes and look-alike letters
+    let sloppy = encoded.replace('-', "").replace('0', "o").to_lowercase();
+    assert_eq!(RunCode::decode(&sloppy), Ok(code));
+
+    assert!(RunCode::decode("U").is_err());
+    assert!(RunCode::decode(&encoded[..encoded.len() - 3]).is_err());
+}
@@ -0,0 +1,82 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::save::{MemorySave, SaveBackend};
+use crate::entity::ai::AiRandom;
+use crate::entity::control::Controller;
+use crate::entity::player::PLAYER;
+use crate::game::Game;
+use crate::util::game_rng::RngStream;
+use rand::Rng;
+
+#[test]
+fn test_memory_save() {
+    let mut backend = MemorySave::default();
+    assert!(backend.load_game().is_err());
+
+    let mut state = GameState::new(3);
+    state.turn = 42;
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    backend.save_game(&state, &objects).unwrap();
+
+    let (loaded_state, _loaded_objects) = backend.load_game().unwrap();
+    assert_eq!(loaded_state.turn, 42);
+    assert_eq!(loaded_state.dungeon_level, 3);
+}
+
+/// Dying in checkpoint mode counts against the checkpoint, dying with permadeath deletes the save.
+#[test]
+fn test_save_lifecycle() {
+    let mut backend = MemorySave::default();
+    assert!(backend.charge_respawn().is_err());
+
+    let mut state = GameState::new(1);
+    state.turn = 100;
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    backend.save_game(&state, &objects).unwrap();
+    backend.charge_respawn().unwrap();
+    let (loaded_state, _loaded_objects) = backend.load_game().unwrap();
+    assert_eq!(loaded_state.respawns, 1);
+    assert_eq!(loaded_state.turn, 100);
+
+    backend.delete().unwrap();
+    assert!(backend.load_game().is_err());
+}
+
+/// Generate the world of the given seed with the player handed over to an AI, so that the game
+/// can be simulated without any input.
+pub fn headless_game(seed: u64) -> (GameState, GameObjects) {
+    let mut errors = Vec::new();
+    let (state, mut objects) = Game::new_game(&mut errors, Some(seed));
+    if let Some(player) = objects[PLAYER].as_mut() {
+        player.control = Some(Controller::Npc(Box::new(AiRandom::new())));
+    }
+    (state, objects)
+}
+
+#[test]
+fn test_resume_is_exact() {
+    let (mut state, mut objects) = headless_game(7);
+    for _ in 0..10 {
+        state.simulate_turn(&mut objects);
+    }
+    let mut backend = MemorySave::default();
+    backend.save_game(&state, &objects).unwrap();
+
+    // the resumed game takes the same turns as the one that kept on running
+    let (mut resumed_state, mut resumed_objects) = backend.load_game().unwrap();
+    resumed_state.visibility.update(&mut resumed_objects);
+    for _ in 0..20 {
+        state.simulate_turn(&mut objects);
+        resumed_state.simulate_turn(&mut resumed_objects);
+    }
+    let snapshot = |s: &GameState, o: &GameObjects| serde_json::to_value((s, o)).unwrap();
+    assert!(snapshot(&state, &objects) == snapshot(&resumed_state, &resumed_objects));
+
+    // and keeps drawing the same random numbers in every subsystem
+    for stream in RngStream::ALL.iter() {
+        let expected: u64 = state.rng_for(*stream).gen();
+        assert_eq!(resumed_state.rng_for(*stream).gen::<u64>(), expected);
+    }
+}
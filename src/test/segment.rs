@@ -0,0 +1,37 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::position::Position;
+use crate::entity::object::Object;
+
+#[test]
+fn test_segment_movement() {
+    let mut worm = Object::new()
+        .position(5, 5)
+        .living(true)
+        .physical(true, false, false)
+        .segmented(3, 2);
+    worm.move_to(Position::new(6, 5));
+    worm.move_to(Position::new(7, 5));
+    worm.move_to(Position::new(7, 6));
+    let trail: Vec<Position> = worm.segments.iter().map(|s| s.pos).collect();
+    assert_eq!(
+        trail,
+        vec![
+            Position::new(7, 5),
+            Position::new(6, 5),
+            Position::new(5, 5)
+        ]
+    );
+    assert!(worm.occupies(&Position::new(5, 5)));
+    assert_eq!(worm.segment_at(&Position::new(6, 5)), Some(1));
+
+    // destroying the middle segment severs the tail as well
+    assert_eq!(worm.damage_segment(1, 1), 0);
+    assert_eq!(worm.damage_segment(1, 1), 2);
+    assert_eq!(worm.segments.len(), 1);
+
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    objects.push(worm);
+    assert!(objects.is_pos_occupied(&Position::new(7, 5)));
+    assert!(!objects.is_pos_occupied(&Position::new(5, 5)));
+}
@@ -0,0 +1,37 @@
+use crate::ui::settings::{DisplaySettings, FontChoice, UiSettings, WindowMode};
+
+#[test]
+fn test_display_settings() {
+    let mut settings: DisplaySettings = serde_json::from_str(r#"{"font": "Rex12x12"}"#).unwrap();
+    assert_eq!(settings.font, FontChoice::Rex12x12);
+    assert_eq!(settings.tile_size(), 12);
+    assert_eq!(settings.window_mode, WindowMode::Windowed);
+    assert_eq!(settings.window_size, None);
+
+    settings.cycle_ui_scale();
+    settings.cycle_ui_scale();
+    assert_eq!(settings.tile_size(), 18);
+    settings.cycle_ui_scale();
+    settings.cycle_ui_scale();
+    assert_eq!(settings.ui_scale, 1.0);
+
+    assert_eq!(FontChoice::Rex16x16.next(), FontChoice::Rex8x8);
+    assert_eq!(WindowMode::Fullscreen.next(), WindowMode::Windowed);
+    assert!(FontChoice::Rex16x16.asset().resolve().is_some());
+}
+
+#[test]
+fn test_ui_settings() {
+    // settings stored before visual effects could be switched off keep them on
+    let settings: UiSettings = serde_json::from_str(r#"{"sidebar_tab":"Log"}"#).unwrap();
+    assert!(settings.effects);
+
+    let settings = UiSettings {
+        effects: false,
+        ..settings
+    };
+    let restored: UiSettings =
+        serde_json::from_str(&serde_json::to_string(&settings).unwrap()).unwrap();
+    assert!(!restored.effects);
+    assert_eq!(restored.sidebar_tab, settings.sidebar_tab);
+}
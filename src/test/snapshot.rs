@@ -0,0 +1,26 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::snapshot::ascii_map;
+use crate::core::world::Tile;
+use crate::entity::object::Object;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+
+#[test]
+fn test_ascii_map() {
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    objects.get_tile_at(3, 2).replace(Tile::empty(3, 2, false));
+    objects.push(
+        Object::new()
+            .position(3, 2)
+            .visualize("Bacteria", 'B', (0, 0, 0)),
+    );
+
+    let map = ascii_map(&objects);
+    let rows: Vec<&str> = map.lines().collect();
+    assert_eq!(rows.len(), WORLD_HEIGHT as usize);
+    assert!(rows
+        .iter()
+        .all(|r| r.chars().count() == WORLD_WIDTH as usize));
+    assert_eq!(&rows[2][..5], "###B#");
+    assert_eq!(&rows[3][..5], "#####");
+}
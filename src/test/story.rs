@@ -0,0 +1,67 @@
+use crate::core::clock::ClockTime;
+use crate::core::events::GameEvent;
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::story::{reach, Milestone, RunModifier};
+use crate::entity::faction::Faction;
+use crate::entity::object::Object;
+use crate::entity::player::PLAYER;
+
+#[test]
+fn test_milestones_are_reached_once() {
+    let milestones: Vec<Milestone> =
+        serde_json::from_str(include_str!("../../raws/story.json")).unwrap();
+    let mut state = GameState::with_seed(1, 7);
+
+    let reached = reach(
+        &mut state,
+        &milestones,
+        &[GameEvent::BloodstreamReached, GameEvent::BloodstreamReached],
+    );
+    assert_eq!(reached.len(), 1);
+    assert_eq!(reached[0].id, "bloodstream");
+    assert!(reach(&mut state, &milestones, &[GameEvent::BloodstreamReached]).is_empty());
+
+    let reached = reach(&mut state, &milestones, &[GameEvent::ImmuneWaveSurvived]);
+    assert_eq!(reached[0].id, "immune_wave");
+
+    // clock times are reached by any later hour as well
+    let morning = ClockTime::from_turn(0);
+    assert!(reach(&mut state, &milestones, &[GameEvent::HourStruck(morning)]).is_empty());
+    let night = ClockTime {
+        day: 2,
+        hour: 1,
+        minute: 0,
+    };
+    let reached = reach(&mut state, &milestones, &[GameEvent::HourStruck(night)]);
+    assert_eq!(reached[0].id, "first_night");
+}
+
+#[test]
+fn test_story_choices_modify_the_run() {
+    let mut state = GameState::with_seed(1, 7);
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    objects.set_player(Object::new().position(5, 5).living(true));
+    state.player_idx = PLAYER;
+    let max_hp = objects[state.player_idx].as_ref().unwrap().actuators.max_hp;
+    let standing = state
+        .relations
+        .standing(Faction::Lineage, Faction::GutFlora);
+
+    RunModifier::MaxHp(2).apply(&mut state, &mut objects);
+    RunModifier::Standing {
+        faction: Faction::GutFlora,
+        delta: 20,
+    }
+    .apply(&mut state, &mut objects);
+
+    let player = objects[state.player_idx].as_ref().unwrap();
+    assert_eq!(player.actuators.max_hp, max_hp + 2);
+    assert_eq!(
+        state
+            .relations
+            .standing(Faction::Lineage, Faction::GutFlora),
+        standing + 20
+    );
+}
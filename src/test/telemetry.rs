@@ -0,0 +1,29 @@
+use crate::core::telemetry::Telemetry;
+use crate::entity::ai::AiRandom;
+use crate::entity::control::Ai;
+
+#[test]
+fn test_telemetry_averages() {
+    let mut telemetry = Telemetry::new();
+    telemetry.record_frame(10);
+    telemetry.record_frame(30);
+    telemetry.record_decision("AiRandom", 2_000);
+    telemetry.record_decision("AiRandom", 4_000);
+    for _ in 0..3 {
+        telemetry.record_decode();
+    }
+    telemetry.record_turn();
+    telemetry.record_turn();
+
+    assert_eq!(telemetry.actions_per_frame(), 20.0);
+    assert_eq!(telemetry.max_actions_per_frame(), 30);
+    assert_eq!(telemetry.decodes_per_turn(), 1.5);
+    assert!(telemetry
+        .to_lines()
+        .contains(&"AiRandom: 3.0 us".to_string()));
+}
+
+#[test]
+fn test_ai_kind_is_the_type_name() {
+    assert_eq!(AiRandom::new().kind(), "AiRandom");
+}
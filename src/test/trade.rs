@@ -0,0 +1,39 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::world::object_from_template;
+use crate::entity::action::trade::ActTrade;
+use crate::entity::action::{Action, ActionResult, Target};
+use crate::entity::faction::Faction;
+use crate::entity::object::Object;
+use crate::entity::trade::{is_willing, offers, TradeGoods};
+use crate::raws::load_object_templates;
+
+#[test]
+fn test_trade_gene_fragment() {
+    let mut errors = Vec::new();
+    let templates = load_object_templates(&mut errors);
+    let template = templates.iter().find(|t| t.npc == "Lactobacillus").unwrap();
+    let mut state = GameState::new(1);
+    let flora = object_from_template(&mut state, template, 6, 5).unwrap();
+    let mut player = Object::new()
+        .position(5, 5)
+        .living(true)
+        .faction(Faction::Lineage);
+
+    // the gut flora only trades with organisms that carry symbiosis genes
+    assert!(!is_willing(&state, &flora, &player));
+    player.processors.symbiosis = 1;
+    assert!(is_willing(&state, &flora, &player));
+
+    let offer = offers(&state, &flora, &player)
+        .into_iter()
+        .find(|o| matches!(o.goods, TradeGoods::GeneFragment { .. }))
+        .unwrap();
+    let mut objects = GameObjects::new();
+    objects.push(flora);
+    let trade = ActTrade::new(Target::East, offer);
+    let result = trade.perform(&mut state, &mut objects, &mut player);
+    assert!(matches!(result, ActionResult::Success { .. }));
+    assert_eq!(player.inventory.items.len(), 1);
+    assert!(!player.dna.simplified.is_empty());
+}
@@ -0,0 +1,37 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::position::Position;
+use crate::core::visibility::Visibility;
+use crate::core::world::{Tile, WALL_INTEGRITY};
+
+#[test]
+fn test_tile_changes_update_visibility() {
+    let mut objects = GameObjects::new();
+    objects.blank_world();
+    objects.get_tile_at(4, 5).replace(Tile::empty(4, 5, false));
+    let mut visibility = Visibility::new();
+    visibility.set_camera(Some((Position::new(4, 5), 5)));
+    visibility.update(&mut objects);
+
+    let behind_wall = Position::new(6, 5);
+    assert!(visibility.is_visible(&Position::new(5, 5)));
+    assert!(!visibility.is_visible(&behind_wall));
+
+    // digesting the wall opens up the view without a full update
+    assert_eq!(
+        objects.digest_wall(&Position::new(5, 5), WALL_INTEGRITY),
+        Some(0)
+    );
+    let changed = objects.take_changed_tiles();
+    assert_eq!(changed, vec![Position::new(5, 5)]);
+    visibility.tiles_changed(&mut objects, &changed);
+    assert!(visibility.is_visible(&behind_wall));
+    let tile = objects.get_tile_at(6, 5).as_ref().unwrap();
+    assert!(tile.physics.is_visible);
+    assert!(tile.tile.as_ref().unwrap().is_explored);
+
+    // changes out of sight leave the fields of view alone
+    objects.digest_wall(&Position::new(40, 30), WALL_INTEGRITY);
+    let changed = objects.take_changed_tiles();
+    visibility.tiles_changed(&mut objects, &changed);
+    assert!(!visibility.is_visible(&Position::new(40, 30)));
+}
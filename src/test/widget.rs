@@ -0,0 +1,85 @@
+use crate::ui::widget::{FocusRing, ListView, ScrollView, TextField, WidgetEvent};
+use rltk::{Point, Rect, VirtualKeyCode};
+
+fn entries(n: usize) -> Vec<String> {
+    (0..n).map(|i| format!("entry {}", i)).collect()
+}
+
+#[test]
+fn test_list_view_selection() {
+    let mut list = ListView::new(Rect::with_size(0, 0, 10, 2), entries(3));
+    list.set_enabled(1, false);
+
+    // the selection wraps around and disabled entries can't be activated
+    assert_eq!(list.handle_key(VirtualKeyCode::Up), WidgetEvent::Changed);
+    assert_eq!(list.selection(), 2);
+    assert_eq!(
+        list.handle_key(VirtualKeyCode::Return),
+        WidgetEvent::Activated
+    );
+    assert_eq!(list.handle_key(VirtualKeyCode::Up), WidgetEvent::Changed);
+    assert_eq!(
+        list.handle_key(VirtualKeyCode::Return),
+        WidgetEvent::Ignored
+    );
+
+    // the list has scrolled to show the last entry, so the first row holds the second entry
+    assert_eq!(
+        list.handle_mouse(Point::new(3, 0), true),
+        WidgetEvent::Ignored
+    );
+    assert_eq!(list.selection(), 1);
+    assert_eq!(
+        list.handle_mouse(Point::new(3, 5), true),
+        WidgetEvent::Ignored
+    );
+
+    list.focused = false;
+    assert_eq!(list.handle_key(VirtualKeyCode::Down), WidgetEvent::Ignored);
+}
+
+#[test]
+fn test_text_field_input() {
+    let mut field = TextField::new(Point::new(0, 0), "ab", 3).upper_case();
+    assert_eq!(
+        field.handle_key(VirtualKeyCode::C, false),
+        WidgetEvent::Changed
+    );
+    assert_eq!(
+        field.handle_key(VirtualKeyCode::D, false),
+        WidgetEvent::Ignored
+    );
+    assert_eq!(field.text(), "abC");
+    assert_eq!(
+        field.handle_key(VirtualKeyCode::Back, false),
+        WidgetEvent::Changed
+    );
+    assert_eq!(field.text(), "ab");
+    assert_eq!(
+        field.handle_key(VirtualKeyCode::Return, false),
+        WidgetEvent::Activated
+    );
+}
+
+#[test]
+fn test_focus_and_scrolling() {
+    let mut focus = FocusRing::new(2);
+    assert!(!focus.handle_key(VirtualKeyCode::Return, false));
+    assert!(focus.handle_key(VirtualKeyCode::Tab, false));
+    assert!(focus.is_focused(1));
+    assert!(focus.handle_key(VirtualKeyCode::Tab, true));
+    assert!(focus.is_focused(0));
+
+    let mut view = ScrollView::new(Rect::with_size(0, 0, 10, 4), entries(10));
+    assert_eq!(view.handle_key(VirtualKeyCode::Up), WidgetEvent::Ignored);
+    assert_eq!(
+        view.handle_key(VirtualKeyCode::PageDown),
+        WidgetEvent::Changed
+    );
+    assert_eq!(
+        view.handle_key(VirtualKeyCode::PageDown),
+        WidgetEvent::Changed
+    );
+    // there are no more lines below the last page
+    assert_eq!(view.handle_key(VirtualKeyCode::Down), WidgetEvent::Ignored);
+}
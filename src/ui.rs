@@ -1,16 +1,26 @@
+pub mod ambience;
+pub mod animations;
 pub mod color_palette;
 pub mod custom;
+pub mod debug_overlay;
 pub mod dialog;
 pub mod frontend;
 pub mod game_input;
 pub mod hud;
+pub mod layers;
 pub mod menu;
 pub mod particle;
 pub mod rex_assets;
+pub mod settings;
+pub mod spectator;
+pub mod widget;
+pub mod window;
 
 use std::sync::{Mutex, MutexGuard};
 
+use crate::core::innit_env;
 use crate::core::position::Position;
+use crate::entity::object::Object;
 use crate::ui::color_palette::{ColorPalette, PALETTE_DEFAULT};
 use crate::ui::particle::{Particle, ParticleSystem};
 
@@ -31,6 +41,73 @@ pub fn register_particle(
         .push(Particle::new(pos, col_fg, col_bg, glyph, lifetime));
 }
 
+/// Register a short text that floats upwards from the given position, one particle per character.
+pub fn register_floating_text(pos: Position, text: &str, col_fg: (u8, u8, u8), lifetime: f32) {
+    let col_bg = palette().world_bg_ground_fov_true;
+    let mut particle_sys = PARTICLE_SYS.lock().unwrap();
+    for (i, glyph) in text.chars().enumerate() {
+        let char_pos = Position {
+            x: pos.x + i as i32,
+            y: pos.y,
+        };
+        particle_sys
+            .particles
+            .push(Particle::new(char_pos, col_fg, col_bg, glyph, lifetime).rising(2.0));
+    }
+}
+
+/// Shake the world view for the given duration in [ms].
+pub fn register_shake(duration: f32) {
+    let mut particle_sys = PARTICLE_SYS.lock().unwrap();
+    particle_sys.shake = particle_sys.shake.max(duration);
+}
+
+/// Color of the numbers floating above organisms that regain hp.
+const HEAL_COLOR: (u8, u8, u8) = (90, 220, 110);
+
+/// Float the number of hp lost or regained above the given position, unless visual effects are
+/// disabled in the game settings.
+pub fn register_hp_change(pos: Position, amount: i32) {
+    if !innit_env().use_visual_effects || amount == 0 {
+        return;
+    }
+    let (text, fg) = if amount < 0 {
+        (format!("{}", amount), palette().hud_fg_bar_health)
+    } else {
+        (format!("+{}", amount), HEAL_COLOR)
+    };
+    register_floating_text(Position::new(pos.x, (pos.y - 1).max(0)), &text, fg, 600.0);
+}
+
+/// Give visual feedback about a visible object regaining hp.
+pub fn register_heal_effects(target: &Object, amount: i32) {
+    if target.physics.is_visible || target.is_player() {
+        register_hp_change(target.pos, amount);
+    }
+}
+
+/// Give visual feedback about an object taking damage, unless disabled in the game settings.
+/// Damage numbers float above any visible object, the player additionally flashes red and shakes
+/// the screen.
+pub fn register_damage_effects(target: &Object, damage: i32) {
+    if !innit_env().use_visual_effects || !(target.physics.is_visible || target.is_player()) {
+        return;
+    }
+
+    register_hp_change(target.pos, -damage);
+
+    if target.is_player() {
+        register_particle(
+            target.pos,
+            target.visual.fg_color,
+            (200, 10, 10),
+            target.visual.glyph,
+            200.0,
+        );
+        register_shake(250.0);
+    }
+}
+
 pub fn particles<'a>() -> MutexGuard<'a, ParticleSystem> {
     PARTICLE_SYS.lock().unwrap()
 }
@@ -0,0 +1,113 @@
+//! Purely cosmetic animation of the host's fluids. Specks of debris drift through the tissue and
+//! the floor shimmers ever so slightly. Nothing in here has any effect on the game itself.
+
+use std::f32::consts::TAU;
+
+use rand::Rng;
+
+use crate::core::position::Position;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::ui::animations::{AnimationClock, Interval};
+
+/// Time in [ms] between two frames of the ambient animation.
+const STEP_MS: f32 = 100.0;
+/// Number of specks drifting through the world at any time.
+const NUM_SPECKS: usize = 120;
+/// Highest speed of a speck, given in [tiles/s].
+const SPECK_SPEED: f32 = 0.8;
+/// Time in [ms] that a speck drifts before it dissolves.
+const SPECK_LIFETIME: std::ops::Range<f32> = 3000.0..9000.0;
+/// Time in [ms] of a full oscillation of the floor color.
+const SHIMMER_PERIOD: f32 = 4000.0;
+/// How far the shimmer shifts the floor color at most, from 0.0 to 1.0.
+const SHIMMER_STRENGTH: f32 = 0.08;
+
+struct Speck {
+    x: f32,
+    y: f32,
+    /// Velocity, given in [tiles/s].
+    dx: f32,
+    dy: f32,
+    /// Remaining lifetime, given in [ms].
+    lifetime: f32,
+}
+
+impl Speck {
+    fn random() -> Self {
+        let mut rng = rand::thread_rng();
+        Speck {
+            x: rng.gen_range(0.0..WORLD_WIDTH as f32),
+            y: rng.gen_range(0.0..WORLD_HEIGHT as f32),
+            dx: rng.gen_range(-SPECK_SPEED..SPECK_SPEED),
+            dy: rng.gen_range(-SPECK_SPEED..SPECK_SPEED),
+            lifetime: rng.gen_range(SPECK_LIFETIME),
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.lifetime > 0.0
+            && (0.0..WORLD_WIDTH as f32).contains(&self.x)
+            && (0.0..WORLD_HEIGHT as f32).contains(&self.y)
+    }
+}
+
+pub struct Ambience {
+    specks: Vec<Speck>,
+    /// Tiles with a speck above them, row by row.
+    speck_tiles: Vec<bool>,
+    /// Point in time in [ms] within the period of the shimmer.
+    time: f32,
+    steps: Interval,
+}
+
+impl Ambience {
+    pub fn new() -> Self {
+        Ambience {
+            specks: Vec::new(),
+            speck_tiles: vec![false; (WORLD_WIDTH * WORLD_HEIGHT) as usize],
+            time: 0.0,
+            steps: Interval::new(STEP_MS),
+        }
+    }
+
+    /// Advance the animation to the time of the clock. Returns true if a new frame is due.
+    pub fn update(&mut self, clock: &AnimationClock) -> bool {
+        self.time = (clock.elapsed() % SHIMMER_PERIOD as f64) as f32;
+        let steps = self.steps.advance(clock);
+        if steps == 0 {
+            return false;
+        }
+
+        let elapsed = steps as f32 * STEP_MS;
+        for speck in &mut self.specks {
+            speck.x += speck.dx * elapsed / 1000.0;
+            speck.y += speck.dy * elapsed / 1000.0;
+            speck.lifetime -= elapsed;
+        }
+        self.specks.retain(Speck::is_alive);
+        while self.specks.len() < NUM_SPECKS {
+            self.specks.push(Speck::random());
+        }
+
+        self.speck_tiles.iter_mut().for_each(|t| *t = false);
+        for speck in &self.specks {
+            let idx = speck.y as i32 * WORLD_WIDTH + speck.x as i32;
+            self.speck_tiles[idx as usize] = true;
+        }
+        true
+    }
+
+    /// Check whether a speck drifts above the given position.
+    pub fn has_speck(&self, pos: &Position) -> bool {
+        (0..WORLD_WIDTH).contains(&pos.x)
+            && (0..WORLD_HEIGHT).contains(&pos.y)
+            && self.speck_tiles[(pos.y * WORLD_WIDTH + pos.x) as usize]
+    }
+
+    /// How far the floor color at the given position is currently shifted, from 0.0 to 1.0. The
+    /// shimmer rolls across the world in waves.
+    pub fn shimmer(&self, pos: &Position) -> f32 {
+        let phase = TAU * self.time / SHIMMER_PERIOD - 0.3 * pos.x as f32 - 0.2 * pos.y as f32;
+        SHIMMER_STRENGTH * (0.5 + 0.5 * phase.sin())
+    }
+}
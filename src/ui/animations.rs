@@ -0,0 +1,64 @@
+//! Timing of everything that moves on screen independent of the turns. Animations are driven by
+//! the time that passes between frames instead of the number of frames, so that they play out the
+//! same regardless of the frame rate.
+
+/// Longest frame time in [ms] that the clock accounts for. Anything longer, e.g. after the window
+/// was dragged around, would make animations skip ahead.
+const MAX_FRAME_TIME: f32 = 250.0;
+
+/// Central clock of all animations, advanced once per frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnimationClock {
+    /// Time in [ms] that passed during the latest frame.
+    delta: f32,
+    /// Time in [ms] that passed since the clock started.
+    elapsed: f64,
+}
+
+impl AnimationClock {
+    pub fn new() -> Self {
+        AnimationClock::default()
+    }
+
+    /// Advance the clock by the time of the latest frame, as reported by `ctx.frame_time_ms`.
+    pub fn tick(&mut self, frame_time_ms: f32) {
+        self.delta = frame_time_ms.clamp(0.0, MAX_FRAME_TIME);
+        self.elapsed += self.delta as f64;
+    }
+
+    /// Time in [ms] that passed during the latest frame.
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    /// Time in [ms] that passed since the clock started.
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+}
+
+/// Accumulates the time of frames to let something happen at a fixed rate.
+#[derive(Clone, Copy, Debug)]
+pub struct Interval {
+    /// Time in [ms] between two occurrences.
+    period: f32,
+    accumulated: f32,
+}
+
+impl Interval {
+    pub fn new(period: f32) -> Self {
+        Interval {
+            period,
+            accumulated: 0.0,
+        }
+    }
+
+    /// Account for the latest frame. Returns how many periods were completed by it, usually none
+    /// or one.
+    pub fn advance(&mut self, clock: &AnimationClock) -> u32 {
+        self.accumulated += clock.delta();
+        let completed = (self.accumulated / self.period).floor();
+        self.accumulated -= completed * self.period;
+        completed as u32
+    }
+}
@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
 pub struct ColorPalette {
     // base color palette
     /// Main color, used as base for the UI and the world, probably.
@@ -2,3 +2,4 @@
 //! [menu](crate::ui:menu).
 
 pub mod genome_editor;
+pub mod sandbox;
@@ -3,10 +3,11 @@ The genome editor provides the facilities for the player to manipulate their own
 they have a plasmid that allows this.
 */
 
-use crate::entity::genetics::{Dna, GeneticTrait, TraitAttribute, TraitFamily};
-use crate::game::{RunState, HUD_CON, SCREEN_HEIGHT, SCREEN_WIDTH};
-use crate::rand::Rng;
-use crate::util::game_rng::RngExtended;
+use crate::core::game_state::{MessageLog, MsgClass};
+use crate::entity::genetics::{is_within_dna_cap, Dna, GeneticTrait, TraitAttribute, TraitFamily};
+use crate::game::{RunState, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::widget::draw_window;
+use crate::util::game_rng::RngStream;
 use crate::util::modulus;
 use crate::{core::game_state::GameState, ui::palette};
 use rltk::{to_cp437, ColorPair, DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
@@ -23,6 +24,8 @@ pub enum GenomeEditingState {
     Cut,
     FlipBit,
     Duplicate,
+    Silence,
+    Optimize,
     Done,
 }
 
@@ -107,25 +110,38 @@ impl GenomeEditor {
     pub fn new(dna: Dna, charges: usize) -> Self {
         use GenomeEditingState::*;
         let mut top_row_x = 1;
-        let enabled_functions: Vec<GenomeEditingState> = vec![Move, Cut, FlipBit, Duplicate, Done];
-        let mut edit_functions: Vec<EditFunction> = [Move, Cut, FlipBit, Duplicate, Done]
-            .iter()
-            .enumerate()
-            .zip(["Move", "Cut", "Mutate", "Duplicate", "Done"].iter())
-            .map(|((idx, e), s)| {
-                let len: i32 = (s.len() + 3) as i32;
-                let is_enabled = enabled_functions.contains(e);
-                let item = EditFunction::new(
-                    Rect::with_size(top_row_x, TOP_ROW_Y_OFFSET, len, 1),
-                    is_enabled,
-                    *e,
-                    idx,
-                    s.to_string(),
-                );
-                top_row_x += len + 2;
-                item
-            })
-            .collect();
+        let enabled_functions: Vec<GenomeEditingState> =
+            vec![Move, Cut, FlipBit, Duplicate, Silence, Optimize, Done];
+        let mut edit_functions: Vec<EditFunction> =
+            [Move, Cut, FlipBit, Duplicate, Silence, Optimize, Done]
+                .iter()
+                .enumerate()
+                .zip(
+                    [
+                        "Move",
+                        "Cut",
+                        "Mutate",
+                        "Duplicate",
+                        "Silence",
+                        "Optimize",
+                        "Done",
+                    ]
+                    .iter(),
+                )
+                .map(|((idx, e), s)| {
+                    let len: i32 = (s.len() + 3) as i32;
+                    let is_enabled = enabled_functions.contains(e);
+                    let item = EditFunction::new(
+                        Rect::with_size(top_row_x, TOP_ROW_Y_OFFSET, len, 1),
+                        is_enabled,
+                        *e,
+                        idx,
+                        s.to_string(),
+                    );
+                    top_row_x += len + 2;
+                    item
+                })
+                .collect();
 
         // calculate layout for whole window
         let mut func_width: i32 = edit_functions.iter().map(|item| item.layout.width()).sum();
@@ -177,6 +193,11 @@ impl GenomeEditor {
                     TraitFamily::Junk(_) => (100, 100, 100), // TODO: coloring
                     TraitFamily::Ltr => (255, 255, 255),     // TODO: coloring
                 };
+                let col = if i.silenced {
+                    palette().hud_fg_inactive
+                } else {
+                    col
+                };
                 let item = GeneItem::new(Rect::with_size(x, y, 1, 1), idx, col);
                 x += 1;
                 item
@@ -186,33 +207,21 @@ impl GenomeEditor {
 
     pub fn display(self, game_state: &mut GameState, ctx: &mut Rltk) -> RunState {
         // 1. render everything
-        self.render(game_state, ctx);
+        self.render(game_state);
 
         // 2. read user input and process
         self.read_input(game_state, ctx)
     }
 
-    fn render(&self, game_state: &mut GameState, ctx: &mut Rltk) {
-        ctx.set_active_console(HUD_CON);
-        ctx.cls();
+    fn render(&self, game_state: &mut GameState) {
         let mut draw_batch = DrawBatch::new();
         let hud_fg = palette().hud_fg;
         let hud_fg_hl = palette().hud_fg_highlight;
         let hud_guide = palette().hud_fg_highlight;
         let hud_bg_active = palette().hud_bg_active;
         let hud_fg_inactive = palette().hud_fg_inactive;
-        let hud_fg_border = palette().hud_fg_border;
         let hud_bg = palette().hud_bg;
-        // draw window border
-        draw_batch.fill_region(self.layout, ColorPair::new(hud_fg, hud_bg), to_cp437(' '));
-        draw_batch.draw_hollow_box(self.layout, ColorPair::new(hud_fg_border, hud_bg));
-
-        // draw title
-        draw_batch.print_color(
-            Point::new(self.layout.x1 + 2, self.layout.y1),
-            " Genome Manipulation ",
-            ColorPair::new(hud_fg_border, hud_bg),
-        );
+        draw_window(self.layout, "Genome Manipulation", &mut draw_batch);
 
         if self.state == GenomeEditingState::ChooseFunction {
             draw_batch.fill_region(
@@ -351,11 +360,14 @@ impl GenomeEditor {
                 let action_header = "action:";
                 let attribute_header = "attribute:";
                 let code_header = "genetic code:";
-                let trait_name: String = if TraitAttribute::Receptor == genome.attribute {
+                let mut trait_name: String = if TraitAttribute::Receptor == genome.attribute {
                     format!("{}-({})", genome.trait_name, genome.position)
                 } else {
                     genome.trait_name.clone()
                 };
+                if genome.silenced {
+                    trait_name.push_str(" (silenced)");
+                }
                 draw_batch.print_color(
                     Point::new(connect_end.x, connect_end.y + 1),
                     "├",
@@ -706,19 +718,21 @@ impl GenomeEditor {
                 FlipBit => {
                     if let Some(item) = self.gene_items.get(self.selected_gene) {
                         if let Some(g_trait) = self.player_dna.simplified.get(item.gene_idx) {
-                            let mut gene_bits: Vec<u8> =
-                                game_state.gene_library.g_trait_refs_to_dna(&[g_trait]);
-                            let random_bit = game_state.rng.gen_range(0..gene_bits.len());
-                            gene_bits[random_bit] ^= game_state.rng.random_bit();
-                            let new_dna: Dna = game_state
-                                .gene_library
-                                .dna_to_traits(self.player_dna.dna_type, &gene_bits)
-                                .3;
-                            if let Some(new_repr) = new_dna.simplified.get(0) {
-                                self.player_dna.simplified[self.selected_gene] = new_repr.clone();
-                                self.decrease_charge();
-                                self.regenerate_dna(game_state);
+                            let is_junk = matches!(g_trait.trait_family, TraitFamily::Junk(_));
+                            let mutated = game_state.gene_library.mutate_gene(
+                                game_state.rng_streams.get(RngStream::Mutation),
+                                self.player_dna.dna_type,
+                                g_trait,
+                            );
+                            if is_junk {
+                                game_state.log.add(
+                                    "The mutation hits junk DNA and changes nothing.",
+                                    MsgClass::Info,
+                                );
                             }
+                            self.player_dna.simplified[self.selected_gene] = mutated;
+                            self.decrease_charge();
+                            self.regenerate_dna(game_state);
                         }
                     }
                     self.state = ChooseFunction;
@@ -730,7 +744,14 @@ impl GenomeEditor {
                             trait_to_duplicate = Some(g_trait.clone());
                         }
 
-                        if trait_to_duplicate.is_some() {
+                        // every gene takes up three bytes of raw DNA
+                        let new_len = (self.player_dna.simplified.len() + 1) * 3;
+                        if trait_to_duplicate.is_some() && !is_within_dna_cap(new_len) {
+                            game_state.log.add(
+                                "Your genome is too long to replicate any more genes.",
+                                MsgClass::Info,
+                            );
+                        } else if trait_to_duplicate.is_some() {
                             self.player_dna
                                 .simplified
                                 .insert(self.selected_gene + 1, trait_to_duplicate.unwrap());
@@ -740,6 +761,32 @@ impl GenomeEditor {
                     }
                     self.state = ChooseFunction;
                 }
+                Silence => {
+                    // epigenetic marks switch genes on and off without touching the DNA itself
+                    if let Some(g_trait) = self.player_dna.simplified.get_mut(self.selected_gene) {
+                        g_trait.silenced = !g_trait.silenced;
+                        self.decrease_charge();
+                        self.regenerate_dna(game_state);
+                    }
+                    self.state = ChooseFunction;
+                }
+                Optimize => {
+                    // stripping junk leaves the phenotype untouched, so it costs no charge
+                    if let Some((_, _, _, dna)) =
+                        game_state.gene_library.compact_dna(&self.player_dna)
+                    {
+                        self.player_dna = dna;
+                        self.selected_gene = self
+                            .selected_gene
+                            .min(self.player_dna.simplified.len().saturating_sub(1));
+                        self.gene_items = GenomeEditor::build_gene_items(
+                            &self.player_dna,
+                            self.layout.x1 + 1,
+                            self.layout.y1 + MID_ROW_Y_OFFSET + 1,
+                        );
+                    }
+                    self.state = ChooseFunction;
+                }
                 Done => {
                     // apply changed genome to player
                     self.state = Done;
@@ -758,7 +805,12 @@ impl GenomeEditor {
             if self.plasmid_charges == 0 {
                 self.edit_functions
                     .iter_mut()
-                    .filter(|f| f.state != GenomeEditingState::Done)
+                    .filter(|f| {
+                        !matches!(
+                            f.state,
+                            GenomeEditingState::Optimize | GenomeEditingState::Done
+                        )
+                    })
                     .for_each(|f| f.is_enabled = false);
                 // set selected function to the last one; "Done" which is now the only one possible.
                 self.selected_function = self.edit_functions.len() - 1;
@@ -773,9 +825,11 @@ impl GenomeEditor {
         let bit_vec = game_state
             .gene_library
             .g_traits_to_dna(self.player_dna.simplified.as_slice());
-        let new_dna = game_state
-            .gene_library
-            .dna_to_traits(self.player_dna.dna_type, &bit_vec);
+        let new_dna = game_state.gene_library.dna_to_marked_traits(
+            self.player_dna.dna_type,
+            &bit_vec,
+            &self.player_dna.marks_from_genes(),
+        );
         self.player_dna = new_dna.3;
         self.gene_items = GenomeEditor::build_gene_items(
             &self.player_dna,
@@ -0,0 +1,255 @@
+/*!
+The sandbox is a debug-mode god view on the world. Tiles can be painted with the mouse, organisms
+spawned from a palette and the simulation paused and stepped one object at a time. Optionally the
+whole world is revealed, regardless of what the player can sense.
+*/
+
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::{GameState, MessageLog, MsgClass, ObjectFeedback};
+use crate::core::host::antibody;
+use crate::core::innit_env;
+use crate::core::position::Position;
+use crate::core::world::{object_from_template, Environment, Tile, BODY_PH, BODY_TEMPERATURE};
+use crate::entity::action::hereditary::ActPass;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::raws::object_template::ObjectTemplate;
+use crate::ui::palette;
+use rltk::{ColorPair, DrawBatch, Point, Rltk, VirtualKeyCode};
+
+/// Conditions painted by the hazard brush, hot and acidic enough to stress any organism without
+/// tolerance genes.
+const HAZARD: Environment = Environment {
+    temperature: BODY_TEMPERATURE + 6.0,
+    ph: BODY_PH - 1.5,
+    nutrients: 1.0,
+    light: 0.0,
+};
+
+/// Nutrient richness painted by the nutrient brush, twice the host's regular supply.
+const NUTRIENT_RICH: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tool {
+    Wall,
+    Floor,
+    Hazard,
+    Nutrient,
+    Spawn,
+}
+
+impl Tool {
+    fn name(&self) -> &str {
+        match self {
+            Tool::Wall => "wall",
+            Tool::Floor => "floor",
+            Tool::Hazard => "hazard",
+            Tool::Nutrient => "nutrient",
+            Tool::Spawn => "spawn",
+        }
+    }
+}
+
+/// Everything that can be spawned in the sandbox.
+#[derive(Clone)]
+enum Species {
+    Template(Box<ObjectTemplate>),
+    Antibody,
+}
+
+impl Species {
+    fn name(&self) -> &str {
+        match self {
+            Species::Template(template) => &template.npc,
+            Species::Antibody => "Antibody",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Sandbox {
+    tool: Tool,
+    palette: Vec<Species>,
+    species_idx: usize,
+    paused: bool,
+}
+
+impl std::fmt::Debug for Sandbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sandbox")
+            .field("tool", &self.tool)
+            .field(
+                "species",
+                &self.palette.get(self.species_idx).map(|s| s.name()),
+            )
+            .field("paused", &self.paused)
+            .finish()
+    }
+}
+
+impl Sandbox {
+    /// Create a new paused sandbox. Every object template except the player can be spawned.
+    pub fn new(object_templates: Vec<ObjectTemplate>) -> Self {
+        let mut species: Vec<Species> = object_templates
+            .into_iter()
+            .filter(|t| t.controller.as_deref() != Some("player"))
+            .map(|t| Species::Template(Box::new(t)))
+            .collect();
+        species.push(Species::Antibody);
+        Sandbox {
+            tool: Tool::Wall,
+            palette: species,
+            species_idx: 0,
+            paused: true,
+        }
+    }
+
+    fn render(&self) {
+        let mut draw_batch = DrawBatch::new();
+        let species = self.palette.get(self.species_idx).map_or("-", |s| s.name());
+        let omniscient = if innit_env().omniscient { "on" } else { "off" };
+        let status = format!(
+            " SANDBOX | tool: {} | species: {} | {} | omniscient: {} ",
+            self.tool.name(),
+            species,
+            if self.paused { "paused" } else { "running" },
+            omniscient
+        );
+        let help = " 1-5 tool, Tab species, click apply, P pause, Space step, O reveal, Esc exit ";
+        let fg = palette().hud_fg_highlight;
+        let colors = ColorPair::new(fg, palette().hud_bg);
+        draw_batch.print_color(Point::new(0, 0), status, colors);
+        draw_batch.print_color(Point::new(0, WORLD_HEIGHT - 1), help, colors);
+        draw_batch.submit(6000).unwrap();
+    }
+
+    /// Process the sandbox input and, if not paused, let the simulation run one turn per frame.
+    pub fn update(
+        &mut self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        ctx: &mut Rltk,
+    ) -> ObjectFeedback {
+        self.render();
+
+        let mut feedback = ObjectFeedback::NoFeedback;
+        match ctx.key {
+            Some(VirtualKeyCode::Key1) => self.tool = Tool::Wall,
+            Some(VirtualKeyCode::Key2) => self.tool = Tool::Floor,
+            Some(VirtualKeyCode::Key3) => self.tool = Tool::Hazard,
+            Some(VirtualKeyCode::Key4) => self.tool = Tool::Nutrient,
+            Some(VirtualKeyCode::Key5) => self.tool = Tool::Spawn,
+            Some(VirtualKeyCode::Tab) => {
+                self.tool = Tool::Spawn;
+                self.species_idx = (self.species_idx + 1) % self.palette.len();
+            }
+            Some(VirtualKeyCode::P) => self.paused = !self.paused,
+            Some(VirtualKeyCode::Space) if self.paused => {
+                feedback = step(state, objects);
+            }
+            Some(VirtualKeyCode::O) => {
+                let omniscient = innit_env().omniscient;
+                innit_env().omniscient = !omniscient;
+                state.visibility.update(objects);
+                feedback = ObjectFeedback::Render;
+            }
+            _ => {}
+        }
+
+        if ctx.left_click {
+            let mouse = Position::from(ctx.mouse_point());
+            if mouse.x >= 0 && mouse.x < WORLD_WIDTH && mouse.y >= 0 && mouse.y < WORLD_HEIGHT {
+                let applied = match self.tool {
+                    Tool::Spawn => self.spawn(state, objects, &mouse),
+                    _ => self.paint(objects, &mouse),
+                };
+                if applied {
+                    state.visibility.update(objects);
+                    feedback = ObjectFeedback::Render;
+                }
+            }
+        }
+
+        if !self.paused && feedback != ObjectFeedback::GameOver {
+            let turn = state.turn;
+            while state.turn == turn && feedback != ObjectFeedback::GameOver {
+                feedback = step(state, objects);
+            }
+            if feedback != ObjectFeedback::GameOver {
+                feedback = ObjectFeedback::Render;
+            }
+        }
+        feedback
+    }
+
+    /// Paint the tile at the given position with the current tool. Walls can't be painted over
+    /// organisms and keep the local conditions, floors reset them to those of a healthy host and
+    /// the nutrient brush enriches walls and floors alike without changing anything else.
+    fn paint(&self, objects: &mut GameObjects, pos: &Position) -> bool {
+        if self.tool == Tool::Wall && objects.is_pos_occupied(pos) {
+            return false;
+        }
+        let old = match objects.get_tile_at(pos.x as usize, pos.y as usize).take() {
+            Some(old) => old,
+            None => return false,
+        };
+        if self.tool == Tool::Nutrient {
+            let mut enriched = old;
+            if let Some(tile) = enriched.tile.as_mut() {
+                tile.is_explored = true;
+                tile.environment.nutrients = NUTRIENT_RICH;
+            }
+            objects.replace_tile(pos, enriched);
+            return true;
+        }
+        let mut new = match self.tool {
+            Tool::Wall => Tile::wall(pos.x, pos.y, true),
+            _ => Tile::empty(pos.x, pos.y, true),
+        };
+        new.dna = old.dna;
+        new.tile = old.tile;
+        if let Some(tile) = new.tile.as_mut() {
+            tile.is_explored = true;
+            match self.tool {
+                Tool::Floor => tile.environment = Environment::default(),
+                Tool::Hazard => tile.environment = HAZARD,
+                _ => {}
+            }
+        }
+        objects.replace_tile(pos, new);
+        true
+    }
+
+    /// Spawn the selected species at the given position, if there is room for it.
+    fn spawn(&self, state: &mut GameState, objects: &mut GameObjects, pos: &Position) -> bool {
+        if objects.is_pos_blocked(pos) || objects.is_pos_occupied(pos) {
+            return false;
+        }
+        let organism = match self.palette.get(self.species_idx) {
+            Some(Species::Template(template)) => {
+                match object_from_template(state, template, pos.x, pos.y) {
+                    Ok(organism) => organism,
+                    Err(msg) => {
+                        state.log.add(msg, MsgClass::Alert);
+                        return false;
+                    }
+                }
+            }
+            Some(Species::Antibody) => antibody(&state.gene_library, *pos),
+            None => return false,
+        };
+        objects.push(organism);
+        true
+    }
+}
+
+/// Process a single object. The player, who is busy playing god, passes their turns.
+fn step(state: &mut GameState, objects: &mut GameObjects) -> ObjectFeedback {
+    if state.is_players_turn() {
+        if let Some(player) = objects[state.player_idx].as_mut() {
+            if !player.has_next_action() {
+                player.set_next_action(Some(Box::new(ActPass::default())));
+            }
+        }
+    }
+    state.process_object(objects)
+}
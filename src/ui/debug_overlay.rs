@@ -0,0 +1,89 @@
+//! Overlays that show what goes on inside the simulation, while building and tuning the AI. They
+//! are only available in debug mode: the traces of the NPCs' decisions and heat maps of the
+//! chemicals and walking distances across the tissue.
+
+use rltk::{Algorithm2D, ColorPair, DijkstraMap, DrawBatch, Point, RGB};
+
+use crate::core::game_env::HeatMap;
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::world::{SCENT_MAX, TOXIN_MAX};
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::ui::palette;
+
+/// Walking distance from the player beyond which the distance heat map goes cold.
+const MAX_DISTANCE: f32 = 40.0;
+/// Strongest tint that the heat map puts on a tile.
+const MAX_HEAT_TINT: f32 = 0.8;
+
+/// Heat of each tile of the world from 0 to 1, in row-major order. Empty if the heat map is off.
+pub fn heat_levels(heat_map: HeatMap, state: &GameState, objects: &GameObjects) -> Vec<f32> {
+    let positions = (0..WORLD_HEIGHT).flat_map(|y| (0..WORLD_WIDTH).map(move |x| Point::new(x, y)));
+    match heat_map {
+        HeatMap::Off => Vec::new(),
+        HeatMap::Scent => positions
+            .map(|p| objects.get_scent(&p.into()) as f32 / SCENT_MAX as f32)
+            .collect(),
+        HeatMap::Toxins => positions
+            .map(|p| objects.get_toxin(&p.into()) as f32 / TOXIN_MAX as f32)
+            .collect(),
+        HeatMap::Distance => {
+            let player = match objects[state.player_idx].as_ref() {
+                Some(player) => player,
+                None => return Vec::new(),
+            };
+            let start = objects.point2d_to_index(player.pos.into());
+            // the tiles are indexed from 1, after the slot of the player
+            let tiles = (WORLD_WIDTH * WORLD_HEIGHT) as usize + 1;
+            let distances = DijkstraMap::new(tiles, 1, &[start], objects, MAX_DISTANCE);
+            positions
+                .map(|p| {
+                    let distance = distances.map[objects.point2d_to_index(p)];
+                    (1.0 - distance / MAX_DISTANCE).max(0.0)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Tint the background of a tile by its heat.
+pub fn heat_tint(bg: RGB, heat: f32) -> RGB {
+    bg.lerp(
+        palette().col_acc3.into(),
+        heat.clamp(0.0, 1.0) * MAX_HEAT_TINT,
+    )
+}
+
+/// Draw the latest decision of each visible NPC: the tiles of its planned path, a marker on its
+/// target and a label with its AI and action above its glyph.
+pub fn render_ai_traces(objects: &GameObjects, draw_batch: &mut DrawBatch) {
+    let world_bg = palette().world_bg;
+    let hud_bg = palette().hud_bg;
+    let path_color = ColorPair::new(palette().col_acc1, world_bg);
+    let target_color = ColorPair::new(palette().hud_fg_msg_alert, world_bg);
+    let label_color = ColorPair::new(palette().hud_fg_highlight, hud_bg);
+    let traced = objects
+        .get_non_tiles()
+        .iter()
+        .flatten()
+        .filter(|o| o.physics.is_visible);
+    for object in traced {
+        let trace = match &object.ai_trace {
+            Some(trace) => trace,
+            None => continue,
+        };
+        for step in &trace.path {
+            draw_batch.print_color(Point::new(step.x, step.y), "·", path_color);
+        }
+        if let Some(target) = trace.target.filter(|t| *t != object.pos) {
+            draw_batch.print_color(Point::new(target.x, target.y), "x", target_color);
+        }
+        let label_x = (object.pos.x - trace.label.len() as i32 / 2)
+            .clamp(0, (WORLD_WIDTH - trace.label.len() as i32).max(0));
+        draw_batch.print_color(
+            Point::new(label_x, (object.pos.y - 1).max(0)),
+            &trace.label,
+            label_color,
+        );
+    }
+}
@@ -1,80 +1,70 @@
+pub mod bookmarks;
 pub mod character;
 pub mod controls;
+pub mod encounter;
+pub mod encyclopedia;
+pub mod error;
+pub mod mutations;
+pub mod organelles;
+pub mod raws_errors;
+pub mod rename;
+pub mod run_code;
+pub mod story;
+pub mod trade;
 
-use crate::{
-    game::{SCREEN_HEIGHT, SCREEN_WIDTH},
-    ui::palette,
-};
-use rltk::{to_cp437, ColorPair, DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
+use crate::game::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::widget::{draw_window, ScrollView};
+use rltk::{DrawBatch, Rect, Rltk, VirtualKeyCode};
 
-/// Simple info box. Can be exited by clicking outside or pressing `Esc`
+/// Info boxes with more lines than this scroll.
+const MAX_VISIBLE_LINES: usize = SCREEN_HEIGHT as usize - 10;
+
+/// Simple info box. Can be exited by clicking outside or pressing `Esc`. Long texts can be
+/// scrolled with the arrow keys.
 #[derive(Clone, Debug)]
 pub struct InfoBox {
     title: String,
-    lines: Vec<String>,
+    content: ScrollView,
     layout: Rect,
 }
 
 impl InfoBox {
     pub fn new(title: String, lines: Vec<String>) -> Self {
+        // leave a column for the scroll arrows
         let box_width: i32 = usize::max(
             title.len() + 5,
-            lines.iter().map(|l| l.len()).max().unwrap() + 1,
+            lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) + 2,
         ) as i32;
-        let box_height = lines.len() as i32 + 1;
+        let box_height = lines.len().min(MAX_VISIBLE_LINES) as i32 + 1;
         let x1 = (SCREEN_WIDTH / 2) - (box_width / 2);
         let y1 = (SCREEN_HEIGHT / 2) - (box_height / 2);
         let x2 = x1 + box_width;
         let y2 = y1 + box_height;
         InfoBox {
             title,
-            lines,
+            content: ScrollView::new(Rect::with_exact(x1 + 1, y1 + 1, x2 - 1, y2), lines),
             layout: Rect::with_exact(x1, y1, x2, y2),
         }
     }
 
     fn render(&self) {
         let mut draw_batch = DrawBatch::new();
-        let fg_hud_border = palette().hud_fg_border;
-        let fg_hud = palette().hud_fg;
-        let bg_hud = palette().hud_bg;
-        // draw box
-        draw_batch.fill_region(self.layout, ColorPair::new(fg_hud, bg_hud), to_cp437(' '));
-        draw_batch.draw_hollow_box(self.layout, ColorPair::new(fg_hud_border, bg_hud));
-
-        // draw title
-        let title_pos = Point::new(self.layout.x1 + 2, self.layout.y1);
-        draw_batch.print_color(
-            title_pos,
-            format!(" {} ", self.title),
-            ColorPair::new(fg_hud_border, bg_hud),
-        );
-
-        for (index, line) in self.lines.iter().enumerate() {
-            draw_batch.print_color(
-                Point::new(self.layout.x1 + 1, self.layout.y1 + 1 + index as i32),
-                line,
-                ColorPair::new(fg_hud, bg_hud),
-            );
-        }
-
+        draw_window(self.layout, &self.title, &mut draw_batch);
+        self.content.render(&mut draw_batch);
         draw_batch.submit(6000).unwrap();
     }
 
-    /// Main menu of the game.
-    /// Display a background image and three options for the player to choose
-    ///     - starting a new game
-    ///     - loading an existing game
-    ///     - quitting the game
-    pub fn display(self, ctx: &mut Rltk) -> Option<InfoBox> {
-        // render current menu
+    /// Display the info box until the player closes it. Returns `None` once it's closed.
+    pub fn display(mut self, ctx: &mut Rltk) -> Option<InfoBox> {
         self.render();
 
-        // wait for user input
         // a) keyboard input
-        // if we have a key activity, process and return immediately
-        if let Some(VirtualKeyCode::Escape) = ctx.key {
-            return None;
+        match ctx.key {
+            Some(VirtualKeyCode::Escape) => return None,
+            Some(key) => {
+                self.content.handle_key(key);
+            }
+            None => {}
         }
 
         // b) mouse input
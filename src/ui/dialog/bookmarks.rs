@@ -0,0 +1,17 @@
+use crate::core::game_state::GameState;
+use crate::ui::dialog::InfoBox;
+
+pub fn bookmarks_screen(state: &GameState) -> InfoBox {
+    let title: String = "Bookmarks".to_string();
+    let lines = if state.bookmarks.entries().is_empty() {
+        vec!["Press B while pointing at an organism to bookmark it.".to_string()]
+    } else {
+        state
+            .bookmarks
+            .entries()
+            .iter()
+            .map(|b| b.to_text())
+            .collect()
+    };
+    InfoBox::new(title, lines)
+}
@@ -1,23 +1,82 @@
+use crate::core::game_error::GameError;
 use crate::core::game_objects::GameObjects;
 use crate::core::game_state::GameState;
+use crate::entity::faction::{describe_attitude, Faction};
+use crate::entity::organelle::ORGANELLE_SLOTS;
 use crate::ui::dialog::InfoBox;
 
-pub fn character_screen(state: &GameState, objects: &GameObjects) -> InfoBox {
+pub fn character_screen(state: &GameState, objects: &GameObjects) -> Result<InfoBox, GameError> {
     if let Some(ref player) = objects[state.player_idx] {
         let title: String = "Character Information".to_string();
-        let lines = vec![
+        let mut resistances: Vec<&str> = player
+            .processors
+            .resistances
+            .iter()
+            .map(|r| r.name())
+            .collect();
+        if player.processors.toxin_resistance > 0 {
+            resistances.push("toxins");
+        }
+        if resistances.is_empty() {
+            resistances.push("none");
+        }
+        let mut lines = vec![
             format!(
                 "Energy:      {}/{}",
                 player.processors.energy, player.processors.energy_storage
             ),
+            format!(
+                "Reserve:     {}/{}",
+                player.processors.reserve, player.processors.reserve_capacity
+            ),
             format!("Metabolism:  {}", player.processors.metabolism),
+            format!("Upkeep:      {}", player.processors.upkeep),
             format!("Sense Range: {}", player.sensors.sensing_range),
             format!("HP:          {}", player.actuators.max_hp),
+            format!(
+                "Tolerances:  heat {}, pH {}",
+                player.processors.heat_tolerance, player.processors.ph_tolerance
+            ),
+            format!("Resistances: {}", resistances.join(", ")),
+            format!("Symbiosis:   {}", player.processors.symbiosis),
+            format!(
+                "Organelles:  {}/{} {}",
+                player.organelles.equipped.len(),
+                ORGANELLE_SLOTS,
+                player
+                    .organelles
+                    .equipped
+                    .iter()
+                    .map(|o| o.name())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ),
+            format!(
+                "Standing:    {}",
+                [Faction::GutFlora, Faction::Pathogens, Faction::ImmuneSystem]
+                    .iter()
+                    .map(|f| format!(
+                        "{} {}",
+                        f.name(),
+                        describe_attitude(state.relations.attitude(*f, player))
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
             format!("Alive:       {}", player.alive),
             format!("Turn:        {}", state.turn),
         ];
-        InfoBox::new(title, lines)
+        // the cost of each trait helps to decide which genes to cut in the genome editor
+        lines.push(String::new());
+        lines.push("Upkeep per trait (energy per turn, summed up and rounded down):".to_string());
+        for share in player.dna.upkeep_by_trait() {
+            lines.push(format!(
+                "  {:<18} {:>2}x {:>5.2}",
+                share.trait_name, share.genes, share.upkeep
+            ));
+        }
+        Ok(InfoBox::new(title, lines))
     } else {
-        panic!("No player");
+        Err(GameError::InvalidState("there is no player".to_string()))
     }
 }
@@ -1,23 +1,55 @@
+use crate::core::innit_env;
 use crate::ui::dialog::InfoBox;
 
 pub fn controls_screen() -> InfoBox {
     let title: String = "Controls".to_string();
-    let lines = vec![
+    let mut lines = vec![
         "Actions".to_string(),
         "↑, ↓, →, ←, left mouse   primary action".to_string(),
+        "numpad 1-9               primary action, incl. diagonals".to_string(),
         "W, A, S, D               secondary action".to_string(),
-        "Q                        first quick action".to_string(),
-        "E                        second quick action".to_string(),
+        "left mouse, far away     travel to an explored position".to_string(),
+        "1-9                      hotbar actions".to_string(),
+        "Q, E                     first and second hotbar action".to_string(),
         "".to_string(),
         "Reassign Actions".to_string(),
         "CTRL + P                 set primary".to_string(),
         "CTRL + S                 set secondary".to_string(),
-        "CTRL + Q                 set first quick".to_string(),
-        "CTRL + E                 set second quick".to_string(),
+        "CTRL + 1-9               set hotbar slot".to_string(),
+        "CTRL + Q, CTRL + E       set first and second hotbar slot".to_string(),
+        "left mouse on hotbar     set hotbar slot".to_string(),
         "".to_string(),
         "Other".to_string(),
         "C                        display character info".to_string(),
+        "H                        display mutation history".to_string(),
+        "N                        rename organism under mouse".to_string(),
+        "T                        trade with organism under mouse".to_string(),
+        "O                        manage organelles".to_string(),
+        "K                        browse the Innitpedia".to_string(),
+        "B                        bookmark organism under mouse".to_string(),
+        "SHIFT + B                display bookmarks".to_string(),
+        "M                        toggle walking/bursting".to_string(),
+        "P                        pause/resume the game".to_string(),
+        ".                        advance a single turn".to_string(),
+        "V                        toggle visual effects".to_string(),
+        "SHIFT + V                toggle detailed combat log".to_string(),
+        "CTRL + V                 toggle ambient animation".to_string(),
+        "I                        toggle faction badges".to_string(),
+        "F                        cycle game speed".to_string(),
+        "TAB, SHIFT + TAB         switch sidebar tab".to_string(),
+        "PAGE UP, PAGE DOWN       scroll sidebar tab".to_string(),
         "F1                       display controls".to_string(),
+        "F2                       save a screenshot and snapshot".to_string(),
+        "F3                       show the code to share this run".to_string(),
+        "F4                       toggle input recording".to_string(),
+        "F5                       create a bug report".to_string(),
     ];
+    if innit_env().debug_mode {
+        lines.push("F6                       toggle the AI traces of NPCs".to_string());
+        lines.push("F7                       cycle the heat map".to_string());
+        lines.push("F11                      toggle the telemetry overlay".to_string());
+        lines.push("SHIFT + F11              export the telemetry".to_string());
+        lines.push("F12                      open the sandbox".to_string());
+    }
     InfoBox::new(title, lines)
 }
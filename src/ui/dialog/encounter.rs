@@ -0,0 +1,16 @@
+use crate::ui::dialog::InfoBox;
+use crate::ui::hud::threat_level;
+
+/// Introduction of a species that the player sees for the first time in this run.
+pub fn encounter_screen(name: &str, threat: f32, genes: &[String]) -> InfoBox {
+    let mut lines = vec![
+        format!("Threat: {}", threat_level(threat).0),
+        "".to_string(),
+        "Notable genes:".to_string(),
+    ];
+    if genes.is_empty() {
+        lines.push("  none".to_string());
+    }
+    lines.extend(genes.iter().map(|g| format!("  {}", g)));
+    InfoBox::new(format!("New species: {}", name), lines)
+}
@@ -0,0 +1,96 @@
+use crate::core::encyclopedia::Entry;
+use crate::game::{RunState, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::palette;
+use crate::ui::widget::{draw_window, ListView};
+use rltk::{ColorPair, DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
+
+const HINT: &str = "Entries unlock as you encounter traits, species and mechanics.";
+const LIST_WIDTH: i32 = 30;
+const BOX_WIDTH: i32 = 78;
+const BOX_HEIGHT: i32 = 40;
+
+/// The encyclopedia, with its entries on the left and the selected one on the right. `Up` and
+/// `Down` browse the entries and `Esc` closes the dialog.
+#[derive(Clone, Debug)]
+pub struct EncyclopediaDialog {
+    entries: Vec<Entry>,
+    list: ListView,
+    layout: Rect,
+}
+
+impl EncyclopediaDialog {
+    pub fn new(entries: Vec<Entry>) -> Self {
+        let labels: Vec<String> = entries
+            .iter()
+            .map(|e| {
+                let name = if e.is_unlocked {
+                    e.name.as_str()
+                } else {
+                    "???"
+                };
+                format!("{:<9}{}", e.topic.name(), name)
+            })
+            .collect();
+        let x1 = (SCREEN_WIDTH / 2) - (BOX_WIDTH / 2);
+        let y1 = (SCREEN_HEIGHT / 2) - (BOX_HEIGHT / 2);
+        let list = ListView::new(
+            Rect::with_size(x1 + 1, y1 + 3, LIST_WIDTH, BOX_HEIGHT - 4),
+            labels,
+        );
+        EncyclopediaDialog {
+            entries,
+            list,
+            layout: Rect::with_size(x1, y1, BOX_WIDTH, BOX_HEIGHT),
+        }
+    }
+
+    fn render(&self) {
+        let mut draw_batch = DrawBatch::new();
+        let unlocked = self.entries.iter().filter(|e| e.is_unlocked).count();
+        let title = format!("Innitpedia ({}/{})", unlocked, self.entries.len());
+        draw_window(self.layout, &title, &mut draw_batch);
+        let fg_hud = palette().hud_fg;
+        let bg_hud = palette().hud_bg;
+        let fg_highlight = palette().hud_fg_highlight;
+        let colors = ColorPair::new(fg_hud, bg_hud);
+        let highlight = ColorPair::new(fg_highlight, bg_hud);
+        draw_batch.print_color(
+            Point::new(self.layout.x1 + 1, self.layout.y1 + 1),
+            HINT,
+            colors,
+        );
+        self.list.render(&mut draw_batch);
+
+        if let Some(entry) = self.entries.get(self.list.selection()) {
+            let x = self.layout.x1 + LIST_WIDTH + 3;
+            let y = self.layout.y1 + 3;
+            if entry.is_unlocked {
+                draw_batch.print_color(Point::new(x, y), &entry.name, highlight);
+                for (i, line) in entry.text.iter().enumerate() {
+                    draw_batch.print_color(Point::new(x, y + 2 + i as i32), line, colors);
+                }
+            } else {
+                draw_batch.print_color(Point::new(x, y), "???", highlight);
+                draw_batch.print_color(
+                    Point::new(x, y + 2),
+                    format!(
+                        "This {} is yet to be discovered.",
+                        entry.topic.name().to_lowercase()
+                    ),
+                    colors,
+                );
+            }
+        }
+        draw_batch.submit(6000).unwrap();
+    }
+
+    pub fn display(mut self, ctx: &mut Rltk) -> RunState {
+        self.render();
+
+        if let Some(VirtualKeyCode::Escape) = ctx.key {
+            return RunState::CheckInput;
+        }
+        self.list.handle_input(ctx);
+        RunState::Encyclopedia(self)
+    }
+}
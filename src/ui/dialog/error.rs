@@ -0,0 +1,17 @@
+use crate::core::game_error::GameError;
+use crate::ui::dialog::InfoBox;
+
+const MAX_LINE_LEN: usize = 60;
+
+/// Tell the player what went wrong. Closing the dialog returns to the main menu.
+pub fn error_screen(error: &GameError) -> InfoBox {
+    let mut lines = Vec::new();
+    // break long messages into several lines to keep the box on screen
+    let chars: Vec<char> = error.message().chars().collect();
+    for chunk in chars.chunks(MAX_LINE_LEN) {
+        lines.push(chunk.iter().collect());
+    }
+    lines.push("".to_string());
+    lines.push("Press Esc to return to the main menu.".to_string());
+    InfoBox::new(error.title().to_string(), lines)
+}
@@ -0,0 +1,18 @@
+use crate::core::game_error::GameError;
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::ui::dialog::InfoBox;
+
+pub fn mutation_screen(state: &GameState, objects: &GameObjects) -> Result<InfoBox, GameError> {
+    if let Some(ref player) = objects[state.player_idx] {
+        let title: String = "Mutation History".to_string();
+        let lines = if player.mutations.is_empty() {
+            vec!["Your genome hasn't changed yet.".to_string()]
+        } else {
+            player.mutations.events().map(|e| e.to_text()).collect()
+        };
+        Ok(InfoBox::new(title, lines))
+    } else {
+        Err(GameError::InvalidState("there is no player".to_string()))
+    }
+}
@@ -0,0 +1,84 @@
+use crate::core::game_objects::GameObjects;
+use crate::entity::action::organelle::ActUnequipOrganelle;
+use crate::entity::organelle::{Organelles, ORGANELLE_SLOTS};
+use crate::game::{RunState, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::palette;
+use crate::ui::widget::{draw_window, ListView, WidgetEvent};
+use rltk::{ColorPair, DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
+
+const HINT: &str = "Enter takes an organelle out. Use organelle items to take them up.";
+
+/// Internal slots of the player's organism. `Up` and `Down` pick a slot, `Enter` takes the
+/// organelle out of it and `Esc` closes the dialog.
+#[derive(Clone, Debug)]
+pub struct OrganelleDialog {
+    list: ListView,
+    layout: Rect,
+}
+
+impl OrganelleDialog {
+    pub fn new(organelles: &Organelles) -> Self {
+        let entries: Vec<String> = (0..ORGANELLE_SLOTS)
+            .map(|slot| match organelles.equipped.get(slot) {
+                Some(o) => format!("{:<14} {}", o.name(), o.description()),
+                None => "(empty)".to_string(),
+            })
+            .collect();
+        let box_width = entries
+            .iter()
+            .map(|e| e.len() as i32 + 3)
+            .fold(HINT.len() as i32 + 2, i32::max);
+        let box_height = ORGANELLE_SLOTS as i32 + 3;
+        let x1 = (SCREEN_WIDTH / 2) - (box_width / 2);
+        let y1 = (SCREEN_HEIGHT / 2) - (box_height / 2);
+        let mut list = ListView::new(
+            Rect::with_size(x1 + 1, y1 + 2, box_width - 1, ORGANELLE_SLOTS as i32),
+            entries,
+        );
+        for slot in 0..ORGANELLE_SLOTS {
+            list.set_enabled(slot, slot < organelles.equipped.len());
+        }
+        OrganelleDialog {
+            list,
+            layout: Rect::with_size(x1, y1, box_width, box_height),
+        }
+    }
+
+    fn render(&self) {
+        let mut draw_batch = DrawBatch::new();
+        draw_window(self.layout, "Organelles", &mut draw_batch);
+        let fg = palette().hud_fg;
+        let bg = palette().hud_bg;
+        draw_batch.print_color(
+            Point::new(self.layout.x1 + 1, self.layout.y1 + 1),
+            HINT,
+            ColorPair::new(fg, bg),
+        );
+        self.list.render(&mut draw_batch);
+        draw_batch.submit(6000).unwrap();
+    }
+
+    pub fn display(
+        mut self,
+        objects: &mut GameObjects,
+        player_idx: usize,
+        ctx: &mut Rltk,
+    ) -> RunState {
+        self.render();
+
+        if let Some(VirtualKeyCode::Escape) = ctx.key {
+            return RunState::CheckInput;
+        }
+        match self.list.handle_input(ctx) {
+            WidgetEvent::Activated => {
+                // only occupied slots can be activated
+                if let Some(player) = objects[player_idx].as_mut() {
+                    let unequip = ActUnequipOrganelle::new(self.list.selection() as i32);
+                    player.set_next_action(Some(Box::new(unequip)));
+                }
+                RunState::Ticking
+            }
+            WidgetEvent::Changed | WidgetEvent::Ignored => RunState::Organelles(self),
+        }
+    }
+}
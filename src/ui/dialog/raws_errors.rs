@@ -0,0 +1,21 @@
+use crate::ui::dialog::InfoBox;
+
+const MAX_LINE_LEN: usize = 80;
+
+/// List all problems that occurred while loading the raws.
+pub fn raws_errors_screen(errors: &[String]) -> InfoBox {
+    let title: String = "Malformed Data Files".to_string();
+    let mut lines = vec![
+        "The following entries could not be loaded and are skipped.".to_string(),
+        "Files that cannot be read at all are replaced by the built-in defaults.".to_string(),
+        "".to_string(),
+    ];
+    for error in errors {
+        // break long messages into several lines to keep the box on screen
+        let chars: Vec<char> = error.chars().collect();
+        for chunk in chars.chunks(MAX_LINE_LEN) {
+            lines.push(chunk.iter().collect());
+        }
+    }
+    InfoBox::new(title, lines)
+}
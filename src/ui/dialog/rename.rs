@@ -0,0 +1,52 @@
+use crate::core::game_objects::GameObjects;
+use crate::game::{RunState, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::widget::{draw_window, TextField, WidgetEvent};
+use rltk::{DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
+
+const MAX_NAME_LEN: usize = 20;
+
+/// Text prompt to give an organism a new name. `Enter` confirms, `Esc` cancels.
+#[derive(Clone, Debug)]
+pub struct RenameDialog {
+    obj_idx: usize,
+    name: TextField,
+    layout: Rect,
+}
+
+impl RenameDialog {
+    pub fn new(obj_idx: usize, name: &str) -> Self {
+        let box_width = MAX_NAME_LEN as i32 + 4;
+        let x1 = (SCREEN_WIDTH / 2) - (box_width / 2);
+        let y1 = SCREEN_HEIGHT / 2 - 1;
+        RenameDialog {
+            obj_idx,
+            name: TextField::new(Point::new(x1 + 2, y1 + 1), name, MAX_NAME_LEN),
+            layout: Rect::with_size(x1, y1, box_width, 2),
+        }
+    }
+
+    fn render(&self) {
+        let mut draw_batch = DrawBatch::new();
+        draw_window(self.layout, "Rename", &mut draw_batch);
+        self.name.render(&mut draw_batch);
+        draw_batch.submit(6000).unwrap();
+    }
+
+    pub fn display(mut self, objects: &mut GameObjects, ctx: &mut Rltk) -> RunState {
+        self.render();
+
+        if let Some(VirtualKeyCode::Escape) = ctx.key {
+            return RunState::CheckInput;
+        }
+        match self.name.handle_input(ctx) {
+            WidgetEvent::Activated => {
+                let name = self.name.text().trim();
+                if let (Some(organism), false) = (objects[self.obj_idx].as_mut(), name.is_empty()) {
+                    organism.visual.name = name.to_string();
+                }
+                RunState::CheckInput
+            }
+            WidgetEvent::Changed | WidgetEvent::Ignored => RunState::RenameDialog(self),
+        }
+    }
+}
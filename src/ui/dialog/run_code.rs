@@ -0,0 +1,106 @@
+use crate::core::run_code::RunCode;
+use crate::game::{RunState, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::menu::main_menu::main_menu;
+use crate::ui::palette;
+use crate::ui::widget::{draw_window, ButtonRow, FocusRing, TextField, WidgetEvent};
+use rltk::{ColorPair, DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
+
+/// Long enough for a code with all its dashes, plus some slack for typos.
+const MAX_CODE_LEN: usize = 32;
+
+/// Widgets of the dialog, in the order the focus passes through them.
+const FOCUS_CODE: usize = 0;
+const FOCUS_BUTTONS: usize = 1;
+
+/// Buttons below the code.
+const BUTTON_START: usize = 0;
+const BUTTON_CANCEL: usize = 1;
+
+/// Text prompt to enter a run code shared by another player. `Enter` starts the run, `Esc` goes
+/// back to the main menu. `Tab` switches between the code and the buttons.
+#[derive(Clone, Debug)]
+pub struct RunCodeDialog {
+    code: TextField,
+    buttons: ButtonRow,
+    focus: FocusRing,
+    error: Option<String>,
+    layout: Rect,
+}
+
+impl RunCodeDialog {
+    pub fn new() -> Self {
+        let box_width = MAX_CODE_LEN as i32 + 4;
+        let x1 = (SCREEN_WIDTH / 2) - (box_width / 2);
+        let y1 = SCREEN_HEIGHT / 2 - 2;
+        RunCodeDialog {
+            code: TextField::new(Point::new(x1 + 2, y1 + 1), "", MAX_CODE_LEN).upper_case(),
+            buttons: ButtonRow::new(
+                Point::new(x1 + 2, y1 + 3),
+                vec!["Start".to_string(), "Cancel".to_string()],
+            ),
+            focus: FocusRing::new(2),
+            error: None,
+            layout: Rect::with_size(x1, y1, box_width, 4),
+        }
+    }
+
+    fn render(&self) {
+        let mut draw_batch = DrawBatch::new();
+        draw_window(self.layout, "Enter Run Code", &mut draw_batch);
+        self.code.render(&mut draw_batch);
+        if let Some(error) = &self.error {
+            let fg = palette().hud_fg_highlight;
+            let bg = palette().hud_bg;
+            draw_batch.print_color(
+                Point::new(self.layout.x1 + 2, self.layout.y1 + 2),
+                error,
+                ColorPair::new(fg, bg),
+            );
+        }
+        self.buttons.render(&mut draw_batch);
+        draw_batch.submit(6000).unwrap();
+    }
+
+    fn update_focus(&mut self) {
+        self.code.focused = self.focus.is_focused(FOCUS_CODE);
+        self.buttons.focused = self.focus.is_focused(FOCUS_BUTTONS);
+    }
+
+    fn start(mut self) -> RunState {
+        match RunCode::decode(self.code.text()) {
+            Ok(code) => RunState::NewSharedRun(code),
+            Err(e) => {
+                self.error = Some(e);
+                RunState::RunCodeDialog(self)
+            }
+        }
+    }
+
+    pub fn display(mut self, ctx: &mut Rltk) -> RunState {
+        self.update_focus();
+        self.render();
+
+        match ctx.key {
+            Some(VirtualKeyCode::Escape) => return RunState::MainMenu(main_menu()),
+            Some(key) if self.focus.handle_key(key, ctx.shift) => {
+                return RunState::RunCodeDialog(self)
+            }
+            _ => {}
+        }
+        match self.code.handle_input(ctx) {
+            WidgetEvent::Activated => return self.start(),
+            WidgetEvent::Changed => {
+                self.error = None;
+                return RunState::RunCodeDialog(self);
+            }
+            WidgetEvent::Ignored => {}
+        }
+        match self.buttons.handle_input(ctx) {
+            WidgetEvent::Activated if self.buttons.selection() == BUTTON_START => self.start(),
+            WidgetEvent::Activated if self.buttons.selection() == BUTTON_CANCEL => {
+                RunState::MainMenu(main_menu())
+            }
+            _ => RunState::RunCodeDialog(self),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::story::Milestone;
+use crate::game::{RunState, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::palette;
+use crate::ui::widget::{draw_window, ListView, WidgetEvent};
+use rltk::{ColorPair, DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
+
+/// Vignette of a story milestone. `Up` and `Down` pick one of its choices, `Enter` makes it and
+/// `Esc` moves on without choosing.
+#[derive(Clone, Debug)]
+pub struct StoryDialog {
+    milestone: Milestone,
+    list: ListView,
+    layout: Rect,
+}
+
+impl StoryDialog {
+    pub fn new(milestone: Milestone) -> Self {
+        let entries: Vec<String> = if milestone.choices.is_empty() {
+            vec!["Continue".to_string()]
+        } else {
+            milestone.choices.iter().map(|c| c.to_text()).collect()
+        };
+        let box_width = milestone
+            .text
+            .iter()
+            .chain(entries.iter())
+            .map(|l| l.chars().count() as i32 + 3)
+            .fold(milestone.title.len() as i32 + 5, i32::max);
+        let box_height = (milestone.text.len() + entries.len()) as i32 + 3;
+        let x1 = (SCREEN_WIDTH / 2) - (box_width / 2);
+        let y1 = (SCREEN_HEIGHT / 2) - (box_height / 2);
+        let list = ListView::new(
+            Rect::with_size(
+                x1 + 1,
+                y1 + milestone.text.len() as i32 + 2,
+                box_width - 1,
+                entries.len() as i32,
+            ),
+            entries,
+        );
+        StoryDialog {
+            milestone,
+            list,
+            layout: Rect::with_size(x1, y1, box_width, box_height),
+        }
+    }
+
+    fn render(&self) {
+        let mut draw_batch = DrawBatch::new();
+        draw_window(self.layout, &self.milestone.title, &mut draw_batch);
+        let fg = palette().hud_fg;
+        let colors = ColorPair::new(fg, palette().hud_bg);
+        for (i, line) in self.milestone.text.iter().enumerate() {
+            draw_batch.print_color(
+                Point::new(self.layout.x1 + 1, self.layout.y1 + 1 + i as i32),
+                line,
+                colors,
+            );
+        }
+        self.list.render(&mut draw_batch);
+        draw_batch.submit(6000).unwrap();
+    }
+
+    pub fn display(
+        mut self,
+        state: &mut GameState,
+        objects: &mut GameObjects,
+        ctx: &mut Rltk,
+    ) -> RunState {
+        self.render();
+
+        if let Some(VirtualKeyCode::Escape) = ctx.key {
+            return RunState::Ticking;
+        }
+        match self.list.handle_input(ctx) {
+            WidgetEvent::Activated => {
+                if let Some(choice) = self.milestone.choices.get(self.list.selection()) {
+                    choice.apply(state, objects);
+                }
+                RunState::Ticking
+            }
+            WidgetEvent::Changed | WidgetEvent::Ignored => RunState::Story(self),
+        }
+    }
+}
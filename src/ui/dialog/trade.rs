@@ -0,0 +1,99 @@
+use crate::core::game_objects::GameObjects;
+use crate::entity::action::trade::ActTrade;
+use crate::entity::action::Target;
+use crate::entity::trade::TradeOffer;
+use crate::game::{RunState, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::palette;
+use crate::ui::widget::{draw_window, ListView, WidgetEvent};
+use rltk::{ColorPair, DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
+
+/// Offers of an organism that is willing to trade with the player. `Up` and `Down` pick an offer,
+/// `Enter` buys it and `Esc` leaves without trading.
+#[derive(Clone, Debug)]
+pub struct TradeDialog {
+    partner: String,
+    /// Direction of the partner as seen from the player.
+    target: Target,
+    offers: Vec<TradeOffer>,
+    /// Energy the player can spend at most.
+    budget: i32,
+    list: ListView,
+    layout: Rect,
+}
+
+impl TradeDialog {
+    pub fn new(partner: &str, target: Target, offers: Vec<TradeOffer>, budget: i32) -> Self {
+        let title_width = partner.len() as i32 + 14;
+        let box_width = offers
+            .iter()
+            .map(|o| o.description.len() as i32 + 12)
+            .fold(title_width, i32::max);
+        let box_height = offers.len() as i32 + 3;
+        let x1 = (SCREEN_WIDTH / 2) - (box_width / 2);
+        let y1 = (SCREEN_HEIGHT / 2) - (box_height / 2);
+        let entries = offers
+            .iter()
+            .map(|o| format!("{:>2} energy  {}", o.price, o.description))
+            .collect();
+        let mut list = ListView::new(
+            Rect::with_size(x1 + 1, y1 + 2, box_width - 1, offers.len() as i32),
+            entries,
+        );
+        for (index, offer) in offers.iter().enumerate() {
+            list.set_enabled(index, offer.price <= budget);
+        }
+        TradeDialog {
+            partner: partner.to_string(),
+            target,
+            offers,
+            budget,
+            list,
+            layout: Rect::with_size(x1, y1, box_width, box_height),
+        }
+    }
+
+    fn render(&self) {
+        let mut draw_batch = DrawBatch::new();
+        draw_window(
+            self.layout,
+            &format!("Trade with {}", self.partner),
+            &mut draw_batch,
+        );
+        let fg = palette().hud_fg;
+        let bg = palette().hud_bg;
+        draw_batch.print_color(
+            Point::new(self.layout.x1 + 1, self.layout.y1 + 1),
+            format!("your energy: {}", self.budget),
+            ColorPair::new(fg, bg),
+        );
+        self.list.render(&mut draw_batch);
+        draw_batch.submit(6000).unwrap();
+    }
+
+    pub fn display(
+        mut self,
+        objects: &mut GameObjects,
+        player_idx: usize,
+        ctx: &mut Rltk,
+    ) -> RunState {
+        self.render();
+
+        if let Some(VirtualKeyCode::Escape) = ctx.key {
+            return RunState::CheckInput;
+        }
+        match self.list.handle_input(ctx) {
+            WidgetEvent::Activated => {
+                // only affordable offers can be activated
+                if let (Some(player), Some(offer)) = (
+                    objects[player_idx].as_mut(),
+                    self.offers.get(self.list.selection()),
+                ) {
+                    let trade = ActTrade::new(self.target.clone(), offer.clone());
+                    player.set_next_action(Some(Box::new(trade)));
+                }
+                RunState::Ticking
+            }
+            WidgetEvent::Changed | WidgetEvent::Ignored => RunState::Trading(self),
+        }
+    }
+}
@@ -1,16 +1,48 @@
+use crate::core::game_env::HeatMap;
+use crate::core::game_state::GameState;
 use crate::core::innit_env;
 use crate::core::position::Position;
-use crate::core::world::is_explored;
+use crate::core::visibility::Visibility;
+use crate::core::world::{is_explored, TOXIN_MAX};
+use crate::entity::faction::{Relations, Stance};
 use crate::entity::object::Object;
-use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::entity::signal::{SignalKind, SIGNAL_RADIUS};
+use crate::game::{WORLD_CON, WORLD_HEIGHT, WORLD_WIDTH};
+use crate::ui::ambience::Ambience;
+use crate::ui::debug_overlay::{heat_levels, heat_tint, render_ai_traces};
+use crate::ui::particles;
 use crate::util::timer::{time_from, Timer};
 use crate::{core::game_objects::GameObjects, ui::palette};
 use num::Float;
-use rltk::{field_of_view, to_cp437, ColorPair, DrawBatch, Point, Rect, Rltk, RGB};
+use rltk::{to_cp437, ColorPair, DrawBatch, Point, Rect, Rltk, RGB};
 
-pub fn render_world(objects: &mut GameObjects, _ctx: &mut Rltk) {
+/// How strongly auras tint the background of the tissue they cover.
+const AURA_TINT: f32 = 0.3;
+/// How strongly the highest concentration of toxins tints the background of its tile.
+const TOXIN_TINT: f32 = 0.6;
+/// How strongly the remains of dead organisms tint the background of their tile.
+const DEBRIS_TINT: f32 = 0.35;
+/// How strongly the badge of allied organisms shades their background.
+const ALLY_BADGE_SHADE: f32 = 0.4;
+/// Glyph of the specks that drift through the tissue.
+const SPECK_GLYPH: char = '•';
+
+/// Render the world as seen by the player, or by the spectator camera if there is one.
+pub fn render_world(objects: &GameObjects, state: &GameState, _ctx: &mut Rltk) {
     let mut timer = Timer::new("render world");
+    let visibility = &state.visibility;
+    // organisms carry badges of their stance towards the player, if the player asks for them
+    let badges = if innit_env().use_faction_badges {
+        objects
+            .get_vector()
+            .get(state.player_idx)
+            .and_then(|p| p.as_ref())
+            .map(|player| (&state.relations, player))
+    } else {
+        None
+    };
     let mut draw_batch = DrawBatch::new();
+    draw_batch.target(WORLD_CON);
     let world_col = palette().world_bg;
     draw_batch.fill_region(
         Rect::with_size(0, 0, WORLD_WIDTH, WORLD_HEIGHT),
@@ -18,8 +50,6 @@ pub fn render_world(objects: &mut GameObjects, _ctx: &mut Rltk) {
         to_cp437(' '),
     );
 
-    update_visibility(objects);
-
     let mut to_draw: Vec<&Object> = objects
         .get_vector()
         .iter()
@@ -33,17 +63,85 @@ pub fn render_world(objects: &mut GameObjects, _ctx: &mut Rltk) {
         })
         .collect();
 
+    // chemical signals tint the tissue around their visible emitters
+    let auras: Vec<(Position, RGB)> = to_draw
+        .iter()
+        .filter(|o| o.physics.is_visible)
+        .filter_map(|o| o.aura.map(|a| (o.pos, aura_color(a.kind))))
+        .collect();
+
     // sort, so that non-blocking objects come first
     to_draw.sort_by(|o1, o2| o1.physics.is_blocking.cmp(&o2.physics.is_blocking));
+    // draw the segments of multi-tile organisms first, so that they never hide anything
+    for object in to_draw.iter().filter(|o| !o.segments.is_empty()) {
+        let (fg, bg) = object_colors(object, visibility, &auras);
+        let mut previous = object.pos;
+        for segment in &object.segments {
+            if visibility.is_visible(&segment.pos) || object.physics.is_always_visible {
+                draw_batch.set(
+                    Point::new(segment.pos.x, segment.pos.y),
+                    ColorPair::new(fg, bg),
+                    to_cp437(connector(&segment.pos, &previous, object.visual.glyph)),
+                );
+            }
+            previous = segment.pos;
+        }
+    }
+
+    // debug overlays show what goes on inside the simulation
+    let (heat, show_ai_traces) = {
+        let env = innit_env();
+        if env.debug_mode {
+            (env.heat_map, env.show_ai_traces)
+        } else {
+            (HeatMap::Off, false)
+        }
+    };
+    let heat = heat_levels(heat, state, objects);
+
+    let use_ambience = innit_env().use_ambience;
+    let particle_sys = particles();
+    let ambience = use_ambience.then_some(&particle_sys.ambience);
+
     // draw the objects in the list
     for object in &to_draw {
+        if !object.segments.is_empty()
+            && !object.physics.is_always_visible
+            && !visibility.is_visible(&object.pos)
+        {
+            continue;
+        }
+        let (fg, bg) = object_colors(object, visibility, &auras);
+        let (fg, bg, glyph) = match ambience {
+            Some(ambience) if object.tile.is_some() => ambient_tile(object, fg, bg, ambience),
+            _ => (fg, bg, object.display_glyph()),
+        };
+        let (fg, bg) = match badges {
+            Some((relations, player)) => faction_badge(object, relations, player, fg, bg),
+            None => (fg, bg),
+        };
+        let bg = match heat.get((object.pos.y * WORLD_WIDTH + object.pos.x) as usize) {
+            Some(level) if object.tile.is_some() => heat_tint(bg, *level),
+            _ => bg,
+        };
         draw_batch.set(
             Point::new(object.pos.x, object.pos.y),
-            ColorPair::new::<RGB, RGB>(
-                object.visual.fg_color.into(),
-                object.visual.bg_color.into(),
-            ),
-            to_cp437(object.visual.glyph),
+            ColorPair::new(fg, bg),
+            to_cp437(glyph),
+        );
+    }
+
+    if show_ai_traces {
+        render_ai_traces(objects, &mut draw_batch);
+    }
+
+    if let Some(camera) = visibility.camera() {
+        let fg = palette().hud_fg_highlight;
+        let bg = palette().world_bg;
+        draw_batch.set(
+            Point::new(camera.x, camera.y),
+            ColorPair::new(fg, bg),
+            to_cp437('+'),
         );
     }
 
@@ -55,48 +153,69 @@ pub fn render_world(objects: &mut GameObjects, _ctx: &mut Rltk) {
     draw_batch.submit(0).unwrap()
 }
 
-fn update_visibility(objects: &mut GameObjects) {
-    let player_positions: Vec<(Position, i32)> = objects
-        .get_vector()
-        .iter()
-        .flatten()
-        .filter(|o| o.is_player())
-        .map(|o| (o.pos, o.sensors.sensing_range))
-        .collect();
+/// Let the ambient animation play over a visible floor tile: its background shimmers and specks
+/// drifting above it are shown in place of its glyph.
+fn ambient_tile(object: &Object, fg: RGB, bg: RGB, ambience: &Ambience) -> (RGB, RGB, char) {
+    if !object.physics.is_visible || object.physics.is_blocking {
+        return (fg, bg, object.display_glyph());
+    }
+    let bg = bg.lerp(palette().col_acc1.into(), ambience.shimmer(&object.pos));
+    if ambience.has_speck(&object.pos) {
+        (fg.lerp(palette().col_acc1.into(), 0.5), bg, SPECK_GLYPH)
+    } else {
+        (fg, bg, object.display_glyph())
+    }
+}
 
-    // set all objects invisible by default
-    let mut dist_map: Vec<f32> =
-        vec![f32::max_value(); (WORLD_HEIGHT * WORLD_WIDTH) as usize + WORLD_WIDTH as usize];
-    for object_opt in objects.get_vector_mut() {
-        if let Some(object) = object_opt {
-            object.physics.is_visible = false;
-            update_visual(object, -1, Position::default(), &mut dist_map);
-        }
+/// Mark a visible organism by its stance towards the player through brightness rather than hue,
+/// so that it can be told apart regardless of color vision: hostile organisms are shown inverted on
+/// a bright badge, allies on a shaded one and neutral organisms as they are.
+fn faction_badge(
+    object: &Object,
+    relations: &Relations,
+    player: &Object,
+    fg: RGB,
+    bg: RGB,
+) -> (RGB, RGB) {
+    let is_organism = object.control.is_some() && object.tile.is_none() && object.item.is_none();
+    if !is_organism || !object.physics.is_visible || object.is_player() {
+        return (fg, bg);
+    }
+    let badge: RGB = palette().hud_fg.into();
+    match relations.stance(object, player) {
+        Stance::Hostile => (palette().world_bg.into(), badge),
+        Stance::Allied => (fg, bg.lerp(badge, ALLY_BADGE_SHADE)),
+        Stance::Neutral => (fg, bg),
     }
+}
 
-    for (pos, range) in player_positions {
-        let mut visible_pos = field_of_view(pos.into(), range, objects);
-        visible_pos.retain(|p| p.x >= 0 && p.x < WORLD_WIDTH && p.y >= 0 && p.y < WORLD_HEIGHT);
+/// Background color of the tissue around an organism that emits the given signal.
+fn aura_color(kind: SignalKind) -> RGB {
+    match kind {
+        SignalKind::Peace => palette().col_acc1.into(),
+        SignalKind::Threat => palette().col_acc3.into(),
+    }
+}
 
-        for object_opt in objects.get_vector_mut() {
-            if let Some(object) = object_opt {
-                if visible_pos.contains(&object.pos.into()) {
-                    object.physics.is_visible = true;
-                    update_visual(object, range, pos, &mut dist_map);
-                }
-            }
-        }
+/// Glyph of a segment that links it to the next body part towards the head. Segments that are
+/// still coiled up underneath it show the organism's glyph.
+fn connector(segment: &Position, towards: &Position, glyph: char) -> char {
+    match (towards.x - segment.x, towards.y - segment.y) {
+        (0, 0) => glyph,
+        (_, 0) => '─',
+        (0, _) => '│',
+        (dx, dy) if dx * dy > 0 => '\\',
+        _ => '/',
     }
 }
 
-/// Update the player's field of view and updated which tiles are visible/explored.
-fn update_visual(
-    object: &mut Object,
-    player_sensing_range: i32,
-    player_pos: Position,
-    dist_map: &mut Vec<f32>,
-) {
-    // go through all tiles and set their background color
+/// Pick the colors of an object depending on whether it is in sight and how far it is from the
+/// nearest viewer. Tiles outside of sight keep their shading once explored.
+fn object_colors(
+    object: &Object,
+    visibility: &Visibility,
+    auras: &[(Position, RGB)],
+) -> (RGB, RGB) {
     let bwft: RGB = palette().world_bg_wall_fov_true.into();
     let bwff: RGB = palette().world_bg_wall_fov_false.into();
     let bgft: RGB = palette().world_bg_ground_fov_true.into();
@@ -108,52 +227,42 @@ fn update_visual(
 
     let wall = object.physics.is_blocking_sight;
 
-    let idx = object.pos.y as usize * (WORLD_WIDTH as usize) + object.pos.x as usize;
-    if idx >= dist_map.len() {
-        panic!("Invalid object index!");
-    }
-    dist_map[idx] = dist_map[idx].min(object.pos.distance(&player_pos));
+    // fade the colors towards the edge of the closest viewer's sensing range
+    let fade = visibility
+        .viewers()
+        .iter()
+        .map(|(pos, range)| object.pos.distance(pos) / (range + 1) as f32)
+        .fold(f32::max_value(), f32::min);
 
     // set tile foreground and background colors
-    let (tile_color_fg, tile_color_bg) = match (object.physics.is_visible, wall) {
+    let (tile_color_fg, mut tile_color_bg) = match (object.physics.is_visible, wall) {
         // outside field of view:
         (false, true) => (fwff, bwff),
         (false, false) => (fgff, bgff),
         // inside fov:
-        // (true, true) => COLOR_LIGHT_WALL,
-        (true, true) => (
-            fwft.lerp(fwff, dist_map[idx] / (player_sensing_range + 1) as f32),
-            bwft.lerp(bwff, dist_map[idx] / (player_sensing_range + 1) as f32),
-        ),
-        // (true, false) => COLOR_ground_in_fov,
-        (true, false) => (
-            fgft.lerp(fgff, dist_map[idx] / (player_sensing_range + 1) as f32),
-            bgft.lerp(bgff, dist_map[idx] / (player_sensing_range + 1) as f32),
-        ),
+        (true, true) => (fwft.lerp(fwff, fade), bwft.lerp(bwff, fade)),
+        (true, false) => (fgft.lerp(fgff, fade), bgft.lerp(bgff, fade)),
     };
 
-    if let Some(tile) = &mut object.tile {
-        if object.physics.is_visible {
-            tile.is_explored = true;
+    if object.physics.is_visible && !wall {
+        for (pos, color) in auras {
+            if object.pos.distance(pos) <= SIGNAL_RADIUS {
+                tile_color_bg = tile_color_bg.lerp(*color, AURA_TINT);
+            }
         }
-        if tile.is_explored {
-            // show explored tiles only (any visible tile is explored already)
-            object.visual.fg_color = (
-                (tile_color_fg.r * 255.0) as u8,
-                (tile_color_fg.g * 255.0) as u8,
-                (tile_color_fg.b * 255.0) as u8,
-            );
-            object.visual.bg_color = (
-                (tile_color_bg.r * 255.0) as u8,
-                (tile_color_bg.g * 255.0) as u8,
-                (tile_color_bg.b * 255.0) as u8,
-            );
+        if let Some(tile) = object.tile.as_ref().filter(|t| t.toxin > 0) {
+            let tint = TOXIN_TINT * tile.toxin as f32 / TOXIN_MAX as f32;
+            tile_color_bg = tile_color_bg.lerp(palette().col_acc2.into(), tint);
         }
-    } else {
-        object.visual.bg_color = (
-            (tile_color_bg.r * 255.0) as u8,
-            (tile_color_bg.g * 255.0) as u8,
-            (tile_color_bg.b * 255.0) as u8,
-        );
+        if object.tile.as_ref().is_some_and(|t| t.debris.is_some()) {
+            tile_color_bg = tile_color_bg.lerp(palette().col_acc3.into(), DEBRIS_TINT);
+        }
+    }
+
+    match &object.tile {
+        // show explored tiles only (any visible tile is explored already)
+        Some(tile) if tile.is_explored => (tile_color_fg, tile_color_bg),
+        Some(_) => (object.visual.fg_color.into(), object.visual.bg_color.into()),
+        None => (object.display_color().into(), tile_color_bg),
     }
 }
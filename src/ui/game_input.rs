@@ -1,14 +1,20 @@
 use crate::core::game_objects::GameObjects;
-use crate::core::game_state::GameState;
+use crate::core::game_state::{GameState, MessageLog, MsgClass};
+use crate::core::innit_env;
 use crate::core::position::Position;
 use crate::entity::action::*;
 use crate::entity::control::Controller::Player;
+use crate::entity::danger::DangerWatch;
+use crate::entity::inventory::{is_identified, item_name};
+use crate::entity::player::HOTBAR_SIZE;
 use crate::game::WORLD_WIDTH;
 use crate::ui::game_input::PlayerAction::PrimaryAction;
 use crate::ui::game_input::PlayerInput::{MetaInput, PlayInput};
-use crate::ui::hud::{Hud, HudItem, ToolTip};
+use crate::ui::hud::{Hud, HudItem, SidebarTab, ToolTip};
+use crate::util::platform;
 use rltk::prelude::INPUT;
-use rltk::{BEvent, Point, Rltk, VirtualKeyCode};
+use rltk::{Point, Rltk, VirtualKeyCode};
+use std::collections::HashSet;
 
 #[derive(Clone, Debug)]
 pub enum PlayerInput {
@@ -21,28 +27,162 @@ pub enum PlayerInput {
 pub enum UiAction {
     ExitGameLoop,
     CharacterScreen,
+    MutationHistory,
+    RenameOrganism,
+    Trade,
+    Organelles,
+    Encyclopedia,
+    ToggleBookmark,
+    Bookmarks,
     ToggleDarkLightMode,
+    ToggleVisualEffects,
+    ToggleAmbience,
+    ToggleFactionBadges,
+    CycleCombatDetail,
+    CycleGameSpeed,
+    ToggleMoveMode,
     ChoosePrimaryAction,
     ChooseSecondaryAction,
-    ChooseQuick1Action,
-    ChooseQuick2Action,
+    ChooseHotbarAction(usize),
     GenomeEditor,
     Help,
+    Snapshot,
+    ShowRunCode,
+    ToggleInputRecording,
+    BugReport,
+    ToggleTelemetry,
+    ToggleAiTraces,
+    CycleHeatMap,
+    ExportTelemetry,
+    SelectTab(SidebarTab),
+    /// Switch to the sidebar tab the given number of places to the right.
+    CycleTab(i32),
+    ToggleActions,
+    /// Scroll the content of the active sidebar tab by the given number of lines.
+    ScrollPanel(i32),
+    Sandbox,
+    TogglePause,
+    AdvanceTurn,
+    ShowLogOrigin(Position),
+    TravelTo(Position),
 }
 
 #[derive(Clone, Debug)]
 pub enum PlayerAction {
-    PrimaryAction(Target),   // using the arrow keys
+    PrimaryAction(Target),   // using the arrow keys or the numpad
     SecondaryAction(Target), // using 'W','A','S','D' keys
-    Quick1Action,            // using 'Q', un-targeted quick action
-    Quick2Action,            // using 'E', un-targeted second quick action
+    HotbarAction(usize),     // using '1'-'9', 'Q' and 'E', un-targeted hotbar actions
     PassTurn,
     UseInventoryItem(usize),
     DropItem(usize),
 }
 
+/// Repeats a held movement key, so that walking doesn't take a key press per turn. Repeating
+/// stops when the key is released or the danger watch raises an alarm.
+#[derive(Debug, Default)]
+pub struct KeyRepeat {
+    held: Option<HeldKey>,
+}
+
+#[derive(Debug)]
+struct HeldKey {
+    key: VirtualKeyCode,
+    input: PlayerInput,
+    /// Time in [ms] at which the key is repeated next.
+    next_repeat: u128,
+    /// Situation when the key was pressed.
+    watch: DangerWatch,
+}
+
+impl KeyRepeat {
+    pub fn new() -> Self {
+        KeyRepeat { held: None }
+    }
+
+    /// Forget the held key once it's released. This is checked every frame, so that pressing the
+    /// key again while the world is busy isn't mistaken for a repeat.
+    pub fn check_release(&mut self) {
+        let input = INPUT.lock();
+        if self
+            .held
+            .as_ref()
+            .is_some_and(|h| !input.key_pressed_set().contains(&h.key))
+        {
+            self.held = None;
+        }
+    }
+
+    /// Check whether the key is already held down, in which case a new key press is only the
+    /// operating system's own key repeat.
+    fn is_held(&self, key: VirtualKeyCode) -> bool {
+        self.held.as_ref().is_some_and(|h| h.key == key)
+    }
+
+    fn press(
+        &mut self,
+        key: VirtualKeyCode,
+        input: &PlayerInput,
+        state: &GameState,
+        objects: &GameObjects,
+        hud: &Hud,
+    ) {
+        let delay = innit_env().key_repeat_delay_ms;
+        let rate = innit_env().key_repeat_rate_ms;
+        let is_movement = matches!(
+            input,
+            PlayInput(PrimaryAction(_)) | PlayInput(PlayerAction::SecondaryAction(_))
+        );
+        self.held = if is_movement && rate > 0 {
+            Some(HeldKey {
+                key,
+                input: input.clone(),
+                next_repeat: now_ms() + delay as u128,
+                watch: DangerWatch::new(state, objects, hud.threat),
+            })
+        } else {
+            None
+        };
+    }
+
+    /// Return the input of the held key if it's due to be repeated.
+    fn repeat(
+        &mut self,
+        pressed: &HashSet<VirtualKeyCode>,
+        state: &mut GameState,
+        objects: &GameObjects,
+        hud: &Hud,
+    ) -> Option<PlayerInput> {
+        let held = self.held.as_mut()?;
+        if !pressed.contains(&held.key) {
+            self.held = None;
+            return None;
+        }
+        if let Some(danger) = held.watch.check(state, objects, hud.threat) {
+            self.held = None;
+            if let Some(msg) = danger.message() {
+                state.log.add(msg, MsgClass::Info);
+            }
+            return None;
+        }
+        let now = now_ms();
+        if now < held.next_repeat {
+            return None;
+        }
+        let rate = innit_env().key_repeat_rate_ms;
+        held.next_repeat = now + rate as u128;
+        Some(held.input.clone())
+    }
+}
+
+fn now_ms() -> u128 {
+    platform::now_nanos() / 1_000_000
+}
+
+/// Number of lines that `PageUp` and `PageDown` scroll the sidebar.
+const PAGE_LINES: i32 = 10;
+
 /// Translate between bracket's keys and our own key codes.
-fn key_to_action(key: VirtualKeyCode, ctrl: bool, shift: bool) -> PlayerInput {
+pub fn key_to_action(key: VirtualKeyCode, ctrl: bool, shift: bool) -> PlayerInput {
     use self::PlayerAction::*;
     use self::PlayerInput::*;
     use self::Target::*;
@@ -50,29 +190,88 @@ fn key_to_action(key: VirtualKeyCode, ctrl: bool, shift: bool) -> PlayerInput {
     match (key, ctrl, shift) {
         // letters
         (VirtualKeyCode::A, false, false) => PlayInput(SecondaryAction(West)),
+        (VirtualKeyCode::B, false, false) => MetaInput(ToggleBookmark),
+        (VirtualKeyCode::B, false, true) => MetaInput(Bookmarks),
         (VirtualKeyCode::C, false, false) => MetaInput(CharacterScreen),
         (VirtualKeyCode::D, false, false) => PlayInput(SecondaryAction(East)),
-        (VirtualKeyCode::E, false, false) => PlayInput(Quick2Action),
-        (VirtualKeyCode::E, true, false) => MetaInput(ChooseQuick2Action),
+        (VirtualKeyCode::E, false, false) => PlayInput(HotbarAction(1)),
+        (VirtualKeyCode::E, true, false) => MetaInput(ChooseHotbarAction(1)),
+        (VirtualKeyCode::F, false, false) => MetaInput(CycleGameSpeed),
         (VirtualKeyCode::G, false, false) => MetaInput(GenomeEditor),
+        (VirtualKeyCode::H, false, false) => MetaInput(MutationHistory),
+        (VirtualKeyCode::I, false, false) => MetaInput(ToggleFactionBadges),
+        (VirtualKeyCode::K, false, false) => MetaInput(Encyclopedia),
         (VirtualKeyCode::L, false, false) => MetaInput(ToggleDarkLightMode),
+        (VirtualKeyCode::M, false, false) => MetaInput(ToggleMoveMode),
+        (VirtualKeyCode::N, false, false) => MetaInput(RenameOrganism),
+        (VirtualKeyCode::O, false, false) => MetaInput(Organelles),
+        (VirtualKeyCode::P, false, false) => MetaInput(TogglePause),
         (VirtualKeyCode::P, true, false) => MetaInput(ChoosePrimaryAction),
-        (VirtualKeyCode::Q, false, false) => PlayInput(Quick1Action),
-        (VirtualKeyCode::Q, true, false) => MetaInput(ChooseQuick1Action),
+        (VirtualKeyCode::Q, false, false) => PlayInput(HotbarAction(0)),
+        (VirtualKeyCode::Q, true, false) => MetaInput(ChooseHotbarAction(0)),
         (VirtualKeyCode::S, false, false) => PlayInput(SecondaryAction(South)),
+        (VirtualKeyCode::T, false, false) => MetaInput(Trade),
+        (VirtualKeyCode::V, false, false) => MetaInput(ToggleVisualEffects),
+        (VirtualKeyCode::V, false, true) => MetaInput(CycleCombatDetail),
+        (VirtualKeyCode::V, true, false) => MetaInput(ToggleAmbience),
         (VirtualKeyCode::S, true, false) => MetaInput(ChooseSecondaryAction),
         (VirtualKeyCode::W, false, false) => PlayInput(SecondaryAction(North)),
         (VirtualKeyCode::Up, false, false) => PlayInput(PrimaryAction(North)),
         (VirtualKeyCode::Down, false, false) => PlayInput(PrimaryAction(South)),
         (VirtualKeyCode::Left, false, false) => PlayInput(PrimaryAction(West)),
         (VirtualKeyCode::Right, false, false) => PlayInput(PrimaryAction(East)),
+        // number keys, hotbar
+        (VirtualKeyCode::Key1, ctrl, false) => hotbar_input(0, ctrl),
+        (VirtualKeyCode::Key2, ctrl, false) => hotbar_input(1, ctrl),
+        (VirtualKeyCode::Key3, ctrl, false) => hotbar_input(2, ctrl),
+        (VirtualKeyCode::Key4, ctrl, false) => hotbar_input(3, ctrl),
+        (VirtualKeyCode::Key5, ctrl, false) => hotbar_input(4, ctrl),
+        (VirtualKeyCode::Key6, ctrl, false) => hotbar_input(5, ctrl),
+        (VirtualKeyCode::Key7, ctrl, false) => hotbar_input(6, ctrl),
+        (VirtualKeyCode::Key8, ctrl, false) => hotbar_input(7, ctrl),
+        (VirtualKeyCode::Key9, ctrl, false) => hotbar_input(8, ctrl),
+        // numpad, including diagonals
+        (VirtualKeyCode::Numpad8, false, false) => PlayInput(PrimaryAction(North)),
+        (VirtualKeyCode::Numpad9, false, false) => PlayInput(PrimaryAction(NorthEast)),
+        (VirtualKeyCode::Numpad6, false, false) => PlayInput(PrimaryAction(East)),
+        (VirtualKeyCode::Numpad3, false, false) => PlayInput(PrimaryAction(SouthEast)),
+        (VirtualKeyCode::Numpad2, false, false) => PlayInput(PrimaryAction(South)),
+        (VirtualKeyCode::Numpad1, false, false) => PlayInput(PrimaryAction(SouthWest)),
+        (VirtualKeyCode::Numpad4, false, false) => PlayInput(PrimaryAction(West)),
+        (VirtualKeyCode::Numpad7, false, false) => PlayInput(PrimaryAction(NorthWest)),
+        (VirtualKeyCode::Numpad5, false, false) => PlayInput(PassTurn),
         (VirtualKeyCode::Space, false, false) => PlayInput(PassTurn),
+        (VirtualKeyCode::Period, false, false) => MetaInput(AdvanceTurn),
         (VirtualKeyCode::Escape, false, false) => MetaInput(ExitGameLoop),
+        (VirtualKeyCode::Tab, false, false) => MetaInput(CycleTab(1)),
+        (VirtualKeyCode::Tab, false, true) => MetaInput(CycleTab(-1)),
+        (VirtualKeyCode::PageUp, false, false) => MetaInput(ScrollPanel(-PAGE_LINES)),
+        (VirtualKeyCode::PageDown, false, false) => MetaInput(ScrollPanel(PAGE_LINES)),
         (VirtualKeyCode::F1, false, false) => MetaInput(Help),
+        (VirtualKeyCode::F2, false, false) => MetaInput(Snapshot),
+        (VirtualKeyCode::F3, false, false) => MetaInput(ShowRunCode),
+        (VirtualKeyCode::F4, false, false) => MetaInput(ToggleInputRecording),
+        (VirtualKeyCode::F5, false, false) => MetaInput(BugReport),
+        (VirtualKeyCode::F6, false, false) => MetaInput(ToggleAiTraces),
+        (VirtualKeyCode::F7, false, false) => MetaInput(CycleHeatMap),
+        (VirtualKeyCode::F11, false, false) => MetaInput(ToggleTelemetry),
+        (VirtualKeyCode::F11, false, true) => MetaInput(ExportTelemetry),
+        (VirtualKeyCode::F12, false, false) => MetaInput(Sandbox),
         _ => Undefined,
     }
 }
 
+/// Number keys trigger a hotbar slot, or let the player reassign it when holding `Ctrl`.
+fn hotbar_input(slot: usize, ctrl: bool) -> PlayerInput {
+    if slot >= HOTBAR_SIZE {
+        PlayerInput::Undefined
+    } else if ctrl {
+        MetaInput(UiAction::ChooseHotbarAction(slot))
+    } else {
+        PlayInput(PlayerAction::HotbarAction(slot))
+    }
+}
+
 // Create A detailed info panel as tooltip.
 // - list stats and (compare with player) to give hints about strength, receptors and such
 // - get player sensor quality, quantity and adjust how much info is shown
@@ -81,7 +280,7 @@ fn key_to_action(key: VirtualKeyCode, ctrl: bool, shift: bool) -> PlayerInput {
 // useful info:
 // - receptor matching or not
 // - virus RNA or DNA
-fn get_names_under_mouse(
+fn get_tooltips_under_mouse(
     state: &GameState,
     objects: &mut GameObjects,
     mouse: Position,
@@ -101,7 +300,17 @@ fn get_names_under_mouse(
             .flatten()
             .filter(|o| o.pos.eq(&mouse) && o.physics.is_visible)
             //                              vvvvv---- replace function with `key-value`-list generating function.
-            .map(|o| o.generate_tooltip(&player))
+            .map(|o| {
+                // unidentified items don't give away their genes
+                if is_identified(state, o) {
+                    o.generate_tooltip(&player)
+                } else {
+                    ToolTip::new(
+                        item_name(state, o),
+                        vec![("genes:".to_string(), "unknown".to_string())],
+                    )
+                }
+            })
             .collect::<Vec<_>>(),
     );
 
@@ -113,14 +322,10 @@ pub fn read_input(
     state: &mut GameState,
     objects: &mut GameObjects,
     hud: &mut Hud,
+    key_repeat: &mut KeyRepeat,
     ctx: &mut Rltk,
 ) -> PlayerInput {
-    let mut input = INPUT.lock();
-    #[allow(clippy::single_match)]
-    input.for_each_message(|event| match event {
-        BEvent::CloseRequested => ctx.quitting = true,
-        _ => (),
-    });
+    let input = INPUT.lock();
 
     // 1) check if key has been pressed
     let ctrl = input.key_pressed_set().contains(&VirtualKeyCode::LControl)
@@ -129,7 +334,16 @@ pub fn read_input(
         || input.key_pressed_set().contains(&VirtualKeyCode::RShift);
 
     if let Some(key) = ctx.key {
-        return key_to_action(key, ctrl, shift);
+        // held keys are repeated at our own pace
+        if key_repeat.is_held(key) {
+            return PlayerInput::Undefined;
+        }
+        let action = key_to_action(key, ctrl, shift);
+        key_repeat.press(key, &action, state, objects, hud);
+        return action;
+    }
+    if let Some(action) = key_repeat.repeat(input.key_pressed_set(), state, objects, hud) {
+        return action;
     }
 
     let mouse = Position::from(ctx.mouse_point());
@@ -138,7 +352,7 @@ pub fn read_input(
     // 2) update hovered objects
     hud.update_tooltips(
         Point::from((mouse.x, mouse.y)),
-        get_names_under_mouse(state, objects, mouse),
+        get_tooltips_under_mouse(state, objects, mouse),
     );
 
     // 3) if mouse is over world
@@ -150,8 +364,10 @@ pub fn read_input(
                 if let Some(Player(ctrl)) = &player.control {
                     if let TargetCategory::Any = ctrl.primary_action.get_target_category() {
                         return PlayInput(PrimaryAction(Target::from_pos(&player.pos, &mouse)));
-                    } else if player.pos.is_adjacent(&mouse) {
+                    } else if player.pos.is_neighbour(&mouse) {
                         return PlayInput(PrimaryAction(Target::from_pos(&player.pos, &mouse)));
+                    } else if !player.pos.is_equal(&mouse) {
+                        return MetaInput(UiAction::TravelTo(mouse));
                     }
                 }
             }
@@ -169,8 +385,7 @@ pub fn read_input(
                 match item.item_enum {
                     HudItem::PrimaryAction => MetaInput(UiAction::ChoosePrimaryAction),
                     HudItem::SecondaryAction => MetaInput(UiAction::ChooseSecondaryAction),
-                    HudItem::Quick1Action => MetaInput(UiAction::ChooseQuick1Action),
-                    HudItem::Quick2Action => MetaInput(UiAction::ChooseQuick2Action),
+                    HudItem::HotbarSlot { idx } => MetaInput(UiAction::ChooseHotbarAction(idx)),
                     HudItem::DnaItem => PlayerInput::Undefined,
                     HudItem::UseInventory { idx } => {
                         PlayerInput::PlayInput(PlayerAction::UseInventoryItem(idx))
@@ -178,11 +393,23 @@ pub fn read_input(
                     HudItem::DropInventory { idx } => {
                         PlayerInput::PlayInput(PlayerAction::DropItem(idx))
                     }
+                    HudItem::ToggleActions => MetaInput(UiAction::ToggleActions),
+                    HudItem::Tab(tab) => MetaInput(UiAction::SelectTab(tab)),
+                    HudItem::DnaOverflow => MetaInput(UiAction::SelectTab(SidebarTab::Dna)),
+                    HudItem::ScrollPanel(step) => MetaInput(UiAction::ScrollPanel(step)),
                 }
             } else {
                 PlayerInput::Undefined
             };
         };
+        // 4b) clicking a log line points out where the logged event happened
+        if is_clicked {
+            if let Some(line) = hud.log_line_at(Point::new(mouse.x, mouse.y)) {
+                if let Some(origin) = line.origin {
+                    return MetaInput(UiAction::ShowLogOrigin(origin));
+                }
+            }
+        }
         // 3b) check for button press to activate ui buttons
         PlayerInput::Undefined
     }
@@ -3,15 +3,30 @@
 //!
 //! - varying visibility and detail, depending on perception (add to genetic traits)
 //! - potential structure:
-//!   - header
+//!   - header, colored by faction
+//!   - bars for hp and energy
 //!   - table with attributes and values:
 //!     - hp
 //!     - energy
 //!     - receptor and whether it's matching with us
 
-use crate::entity::genetics::TraitFamily;
+use crate::core::game_objects::GameObjects;
+use crate::core::host::HOST_HEALTH_MAX;
+use crate::core::innit_env;
+use crate::core::position::Position;
+use crate::core::telemetry::Telemetry;
+use crate::core::world::{Environment, Region};
+use crate::entity::faction::Faction;
+use crate::entity::genetics::{GeneLibrary, GeneticTrait, TraitFamily};
+use crate::entity::inventory::item_name;
 use crate::entity::object::Object;
-use crate::game::{SCREEN_HEIGHT, SCREEN_WIDTH, SIDE_PANEL_HEIGHT, SIDE_PANEL_WIDTH};
+use crate::entity::player::HOTBAR_SIZE;
+use crate::game::{
+    HUD_CON, SCREEN_HEIGHT, SCREEN_WIDTH, SIDE_PANEL_HEIGHT, SIDE_PANEL_WIDTH, WORLD_HEIGHT,
+    WORLD_WIDTH,
+};
+use crate::ui::animations::AnimationClock;
+use crate::ui::settings::UiSettings;
 use crate::util::modulus;
 use crate::{
     core::game_state::{GameState, MsgClass},
@@ -19,6 +34,27 @@ use crate::{
 };
 use crate::{entity::action::Target, util::text_to_width};
 use rltk::{to_cp437, ColorPair, DrawBatch, Point, Rect, Rltk};
+use serde::{Deserialize, Serialize};
+
+/// Color that organisms of the given faction are marked with in tooltips.
+pub fn faction_color(faction: Faction) -> (u8, u8, u8) {
+    match faction {
+        Faction::Unaligned => palette().hud_fg,
+        Faction::Lineage => palette().entity_player,
+        Faction::GutFlora => palette().entity_bacteria,
+        Faction::Pathogens => palette().entity_virus,
+        Faction::ImmuneSystem => palette().hud_fg_msg_alert,
+    }
+}
+
+/// Color that marks the given region on the map.
+pub fn region_color(region: Region) -> (u8, u8, u8) {
+    match region {
+        Region::MucusLayer => palette().col_acc1,
+        Region::StomachLining => palette().col_acc2,
+        Region::CapillaryBed => palette().col_acc3,
+    }
+}
 
 /// Menu item properties
 /// - `text` for rendering
@@ -56,15 +92,205 @@ impl<T> UiItem<T> {
     }
 }
 
+/// Screen row of the hotbar at the bottom of the side panel.
+const HOTBAR_Y: i32 = SIDE_PANEL_HEIGHT - 2;
+/// Screen row of the header of the actions section.
+const ACTIONS_Y: i32 = 5;
+/// Number of genes shown along the top of the side panel.
+const DNA_STRIP_TOP_LEN: usize = SIDE_PANEL_WIDTH as usize - 4;
+/// Number of genes shown in the DNA strip, along the top and down the right edge.
+const DNA_STRIP_LEN: usize = DNA_STRIP_TOP_LEN + SCREEN_HEIGHT as usize;
+
+/// Each cell of the map in the objectives tab covers a square of this many tiles.
+const MAP_SCALE: i32 = 4;
+const MAP_WIDTH: i32 = WORLD_WIDTH / MAP_SCALE;
+const MAP_HEIGHT: i32 = WORLD_HEIGHT / MAP_SCALE;
+
+/// Explored part of the world within a cell of the map.
+#[derive(Clone, Copy, Debug)]
+struct MapCell {
+    region: Region,
+    /// Whether any explored tile of the cell is open.
+    is_open: bool,
+}
+
+/// Color of the genes of the given trait family.
+fn family_color(family: &TraitFamily) -> (u8, u8, u8) {
+    match family {
+        TraitFamily::Sensing => palette().hud_fg_dna_processor,
+        TraitFamily::Processing => palette().hud_fg_dna_actuator,
+        TraitFamily::Actuating => palette().hud_fg_dna_sensor,
+        TraitFamily::Junk(_) => (59, 59, 59), // TODO
+        TraitFamily::Ltr => (255, 255, 255),  // TODO
+    }
+}
+
+/// Color of the given gene, dimmed if it's silenced.
+fn gene_color(g_trait: &GeneticTrait) -> (u8, u8, u8) {
+    if g_trait.silenced {
+        palette().hud_fg_inactive
+    } else {
+        family_color(&g_trait.trait_family)
+    }
+}
+
+/// Trait families explained by the legend of the genome tab, with their short labels.
+const DNA_LEGEND: [(TraitFamily, &str); 3] = [
+    (TraitFamily::Sensing, "sense"),
+    (TraitFamily::Processing, "proc"),
+    (TraitFamily::Actuating, "act"),
+];
+
+/// Position of the trait family in the genome tab.
+fn family_rank(family: &TraitFamily) -> usize {
+    match family {
+        TraitFamily::Sensing => 0,
+        TraitFamily::Processing => 1,
+        TraitFamily::Actuating => 2,
+        TraitFamily::Ltr => 3,
+        TraitFamily::Junk(_) => 4,
+    }
+}
+
+/// A line of the genome tab, either the header of a trait family or one of its genes.
+enum GenomeLine<'a> {
+    Family {
+        name: String,
+        count: usize,
+    },
+    Gene {
+        idx: usize,
+        g_trait: &'a GeneticTrait,
+    },
+}
+
+/// Name, family and code of a gene, and how it contributes to the stats of the player.
+fn gene_tooltip(g_trait: &GeneticTrait, player: &Object, gene_library: &GeneLibrary) -> ToolTip {
+    let mut attributes = vec![
+        ("family:".to_string(), g_trait.trait_family.to_string()),
+        (
+            "code:".to_string(),
+            format!("{:08b}", gene_library.trait_code(g_trait)),
+        ),
+    ];
+    if g_trait.silenced {
+        attributes.push(("state:".to_string(), "silenced".to_string()));
+    } else if let Some(effect) = g_trait.attribute.describe() {
+        attributes.push(("effect:".to_string(), effect));
+    }
+    if let Some(action) = g_trait.action.as_ref().filter(|_| !g_trait.silenced) {
+        // every expressed copy of the gene raises the level of its action
+        let copies = player
+            .dna
+            .simplified
+            .iter()
+            .filter(|g| g.trait_name == g_trait.trait_name && !g.silenced)
+            .count();
+        attributes.push((
+            "action:".to_string(),
+            format!("{} lvl {}", action.get_identifier(), copies),
+        ));
+    }
+    ToolTip::new(g_trait.trait_name.clone(), attributes).with_color(gene_color(g_trait))
+}
+
+/// Group the genes by trait family, each group headed by the family and its number of genes.
+fn genome_lines(player: &Object) -> Vec<GenomeLine<'_>> {
+    let mut lines = Vec::new();
+    for rank in 0..=family_rank(&TraitFamily::Junk(0)) {
+        let genes: Vec<(usize, &GeneticTrait)> = player
+            .dna
+            .simplified
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| family_rank(&g.trait_family) == rank)
+            .collect();
+        if let Some((_, first)) = genes.first() {
+            lines.push(GenomeLine::Family {
+                name: first.trait_family.to_string(),
+                count: genes.len(),
+            });
+        }
+        lines.extend(
+            genes
+                .into_iter()
+                .map(|(idx, g_trait)| GenomeLine::Gene { idx, g_trait }),
+        );
+    }
+    lines
+}
+/// Time in [ms] that notices stay up above the world.
+const TOAST_DURATION_MS: f32 = 4000.0;
+
+/// Tabs of the side panel below the actions, each showing a different side of the game.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SidebarTab {
+    /// Inventory and local conditions.
+    #[default]
+    Status,
+    Dna,
+    Log,
+    Threats,
+    Objectives,
+}
+
+impl SidebarTab {
+    const ALL: [SidebarTab; 5] = [
+        SidebarTab::Status,
+        SidebarTab::Dna,
+        SidebarTab::Log,
+        SidebarTab::Threats,
+        SidebarTab::Objectives,
+    ];
+
+    fn label(&self) -> &str {
+        match self {
+            SidebarTab::Status => "Stat",
+            SidebarTab::Dna => "DNA",
+            SidebarTab::Log => "Log",
+            SidebarTab::Threats => "Thr",
+            SidebarTab::Objectives => "Obj",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self {
+            SidebarTab::Status => "inventory and conditions",
+            SidebarTab::Dna => "genome",
+            SidebarTab::Log => "message log",
+            SidebarTab::Threats => "threats in sight",
+            SidebarTab::Objectives => "objectives",
+        }
+    }
+
+    /// Return the tab `step` places further to the right, wrapping around at either end.
+    pub fn cycle(&self, step: i32) -> SidebarTab {
+        let idx = Self::ALL.iter().position(|t| t == self).unwrap_or(0) as i32;
+        Self::ALL[modulus(idx + step, Self::ALL.len() as i32) as usize]
+    }
+}
+
 #[derive(PartialEq)]
 pub enum HudItem {
     PrimaryAction,
     SecondaryAction,
-    Quick1Action,
-    Quick2Action,
+    HotbarSlot {
+        idx: usize,
+    },
     DnaItem,
-    UseInventory { idx: usize },
-    DropInventory { idx: usize },
+    /// Stands in for the genes that don't fit into the DNA strip.
+    DnaOverflow,
+    UseInventory {
+        idx: usize,
+    },
+    DropInventory {
+        idx: usize,
+    },
+    /// Header of the actions section, which folds it away or unfolds it again.
+    ToggleActions,
+    Tab(SidebarTab),
+    /// Scroll the content of the active tab by the given number of lines.
+    ScrollPanel(i32),
 }
 
 impl HudItem {
@@ -84,62 +310,75 @@ impl HudItem {
 
     fn is_dna_item(&self) -> bool {
         match *self {
-            Self::DnaItem => true,
+            Self::DnaItem | Self::DnaOverflow => true,
             _ => false,
         }
     }
+
+    /// Check whether the item moves or disappears when the layout of the side panel changes.
+    fn is_panel_item(&self) -> bool {
+        matches!(
+            self,
+            Self::PrimaryAction
+                | Self::SecondaryAction
+                | Self::ToggleActions
+                | Self::Tab(_)
+                | Self::ScrollPanel(_)
+        )
+    }
 }
 
 fn create_hud_items(hud_layout: &Rect) -> Vec<UiItem<HudItem>> {
-    let button_len = SIDE_PANEL_WIDTH / 2;
-    let button_x = hud_layout.x1 + 3;
     let fg_col = palette().hud_fg;
     let bg_col = palette().hud_bg;
     let col_pair = ColorPair::new(fg_col, bg_col);
-    let items = vec![
-        UiItem::new(
-            HudItem::PrimaryAction,
-            "",
-            ToolTip::header_only("select new primary action"),
-            Rect::with_size(button_x, 6, button_len, 1),
-            col_pair,
-        ),
-        UiItem::new(
-            HudItem::SecondaryAction,
-            "",
-            ToolTip::header_only("select new secondary action"),
-            Rect::with_size(button_x, 7, button_len, 1),
-            col_pair,
-        ),
-        UiItem::new(
-            HudItem::Quick1Action,
-            "",
-            ToolTip::header_only("select new quick action"),
-            Rect::with_size(button_x, 8, button_len, 1),
-            col_pair,
-        ),
-        UiItem::new(
-            HudItem::Quick2Action,
-            "",
-            ToolTip::header_only("select new quick action"),
-            Rect::with_size(button_x, 9, button_len, 1),
-            col_pair,
-        ),
-    ];
-
-    items
+    let hotbar_x = hud_layout.x1 + 1;
+    (0..HOTBAR_SIZE)
+        .map(|idx| {
+            UiItem::new(
+                HudItem::HotbarSlot { idx },
+                "",
+                ToolTip::header_only("select new hotbar action"),
+                Rect::with_size(hotbar_x + 2 * idx as i32, HOTBAR_Y, 2, 1),
+                col_pair,
+            )
+        })
+        .collect()
 }
 
+/// Width in `[cells]` of the bars shown in tooltips.
+const TOOLTIP_BAR_WIDTH: i32 = 10;
+
 #[derive(Clone, Debug)]
 pub struct ToolTip {
     header: Option<String>,
+    /// Color of the header, if it differs from the regular text.
+    color: Option<(u8, u8, u8)>,
+    bars: Vec<ToolTipBar>,
     attributes: Vec<(String, String)>,
 }
 
+/// A labeled bar in a tooltip, e.g. for hp or energy.
+#[derive(Clone, Debug)]
+struct ToolTipBar {
+    label: String,
+    value: i32,
+    max: i32,
+    color: (u8, u8, u8),
+}
+
+impl ToolTipBar {
+    fn text(&self) -> String {
+        format!("{}/{}", self.value, self.max)
+    }
+}
+
 impl ToolTip {
     pub fn new<S1: Into<String>>(header: S1, attrs: Vec<(String, String)>) -> Self {
         ToolTip {
             header: Some(header.into()),
+            color: None,
+            bars: Vec::new(),
             attributes: attrs
                 .iter()
                 .map(|(e1, e2)| (e1.into(), e2.into()))
@@ -150,6 +389,8 @@ impl ToolTip {
     pub fn no_header(attrs: Vec<(String, String)>) -> Self {
         ToolTip {
             header: None,
+            color: None,
+            bars: Vec::new(),
             attributes: attrs
                 .iter()
                 .map(|(e1, e2)| (e1.into(), e2.into()))
@@ -160,10 +401,35 @@ impl ToolTip {
     pub fn header_only<S1: Into<String>>(header: S1) -> Self {
         ToolTip {
             header: Some(header.into()),
+            color: None,
+            bars: Vec::new(),
             attributes: Vec::new(),
         }
     }
 
+    /// Print the header in the given color.
+    pub fn with_color(mut self, color: (u8, u8, u8)) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Add a bar below the header, the bars are shown in the order they are added.
+    pub fn with_bar<S: Into<String>>(
+        mut self,
+        label: S,
+        value: i32,
+        max: i32,
+        color: (u8, u8, u8),
+    ) -> Self {
+        self.bars.push(ToolTipBar {
+            label: label.into(),
+            value,
+            max,
+            color,
+        });
+        self
+    }
+
     /// Calculate the width in `[cells]` that a text box will require to be rendered on screen.
     fn content_width(&self) -> i32 {
         let header_width: usize = if let Some(h) = &self.header {
@@ -179,15 +445,22 @@ impl ToolTip {
             .max()
             .unwrap_or(0);
 
+        let bars_width: usize = self
+            .bars
+            .iter()
+            .map(|b| b.label.len() + b.text().len() + TOOLTIP_BAR_WIDTH as usize + 2)
+            .max()
+            .unwrap_or(0);
+
         // pad header with 2 and attributes with 3 to account for borders and separators
-        (header_width + 2).max(attributes_width) as i32
+        (header_width + 2).max(attributes_width).max(bars_width) as i32
     }
 
     /// Calculate the height in `[cells]` that a text box will require to be rendered on screen.
     fn content_height(&self) -> i32 {
         // header takes two lines for header text and separator
         let header_height = if self.header.is_some() {
-            if self.attributes.is_empty() {
+            if self.attributes.is_empty() && self.bars.is_empty() {
                 1
             } else {
                 2
@@ -196,7 +469,7 @@ impl ToolTip {
             0
         };
 
-        let attributes_height = self.attributes.len();
+        let attributes_height = self.attributes.len() + self.bars.len();
         // println!("ATTRIBUTES LEN {}", self.attributes.len());
 
         // pad height with 2 cells to account for rendering borders top and bottom
@@ -211,14 +484,43 @@ impl ToolTip {
 //     ]
 // }
 
+/// Screen row of a rendered log line, together with the turn and origin of its message.
+#[derive(Clone, Debug)]
+pub struct LogLine {
+    pub y: i32,
+    pub turn: u128,
+    pub origin: Option<Position>,
+}
+
 pub struct Hud {
     layout: Rect,
     pub inv_area: Rect,
     pub log_area: Rect,
+    pub log_lines: Vec<LogLine>,
+    /// Accumulated threat of all hostile organisms within the player's sensing range.
+    pub threat: f32,
+    /// Name, faction and threat of each of these organisms, the most dangerous first.
+    threats: Vec<(String, Faction, f32)>,
+    /// Local conditions at the player's position, which make actions cheaper or more expensive.
+    pub environment: Environment,
+    /// Region of the host that the player is in.
+    region: Region,
+    /// Cells of the map, row by row, `None` where nothing has been explored yet.
+    map: Vec<Option<MapCell>>,
+    /// Map cell that the player is in.
+    map_player: Point,
+    /// Whether the game is paused, to remind the player why nothing is happening.
+    pub paused: bool,
+    /// Short notice shown above the world, e.g. for unlocked achievements, together with the
+    /// time in [ms] it stays up.
+    toast: Option<(String, f32)>,
     last_mouse: Point,
     pub require_refresh: bool,
     pub items: Vec<UiItem<HudItem>>,
     tooltips: Vec<ToolTip>,
+    pub settings: UiSettings,
+    /// Number of lines the genome tab is scrolled down.
+    genome_scroll: usize,
 }
 
 impl Hud {
@@ -228,19 +530,91 @@ impl Hud {
         let x2 = x1 + SIDE_PANEL_WIDTH;
         let y2 = SIDE_PANEL_HEIGHT - 1;
         let layout = Rect::with_exact(x1, y1, x2, y2);
-        let inv_area = Rect::with_exact(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 12, SCREEN_WIDTH - 2, 22);
-        let log_area = Rect::with_exact(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 25, SCREEN_WIDTH - 2, 58);
-        Hud {
+        let mut hud = Hud {
             layout,
-            inv_area,
-            log_area,
+            inv_area: Rect::zero(),
+            log_area: Rect::zero(),
+            log_lines: Vec::new(),
+            threat: 0.0,
+            threats: Vec::new(),
+            environment: Environment::default(),
+            region: Region::default(),
+            map: Vec::new(),
+            map_player: Point::new(0, 0),
+            paused: false,
+            toast: None,
             last_mouse: Point::new(0, 0),
             require_refresh: false,
             items: create_hud_items(&layout),
             tooltips: Vec::new(),
+            settings: UiSettings::load(),
+            genome_scroll: 0,
+        };
+        hud.arrange_panels();
+        hud
+    }
+
+    /// Screen row of the tab bar, right below the actions section.
+    fn tab_bar_y(&self) -> i32 {
+        if self.settings.actions_collapsed {
+            ACTIONS_Y + 1
+        } else {
+            ACTIONS_Y + 4
+        }
+    }
+
+    /// Fit the areas of the tabs into the space below the tab bar.
+    fn arrange_panels(&mut self) {
+        let content_y = self.tab_bar_y() + 1;
+        self.inv_area = Rect::with_exact(
+            SCREEN_WIDTH - SIDE_PANEL_WIDTH,
+            content_y + 1,
+            SCREEN_WIDTH - 2,
+            content_y + 11,
+        );
+        self.log_area = Rect::with_exact(
+            SCREEN_WIDTH - SIDE_PANEL_WIDTH,
+            content_y,
+            SCREEN_WIDTH - 2,
+            HOTBAR_Y - 3,
+        );
+    }
+
+    pub fn select_tab(&mut self, tab: SidebarTab) {
+        self.settings.sidebar_tab = tab;
+        self.settings.save();
+    }
+
+    /// Scroll the content of the active tab, as far as there is more to show.
+    pub fn scroll_panel(&mut self, step: i32) {
+        if self.settings.sidebar_tab == SidebarTab::Dna {
+            self.genome_scroll = (self.genome_scroll as i32 + step).max(0) as usize;
         }
     }
 
+    /// Number of lines the genome tab can be scrolled down at most.
+    fn max_genome_scroll(&self, player: &Object) -> usize {
+        // the first line of the tab is taken by its header and the last by the legend
+        let rows = self.log_area.height() as usize - 1;
+        genome_lines(player).len().saturating_sub(rows)
+    }
+
+    /// Fold the actions section away to make room for the tabs, or unfold it again.
+    pub fn toggle_actions(&mut self) {
+        self.settings.actions_collapsed = !self.settings.actions_collapsed;
+        self.settings.save();
+        self.arrange_panels();
+    }
+
+    /// Switch visual effects on or off and remember the choice for the next run. Returns whether
+    /// effects are shown from now on.
+    pub fn toggle_effects(&mut self) -> bool {
+        self.settings.effects = !innit_env().use_visual_effects;
+        innit_env().set_visual_effects(self.settings.effects);
+        self.settings.save();
+        self.settings.effects
+    }
+
     pub fn update_tooltips(&mut self, mouse_pos: Point, names: Vec<ToolTip>) {
         self.tooltips.clear();
         if let Some(item) = self
@@ -249,6 +623,15 @@ impl Hud {
             .find(|i| i.layout.point_in_rect(mouse_pos))
         {
             self.tooltips.push(item.tooltip.clone());
+        } else if let Some(line) = self.log_line_at(mouse_pos) {
+            let mut attributes = vec![("turn:".to_string(), line.turn.to_string())];
+            if let Some(origin) = line.origin {
+                attributes.push((
+                    "at:".to_string(),
+                    format!("{}, {} (click)", origin.x, origin.y),
+                ));
+            }
+            self.tooltips.push(ToolTip::no_header(attributes));
         } else {
             self.tooltips = names;
         }
@@ -257,90 +640,268 @@ impl Hud {
         self.last_mouse = mouse_pos;
     }
 
-    pub fn update_ui_items(&mut self, player: &Object) {
+    /// Show a notice above the world for a few seconds, replacing the current one.
+    pub fn show_toast<S: Into<String>>(&mut self, text: S) {
+        self.toast = Some((text.into(), TOAST_DURATION_MS));
+        self.require_refresh = true;
+    }
+
+    /// Count down the time the current notice stays up and take it down once it's over.
+    pub fn update_toast(&mut self, clock: &AnimationClock) {
+        if let Some((_, time_left)) = &mut self.toast {
+            *time_left -= clock.delta();
+            if *time_left <= 0.0 {
+                self.toast = None;
+                self.require_refresh = true;
+            }
+        }
+    }
+
+    /// Scale the explored part of the world down to the map of the objectives tab. The region of
+    /// a cell is the one of its first explored open tile.
+    pub fn update_map(&mut self, objects: &GameObjects, player: &Object) {
+        self.region = objects.get_region(&player.pos);
+        self.map_player = Point::new(player.pos.x / MAP_SCALE, player.pos.y / MAP_SCALE);
+        self.map = vec![None; (MAP_WIDTH * MAP_HEIGHT) as usize];
+        for object in objects.get_tiles().iter().flatten() {
+            let tile = match &object.tile {
+                Some(tile) if tile.is_explored => tile,
+                _ => continue,
+            };
+            let idx = (object.pos.y / MAP_SCALE * MAP_WIDTH + object.pos.x / MAP_SCALE) as usize;
+            let is_open = !object.physics.is_blocking;
+            match self.map.get_mut(idx) {
+                Some(cell @ None) => {
+                    *cell = Some(MapCell {
+                        region: tile.region,
+                        is_open,
+                    })
+                }
+                Some(Some(cell)) if is_open && !cell.is_open => {
+                    *cell = MapCell {
+                        region: tile.region,
+                        is_open,
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Re-assess the danger posed by all visible organisms within the player's sensing range.
+    pub fn update_threat(&mut self, player: &Object, objects: &GameObjects) {
+        self.threats = objects
+            .get_vector()
+            .iter()
+            .flatten()
+            .filter(|o| {
+                o.physics.is_visible
+                    && player.pos.distance(&o.pos) <= player.sensors.sensing_range as f32
+            })
+            .map(|o| (o.visual.name.clone(), o.faction, player.assess_threat(o)))
+            .filter(|(_, _, threat)| *threat > 0.0)
+            .collect();
+        self.threats
+            .sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+        self.threat = self.threats.iter().map(|(_, _, threat)| threat).sum();
+    }
+
+    /// Return the log line rendered at the given screen position, if there is any.
+    pub fn log_line_at(&self, pos: Point) -> Option<&LogLine> {
+        if !self.log_area.point_in_rect(pos) {
+            return None;
+        }
+        self.log_lines.iter().find(|l| l.y == pos.y)
+    }
+
+    /// Add the header of the actions section, the action buttons and the tabs where they belong
+    /// in the current layout.
+    fn add_panel_items(&mut self) {
+        let x = SCREEN_WIDTH - SIDE_PANEL_WIDTH;
+        let fg_hud = palette().hud_fg;
+        let fg_hl = palette().hud_fg_highlight;
+        let bg_hud = palette().hud_bg;
+        let bg_header = palette().hud_bg_dna;
+        let bg_active = palette().hud_bg_active;
+
+        let (fold, fold_hint) = if self.settings.actions_collapsed {
+            ('►', "show actions")
+        } else {
+            ('▼', "hide actions")
+        };
+        self.items.push(UiItem::new(
+            HudItem::ToggleActions,
+            format!("{} Actions", fold),
+            ToolTip::header_only(fold_hint),
+            Rect::with_size(x, ACTIONS_Y, SIDE_PANEL_WIDTH - 1, 1),
+            ColorPair::new(fg_hud, bg_header),
+        ));
+        if !self.settings.actions_collapsed {
+            let button_len = SIDE_PANEL_WIDTH / 2;
+            self.items.push(UiItem::new(
+                HudItem::PrimaryAction,
+                "",
+                ToolTip::header_only("select new primary action"),
+                Rect::with_size(x + 2, ACTIONS_Y + 1, button_len, 1),
+                ColorPair::new(fg_hud, bg_hud),
+            ));
+            self.items.push(UiItem::new(
+                HudItem::SecondaryAction,
+                "",
+                ToolTip::header_only("select new secondary action"),
+                Rect::with_size(x + 2, ACTIONS_Y + 2, button_len, 1),
+                ColorPair::new(fg_hud, bg_hud),
+            ));
+        }
+
+        let tab_y = self.tab_bar_y();
+        for (idx, tab) in SidebarTab::ALL.iter().enumerate() {
+            let color = if *tab == self.settings.sidebar_tab {
+                ColorPair::new(fg_hl, bg_active)
+            } else {
+                ColorPair::new(fg_hud, bg_header)
+            };
+            self.items.push(UiItem::new(
+                HudItem::Tab(*tab),
+                format!("{:^4}", tab.label()),
+                ToolTip::header_only(format!("{} (TAB)", tab.description())),
+                Rect::with_size(x - 1 + 4 * idx as i32, tab_y, 4, 1),
+                color,
+            ));
+        }
+    }
+
+    pub fn update_ui_items(&mut self, state: &GameState, player: &Object) {
+        let gene_library = &state.gene_library;
         self.items.retain(|i| {
             !i.item_enum.is_dna_item()
                 && !i.item_enum.is_use_inventory_item()
                 && !i.item_enum.is_drop_inventory_item()
+                && !i.item_enum.is_panel_item()
         });
-
-        for (h_offset, g_trait) in player
-            .dna
-            .simplified
-            .iter()
-            .take(SIDE_PANEL_WIDTH as usize - 4)
-            .enumerate()
-        {
-            let col: (u8, u8, u8) = match g_trait.trait_family {
-                TraitFamily::Sensing => palette().hud_fg_dna_processor,
-                TraitFamily::Processing => palette().hud_fg_dna_actuator,
-                TraitFamily::Actuating => palette().hud_fg_dna_sensor,
-                TraitFamily::Junk(_) => (59, 59, 59), // TODO
-                TraitFamily::Ltr => (255, 255, 255),  // TODO
+        self.add_panel_items();
+
+        // genes run along the top of the side panel and then down its right edge, where the last
+        // cell points to the DNA tab if there are more genes than fit
+        let genes = &player.dna.simplified;
+        let latest = player.mutations.latest_gene();
+        let overflow = genes.len() > DNA_STRIP_LEN;
+        let shown = if overflow {
+            DNA_STRIP_LEN - 1
+        } else {
+            genes.len()
+        };
+        for (idx, g_trait) in genes.iter().take(shown).enumerate() {
+            let (c, layout, bg) = if idx < DNA_STRIP_TOP_LEN {
+                let c = if modulus(idx, 2) == 0 { '►' } else { '◄' };
+                let x = SCREEN_WIDTH - SIDE_PANEL_WIDTH + 3 + idx as i32;
+                (c, Rect::with_size(x, 0, 1, 1), palette().hud_bg)
+            } else {
+                let v_offset = idx - DNA_STRIP_TOP_LEN;
+                let c = if modulus(v_offset, 2) == 0 {
+                    '▼'
+                } else {
+                    '▲'
+                };
+                let layout = Rect::with_size(SCREEN_WIDTH - 1, v_offset as i32, 1, 1);
+                (c, layout, palette().hud_bg_dna)
             };
-
-            let c: char = if modulus(h_offset, 2) == 0 {
-                '►'
+            let bg = if latest == Some(idx) {
+                palette().hud_bg_active
             } else {
-                '◄'
+                bg
             };
 
-            let tooltip = ToolTip::no_header(vec![
-                ("trait:".to_string(), g_trait.trait_name.clone()),
-                ("group:".to_string(), g_trait.trait_family.to_string()),
-            ]);
-
-            let bg_item = palette().hud_bg;
             self.items.push(UiItem::new(
                 HudItem::DnaItem,
                 c,
+                gene_tooltip(g_trait, player, gene_library),
+                layout,
+                ColorPair::new(gene_color(g_trait), bg),
+            ));
+        }
+        if overflow {
+            let fg_hl = palette().hud_fg_highlight;
+            let tooltip = ToolTip::new(
+                format!("{} more genes", genes.len() - shown),
+                vec![("click to show all".to_string(), "".to_string())],
+            );
+            self.items.push(UiItem::new(
+                HudItem::DnaOverflow,
+                '+',
                 tooltip,
                 Rect::with_size(
-                    SCREEN_WIDTH - SIDE_PANEL_WIDTH + 3 + h_offset as i32,
-                    0,
+                    SCREEN_WIDTH - 1,
+                    (DNA_STRIP_LEN - DNA_STRIP_TOP_LEN - 1) as i32,
                     1,
                     1,
                 ),
-                ColorPair::new(col, bg_item),
+                ColorPair::new(fg_hl, palette().hud_bg_dna),
             ));
         }
 
-        for (v_offset, g_trait) in player
-            .dna
-            .simplified
-            .iter()
-            .skip(SIDE_PANEL_WIDTH as usize - 4)
-            .enumerate()
-        {
-            let col: (u8, u8, u8) = match g_trait.trait_family {
-                TraitFamily::Sensing => palette().hud_fg_dna_processor,
-                TraitFamily::Processing => palette().hud_fg_dna_actuator,
-                TraitFamily::Actuating => palette().hud_fg_dna_sensor,
-                TraitFamily::Junk(_) => (59, 59, 59), // TODO
-                TraitFamily::Ltr => (255, 255, 255),  // TODO
-            };
-
-            let c: char = if modulus(v_offset, 2) == 0 {
-                '▼'
-            } else {
-                '▲'
-            };
-
-            let tooltip = ToolTip::no_header(vec![
-                ("trait:".to_string(), g_trait.trait_name.clone()),
-                ("group:".to_string(), g_trait.trait_family.to_string()),
-            ]);
+        // the genome tab can be scrolled with buttons next to its header
+        if self.settings.sidebar_tab == SidebarTab::Dna {
+            let max_scroll = self.max_genome_scroll(player);
+            self.genome_scroll = self.genome_scroll.min(max_scroll);
+            let fg_hl = palette().hud_fg_highlight;
+            let color = ColorPair::new(fg_hl, palette().hud_bg_dna);
+            if self.genome_scroll > 0 {
+                self.items.push(UiItem::new(
+                    HudItem::ScrollPanel(-1),
+                    '▲',
+                    ToolTip::header_only("scroll up (PAGE UP)"),
+                    Rect::with_size(SCREEN_WIDTH - 3, self.log_area.y1, 1, 1),
+                    color,
+                ));
+            }
+            if self.genome_scroll < max_scroll {
+                self.items.push(UiItem::new(
+                    HudItem::ScrollPanel(1),
+                    '▼',
+                    ToolTip::header_only("scroll down (PAGE DOWN)"),
+                    Rect::with_size(SCREEN_WIDTH - 2, self.log_area.y1, 1, 1),
+                    color,
+                ));
+            }
 
-            let bg_dna = palette().hud_bg_dna;
+            // hovering a gene in the tab shows the same details as in the DNA strip
+            let x = SCREEN_WIDTH - SIDE_PANEL_WIDTH;
+            let lines = genome_lines(player);
+            let rows =
+                (self.log_area.y1 + 1..self.log_area.y2).zip(lines.iter().skip(self.genome_scroll));
+            for (y, line) in rows {
+                if let GenomeLine::Gene { g_trait, .. } = line {
+                    self.items.push(UiItem::new(
+                        HudItem::DnaItem,
+                        "",
+                        gene_tooltip(g_trait, player, gene_library),
+                        Rect::with_size(x, y, SIDE_PANEL_WIDTH - 1, 1),
+                        color,
+                    ));
+                }
+            }
+            let legend = ToolTip::new(
+                "gene colors",
+                vec![
+                    ("sense:".to_string(), "perception".to_string()),
+                    ("proc:".to_string(), "energy and metabolism".to_string()),
+                    ("act:".to_string(), "movement and combat".to_string()),
+                ],
+            );
             self.items.push(UiItem::new(
                 HudItem::DnaItem,
-                c,
-                tooltip,
-                Rect::with_size(SCREEN_WIDTH - 1, v_offset as i32, 1, 1),
-                ColorPair::new(col, bg_dna),
+                "",
+                legend,
+                Rect::with_size(x, self.log_area.y2, SIDE_PANEL_WIDTH - 1, 1),
+                color,
             ));
         }
 
+        // the inventory can only be used while it's shown
+        if self.settings.sidebar_tab != SidebarTab::Status {
+            return;
+        }
         for (idx, obj) in player.inventory.items.iter().enumerate() {
             if idx as i32 > self.inv_area.height() {
                 break;
@@ -348,9 +909,8 @@ impl Hud {
 
             // take only as many chars as fit into the inventory item name field, or less
             // if the name is shorter
-            let name_fitted: String = obj
-                .visual
-                .name
+            let name = item_name(state, obj);
+            let name_fitted: String = name
                 .chars()
                 .take((self.inv_area.width() - 5) as usize)
                 .collect();
@@ -365,7 +925,7 @@ impl Hud {
 
             let item_tooltip = if let Some(item) = &obj.item {
                 ToolTip::new(
-                    format!("use {}", &obj.visual.name),
+                    format!("use {}", name),
                     vec![(item.description.clone(), "".to_string())],
                 )
             } else {
@@ -390,7 +950,7 @@ impl Hud {
             self.items.push(UiItem::new(
                 HudItem::DropInventory { idx },
                 " x",
-                ToolTip::header_only(format!("drop {}", &obj.visual.name)),
+                ToolTip::header_only(format!("drop {}", name)),
                 drop_layout,
                 ColorPair::new(magenta, bg_col),
             ));
@@ -399,8 +959,9 @@ impl Hud {
 }
 
 pub fn render_gui(state: &GameState, hud: &mut Hud, _ctx: &mut Rltk, player: &Object) {
-    hud.update_ui_items(player);
+    hud.update_ui_items(state, player);
     let mut draw_batch = DrawBatch::new();
+    draw_batch.target(HUD_CON);
     let fg_hud = palette().hud_fg;
     let bg_hud = palette().hud_bg;
 
@@ -412,16 +973,54 @@ pub fn render_gui(state: &GameState, hud: &mut Hud, _ctx: &mut Rltk, player: &Ob
     );
 
     render_dna_region(&mut draw_batch);
+    render_host(state, &mut draw_batch);
     render_bars(player, &mut draw_batch);
+    render_threat(hud, &mut draw_batch);
     render_action_fields(player, hud, &mut draw_batch);
-    render_inventory(hud, player, hud.inv_area, &mut draw_batch);
-    render_log(state, hud.log_area, &mut draw_batch);
+    render_tab_bar(hud, &mut draw_batch);
+    hud.log_lines.clear();
+    match hud.settings.sidebar_tab {
+        SidebarTab::Status => {
+            render_inventory(hud, player, hud.inv_area, &mut draw_batch);
+            render_conditions(state, hud, &mut draw_batch);
+        }
+        SidebarTab::Dna => render_genome(hud, player, &mut draw_batch),
+        SidebarTab::Log => render_log(state, hud, &mut draw_batch),
+        SidebarTab::Threats => render_threats(hud, &mut draw_batch),
+        SidebarTab::Objectives => render_objectives(state, hud, &mut draw_batch),
+    }
+    render_hotbar(player, hud, &mut draw_batch);
     render_ui_items(hud, &mut draw_batch);
     render_tooltip(hud, &mut draw_batch);
+    if hud.paused {
+        draw_batch.print_color_centered_at(
+            Point::new(WORLD_WIDTH / 2, 0),
+            " paused ",
+            ColorPair::new(palette().hud_fg_highlight, bg_hud),
+        );
+    }
+    if let Some((text, _)) = &hud.toast {
+        let fg = palette().hud_fg_highlight;
+        let bg = palette().hud_bg_active;
+        draw_batch.print_color_centered_at(
+            Point::new(WORLD_WIDTH / 2, 2),
+            format!(" {} ", text),
+            ColorPair::new(fg, bg),
+        );
+    }
 
     draw_batch.submit(5000).unwrap();
 }
 
+/// Debug overlay with the telemetry of the scheduler, below the frame rate in the top left corner.
+pub fn render_telemetry(telemetry: &Telemetry, draw_batch: &mut DrawBatch) {
+    let fg_hud = palette().hud_fg;
+    let colors = ColorPair::new(fg_hud, palette().hud_bg);
+    for (i, line) in telemetry.to_lines().iter().enumerate() {
+        draw_batch.print_color(Point::new(1, 3 + i as i32), line, colors);
+    }
+}
+
 fn render_dna_region(draw_batch: &mut DrawBatch) {
     let fg_hud = palette().hud_fg;
     let bg_dna = palette().hud_bg_dna;
@@ -471,6 +1070,12 @@ fn render_bars(player: &Object, draw_batch: &mut DrawBatch) {
         format!("{}/{}", player.actuators.hp, player.actuators.max_hp),
     );
 
+    // the energy bar turns to alert colors while starving
+    let energy = if player.processors.starvation > 0 {
+        palette().hud_fg_msg_alert
+    } else {
+        energy
+    };
     draw_batch.bar_horizontal(
         Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH + 2, 3),
         17,
@@ -479,86 +1084,138 @@ fn render_bars(player: &Object, draw_batch: &mut DrawBatch) {
         ColorPair::new(energy, bg_bar),
     );
 
-    draw_batch.print_centered_at(
-        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH + 10, 3),
+    let energy_text = if player.dormant {
+        "dormant".to_string()
+    } else if player.processors.reserve_capacity > 0 {
+        format!(
+            "{}/{} +{}",
+            player.processors.energy, player.processors.energy_storage, player.processors.reserve
+        )
+    } else {
         format!(
             "{}/{}",
             player.processors.energy, player.processors.energy_storage
-        ),
+        )
+    };
+    draw_batch.print_centered_at(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH + 10, 3),
+        energy_text,
     );
 }
 
+fn render_host(state: &GameState, draw_batch: &mut DrawBatch) {
+    let fg_hud = palette().hud_fg;
+    let bg_hud = palette().hud_bg;
+    let bg_bar = palette().hud_bg_bar;
+    let story = palette().hud_fg_msg_story;
+
+    draw_batch.print_color(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 1),
+        '+',
+        ColorPair::new(fg_hud, bg_hud),
+    );
+    draw_batch.bar_horizontal(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH + 2, 1),
+        17,
+        state.host.health as i32,
+        HOST_HEALTH_MAX as i32,
+        ColorPair::new(story, bg_bar),
+    );
+    let host_text = if state.host.collapsed {
+        "host collapsed".to_string()
+    } else {
+        format!("host {}%", state.host.health as i32)
+    };
+    draw_batch.print_centered_at(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH + 10, 1),
+        host_text,
+    );
+}
+
+/// Label and percentage of the threat bar for the given threat. A threat of 1.0 equals a foe that
+/// can kill us in a single turn.
+pub fn threat_level(threat: f32) -> (&'static str, i32) {
+    match threat {
+        t if t <= 0.0 => ("none", 0),
+        t if t < 0.25 => ("low", 25),
+        t if t < 0.5 => ("moderate", 50),
+        t if t < 1.0 => ("high", 75),
+        _ => ("deadly", 100),
+    }
+}
+
+fn render_threat(hud: &Hud, draw_batch: &mut DrawBatch) {
+    let fg_hud = palette().hud_fg;
+    let bg_hud = palette().hud_bg;
+    let bg_bar = palette().hud_bg_bar;
+    let alert = palette().hud_fg_msg_alert;
+
+    let (label, value) = threat_level(hud.threat);
+
+    draw_batch.print_color(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 4),
+        '!',
+        ColorPair::new(fg_hud, bg_hud),
+    );
+    draw_batch.bar_horizontal(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH + 2, 4),
+        17,
+        value,
+        100,
+        ColorPair::new(alert, bg_bar),
+    );
+    draw_batch.print_centered_at(Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH + 10, 4), label);
+}
+
 fn render_action_fields(player: &Object, hud: &mut Hud, draw_batch: &mut DrawBatch) {
     let action_header_bg = palette().hud_bg_dna;
     let action_bg = palette().hud_bg;
     let action_fg = palette().hud_fg;
     let action_fg_hl = palette().hud_fg_highlight;
 
-    // draw action header
+    // draw action header, its text comes with the item that folds the section
     draw_batch.fill_region(
-        Rect::with_size(SCREEN_WIDTH - SIDE_PANEL_WIDTH - 1, 5, SIDE_PANEL_WIDTH, 0),
+        Rect::with_size(
+            SCREEN_WIDTH - SIDE_PANEL_WIDTH - 1,
+            ACTIONS_Y,
+            SIDE_PANEL_WIDTH,
+            0,
+        ),
         ColorPair::new(action_fg, action_header_bg),
         to_cp437(' '),
     );
-    draw_batch.print_color(
-        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 5),
-        "Actions",
-        ColorPair::new(action_fg, action_header_bg),
-    );
+    if hud.settings.actions_collapsed {
+        return;
+    }
     // draw buttons
     draw_batch.print_color(
-        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 6),
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, ACTIONS_Y + 1),
         "P",
         ColorPair::new(action_fg_hl, action_bg),
     );
     draw_batch.print_color(
-        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 7),
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, ACTIONS_Y + 2),
         "S",
         ColorPair::new(action_fg_hl, action_bg),
     );
-    draw_batch.print_color(
-        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 8),
-        "Q",
-        ColorPair::new(action_fg_hl, action_bg),
-    );
-    draw_batch.print_color(
-        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, 9),
-        "E",
-        ColorPair::new(action_fg_hl, action_bg),
-    );
 
     // update action button texts
     let p_action = player.get_primary_action(Target::Center);
     let s_action = player.get_secondary_action(Target::Center);
-    let q1_action = player.get_quick1_action();
-    let q2_action = player.get_quick2_action();
+    let env = hud.environment;
     hud.items.iter_mut().for_each(|i| match i.item_enum {
         HudItem::PrimaryAction => {
             i.text = format!(
                 "{} ({}√)",
                 p_action.get_identifier(),
-                p_action.get_energy_cost()
+                player.action_cost(p_action.as_ref(), &env)
             )
         }
         HudItem::SecondaryAction => {
             i.text = format!(
                 "{} ({}√)",
                 s_action.get_identifier(),
-                s_action.get_energy_cost()
-            )
-        }
-        HudItem::Quick1Action => {
-            i.text = format!(
-                "{} ({}√)",
-                q1_action.get_identifier(),
-                q1_action.get_energy_cost()
-            )
-        }
-        HudItem::Quick2Action => {
-            i.text = format!(
-                "{} ({}√)",
-                q2_action.get_identifier(),
-                q2_action.get_energy_cost()
+                player.action_cost(s_action.as_ref(), &env)
             )
         }
         _ => {} // HudItem::DnaItem => {}
@@ -566,6 +1223,55 @@ fn render_action_fields(player: &Object, hud: &mut Hud, draw_batch: &mut DrawBat
     });
 }
 
+fn render_hotbar(player: &Object, hud: &mut Hud, draw_batch: &mut DrawBatch) {
+    let fg_hud = palette().hud_fg;
+    let fg_hl = palette().hud_fg_highlight;
+    let bg_hud = palette().hud_bg;
+    let bg_header = palette().hud_bg_dna;
+
+    draw_batch.fill_region(
+        Rect::with_size(
+            SCREEN_WIDTH - SIDE_PANEL_WIDTH - 1,
+            HOTBAR_Y - 1,
+            SIDE_PANEL_WIDTH,
+            0,
+        ),
+        ColorPair::new(fg_hud, bg_header),
+        to_cp437(' '),
+    );
+    draw_batch.print_color(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, HOTBAR_Y - 1),
+        "Hotbar",
+        ColorPair::new(fg_hud, bg_header),
+    );
+
+    // each slot shows its number key and the initial of its action, details are in the tooltip
+    let env = hud.environment;
+    hud.items.iter_mut().for_each(|i| {
+        if let HudItem::HotbarSlot { idx } = i.item_enum {
+            let action = player.get_hotbar_action(idx);
+            let is_empty = action.get_identifier() == "pass";
+            let initial = if is_empty {
+                '·'
+            } else {
+                action.get_identifier().chars().next().unwrap_or('?')
+            };
+            i.text = format!("{}{}", idx + 1, initial);
+            i.color = ColorPair::new(if is_empty { fg_hud } else { fg_hl }, bg_hud);
+            i.tooltip = ToolTip::new(
+                format!("{}: {}", idx + 1, action.get_identifier()),
+                vec![
+                    (
+                        "cost:".to_string(),
+                        format!("{}√", player.action_cost(action.as_ref(), &env)),
+                    ),
+                    ("click to reassign".to_string(), "".to_string()),
+                ],
+            );
+        }
+    });
+}
+
 fn render_inventory(hud: &Hud, player: &Object, layout: Rect, draw_batch: &mut DrawBatch) {
     let fg_inv = palette().hud_fg;
     let bg_inv_header = palette().hud_bg_dna;
@@ -576,14 +1282,22 @@ fn render_inventory(hud: &Hud, player: &Object, layout: Rect, draw_batch: &mut D
         to_cp437(' '),
     );
 
+    // an overloaded inventory takes its toll each turn
+    let fg_capacity = if player.is_overloaded() {
+        palette().hud_fg_msg_alert
+    } else {
+        fg_inv
+    };
     draw_batch.print_color(
         Point::new(layout.x1, layout.y1 - 1),
         format!(
-            "Inventory [{}/{}]",
+            "Items {}/{}, wt {}/{}",
             player.inventory.items.len(),
-            player.actuators.volume
+            player.actuators.volume,
+            player.inventory.weight(),
+            player.actuators.carry_weight
         ),
-        ColorPair::new(fg_inv, bg_inv_header),
+        ColorPair::new(fg_capacity, bg_inv_header),
     );
 
     hud.items
@@ -594,28 +1308,15 @@ fn render_inventory(hud: &Hud, player: &Object, layout: Rect, draw_batch: &mut D
         });
 }
 
-fn render_log(state: &GameState, layout: Rect, draw_batch: &mut DrawBatch) {
-    let fg_log = palette().hud_fg;
-    let bg_log_header = palette().hud_bg_dna;
-
-    draw_batch.fill_region(
-        Rect::with_size(layout.x1 - 1, layout.y1 - 1, layout.width() + 1, 0),
-        ColorPair::new(fg_log, bg_log_header),
-        to_cp437(' '),
-    );
-
-    draw_batch.print_color(
-        Point::new(layout.x1, layout.y1 - 1),
-        "Log",
-        ColorPair::new(fg_log, bg_log_header),
-    );
+fn render_log(state: &GameState, hud: &mut Hud, draw_batch: &mut DrawBatch) {
+    let layout = hud.log_area;
 
-    // convert messages into log text lines (str, fg_col, bg_col)
+    // convert messages into log text lines (str, fg_col, bg_col, msg_idx)
     let mut bg_flag: bool = modulus(state.log.messages.len(), 2) == 0;
-    let mut log_lines: Vec<(String, (u8, u8, u8), (u8, u8, u8))> = Vec::new();
-    for (msg, class) in &state.log.messages {
-        let lines = text_to_width(&msg, layout.width());
-        let fg_color = match class {
+    let mut log_lines: Vec<(String, (u8, u8, u8), (u8, u8, u8), usize)> = Vec::new();
+    for (msg_idx, msg) in state.log.messages.iter().enumerate() {
+        let lines = text_to_width(&msg.to_text(), layout.width());
+        let fg_color = match msg.class {
             MsgClass::Alert => palette().hud_fg_msg_alert,
             MsgClass::Info => palette().hud_fg_msg_info,
             MsgClass::Action => palette().hud_fg_msg_action,
@@ -628,7 +1329,7 @@ fn render_log(state: &GameState, layout: Rect, draw_batch: &mut DrawBatch) {
         };
         bg_flag = !bg_flag;
         for l in lines {
-            log_lines.push((l, fg_color, bg_color));
+            log_lines.push((l, fg_color, bg_color, msg_idx));
         }
     }
 
@@ -652,10 +1353,271 @@ fn render_log(state: &GameState, layout: Rect, draw_batch: &mut DrawBatch) {
             &l.0,
             ColorPair::new(l.1, l.2),
         );
+        let msg = &state.log.messages[l.3];
+        hud.log_lines.push(LogLine {
+            y,
+            turn: msg.turn,
+            origin: msg.origin,
+        });
         y += 1;
     }
 }
 
+fn render_tab_bar(hud: &Hud, draw_batch: &mut DrawBatch) {
+    // the labels come with the tab items, this only fills the gaps between them
+    let fg_hud = palette().hud_fg;
+    draw_batch.fill_region(
+        Rect::with_size(
+            SCREEN_WIDTH - SIDE_PANEL_WIDTH - 1,
+            hud.tab_bar_y(),
+            SIDE_PANEL_WIDTH,
+            0,
+        ),
+        ColorPair::new(fg_hud, palette().hud_bg_dna),
+        to_cp437(' '),
+    );
+}
+
+/// Print a header line across the side panel at the given row.
+fn render_section_header(y: i32, text: &str, draw_batch: &mut DrawBatch) {
+    let fg_hud = palette().hud_fg;
+    let bg_header = palette().hud_bg_dna;
+    draw_batch.fill_region(
+        Rect::with_size(SCREEN_WIDTH - SIDE_PANEL_WIDTH - 1, y, SIDE_PANEL_WIDTH, 0),
+        ColorPair::new(fg_hud, bg_header),
+        to_cp437(' '),
+    );
+    draw_batch.print_color(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, y),
+        text,
+        ColorPair::new(fg_hud, bg_header),
+    );
+}
+
+/// Print a line of text with a value aligned to the right edge of the side panel.
+fn render_panel_line(
+    y: i32,
+    text: &str,
+    value: &str,
+    color: (u8, u8, u8),
+    draw_batch: &mut DrawBatch,
+) {
+    let bg_hud = palette().hud_bg;
+    let text: String = text
+        .chars()
+        .take((SIDE_PANEL_WIDTH as usize - 2).saturating_sub(value.len()))
+        .collect();
+    draw_batch.print_color(
+        Point::new(SCREEN_WIDTH - SIDE_PANEL_WIDTH, y),
+        text,
+        ColorPair::new(color, bg_hud),
+    );
+    draw_batch.print_color_right(
+        Point::new(SCREEN_WIDTH - 1, y),
+        value,
+        ColorPair::new(palette().hud_fg, bg_hud),
+    );
+}
+
+/// Local conditions at the player's position and the host's time of day, below the inventory.
+fn render_conditions(state: &GameState, hud: &Hud, draw_batch: &mut DrawBatch) {
+    let fg_hud = palette().hud_fg;
+    let y = hud.inv_area.y2 + 2;
+    let env = &hud.environment;
+    render_section_header(y, "Conditions", draw_batch);
+    render_panel_line(
+        y + 1,
+        hud.region.name(),
+        "",
+        region_color(hud.region),
+        draw_batch,
+    );
+    render_panel_line(
+        y + 2,
+        "temperature",
+        &format!("{:.1}°C", env.temperature),
+        fg_hud,
+        draw_batch,
+    );
+    render_panel_line(y + 3, "pH", &format!("{:.1}", env.ph), fg_hud, draw_batch);
+    render_panel_line(
+        y + 4,
+        "nutrients",
+        &format!("{:.0}%", env.nutrients * 100.0),
+        fg_hud,
+        draw_batch,
+    );
+    render_panel_line(
+        y + 5,
+        "light",
+        &format!("{:.0}%", env.light * 100.0),
+        fg_hud,
+        draw_batch,
+    );
+    render_panel_line(
+        y + 6,
+        "time",
+        &state.clock().to_string(),
+        fg_hud,
+        draw_batch,
+    );
+    let alert = palette().hud_fg_msg_alert;
+    for (i, phase) in state.host.conditions.iter().enumerate() {
+        render_panel_line(
+            y + 7 + i as i32,
+            phase.condition.name(),
+            &format!("{} turns", phase.turns_left),
+            alert,
+            draw_batch,
+        );
+    }
+}
+
+/// All genes of the player grouped by their family, the most recently mutated one highlighted,
+/// above a legend of the family colors.
+fn render_genome(hud: &Hud, player: &Object, draw_batch: &mut DrawBatch) {
+    let layout = hud.log_area;
+    let latest = player.mutations.latest_gene();
+    render_section_header(
+        layout.y1,
+        &format!("Genome [{} genes]", player.dna.simplified.len()),
+        draw_batch,
+    );
+    let lines = genome_lines(player);
+    for (y, line) in (layout.y1 + 1..layout.y2).zip(lines.iter().skip(hud.genome_scroll)) {
+        match line {
+            GenomeLine::Family { name, count } => {
+                let fg_hud = palette().hud_fg;
+                render_panel_line(y, name, &count.to_string(), fg_hud, draw_batch)
+            }
+            GenomeLine::Gene { idx, g_trait } => {
+                let (marker, color) = if latest == Some(*idx) {
+                    ("*", palette().hud_fg_highlight)
+                } else {
+                    ("", gene_color(g_trait))
+                };
+                render_panel_line(
+                    y,
+                    &format!(" {}", g_trait.trait_name),
+                    &format!("{}#{}", marker, idx),
+                    color,
+                    draw_batch,
+                );
+            }
+        }
+    }
+    render_dna_legend(layout.y2, draw_batch);
+}
+
+/// Legend of the colors of the trait families, at the bottom of the genome tab.
+fn render_dna_legend(y: i32, draw_batch: &mut DrawBatch) {
+    let mut x = SCREEN_WIDTH - SIDE_PANEL_WIDTH;
+    let bg_hud = palette().hud_bg;
+    for (family, label) in &DNA_LEGEND {
+        draw_batch.print_color(
+            Point::new(x, y),
+            format!("►{}", label),
+            ColorPair::new(family_color(family), bg_hud),
+        );
+        x += label.chars().count() as i32 + 2;
+    }
+}
+
+/// Hostile organisms within sensing range, the most dangerous first.
+fn render_threats(hud: &Hud, draw_batch: &mut DrawBatch) {
+    let layout = hud.log_area;
+    render_section_header(layout.y1, "Threats", draw_batch);
+    if hud.threats.is_empty() {
+        let fg_hud = palette().hud_fg;
+        render_panel_line(layout.y1 + 1, "none in sight", "", fg_hud, draw_batch);
+    }
+    for (y, (name, faction, threat)) in (layout.y1 + 1..=layout.y2).zip(hud.threats.iter()) {
+        render_panel_line(
+            y,
+            name,
+            &format!("{:.0}%", threat * 100.0),
+            faction_color(*faction),
+            draw_batch,
+        );
+    }
+}
+
+/// What the player should keep an eye on: the host's health, the treatment it receives, the
+/// bosses defeated so far and a map of the explored regions at the bottom.
+fn render_objectives(state: &GameState, hud: &Hud, draw_batch: &mut DrawBatch) {
+    let fg_hud = palette().hud_fg;
+    let alert = palette().hud_fg_msg_alert;
+    let layout = hud.log_area;
+    render_section_header(layout.y1, "Objectives", draw_batch);
+    let host = if state.host.collapsed {
+        "collapsed".to_string()
+    } else {
+        format!("{}%", state.host.health as i32)
+    };
+    render_panel_line(layout.y1 + 1, "keep host alive", &host, fg_hud, draw_batch);
+    match &state.host.treatment {
+        Some(treatment) => render_panel_line(
+            layout.y1 + 2,
+            &format!("survive {}", treatment.antibiotic.name()),
+            &treatment.turns_left.to_string(),
+            alert,
+            draw_batch,
+        ),
+        None => render_panel_line(layout.y1 + 2, "no treatment", "", fg_hud, draw_batch),
+    }
+    render_panel_line(
+        layout.y1 + 3,
+        "turns survived",
+        &state.turn.to_string(),
+        fg_hud,
+        draw_batch,
+    );
+
+    render_section_header(
+        layout.y1 + 5,
+        &format!("Bosses [{}]", state.defeated_bosses.len()),
+        draw_batch,
+    );
+    let map_y = layout.y2 - MAP_HEIGHT - Region::ALL.len() as i32;
+    for (y, boss) in (layout.y1 + 6..map_y - 1).zip(state.defeated_bosses.iter()) {
+        render_panel_line(y, boss, "defeated", fg_hud, draw_batch);
+    }
+    render_map(hud, map_y, draw_batch);
+}
+
+/// Map of the explored world with each cell colored by its region, above a legend of the regions.
+fn render_map(hud: &Hud, y: i32, draw_batch: &mut DrawBatch) {
+    render_section_header(y, "Map", draw_batch);
+    let x = SCREEN_WIDTH - SIDE_PANEL_WIDTH - 1;
+    let bg_hud = palette().hud_bg;
+    for (idx, cell) in hud.map.iter().enumerate() {
+        let pos = Point::new(idx as i32 % MAP_WIDTH, idx as i32 / MAP_WIDTH);
+        let (glyph, color) = if pos == hud.map_player {
+            ('@', palette().entity_player)
+        } else {
+            match cell {
+                Some(cell) if cell.is_open => ('█', region_color(cell.region)),
+                Some(cell) => ('░', region_color(cell.region)),
+                None => (' ', bg_hud),
+            }
+        };
+        draw_batch.print_color(
+            Point::new(x + pos.x, y + 1 + pos.y),
+            glyph,
+            ColorPair::new(color, bg_hud),
+        );
+    }
+    for (row, region) in Region::ALL.iter().enumerate() {
+        render_panel_line(
+            y + 1 + MAP_HEIGHT + row as i32,
+            &format!("█ {}", region.name()),
+            "",
+            region_color(*region),
+            draw_batch,
+        );
+    }
+}
+
 fn render_ui_items(hud: &Hud, draw_batch: &mut DrawBatch) {
     for item in &hud.items {
         draw_batch.print_color(item.top_left_corner(), &item.text, item.color);
@@ -718,9 +1680,10 @@ fn render_tooltip(hud: &Hud, draw_batch: &mut DrawBatch) {
     let fg_tt_border = palette().hud_fg_border;
     let fg_tt = palette().hud_fg;
     let bg_tt = palette().hud_bg;
+    let bg_tt_bar = palette().hud_bg_content;
 
     for tooltip in &hud.tooltips {
-        if tooltip.header.is_none() && tooltip.attributes.is_empty() {
+        if tooltip.header.is_none() && tooltip.attributes.is_empty() && tooltip.bars.is_empty() {
             continue;
         }
         // (+2) for borders and (-1) for starting from 0, equals (+1)
@@ -740,7 +1703,7 @@ fn render_tooltip(hud: &Hud, draw_batch: &mut DrawBatch) {
         if tooltip.header.is_some() {
             top_offset = 3;
 
-            if !tooltip.attributes.is_empty() {
+            if !tooltip.attributes.is_empty() || !tooltip.bars.is_empty() {
                 draw_batch.print_color(
                     Point::new(next_x, next_y + 2),
                     // to_cp437('─'),
@@ -766,8 +1729,30 @@ fn render_tooltip(hud: &Hud, draw_batch: &mut DrawBatch) {
             draw_batch.print_color_centered_at(
                 Point::new(next_x + (tt_width / 2), next_y + 1),
                 tooltip.header.as_ref().unwrap(),
+                ColorPair::new(tooltip.color.unwrap_or(fg_tt), bg_tt),
+            );
+        }
+
+        for bar in &tooltip.bars {
+            let bar_x = next_x + bar.label.len() as i32 + 2;
+            draw_batch.print_color(
+                Point::new(next_x + 1, next_y + top_offset),
+                &bar.label,
+                ColorPair::new(fg_tt, bg_tt),
+            );
+            draw_batch.bar_horizontal(
+                Point::new(bar_x, next_y + top_offset),
+                TOOLTIP_BAR_WIDTH,
+                bar.value,
+                bar.max,
+                ColorPair::new(bar.color, bg_tt_bar),
+            );
+            draw_batch.print_color_right(
+                Point::new(next_x + tt_width, next_y + top_offset),
+                bar.text(),
                 ColorPair::new(fg_tt, bg_tt),
             );
+            top_offset += 1;
         }
 
         for (idx, (s1, s2)) in tooltip.attributes.iter().enumerate() {
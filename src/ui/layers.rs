@@ -0,0 +1,91 @@
+//! The screen is made up of layers, each of them drawn onto its own console. A layer keeps its
+//! content until it's marked as dirty, so that unchanged layers don't have to be cleared and
+//! drawn anew every frame.
+
+use crate::util::timer::time_from;
+
+/// Number of frames after which the render statistics are logged.
+const STATS_FRAMES: u32 = 600;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    /// Tissue and organisms, drawn onto `WORLD_CON`.
+    World,
+    /// Side panel and notices above the world, drawn onto `HUD_CON`.
+    Hud,
+    /// Particles, dialogs and menus, drawn onto `PAR_CON`.
+    Overlay,
+}
+
+impl Layer {
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Layers whose content changed since they were last drawn.
+#[derive(Debug, Default)]
+pub struct DirtyLayers {
+    dirty: [bool; 3],
+    stats: RenderStats,
+}
+
+impl DirtyLayers {
+    pub fn new() -> Self {
+        DirtyLayers::default()
+    }
+
+    pub fn mark(&mut self, layer: Layer) {
+        self.dirty[layer.index()] = true;
+    }
+
+    pub fn mark_all(&mut self) {
+        self.dirty = [true; 3];
+    }
+
+    /// Forget about any changes, e.g. because the layers are hidden anyway.
+    pub fn clear(&mut self) {
+        self.dirty = [false; 3];
+    }
+
+    pub fn is_dirty(&self, layer: Layer) -> bool {
+        self.dirty[layer.index()]
+    }
+
+    /// Check whether the layer needs to be drawn and consider it clean from now on.
+    pub fn take(&mut self, layer: Layer) -> bool {
+        let is_dirty = self.is_dirty(layer);
+        self.dirty[layer.index()] = false;
+        if is_dirty {
+            self.stats.drawn[layer.index()] += 1;
+        }
+        is_dirty
+    }
+
+    /// Account for the time in [ns] it took to render a frame. Every so often the statistics are
+    /// logged, to see how many redraws the dirty flags save.
+    pub fn record_frame(&mut self, render_time: u128) {
+        self.stats.frames += 1;
+        self.stats.render_time += render_time;
+        if self.stats.frames >= STATS_FRAMES {
+            let stats = std::mem::take(&mut self.stats);
+            info!(
+                "rendered {} frames in {}, drawn world {}x, hud {}x, overlay {}x",
+                stats.frames,
+                time_from(stats.render_time),
+                stats.drawn[Layer::World.index()],
+                stats.drawn[Layer::Hud.index()],
+                stats.drawn[Layer::Overlay.index()]
+            );
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RenderStats {
+    frames: u32,
+    /// Number of frames each layer was drawn in.
+    drawn: [u32; 3],
+    /// Accumulated time in [ns] spent on rendering.
+    render_time: u128,
+}
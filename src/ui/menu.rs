@@ -1,13 +1,14 @@
 pub mod choose_action_menu;
+pub mod difficulty_menu;
+pub mod display_menu;
 pub mod game_over_menu;
 pub mod main_menu;
 
+use crate::core::game_objects::GameObjects;
 use crate::core::game_state::GameState;
-use crate::game::{RunState, HUD_CON, MENU_WIDTH, SCREEN_HEIGHT, SCREEN_WIDTH};
-use crate::ui::hud::{ToolTip, UiItem};
-use crate::util::modulus;
-use crate::{core::game_objects::GameObjects, ui::palette};
-use rltk::{to_cp437, ColorPair, DrawBatch, Rect, Rltk, VirtualKeyCode};
+use crate::game::{RunState, MENU_WIDTH, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::ui::widget::{draw_window, ListView, WidgetEvent};
+use rltk::{DrawBatch, Rect, Rltk};
 
 pub trait MenuItem: Clone {
     fn process(
@@ -21,107 +22,85 @@ pub trait MenuItem: Clone {
 /// Non-click-away-able window menu.
 #[derive(Clone, Debug)]
 pub struct Menu<T: MenuItem> {
-    items: Vec<UiItem<T>>,
-    selection: usize,
+    items: Vec<T>,
+    list: ListView,
     layout: Rect,
 }
 
 impl<T: MenuItem> Menu<T> {
     pub fn new(item_vec: Vec<(T, String)>) -> Self {
-        let menu_height = item_vec.len() as i32 + 2;
-        let x1 = (SCREEN_WIDTH / 2) - (MENU_WIDTH / 2);
+        let (items, texts): (Vec<T>, Vec<String>) = item_vec.into_iter().unzip();
+        Menu::layout(items, texts)
+    }
+
+    /// Create a menu whose rows consist of several columns, which are aligned with each other.
+    /// The menu grows wider to fit all of them.
+    pub fn with_columns(rows: Vec<(T, Vec<String>)>) -> Self {
+        let mut widths: Vec<usize> = Vec::new();
+        for (_, columns) in &rows {
+            for (i, column) in columns.iter().enumerate() {
+                match widths.get_mut(i) {
+                    Some(width) => *width = (*width).max(column.chars().count()),
+                    None => widths.push(column.chars().count()),
+                }
+            }
+        }
+        let (items, texts): (Vec<T>, Vec<String>) = rows
+            .into_iter()
+            .map(|(item, columns)| {
+                let cells: Vec<String> = columns
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(column, width)| format!("{:<width$}", column, width = width))
+                    .collect();
+                (item, cells.join("  ").trim_end().to_string())
+            })
+            .unzip();
+        Menu::layout(items, texts)
+    }
+
+    /// Preselect the item with the given index, e.g. to keep the selection of a rebuilt menu.
+    pub fn with_selection(mut self, idx: usize) -> Self {
+        self.list.select(idx);
+        self
+    }
+
+    fn layout(items: Vec<T>, texts: Vec<String>) -> Self {
+        let menu_width = texts
+            .iter()
+            .map(|t| t.chars().count() as i32 + 2)
+            .fold(MENU_WIDTH, i32::max);
+        let menu_height = texts.len() as i32 + 2;
+        let x1 = (SCREEN_WIDTH / 2) - (menu_width / 2);
         let y1 = (SCREEN_HEIGHT / 2) - (menu_height / 2);
-        let x2 = x1 + MENU_WIDTH;
+        let x2 = x1 + menu_width;
         let y2 = y1 + menu_height - 1;
-        let items: Vec<UiItem<T>> = item_vec
-            .iter()
-            .cloned()
-            .enumerate()
-            .map(|(i, (enum_item, text))| {
-                UiItem::new(
-                    enum_item,
-                    text,
-                    ToolTip::header_only(""),
-                    Rect::with_size(x1 + 1, y1 + 1 + i as i32, MENU_WIDTH - 2, 1),
-                    ColorPair::new((0, 0, 0), (0, 0, 0)),
-                )
-            })
-            .collect();
+        let list = ListView::new(
+            Rect::with_size(x1 + 1, y1 + 1, menu_width - 2, texts.len() as i32),
+            texts,
+        );
         Menu {
             items,
-            selection: 0,
+            list,
             layout: Rect::with_exact(x1, y1, x2, y2),
         }
     }
 
-    fn render(&self, ctx: &mut Rltk) {
-        ctx.set_active_console(HUD_CON);
-        ctx.cls();
+    fn render(&self) {
         let mut draw_batch = DrawBatch::new();
-        let bg_menu = palette().hud_bg;
-        let fg_menu = palette().hud_fg;
-        let fg_menu_border = palette().hud_fg_border;
-        let fg_menu_highlight = palette().hud_fg_highlight;
-        draw_batch.fill_region(self.layout, ColorPair::new(fg_menu, bg_menu), to_cp437(' '));
-        draw_batch.draw_hollow_box(self.layout, ColorPair::new(fg_menu_border, bg_menu));
-        for (index, item) in self.items.iter().enumerate() {
-            let color = if index == self.selection {
-                ColorPair::new(fg_menu_highlight, bg_menu)
-            } else {
-                ColorPair::new(fg_menu, bg_menu)
-            };
-            draw_batch.print_color(item.top_left_corner(), &item.text, color);
-        }
-
+        draw_window(self.layout, "", &mut draw_batch);
+        self.list.render(&mut draw_batch);
         draw_batch.submit(6000).unwrap();
     }
 
-    /// Main menu of the game.
-    /// Display a background image and three options for the player to choose
-    ///     - starting a new game
-    ///     - loading an existing game
-    ///     - quitting the game
+    /// Display the menu and let the player pick one of its items, either with the arrow keys and
+    /// `Enter` or with the mouse. Returns the picked item, if any.
     pub fn display(&mut self, ctx: &mut Rltk) -> Option<T> {
-        // render current menu
-        self.render(ctx);
-
-        // wait for user input
-        // a) keyboard input
-        // if we have a key activity, process and return immediately
-        if let Some(key) = ctx.key {
-            match key {
-                VirtualKeyCode::Up => {
-                    self.selection =
-                        modulus(self.selection as i32 - 1, self.items.len() as i32) as usize;
-                }
-                VirtualKeyCode::Down => {
-                    self.selection =
-                        modulus(self.selection as i32 + 1, self.items.len() as i32) as usize;
-                }
-                VirtualKeyCode::Return => {
-                    // return process_item(game, ctx, &self.items[self.selection].item_enum);
-                    return Some(self.items[self.selection].item_enum.clone());
-                }
-                _ => {}
-            }
-        }
+        self.render();
 
-        // b) mouse input
-        // if we have a mouse activity, check first for clicks, then for hovers
-        if let Some(item) = self
-            .items
-            .iter()
-            .find(|i| i.layout.point_in_rect(ctx.mouse_point()))
-        {
-            // update active index
-            if let Some(index) = self.items.iter().position(|m| m.text.eq(&item.text)) {
-                self.selection = index;
-            }
-            if ctx.left_click {
-                return Some(self.items[self.selection].item_enum.clone());
-            }
+        match self.list.handle_input(ctx) {
+            WidgetEvent::Activated => Some(self.items[self.list.selection()].clone()),
+            WidgetEvent::Changed | WidgetEvent::Ignored => None,
         }
-
-        None
     }
 }
@@ -1,5 +1,6 @@
 use crate::core::game_objects::GameObjects;
 use crate::core::game_state::GameState;
+use crate::entity::action::Action;
 use crate::game::RunState;
 use crate::ui::menu::{Menu, MenuItem};
 
@@ -7,8 +8,7 @@ use crate::ui::menu::{Menu, MenuItem};
 pub enum ActionCategory {
     Primary,
     Secondary,
-    Quick1,
-    Quick2,
+    Hotbar(usize),
 }
 
 #[derive(Clone, Debug)]
@@ -37,8 +37,9 @@ impl MenuItem for ActionItem {
                 match item.category {
                     ActionCategory::Primary => object.set_primary_action(action.clone_action()),
                     ActionCategory::Secondary => object.set_secondary_action(action.clone_action()),
-                    ActionCategory::Quick1 => object.set_quick1_action(action.clone_action()),
-                    ActionCategory::Quick2 => object.set_quick2_action(action.clone_action()),
+                    ActionCategory::Hotbar(slot) => {
+                        object.set_hotbar_action(slot, action.clone_action())
+                    }
                 }
             }
         }
@@ -47,14 +48,22 @@ impl MenuItem for ActionItem {
     }
 }
 
+/// List the available actions together with their energy cost, range and what they target.
 pub fn choose_action_menu(
-    available_actions: Vec<String>,
+    available_actions: &[Box<dyn Action>],
     category: ActionCategory,
 ) -> Menu<ActionItem> {
-    let items: Vec<(ActionItem, String)> = available_actions
+    let rows: Vec<(ActionItem, Vec<String>)> = available_actions
         .iter()
-        .cloned()
-        .map(|s| (ActionItem::new(s.clone(), category), s))
+        .map(|a| {
+            let columns = vec![
+                a.get_identifier(),
+                format!("{} energy", a.get_energy_cost()),
+                format!("range {}", a.get_range()),
+                a.get_target_category().name().to_string(),
+            ];
+            (ActionItem::new(a.get_identifier(), category), columns)
+        })
         .collect();
-    Menu::new(items)
+    Menu::with_columns(rows)
 }
@@ -0,0 +1,74 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::core::innit_env;
+use crate::game::RunState;
+use crate::raws::difficulty::Difficulty;
+use crate::raws::load_difficulties;
+use crate::ui::menu::main_menu::main_menu;
+use crate::ui::menu::{Menu, MenuItem};
+
+#[derive(Clone, Debug)]
+pub enum DifficultyMenuItem {
+    Pick(Difficulty),
+    /// Switch between permadeath and checkpoints.
+    Mode,
+    Back,
+}
+
+impl MenuItem for DifficultyMenuItem {
+    fn process(
+        _state: &mut GameState,
+        _objects: &mut GameObjects,
+        _menu: &mut Menu<DifficultyMenuItem>,
+        item: &DifficultyMenuItem,
+    ) -> RunState {
+        match item {
+            DifficultyMenuItem::Pick(difficulty) => {
+                innit_env().set_difficulty(difficulty.clone());
+                RunState::NewGame
+            }
+            DifficultyMenuItem::Mode => {
+                let game_mode = innit_env().game_mode.next();
+                innit_env().set_game_mode(game_mode);
+                RunState::ChooseDifficulty(build_menu(true))
+            }
+            DifficultyMenuItem::Back => RunState::MainMenu(main_menu()),
+        }
+    }
+}
+
+/// Menu of the difficulty presets, picking one starts a new game at that difficulty in the chosen
+/// mode. The last picked difficulty is preselected.
+pub fn difficulty_menu() -> Menu<DifficultyMenuItem> {
+    build_menu(false)
+}
+
+/// Build the menu with either the current difficulty or the mode preselected.
+fn build_menu(select_mode: bool) -> Menu<DifficultyMenuItem> {
+    // problems with the raws have been reported at startup already
+    let mut difficulties = load_difficulties(&mut Vec::new());
+    if difficulties.is_empty() {
+        difficulties.push(Difficulty::default());
+    }
+    let current = innit_env().difficulty.name.clone();
+    let selection = difficulties
+        .iter()
+        .position(|d| d.name == current)
+        .unwrap_or(0);
+    let mut rows: Vec<(DifficultyMenuItem, Vec<String>)> = difficulties
+        .into_iter()
+        .map(|d| {
+            let columns = vec![d.name.clone(), d.description.clone()];
+            (DifficultyMenuItem::Pick(d), columns)
+        })
+        .collect();
+    let mode = innit_env().game_mode.name().to_string();
+    rows.push((DifficultyMenuItem::Mode, vec!["Mode".to_string(), mode]));
+    rows.push((DifficultyMenuItem::Back, vec!["Back".to_string()]));
+    let selection = if select_mode {
+        rows.len() - 2
+    } else {
+        selection
+    };
+    Menu::with_columns(rows).with_selection(selection)
+}
@@ -0,0 +1,80 @@
+use crate::core::game_objects::GameObjects;
+use crate::core::game_state::GameState;
+use crate::game::RunState;
+use crate::ui::menu::main_menu::main_menu;
+use crate::ui::menu::{Menu, MenuItem};
+use crate::ui::settings::DisplaySettings;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DisplayMenuItem {
+    Font,
+    UiScale,
+    WindowMode,
+    Resizable,
+    Back,
+}
+
+impl MenuItem for DisplayMenuItem {
+    fn process(
+        _state: &mut GameState,
+        _objects: &mut GameObjects,
+        _menu: &mut Menu<DisplayMenuItem>,
+        item: &DisplayMenuItem,
+    ) -> RunState {
+        let mut settings = DisplaySettings::load();
+        match item {
+            DisplayMenuItem::Font => settings.font = settings.font.next(),
+            DisplayMenuItem::UiScale => settings.cycle_ui_scale(),
+            DisplayMenuItem::WindowMode => settings.window_mode = settings.window_mode.next(),
+            DisplayMenuItem::Resizable => settings.resizable = !settings.resizable,
+            DisplayMenuItem::Back => return RunState::MainMenu(main_menu()),
+        }
+        // the remembered window size was chosen for the old tiles and no longer fits
+        if matches!(item, DisplayMenuItem::Font | DisplayMenuItem::UiScale) {
+            settings.window_size = None;
+        }
+        settings.save();
+        RunState::DisplaySettings(display_menu(&settings, *item))
+    }
+}
+
+/// Menu of the display settings, each item switches to the next option when picked. The settings
+/// are saved right away and take effect after a restart.
+pub fn display_menu(
+    settings: &DisplaySettings,
+    selected: DisplayMenuItem,
+) -> Menu<DisplayMenuItem> {
+    let rows = vec![
+        (
+            DisplayMenuItem::Font,
+            vec!["Font".to_string(), settings.font.name()],
+        ),
+        (
+            DisplayMenuItem::UiScale,
+            vec![
+                "UI Scale".to_string(),
+                format!("{:.0}%", settings.ui_scale * 100.0),
+            ],
+        ),
+        (
+            DisplayMenuItem::WindowMode,
+            vec![
+                "Window".to_string(),
+                settings.window_mode.name().to_string(),
+            ],
+        ),
+        (
+            DisplayMenuItem::Resizable,
+            vec![
+                "Resizable".to_string(),
+                if settings.resizable { "yes" } else { "no" }.to_string(),
+            ],
+        ),
+        (DisplayMenuItem::Back, vec!["Back".to_string()]),
+    ];
+    let selection = rows
+        .iter()
+        .position(|(item, _)| *item == selected)
+        .unwrap_or(0);
+    Menu::with_columns(rows).with_selection(selection)
+}
@@ -1,35 +1,70 @@
 use crate::core::game_objects::GameObjects;
-use crate::core::game_state::GameState;
+use crate::core::game_state::{GameState, MessageLog, MsgClass};
+use crate::core::innit_env;
 use crate::game::RunState;
 use crate::ui::menu::main_menu::main_menu;
 use crate::ui::menu::{Menu, MenuItem};
+use crate::ui::spectator::Spectator;
 
 #[derive(Copy, Clone, Debug)]
 pub enum GameOverMenuItem {
+    Respawn,
+    Spectate,
     Credits,
     ReturnToMain,
 }
 
 impl MenuItem for GameOverMenuItem {
     fn process(
-        _state: &mut GameState,
-        _objects: &mut GameObjects,
+        state: &mut GameState,
+        objects: &mut GameObjects,
         _menu: &mut Menu<GameOverMenuItem>,
         item: &GameOverMenuItem,
     ) -> RunState {
         match item {
+            // the respawn has been charged to the checkpoint already, it just has to be loaded
+            GameOverMenuItem::Respawn => RunState::LoadGame,
+            GameOverMenuItem::Spectate => {
+                let turns = innit_env().spectator_turns;
+                state.log.add(
+                    format!(
+                        "The world goes on for another {} turns. Move the camera with the arrow keys, Esc to stop watching.",
+                        turns
+                    ),
+                    MsgClass::Story,
+                );
+                let camera = objects[state.player_idx]
+                    .as_ref()
+                    .map_or_else(Default::default, |p| p.pos);
+                RunState::Spectating(Spectator::new(camera, turns))
+            }
             GameOverMenuItem::Credits => unimplemented!(),
             GameOverMenuItem::ReturnToMain => RunState::MainMenu(main_menu()),
         }
     }
 }
 
-pub fn game_over_menu() -> Menu<GameOverMenuItem> {
-    Menu::new(vec![
+/// Menu shown after the player died, optionally offering to watch the world go on and to respawn
+/// at the last checkpoint.
+pub fn game_over_menu(can_spectate: bool, can_respawn: bool) -> Menu<GameOverMenuItem> {
+    let mut items = vec![
         (GameOverMenuItem::Credits, "Credits".to_string()),
         (
             GameOverMenuItem::ReturnToMain,
             "Return to Main Menu".to_string(),
         ),
-    ])
+    ];
+    if can_spectate {
+        items.insert(0, (GameOverMenuItem::Spectate, "Spectate".to_string()));
+    }
+    if can_respawn {
+        items.insert(
+            0,
+            (
+                GameOverMenuItem::Respawn,
+                "Respawn at Checkpoint".to_string(),
+            ),
+        );
+    }
+    Menu::new(items)
 }
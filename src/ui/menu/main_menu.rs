@@ -1,14 +1,20 @@
 use crate::core::game_objects::GameObjects;
 use crate::core::game_state::GameState;
 use crate::game::RunState;
+use crate::ui::dialog::run_code::RunCodeDialog;
+use crate::ui::menu::difficulty_menu::difficulty_menu;
+use crate::ui::menu::display_menu::{display_menu, DisplayMenuItem};
 use crate::ui::menu::{Menu, MenuItem};
+use crate::ui::settings::DisplaySettings;
 
 #[derive(Copy, Clone, Debug)]
 pub enum MainMenuItem {
     NewGame,
+    DailyRun,
+    EnterRunCode,
     Resume,
     // Controls,
-    // Options,
+    Display,
     Quit,
 }
 
@@ -20,8 +26,14 @@ impl MenuItem for MainMenuItem {
         item: &MainMenuItem,
     ) -> RunState {
         match item {
-            MainMenuItem::NewGame => RunState::NewGame,
+            MainMenuItem::NewGame => RunState::ChooseDifficulty(difficulty_menu()),
+            MainMenuItem::DailyRun => RunState::NewDailyRun,
+            MainMenuItem::EnterRunCode => RunState::RunCodeDialog(RunCodeDialog::new()),
             MainMenuItem::Resume => RunState::LoadGame,
+            MainMenuItem::Display => RunState::DisplaySettings(display_menu(
+                &DisplaySettings::load(),
+                DisplayMenuItem::Font,
+            )),
             MainMenuItem::Quit => std::process::exit(0),
         }
     }
@@ -30,7 +42,10 @@ impl MenuItem for MainMenuItem {
 pub fn main_menu() -> Menu<MainMenuItem> {
     Menu::new(vec![
         (MainMenuItem::NewGame, "New Game".to_string()),
+        (MainMenuItem::DailyRun, "Daily Run".to_string()),
+        (MainMenuItem::EnterRunCode, "Enter Run Code".to_string()),
         (MainMenuItem::Resume, "Resume Last Game".to_string()),
+        (MainMenuItem::Display, "Display Settings".to_string()),
         (MainMenuItem::Quit, "Quit".to_string()),
     ])
 }
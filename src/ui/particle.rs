@@ -1,8 +1,15 @@
 //! This module contains the particle/animation system
 
-use rltk::Rltk;
+use rand::Rng;
+use rltk::Point;
 
+use crate::core::innit_env;
 use crate::core::position::Position;
+use crate::ui::ambience::Ambience;
+use crate::ui::animations::{AnimationClock, Interval};
+
+/// Time in [ms] that the world stays put between two jolts of a screen shake.
+const SHAKE_STEP_MS: f32 = 40.0;
 
 pub struct Particle {
     pub pos: Position,
@@ -11,6 +18,10 @@ pub struct Particle {
     pub glyph: char,
     /// Lifetime of the particle, given in [ms]
     pub lifetime: f32,
+    /// Time the particle has been alive, given in [ms]
+    pub age: f32,
+    /// Upwards drift of the particle, given in [tiles/s]
+    pub rise: f32,
 }
 
 impl Particle {
@@ -27,30 +38,72 @@ impl Particle {
             col_bg,
             glyph,
             lifetime,
+            age: 0.0,
+            rise: 0.0,
         }
     }
+
+    /// Let the particle float upwards at the given speed. Part of the builder pattern.
+    pub(crate) fn rising(mut self, rise: f32) -> Self {
+        self.rise = rise;
+        self
+    }
+
+    /// Return the current screen position of the particle, taking drift into account.
+    pub fn render_pos(&self) -> Point {
+        let drift = (self.age * self.rise / 1000.0) as i32;
+        Point::new(self.pos.x, (self.pos.y - drift).max(0))
+    }
 }
 
 pub struct ParticleSystem {
     pub particles: Vec<Particle>,
+    /// Remaining duration of the current screen shake, given in [ms]
+    pub shake: f32,
+    shake_steps: Interval,
+    /// Current offset of the shaking world view.
+    shake_offset: (f32, f32),
+    pub ambience: Ambience,
 }
 
 impl ParticleSystem {
     pub fn new() -> Self {
         ParticleSystem {
             particles: Vec::new(),
+            shake: 0.0,
+            shake_steps: Interval::new(SHAKE_STEP_MS),
+            shake_offset: (0.0, 0.0),
+            ambience: Ambience::new(),
         }
     }
 
-    /// Advance the particle lifetimes and cull all those that have expired.
-    /// Returns true if some particles expired in this call.
-    pub fn update(&mut self, ctx: &Rltk) -> bool {
+    /// Advance the particle lifetimes and cull all those that have expired, as well as the ambient
+    /// animation if enabled. Returns true if some particles expired or the ambience moved on in
+    /// this call, so that the world needs to be rendered again.
+    pub fn update(&mut self, clock: &AnimationClock) -> bool {
+        let use_ambience = innit_env().use_ambience;
         let start_size: usize = self.particles.len();
-        self.particles
-            .iter_mut()
-            .for_each(|p| p.lifetime -= ctx.frame_time_ms);
+        self.particles.iter_mut().for_each(|p| {
+            p.lifetime -= clock.delta();
+            p.age += clock.delta();
+        });
+
+        self.shake = (self.shake - clock.delta()).max(0.0);
+        let jolts = self.shake_steps.advance(clock);
+        if self.shake <= 0.0 {
+            self.shake_offset = (0.0, 0.0);
+        } else if jolts > 0 {
+            let mut rng = rand::thread_rng();
+            self.shake_offset = (rng.gen_range(-0.5..=0.5), rng.gen_range(-0.5..=0.5));
+        }
 
         self.particles.retain(|p| p.lifetime > 0.0);
-        self.particles.len() < start_size
+        let ambient_frame = use_ambience && self.ambience.update(clock);
+        self.particles.len() < start_size || ambient_frame
+    }
+
+    /// Offset of the world view while the screen shakes.
+    pub fn shake_offset(&self) -> (f32, f32) {
+        self.shake_offset
     }
 }
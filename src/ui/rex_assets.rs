@@ -1,18 +1,38 @@
+use crate::assets::{self, Asset};
+use crate::core::game_error::GameError;
 use rltk::rex::XpFile;
 
-rltk::embedded_resource!(SMALL_DUNGEON, "../../resources/art/main.xp");
-
 pub struct RexAssets {
-    pub menu: XpFile,
+    /// Menu background, `None` if it could not be loaded.
+    pub menu: Option<XpFile>,
 }
 
 impl RexAssets {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> RexAssets {
-        rltk::link_resource!(SMALL_DUNGEON, "../../resources/art/main.xp");
-
-        RexAssets {
-            menu: XpFile::from_resource("../../resources/art/main.xp").unwrap(),
+    pub fn new() -> (RexAssets, Option<GameError>) {
+        let mut errors = Vec::new();
+        match load_xp(&assets::MENU_ART, &mut errors) {
+            Some(menu) => (RexAssets { menu: Some(menu) }, None),
+            None => {
+                let error =
+                    GameError::MissingAsset(format!("menu background: {}", errors.join(", ")));
+                (RexAssets { menu: None }, Some(error))
+            }
         }
     }
 }
+
+/// Load a REX Paint image from the first source of the asset that provides a readable one.
+/// Problems with the sources are appended to `errors`.
+pub fn load_xp(asset: &Asset, errors: &mut Vec<String>) -> Option<XpFile> {
+    asset.load(errors, |origin, mut bytes, errors| {
+        match XpFile::read(&mut bytes) {
+            Ok(xp) => Some(xp),
+            Err(e) => {
+                error!("cannot load {}: {}", origin, e);
+                errors.push(format!("{}: {}", origin, e));
+                None
+            }
+        }
+    })
+}
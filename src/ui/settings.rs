@@ -0,0 +1,209 @@
+//! Preferences of the player that only concern the user interface. They are kept in the data
+//! directory, so that they persist from one run to the next.
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::{self, Asset};
+use crate::ui::hud::SidebarTab;
+use crate::util::platform;
+
+const STORAGE_KEY: &str = "settings.json";
+const DISPLAY_STORAGE_KEY: &str = "display.json";
+
+/// Factors by which the font can be scaled up on screen.
+const UI_SCALES: [f32; 4] = [1.0, 1.25, 1.5, 2.0];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct UiSettings {
+    /// Tab of the side panel that was open last.
+    pub sidebar_tab: SidebarTab,
+    /// Whether the actions section of the side panel is folded away.
+    pub actions_collapsed: bool,
+    /// Whether visual effects like floating damage numbers are shown.
+    pub effects: bool,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        UiSettings {
+            sidebar_tab: SidebarTab::default(),
+            actions_collapsed: false,
+            effects: true,
+        }
+    }
+}
+
+impl UiSettings {
+    /// Read the settings from storage, falling back to the defaults if there is nothing stored yet
+    /// or the stored data can't be read.
+    pub fn load() -> Self {
+        match platform::read_data(STORAGE_KEY) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| {
+                error!("cannot parse settings: {}", e);
+                UiSettings::default()
+            }),
+            Err(_) => UiSettings::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|e| e.into())
+            .and_then(|data| platform::write_data(STORAGE_KEY, &data));
+        if let Err(e) = result {
+            error!("cannot save settings: {}", e);
+        }
+    }
+}
+
+/// Fonts bundled with the game, all of them laid out like the REX Paint fonts.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FontChoice {
+    #[default]
+    Rex8x8,
+    Rex10x10,
+    Rex12x12,
+    Rex14x14,
+    Rex16x16,
+}
+
+impl FontChoice {
+    const ALL: [FontChoice; 5] = [
+        FontChoice::Rex8x8,
+        FontChoice::Rex10x10,
+        FontChoice::Rex12x12,
+        FontChoice::Rex14x14,
+        FontChoice::Rex16x16,
+    ];
+
+    pub fn asset(&self) -> Asset {
+        match self {
+            FontChoice::Rex8x8 => assets::FONT_8X8,
+            FontChoice::Rex10x10 => assets::FONT_10X10,
+            FontChoice::Rex12x12 => assets::FONT_12X12,
+            FontChoice::Rex14x14 => assets::FONT_14X14,
+            FontChoice::Rex16x16 => assets::FONT_16X16,
+        }
+    }
+
+    /// Width and height in [px] of a glyph within the font.
+    pub fn glyph_size(&self) -> u32 {
+        match self {
+            FontChoice::Rex8x8 => 8,
+            FontChoice::Rex10x10 => 10,
+            FontChoice::Rex12x12 => 12,
+            FontChoice::Rex14x14 => 14,
+            FontChoice::Rex16x16 => 16,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("REX Paint {0}x{0}", self.glyph_size())
+    }
+
+    /// Return the next font, wrapping around after the last one.
+    pub fn next(&self) -> FontChoice {
+        let idx = Self::ALL.iter().position(|f| f == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+/// How the game window is shown on the desktop.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    /// A window without title bar and frame.
+    Borderless,
+    /// A window covering the whole primary monitor.
+    Fullscreen,
+}
+
+impl WindowMode {
+    pub fn name(&self) -> &str {
+        match self {
+            WindowMode::Windowed => "Windowed",
+            WindowMode::Borderless => "Borderless",
+            WindowMode::Fullscreen => "Fullscreen",
+        }
+    }
+
+    pub fn next(&self) -> WindowMode {
+        match self {
+            WindowMode::Windowed => WindowMode::Borderless,
+            WindowMode::Borderless => WindowMode::Fullscreen,
+            WindowMode::Fullscreen => WindowMode::Windowed,
+        }
+    }
+}
+
+/// Preferences of how the game window looks. They are read before the window is created, so
+/// changes take effect after a restart.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub font: FontChoice,
+    /// Factor by which the font is scaled up on screen.
+    pub ui_scale: f32,
+    pub window_mode: WindowMode,
+    /// Whether the window can be resized, the grid of tiles is scaled to fit it.
+    pub resizable: bool,
+    /// Size in [px] of the window's content when it was last resized, if it was.
+    pub window_size: Option<(u32, u32)>,
+    /// Position in [px] of the window on the desktop when it was last moved, if it was.
+    pub window_position: Option<(i32, i32)>,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        DisplaySettings {
+            font: FontChoice::default(),
+            ui_scale: UI_SCALES[0],
+            window_mode: WindowMode::default(),
+            resizable: false,
+            window_size: None,
+            window_position: None,
+        }
+    }
+}
+
+impl DisplaySettings {
+    /// Read the settings from storage, falling back to the defaults if there is nothing stored yet
+    /// or the stored data can't be read.
+    pub fn load() -> Self {
+        match platform::read_data(DISPLAY_STORAGE_KEY) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| {
+                error!("cannot parse display settings: {}", e);
+                DisplaySettings::default()
+            }),
+            Err(_) => DisplaySettings::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|e| e.into())
+            .and_then(|data| platform::write_data(DISPLAY_STORAGE_KEY, &data));
+        if let Err(e) = result {
+            error!("cannot save display settings: {}", e);
+        }
+    }
+
+    /// Width and height in [px] of a tile on screen.
+    pub fn tile_size(&self) -> u32 {
+        let scale = self
+            .ui_scale
+            .clamp(UI_SCALES[0], UI_SCALES[UI_SCALES.len() - 1]);
+        (self.font.glyph_size() as f32 * scale).round() as u32
+    }
+
+    /// Switch to the next larger scale, wrapping around after the largest one.
+    pub fn cycle_ui_scale(&mut self) {
+        let idx = UI_SCALES
+            .iter()
+            .position(|scale| *scale > self.ui_scale)
+            .unwrap_or(0);
+        self.ui_scale = UI_SCALES[idx];
+    }
+}
@@ -0,0 +1,60 @@
+//! After the player's death the world can be watched for a while longer, to see whether offspring
+//! or rivals thrive. The camera roams freely and has its own field of view.
+
+use crate::core::position::Position;
+use crate::game::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::ui::animations::AnimationClock;
+use rltk::VirtualKeyCode;
+
+/// Sensing range of the spectator camera.
+pub const SPECTATOR_RANGE: i32 = 12;
+/// Real time between two simulated turns, so that there is something to watch.
+const SPECTATOR_TURN_MS: f32 = 150.0;
+/// Number of tiles the camera moves at once while holding `Shift`.
+const CAMERA_FAST_STEP: i32 = 5;
+
+#[derive(Clone, Debug)]
+pub struct Spectator {
+    pub camera: Position,
+    pub turns_left: u32,
+    elapsed_ms: f32,
+}
+
+impl Spectator {
+    pub fn new(camera: Position, turns: u32) -> Self {
+        Spectator {
+            camera,
+            turns_left: turns,
+            elapsed_ms: 0.0,
+        }
+    }
+
+    /// Move the camera with the arrow keys or WASD. Returns whether the camera moved.
+    pub fn move_camera(&mut self, key: VirtualKeyCode, shift: bool) -> bool {
+        let (dx, dy) = match key {
+            VirtualKeyCode::Up | VirtualKeyCode::W => (0, -1),
+            VirtualKeyCode::Down | VirtualKeyCode::S => (0, 1),
+            VirtualKeyCode::Left | VirtualKeyCode::A => (-1, 0),
+            VirtualKeyCode::Right | VirtualKeyCode::D => (1, 0),
+            _ => return false,
+        };
+        let step = if shift { CAMERA_FAST_STEP } else { 1 };
+        self.camera.set(
+            (self.camera.x + dx * step).clamp(0, WORLD_WIDTH - 1),
+            (self.camera.y + dy * step).clamp(0, WORLD_HEIGHT - 1),
+        );
+        true
+    }
+
+    /// Let real time pass. Returns whether the next turn is due.
+    pub fn advance(&mut self, clock: &AnimationClock) -> bool {
+        self.elapsed_ms += clock.delta();
+        if self.elapsed_ms >= SPECTATOR_TURN_MS && self.turns_left > 0 {
+            self.elapsed_ms -= SPECTATOR_TURN_MS;
+            self.turns_left -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! A small toolkit of widgets that menus and dialogs are built from: lists, rows of buttons, text
+//! fields and scrollable views. Every widget draws itself in the style of the side panel and only
+//! reacts to the keyboard while it has the focus. Dialogs with several widgets keep track of the
+//! focus with a [`FocusRing`].
+
+use crate::ui::palette;
+use crate::util::modulus;
+use rltk::{to_cp437, ColorPair, DrawBatch, Point, Rect, Rltk, VirtualKeyCode};
+
+/// What a widget made of the input it has been given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WidgetEvent {
+    /// The input isn't meant for this widget.
+    Ignored,
+    /// The selection, text or scroll position changed.
+    Changed,
+    /// The selected entry was picked, with `Enter` or a click.
+    Activated,
+}
+
+/// Draw the frame shared by all windows: the background, a border and the title set into the
+/// top border.
+pub fn draw_window(layout: Rect, title: &str, draw_batch: &mut DrawBatch) {
+    let fg_hud_border = palette().hud_fg_border;
+    let fg_hud = palette().hud_fg;
+    let bg_hud = palette().hud_bg;
+    draw_batch.fill_region(layout, ColorPair::new(fg_hud, bg_hud), to_cp437(' '));
+    draw_batch.draw_hollow_box(layout, ColorPair::new(fg_hud_border, bg_hud));
+    if !title.is_empty() {
+        draw_batch.print_color(
+            Point::new(layout.x1 + 2, layout.y1),
+            format!(" {} ", title),
+            ColorPair::new(fg_hud_border, bg_hud),
+        );
+    }
+}
+
+/// Check whether the key confirms a choice.
+fn is_confirm(key: VirtualKeyCode) -> bool {
+    matches!(key, VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter)
+}
+
+/// Keeps track of which of a dialog's widgets receives keyboard input. `Tab` passes the focus on
+/// to the next widget, `Shift + Tab` back to the previous one.
+#[derive(Clone, Debug)]
+pub struct FocusRing {
+    len: usize,
+    current: usize,
+}
+
+impl FocusRing {
+    pub fn new(len: usize) -> Self {
+        FocusRing { len, current: 0 }
+    }
+
+    pub fn is_focused(&self, idx: usize) -> bool {
+        self.current == idx
+    }
+
+    /// Move the focus if the key asks for it. Returns whether the key has been used up.
+    pub fn handle_key(&mut self, key: VirtualKeyCode, shift: bool) -> bool {
+        if key != VirtualKeyCode::Tab || self.len == 0 {
+            return false;
+        }
+        let step = if shift { -1 } else { 1 };
+        self.current = modulus(self.current as i32 + step, self.len as i32) as usize;
+        true
+    }
+}
+
+/// Vertical list of entries, one per line. Entries can be disabled, which keeps them selectable
+/// but stops them from being activated. Lists longer than their layout scroll along with the
+/// selection.
+#[derive(Clone, Debug)]
+pub struct ListView {
+    entries: Vec<String>,
+    enabled: Vec<bool>,
+    selection: usize,
+    scroll: usize,
+    layout: Rect,
+    pub focused: bool,
+}
+
+impl ListView {
+    pub fn new(layout: Rect, entries: Vec<String>) -> Self {
+        let enabled = vec![true; entries.len()];
+        ListView {
+            entries,
+            enabled,
+            selection: 0,
+            scroll: 0,
+            layout,
+            focused: true,
+        }
+    }
+
+    pub fn set_enabled(&mut self, idx: usize, is_enabled: bool) {
+        if let Some(enabled) = self.enabled.get_mut(idx) {
+            *enabled = is_enabled;
+        }
+    }
+
+    pub fn selection(&self) -> usize {
+        self.selection
+    }
+
+    /// Select the entry with the given index and scroll it into view.
+    pub fn select(&mut self, idx: usize) {
+        if idx >= self.entries.len() {
+            return;
+        }
+        self.selection = idx;
+        let rows = self.rows();
+        if self.selection < self.scroll {
+            self.scroll = self.selection;
+        } else if self.selection >= self.scroll + rows {
+            self.scroll = self.selection + 1 - rows;
+        }
+    }
+
+    fn rows(&self) -> usize {
+        self.layout.height().max(1) as usize
+    }
+
+    fn activate(&self) -> WidgetEvent {
+        if self.enabled.get(self.selection).copied().unwrap_or(false) {
+            WidgetEvent::Activated
+        } else {
+            WidgetEvent::Ignored
+        }
+    }
+
+    /// `Up` and `Down` move the selection, wrapping around at either end, `Enter` activates it.
+    pub fn handle_key(&mut self, key: VirtualKeyCode) -> WidgetEvent {
+        if !self.focused || self.entries.is_empty() {
+            return WidgetEvent::Ignored;
+        }
+        let len = self.entries.len() as i32;
+        match key {
+            VirtualKeyCode::Up => {
+                self.select(modulus(self.selection as i32 - 1, len) as usize);
+                WidgetEvent::Changed
+            }
+            VirtualKeyCode::Down => {
+                self.select(modulus(self.selection as i32 + 1, len) as usize);
+                WidgetEvent::Changed
+            }
+            key if is_confirm(key) => self.activate(),
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    /// Hovering an entry selects it, clicking activates it.
+    pub fn handle_mouse(&mut self, pos: Point, click: bool) -> WidgetEvent {
+        if !self.layout.point_in_rect(pos) {
+            return WidgetEvent::Ignored;
+        }
+        let idx = self.scroll + (pos.y - self.layout.y1) as usize;
+        if idx >= self.entries.len() {
+            return WidgetEvent::Ignored;
+        }
+        self.select(idx);
+        if click {
+            self.activate()
+        } else {
+            WidgetEvent::Changed
+        }
+    }
+
+    pub fn handle_input(&mut self, ctx: &Rltk) -> WidgetEvent {
+        let event = ctx
+            .key
+            .map_or(WidgetEvent::Ignored, |key| self.handle_key(key));
+        if event == WidgetEvent::Ignored {
+            self.handle_mouse(ctx.mouse_point(), ctx.left_click)
+        } else {
+            event
+        }
+    }
+
+    pub fn render(&self, draw_batch: &mut DrawBatch) {
+        let fg_hud = palette().hud_fg;
+        let fg_hud_highlight = palette().hud_fg_highlight;
+        let fg_hud_inactive = palette().hud_fg_inactive;
+        let bg_hud = palette().hud_bg;
+        let width = self.layout.width().max(0) as usize;
+        let visible = self.entries.iter().enumerate().skip(self.scroll);
+        for ((idx, entry), y) in visible.zip(self.layout.y1..self.layout.y1 + self.rows() as i32) {
+            let fg = if !self.enabled[idx] {
+                fg_hud_inactive
+            } else if idx == self.selection {
+                fg_hud_highlight
+            } else {
+                fg_hud
+            };
+            let text: String = entry.chars().take(width).collect();
+            draw_batch.print_color(
+                Point::new(self.layout.x1, y),
+                text,
+                ColorPair::new(fg, bg_hud),
+            );
+        }
+    }
+}
+
+/// Horizontal row of buttons. `Left` and `Right` move the selection, `Enter` presses the
+/// selected button.
+#[derive(Clone, Debug)]
+pub struct ButtonRow {
+    labels: Vec<String>,
+    selection: usize,
+    origin: Point,
+    pub focused: bool,
+}
+
+/// Space between two buttons.
+const BUTTON_GAP: i32 = 2;
+
+impl ButtonRow {
+    pub fn new(origin: Point, labels: Vec<String>) -> Self {
+        ButtonRow {
+            labels,
+            selection: 0,
+            origin,
+            focused: false,
+        }
+    }
+
+    pub fn selection(&self) -> usize {
+        self.selection
+    }
+
+    /// Layouts of the buttons, each label padded with a space on either side.
+    fn layouts(&self) -> Vec<Rect> {
+        let mut x = self.origin.x;
+        self.labels
+            .iter()
+            .map(|label| {
+                let width = label.chars().count() as i32 + 2;
+                let layout = Rect::with_size(x, self.origin.y, width, 1);
+                x += width + BUTTON_GAP;
+                layout
+            })
+            .collect()
+    }
+
+    pub fn handle_key(&mut self, key: VirtualKeyCode) -> WidgetEvent {
+        if !self.focused || self.labels.is_empty() {
+            return WidgetEvent::Ignored;
+        }
+        let len = self.labels.len() as i32;
+        match key {
+            VirtualKeyCode::Left => {
+                self.selection = modulus(self.selection as i32 - 1, len) as usize;
+                WidgetEvent::Changed
+            }
+            VirtualKeyCode::Right => {
+                self.selection = modulus(self.selection as i32 + 1, len) as usize;
+                WidgetEvent::Changed
+            }
+            key if is_confirm(key) => WidgetEvent::Activated,
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    /// Clicking a button presses it straight away.
+    pub fn handle_mouse(&mut self, pos: Point, click: bool) -> WidgetEvent {
+        match self.layouts().iter().position(|l| l.point_in_rect(pos)) {
+            Some(idx) if click => {
+                self.selection = idx;
+                WidgetEvent::Activated
+            }
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    pub fn handle_input(&mut self, ctx: &Rltk) -> WidgetEvent {
+        let event = ctx
+            .key
+            .map_or(WidgetEvent::Ignored, |key| self.handle_key(key));
+        if event == WidgetEvent::Ignored {
+            self.handle_mouse(ctx.mouse_point(), ctx.left_click)
+        } else {
+            event
+        }
+    }
+
+    pub fn render(&self, draw_batch: &mut DrawBatch) {
+        let fg_hud = palette().hud_fg;
+        let fg_hud_highlight = palette().hud_fg_highlight;
+        let bg_hud_active = palette().hud_bg_active;
+        for (idx, (label, layout)) in self.labels.iter().zip(self.layouts()).enumerate() {
+            let color = if idx == self.selection && self.focused {
+                ColorPair::new(fg_hud_highlight, bg_hud_active)
+            } else {
+                ColorPair::new(fg_hud, bg_hud_active)
+            };
+            draw_batch.print_color(
+                Point::new(layout.x1, layout.y1),
+                format!(" {} ", label),
+                color,
+            );
+        }
+    }
+}
+
+/// Single line of text input with a maximum length. `Backspace` deletes the last character,
+/// `Enter` submits the text.
+#[derive(Clone, Debug)]
+pub struct TextField {
+    text: String,
+    max_len: usize,
+    /// Turn all letters into capitals, e.g. for codes.
+    upper_case: bool,
+    origin: Point,
+    pub focused: bool,
+}
+
+impl TextField {
+    pub fn new(origin: Point, text: &str, max_len: usize) -> Self {
+        TextField {
+            text: text.chars().take(max_len).collect(),
+            max_len,
+            upper_case: false,
+            origin,
+            focused: true,
+        }
+    }
+
+    pub fn upper_case(mut self) -> Self {
+        self.upper_case = true;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn handle_key(&mut self, key: VirtualKeyCode, shift: bool) -> WidgetEvent {
+        if !self.focused {
+            return WidgetEvent::Ignored;
+        }
+        match key {
+            VirtualKeyCode::Back => {
+                self.text.pop();
+                WidgetEvent::Changed
+            }
+            key if is_confirm(key) => WidgetEvent::Activated,
+            key => match key_to_char(key, shift || self.upper_case) {
+                Some(c) if self.text.chars().count() < self.max_len => {
+                    self.text.push(c);
+                    WidgetEvent::Changed
+                }
+                _ => WidgetEvent::Ignored,
+            },
+        }
+    }
+
+    pub fn handle_input(&mut self, ctx: &Rltk) -> WidgetEvent {
+        ctx.key
+            .map_or(WidgetEvent::Ignored, |key| self.handle_key(key, ctx.shift))
+    }
+
+    /// Draw the text, followed by a cursor while the field has the focus.
+    pub fn render(&self, draw_batch: &mut DrawBatch) {
+        let fg_hud = palette().hud_fg;
+        let bg_hud = palette().hud_bg;
+        let cursor = if self.focused { "_" } else { "" };
+        draw_batch.print_color(
+            self.origin,
+            format!("{}{}", self.text, cursor),
+            ColorPair::new(fg_hud, bg_hud),
+        );
+    }
+}
+
+/// Read-only lines of text that can be scrolled with `Up`, `Down`, `Page Up` and `Page Down`
+/// when there are more than fit into the layout.
+#[derive(Clone, Debug)]
+pub struct ScrollView {
+    lines: Vec<String>,
+    offset: usize,
+    layout: Rect,
+}
+
+impl ScrollView {
+    pub fn new(layout: Rect, lines: Vec<String>) -> Self {
+        ScrollView {
+            lines,
+            offset: 0,
+            layout,
+        }
+    }
+
+    fn rows(&self) -> usize {
+        self.layout.height().max(1) as usize
+    }
+
+    fn max_offset(&self) -> usize {
+        self.lines.len().saturating_sub(self.rows())
+    }
+
+    /// Scroll by the given number of lines, as far as there is more to show.
+    pub fn scroll(&mut self, step: i32) -> WidgetEvent {
+        let offset = (self.offset as i32 + step).clamp(0, self.max_offset() as i32) as usize;
+        if offset == self.offset {
+            WidgetEvent::Ignored
+        } else {
+            self.offset = offset;
+            WidgetEvent::Changed
+        }
+    }
+
+    pub fn handle_key(&mut self, key: VirtualKeyCode) -> WidgetEvent {
+        let page = self.rows() as i32;
+        match key {
+            VirtualKeyCode::Up => self.scroll(-1),
+            VirtualKeyCode::Down => self.scroll(1),
+            VirtualKeyCode::PageUp => self.scroll(-page),
+            VirtualKeyCode::PageDown => self.scroll(page),
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    /// Draw the visible lines, with arrows on the right edge if there's more above or below.
+    pub fn render(&self, draw_batch: &mut DrawBatch) {
+        let fg_hud = palette().hud_fg;
+        let fg_hud_highlight = palette().hud_fg_highlight;
+        let bg_hud = palette().hud_bg;
+        let rows = self.layout.y1..self.layout.y1 + self.rows() as i32;
+        for (line, y) in self.lines.iter().skip(self.offset).zip(rows) {
+            draw_batch.print_color(
+                Point::new(self.layout.x1, y),
+                line,
+                ColorPair::new(fg_hud, bg_hud),
+            );
+        }
+        let arrow_color = ColorPair::new(fg_hud_highlight, bg_hud);
+        if self.offset > 0 {
+            draw_batch.print_color(Point::new(self.layout.x2, self.layout.y1), "▲", arrow_color);
+        }
+        if self.offset < self.max_offset() {
+            draw_batch.print_color(
+                Point::new(self.layout.x2, self.layout.y1 + self.rows() as i32 - 1),
+                "▼",
+                arrow_color,
+            );
+        }
+    }
+}
+
+/// Character typed with the given key, if it's a letter, a digit, a space or a dash.
+pub fn key_to_char(key: VirtualKeyCode, shift: bool) -> Option<char> {
+    use VirtualKeyCode::*;
+    let c = match key {
+        A => 'a',
+        B => 'b',
+        C => 'c',
+        D => 'd',
+        E => 'e',
+        F => 'f',
+        G => 'g',
+        H => 'h',
+        I => 'i',
+        J => 'j',
+        K => 'k',
+        L => 'l',
+        M => 'm',
+        N => 'n',
+        O => 'o',
+        P => 'p',
+        Q => 'q',
+        R => 'r',
+        S => 's',
+        T => 't',
+        U => 'u',
+        V => 'v',
+        W => 'w',
+        X => 'x',
+        Y => 'y',
+        Z => 'z',
+        Key0 => '0',
+        Key1 => '1',
+        Key2 => '2',
+        Key3 => '3',
+        Key4 => '4',
+        Key5 => '5',
+        Key6 => '6',
+        Key7 => '7',
+        Key8 => '8',
+        Key9 => '9',
+        Space => ' ',
+        Minus => '-',
+        _ => return None,
+    };
+    Some(if shift { c.to_ascii_uppercase() } else { c })
+}
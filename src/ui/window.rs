@@ -0,0 +1,140 @@
+//! The game window on the desktop. Its mode is chosen in the display settings and set up when the
+//! window is created, its size and position are remembered from one session to the next.
+//!
+//! The grid of tiles keeps its dimensions in any mode, it's merely scaled to fit the window, so
+//! that the layout of the HUD never changes. Browsers have no window of their own, there the
+//! settings are ignored.
+
+use rltk::{BEvent, Point, Rltk, RltkBuilder, INPUT};
+
+use crate::ui::settings::{DisplaySettings, WindowMode};
+
+/// Number of frames the window has to stay put before its size and position are saved, so that
+/// dragging it around doesn't save on every frame.
+const SETTLE_FRAMES: u32 = 30;
+
+/// Apply the window mode of the settings to a window that is about to be built.
+pub fn configure(builder: RltkBuilder, settings: &DisplaySettings) -> RltkBuilder {
+    builder
+        .with_fullscreen(settings.window_mode == WindowMode::Fullscreen)
+        .with_fitscreen(settings.resizable)
+}
+
+/// Give the freshly built window the decorations, size and position of the last session. Has to
+/// be called before the main loop takes over the window.
+pub fn restore(settings: &DisplaySettings) {
+    imp::restore(settings)
+}
+
+/// Keeps an eye on the size and position of the window, to remember them across sessions.
+#[derive(Debug, Default)]
+pub struct WindowTracker {
+    /// The size of a window that cannot be resized is not worth remembering.
+    resizable: bool,
+    size: Option<(u32, u32)>,
+    position: Option<(i32, i32)>,
+    /// Frames since the window was last resized or moved, `None` if there's nothing to save.
+    unsaved_frames: Option<u32>,
+}
+
+impl WindowTracker {
+    pub fn new() -> Self {
+        let settings = DisplaySettings::load();
+        WindowTracker {
+            resizable: settings.resizable,
+            size: settings.window_size,
+            position: settings.window_position,
+            unsaved_frames: None,
+        }
+    }
+
+    /// Handle the window events of the latest frame and save the window's size and position once
+    /// it stopped changing.
+    pub fn update(&mut self, ctx: &mut Rltk) {
+        let mut position = self.position;
+        INPUT.lock().for_each_message(|event| match event {
+            BEvent::CloseRequested => ctx.quitting = true,
+            BEvent::Moved {
+                new_position: Point { x, y },
+            } => position = Some((x, y)),
+            _ => (),
+        });
+        let size = if self.resizable {
+            imp::window_size().or(self.size)
+        } else {
+            self.size
+        };
+
+        if (size, position) != (self.size, self.position) {
+            self.size = size;
+            self.position = position;
+            self.unsaved_frames = Some(0);
+        }
+        match self.unsaved_frames {
+            Some(frames) if frames >= SETTLE_FRAMES || ctx.quitting => {
+                self.unsaved_frames = None;
+                self.save();
+            }
+            Some(frames) => self.unsaved_frames = Some(frames + 1),
+            None => {}
+        }
+    }
+
+    fn save(&self) {
+        let mut settings = DisplaySettings::load();
+        // a fullscreen window says nothing about the size of a normal one
+        if settings.window_mode == WindowMode::Fullscreen {
+            return;
+        }
+        settings.window_size = self.size;
+        settings.window_position = self.position;
+        settings.save();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    use glutin::dpi::{PhysicalPosition, PhysicalSize};
+
+    use crate::ui::settings::{DisplaySettings, WindowMode};
+
+    pub fn restore(settings: &DisplaySettings) {
+        let backend = rltk::BACKEND.lock();
+        let window = match &backend.context_wrapper {
+            Some(wrapper) => wrapper.wc.window(),
+            None => return,
+        };
+        match settings.window_mode {
+            WindowMode::Fullscreen => return,
+            WindowMode::Borderless => window.set_decorations(false),
+            WindowMode::Windowed => {}
+        }
+        if let (true, Some((width, height))) = (settings.resizable, settings.window_size) {
+            window.set_inner_size(PhysicalSize::new(width, height));
+        }
+        if let Some((x, y)) = settings.window_position {
+            window.set_outer_position(PhysicalPosition::new(x, y));
+        }
+    }
+
+    /// Size in [px] of the window's content, `None` before the window is shown.
+    pub fn window_size() -> Option<(u32, u32)> {
+        let size = rltk::BACKEND.lock().screen_scaler.physical_size;
+        if size.0 == 0 || size.1 == 0 {
+            None
+        } else {
+            Some(size)
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use crate::ui::settings::DisplaySettings;
+
+    pub fn restore(_settings: &DisplaySettings) {}
+
+    pub fn window_size() -> Option<(u32, u32)> {
+        None
+    }
+}
@@ -1,6 +1,7 @@
 //! Utilites contains useful functions that are unrelated to any of the main game modules.
 
 pub mod game_rng;
+pub mod platform;
 pub mod timer;
 
 extern crate num;
@@ -12,6 +13,14 @@ pub fn modulus<T: Num + PartialOrd + Copy>(a: T, b: T) -> T {
     ((a % b) + b) % b
 }
 
+/// FNV-1a hash, which unlike the std hasher is guaranteed to stay the same across releases and
+/// platforms.
+pub fn fnv_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
 pub fn generate_gray_code(n: u8) -> Vec<u8> {
     let base: u8 = 2;
     let code_len: u8 = base.pow(u32::from(n));
@@ -3,16 +3,17 @@ use rand::{Rng, RngCore, SeedableRng};
 use rand_core::{impls, Error};
 use rand_isaac::isaac64::Isaac64Rng;
 use serde::{Deserialize, Serialize};
-use std::mem;
 
 // Type of RNG to be used in-game.
 pub type GameRng = SerializableRng<Isaac64Rng>;
 
 /// A seedable random number generator that can be serialized for consistent random number
 /// generation. For more info on Rust RNGs, refer to https://rust-random.github.io/book/guide-rngs.html
-/// For an example implementation of serializable RNG, refer to
-///     https://github.com/rsaarelm/calx/blob/45a8d78edda35f2acd59bf9d2bf96fbb98b214ed/calx-alg/src/rng.rs#L33-L84
-#[derive(Clone, Debug)]
+/// The complete state of the inner generator is serialized, including the numbers it has
+/// generated in advance but not handed out yet, so that a loaded game continues with exactly the
+/// numbers the saved one would have drawn next.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct SerializableRng<T> {
     inner: T,
 }
@@ -41,38 +42,6 @@ impl<T: SeedableRng + Rng + 'static> SeedableRng for SerializableRng<T> {
     }
 }
 
-impl<T: Rng + 'static> Serialize for SerializableRng<T> {
-    /// Serialize the rng as a binary blob.
-    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-        let mut vec = Vec::new();
-        unsafe {
-            let view = self as *const _ as *const u8;
-            for i in 0..(mem::size_of::<T>()) {
-                // vec.push(*view.offset(i as isize));
-                vec.push(*view.add(i));
-            }
-        }
-        vec.serialize(s)
-    }
-}
-
-impl<'a, T: Rng + 'static> Deserialize<'a> for SerializableRng<T> {
-    /// Deserialize the rng from a binary blob.
-    fn deserialize<D: serde::Deserializer<'a>>(d: D) -> Result<Self, D::Error> {
-        let bin_blob: Vec<u8> = serde::Deserialize::deserialize(d)?;
-        unsafe {
-            if bin_blob.len() == mem::size_of::<T>() {
-                Ok(SerializableRng::new(mem::transmute_copy(&bin_blob[0])))
-            } else {
-                Err(serde::de::Error::invalid_length(
-                    bin_blob.len(),
-                    &"Bad inner RNG length",
-                ))
-            }
-        }
-    }
-}
-
 impl<T: Rng> RngCore for SerializableRng<T> {
     fn next_u32(&mut self) -> u32 {
         self.inner.next_u32()
@@ -92,6 +61,91 @@ impl<T: Rng> RngCore for SerializableRng<T> {
     }
 }
 
+/// Subsystems that draw their random numbers from a stream of their own, so that the amount of
+/// numbers one of them consumes doesn't change what the others get for the same seed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RngStream {
+    /// Layout of the world and everything that is placed in it.
+    WorldGen,
+    /// Changes to genomes, whether natural or made by the player.
+    Mutation,
+    /// Decisions of the organisms that aren't controlled by the player.
+    Ai,
+    /// Treatments, immune responses and other events of the host.
+    Host,
+    /// Remains that organisms leave behind when they die.
+    Loot,
+}
+
+impl RngStream {
+    pub const ALL: [RngStream; 5] = [
+        RngStream::WorldGen,
+        RngStream::Mutation,
+        RngStream::Ai,
+        RngStream::Host,
+        RngStream::Loot,
+    ];
+}
+
+/// One random number generator per `RngStream`, each seeded from the master seed. The streams are
+/// saved as a list in the order of `RngStream::ALL`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "Vec<GameRng>", into = "Vec<GameRng>")]
+pub struct RngStreams {
+    streams: [GameRng; RngStream::ALL.len()],
+}
+
+/// Games saved before a stream was added lack it. Missing streams are seeded from the last saved
+/// one, so that the loaded game stays deterministic.
+impl From<Vec<GameRng>> for RngStreams {
+    fn from(saved: Vec<GameRng>) -> Self {
+        let mut last = saved.last().cloned();
+        let mut streams = RngStreams::default();
+        for (idx, stream) in streams.streams.iter_mut().enumerate() {
+            match (saved.get(idx), last.as_mut()) {
+                (Some(saved), _) => *stream = saved.clone(),
+                (None, Some(last)) => *stream = GameRng::new_from_u64_seed(last.next_u64()),
+                (None, None) => {}
+            }
+        }
+        streams
+    }
+}
+
+impl From<RngStreams> for Vec<GameRng> {
+    fn from(streams: RngStreams) -> Self {
+        streams.streams.to_vec()
+    }
+}
+
+impl Default for RngStreams {
+    fn default() -> Self {
+        RngStreams::new(0)
+    }
+}
+
+impl RngStreams {
+    pub fn new(seed: u64) -> Self {
+        RngStreams {
+            streams: RngStream::ALL
+                .map(|stream| GameRng::new_from_u64_seed(stream_seed(seed, stream))),
+        }
+    }
+
+    pub fn get(&mut self, stream: RngStream) -> &mut GameRng {
+        &mut self.streams[stream as usize]
+    }
+}
+
+/// Mix the stream into the master seed, the way SplitMix64 does, so that the streams of a seed
+/// are unrelated to each other and to those of neighbouring seeds.
+fn stream_seed(seed: u64, stream: RngStream) -> u64 {
+    let mut z = seed.wrapping_add((stream as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d1_33eb_2af3_4a4b);
+    z ^ (z >> 31)
+}
+
 /// Game specific methods for random number generators.
 pub trait RngExtended {
     /// Return true or false with 50/50 chance of being true
@@ -0,0 +1,199 @@
+//! Platform abstraction for everything that talks to the outside world.
+//!
+//! Native builds keep their data as files in the system data directory, whereas browser builds
+//! (`wasm32`) use the local storage of the page and have no access to user files at all.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Store `data` under the given key, overwriting whatever was stored there before.
+pub fn write_data(key: &str, data: &str) -> Result<(), Box<dyn Error>> {
+    imp::write_data(key, data)
+}
+
+/// Append `data` to whatever is stored under the given key.
+pub fn append_data(key: &str, data: &str) -> Result<(), Box<dyn Error>> {
+    imp::append_data(key, data)
+}
+
+/// Remove whatever is stored under the given key, if anything.
+pub fn remove_data(key: &str) -> Result<(), Box<dyn Error>> {
+    imp::remove_data(key)
+}
+
+/// Path of the file that stores the data under the given key, for libraries that write files on
+/// their own. Its directory is created if necessary.
+pub fn data_file_path(key: &str) -> Result<PathBuf, Box<dyn Error>> {
+    imp::data_file_path(key)
+}
+
+/// Retrieve the data stored under the given key.
+pub fn read_data(key: &str) -> Result<String, Box<dyn Error>> {
+    imp::read_data(key)
+}
+
+/// Check whether the user provided a file at the given path.
+pub fn has_user_file(path: &Path) -> bool {
+    imp::has_user_file(path)
+}
+
+/// Read a file provided by the user, e.g. modded raws. Returns `None` if there is no such file.
+pub fn read_user_file(path: &Path) -> Option<Result<Vec<u8>, String>> {
+    imp::read_user_file(path)
+}
+
+/// Wall clock time in seconds since 1970-01-01 UTC.
+pub fn unix_time_secs() -> u64 {
+    imp::unix_time_secs()
+}
+
+/// Monotonic time in nanoseconds, only meaningful relative to other calls of this function.
+pub fn now_nanos() -> u128 {
+    imp::now_nanos()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    use std::error::Error;
+    use std::fs;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    lazy_static! {
+        static ref START: Instant = Instant::now();
+    }
+
+    fn data_path(key: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let mut path = dirs::data_local_dir().ok_or("cannot access system data directory")?;
+        path.push("innit");
+        path.push(key);
+        Ok(path)
+    }
+
+    pub fn write_data(key: &str, data: &str) -> Result<(), Box<dyn Error>> {
+        let path = data_path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, data)?;
+        Ok(())
+    }
+
+    pub fn append_data(key: &str, data: &str) -> Result<(), Box<dyn Error>> {
+        let path = data_path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn read_data(key: &str) -> Result<String, Box<dyn Error>> {
+        Ok(fs::read_to_string(data_path(key)?)?)
+    }
+
+    pub fn remove_data(key: &str) -> Result<(), Box<dyn Error>> {
+        let path = data_path(key)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    pub fn data_file_path(key: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let path = data_path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(path)
+    }
+
+    pub fn has_user_file(path: &Path) -> bool {
+        path.is_file()
+    }
+
+    pub fn read_user_file(path: &Path) -> Option<Result<Vec<u8>, String>> {
+        if !path.exists() {
+            return None;
+        }
+        Some(fs::read(path).map_err(|e| e.to_string()))
+    }
+
+    pub fn unix_time_secs() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    }
+
+    pub fn now_nanos() -> u128 {
+        START.elapsed().as_nanos()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use std::error::Error;
+    use std::path::{Path, PathBuf};
+    use web_sys::Storage;
+
+    fn local_storage() -> Result<Storage, Box<dyn Error>> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| "local storage is not available".into())
+    }
+
+    pub fn write_data(key: &str, data: &str) -> Result<(), Box<dyn Error>> {
+        local_storage()?
+            .set_item(&format!("innit/{}", key), data)
+            .map_err(|e| format!("cannot write to local storage: {:?}", e).into())
+    }
+
+    pub fn append_data(key: &str, data: &str) -> Result<(), Box<dyn Error>> {
+        let stored = read_data(key).unwrap_or_default();
+        write_data(key, &(stored + data))
+    }
+
+    pub fn read_data(key: &str) -> Result<String, Box<dyn Error>> {
+        local_storage()?
+            .get_item(&format!("innit/{}", key))
+            .map_err(|e| format!("cannot read from local storage: {:?}", e))?
+            .ok_or_else(|| format!("nothing stored under {}", key).into())
+    }
+
+    pub fn remove_data(key: &str) -> Result<(), Box<dyn Error>> {
+        local_storage()?
+            .remove_item(&format!("innit/{}", key))
+            .map_err(|e| format!("cannot remove from local storage: {:?}", e).into())
+    }
+
+    pub fn data_file_path(_key: &str) -> Result<PathBuf, Box<dyn Error>> {
+        Err("there is no file system in the browser".into())
+    }
+
+    pub fn has_user_file(_path: &Path) -> bool {
+        false
+    }
+
+    pub fn read_user_file(_path: &Path) -> Option<Result<Vec<u8>, String>> {
+        None
+    }
+
+    pub fn unix_time_secs() -> u64 {
+        web_sys::window()
+            .and_then(|window| window.performance())
+            .map_or(0, |performance| {
+                ((performance.time_origin() + performance.now()) / 1000.0) as u64
+            })
+    }
+
+    pub fn now_nanos() -> u128 {
+        web_sys::window()
+            .and_then(|window| window.performance())
+            .map_or(0, |performance| (performance.now() * 1_000_000.0) as u128)
+    }
+}
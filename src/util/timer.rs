@@ -1,11 +1,10 @@
 //! A simple RAII-based timer for benchmarking function runtimes
 
-use std::time::Instant;
-
 use crate::util::modulus;
+use crate::util::platform;
 
 pub struct Timer {
-    start_t: Instant,
+    start_t: u128,
     is_running: bool,
     msg: &'static str,
 }
@@ -13,32 +12,37 @@ pub struct Timer {
 impl Timer {
     pub fn new(msg: &'static str) -> Self {
         Timer {
-            start_t: Instant::now(),
+            start_t: platform::now_nanos(),
             is_running: true,
             msg,
         }
     }
 
     pub fn lap(&mut self) {
-        self.summary("currently", self.start_t.elapsed().as_nanos());
+        self.summary("currently", self.elapsed());
     }
 
     pub fn stop(&mut self) -> u128 {
         if !self.is_running {
             return 0;
         }
-        let elapsed = self.start_t.elapsed().as_nanos();
+        let elapsed = self.elapsed();
         self.summary("finished", elapsed);
         self.is_running = false;
         elapsed
     }
 
     pub fn stop_silent(&mut self) -> u128 {
-        let elapsed = self.start_t.elapsed().as_nanos();
+        let elapsed = self.elapsed();
         self.is_running = false;
         elapsed
     }
 
+    /// Time in [ns] since the timer was started.
+    pub fn elapsed(&self) -> u128 {
+        platform::now_nanos() - self.start_t
+    }
+
     fn summary(&self, verb: &str, mut elapsed: u128) {
         let nanos = modulus(elapsed, 1000);
         elapsed /= 1000;